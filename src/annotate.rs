@@ -0,0 +1,382 @@
+use ab_glyph::{FontVec, PxScale};
+use image::{DynamicImage, GenericImageView, Rgba, RgbaImage};
+use imageproc::drawing::{draw_filled_rect_mut, draw_hollow_rect_mut, draw_text_mut, text_size, Blend};
+use imageproc::rect::Rect;
+use serde::{Deserialize, Serialize};
+
+use crate::font;
+use crate::ocr::BoundingBox;
+
+const BOX_COLOR: Rgba<u8> = Rgba([220, 20, 60, 255]);
+// 标签底色的不透明度
+pub const LABEL_BACKGROUND_ALPHA: u8 = 170;
+// 计算底色亮度时每个方向最多采样的点数
+const LUMINANCE_SAMPLES: u32 = 16;
+
+// 填充检测框时的不透明度
+pub const FILL_ALPHA: u8 = 50;
+
+// 检测框的着色方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BoxColorMode {
+    #[default]
+    Fixed,
+    // 按置信度从红到绿着色
+    Confidence,
+    // 按检测框文字的语言着色，用于核对中英混排等多语言识别
+    Language,
+}
+
+// 界面上的检测框叠加层与导出的标注图共用的样式
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OverlayStyle {
+    // 线宽：界面上为像素，标注图中按图片尺寸等比放大
+    pub stroke_width: f32,
+    pub fill: bool,
+    pub color_mode: BoxColorMode,
+    pub color: [u8; 3],
+    // 在检测框上方显示识别出的文字
+    pub show_labels: bool,
+}
+
+impl Default for OverlayStyle {
+    fn default() -> Self {
+        let Rgba([r, g, b, _]) = BOX_COLOR;
+        Self {
+            stroke_width: 1.0,
+            fill: false,
+            color_mode: BoxColorMode::Fixed,
+            color: [r, g, b],
+            show_labels: false,
+        }
+    }
+}
+
+impl OverlayStyle {
+    pub fn box_color(&self, bbox: &BoundingBox) -> [u8; 3] {
+        match self.color_mode {
+            BoxColorMode::Fixed => self.color,
+            BoxColorMode::Confidence => Colormap::RedGreen.color(bbox.confidence),
+            BoxColorMode::Language => language_color(bbox.language.as_deref()),
+        }
+    }
+}
+
+// 各语言检测框的颜色，未知语言（如纯数字）为灰色
+pub fn language_color(language: Option<&str>) -> [u8; 3] {
+    match language {
+        Some("chi_sim" | "chi_tra") => [220, 20, 60],
+        Some("eng") => [30, 110, 230],
+        Some("jpn") => [150, 60, 200],
+        Some("kor") => [20, 160, 90],
+        Some("rus") => [230, 130, 0],
+        Some("ara") => [0, 150, 160],
+        Some(_) => [120, 90, 60],
+        None => [128, 128, 128],
+    }
+}
+
+// 标注图导出选项
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AnnotateOptions {
+    pub include_confidence: bool,
+}
+
+// 置信度热力图的配色，从低置信度到高置信度
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Colormap {
+    // 红 → 黄 → 绿
+    #[default]
+    RedGreen,
+    // 紫 → 蓝 → 绿 → 黄，色盲友好且灰度打印时亮度单调
+    Viridis,
+}
+
+impl Colormap {
+    pub const ALL: [Self; 2] = [Self::RedGreen, Self::Viridis];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::RedGreen => "红-绿",
+            Self::Viridis => "Viridis",
+        }
+    }
+
+    fn stops(self) -> &'static [[u8; 3]] {
+        match self {
+            Self::RedGreen => &[[220, 20, 60], [255, 200, 0], [34, 170, 70]],
+            Self::Viridis => &[[68, 1, 84], [59, 82, 139], [33, 145, 140], [94, 201, 98], [253, 231, 37]],
+        }
+    }
+
+    // 在相邻色标之间线性插值，value 为 0~1
+    pub fn color(self, value: f32) -> [u8; 3] {
+        let stops = self.stops();
+        let position = value.clamp(0.0, 1.0) * (stops.len() - 1) as f32;
+        let index = (position.floor() as usize).min(stops.len() - 2);
+        let t = position - index as f32;
+        let (from, to) = (stops[index], stops[index + 1]);
+        std::array::from_fn(|channel| (from[channel] as f32 + (to[channel] as f32 - from[channel] as f32) * t).round() as u8)
+    }
+}
+
+// 置信度热力图导出选项
+#[derive(Debug, Clone, PartialEq)]
+pub struct HeatmapOptions {
+    // 覆盖色的不透明度（0~1）
+    pub opacity: f32,
+    pub colormap: Colormap,
+}
+
+impl Default for HeatmapOptions {
+    fn default() -> Self {
+        Self {
+            opacity: 0.45,
+            colormap: Colormap::default(),
+        }
+    }
+}
+
+pub fn load_label_font() -> Option<FontVec> {
+    let (data, _) = font::load_cjk_font();
+    FontVec::try_from_vec(data.into_owned()).ok()
+}
+
+// 按样式在原图上绘制检测框（及可选的文字标签），没有字体时只画框
+pub fn annotate_image(
+    image: &DynamicImage,
+    boxes: &[BoundingBox],
+    style: &OverlayStyle,
+    opts: &AnnotateOptions,
+    font: Option<&FontVec>,
+) -> RgbaImage {
+    let mut canvas = image.to_rgba8();
+    let (width, height) = canvas.dimensions();
+
+    // 线宽和字号随图片尺寸缩放，保证大尺寸扫描件上也清晰可见
+    let short_side = width.min(height) as f32;
+    let thickness = (short_side / 500.0 * style.stroke_width).round().max(1.0) as i32;
+    let scale = PxScale::from((short_side / 40.0).max(12.0));
+
+    for bbox in boxes {
+        if bbox.width == 0 || bbox.height == 0 {
+            continue;
+        }
+        let [r, g, b] = style.box_color(bbox);
+        if style.fill {
+            let mut blend = Blend(canvas);
+            let rect = Rect::at(bbox.x as i32, bbox.y as i32).of_size(bbox.width, bbox.height);
+            draw_filled_rect_mut(&mut blend, rect, Rgba([r, g, b, FILL_ALPHA]));
+            canvas = blend.0;
+        }
+        for offset in 0..thickness {
+            let w = bbox.width as i32 + offset * 2;
+            let h = bbox.height as i32 + offset * 2;
+            let rect = Rect::at(bbox.x as i32 - offset, bbox.y as i32 - offset).of_size(w as u32, h as u32);
+            draw_hollow_rect_mut(&mut canvas, rect, Rgba([r, g, b, 255]));
+        }
+
+        if let (true, Some(font)) = (style.show_labels || opts.include_confidence, font) {
+            let label = box_label(bbox, style.show_labels, opts.include_confidence);
+            draw_label(&mut canvas, font, scale, &label, bbox.x as i32, bbox.y as i32 - thickness);
+        }
+    }
+
+    canvas
+}
+
+// 按置信度为每个检测框半透明着色，叠加在原图上，用于查看整页中识别较差的区域
+pub fn confidence_heatmap(image: &DynamicImage, boxes: &[BoundingBox], opts: &HeatmapOptions) -> RgbaImage {
+    let alpha = (opts.opacity.clamp(0.0, 1.0) * 255.0).round() as u8;
+    let mut canvas = Blend(image.to_rgba8());
+    for bbox in boxes.iter().filter(|bbox| bbox.width > 0 && bbox.height > 0) {
+        let [r, g, b] = opts.colormap.color(bbox.confidence);
+        let rect = Rect::at(bbox.x as i32, bbox.y as i32).of_size(bbox.width, bbox.height);
+        draw_filled_rect_mut(&mut canvas, rect, Rgba([r, g, b, alpha]));
+    }
+    canvas.0
+}
+
+fn box_label(bbox: &BoundingBox, include_text: bool, include_confidence: bool) -> String {
+    let confidence = format!("{:.0}%", bbox.confidence * 100.0);
+    match (include_text, include_confidence) {
+        (true, true) => format!("{} ({})", bbox.text.trim(), confidence),
+        (true, false) => bbox.text.trim().to_string(),
+        _ => confidence,
+    }
+}
+
+// 在检测框上方绘制带半透明圆角底色的标签，上方空间不足时画在框内。
+// 文字颜色根据标签位置原图的亮度选择黑色或白色
+fn draw_label(canvas: &mut RgbaImage, font: &FontVec, scale: PxScale, label: &str, x: i32, bottom: i32) {
+    let (text_width, text_height) = text_size(scale, font, label);
+    let padding = (text_height as i32 / 4).max(2);
+    let label_width = text_width + padding as u32 * 2;
+    let label_height = text_height as i32 + padding * 2;
+    let top = if bottom - label_height >= 0 { bottom - label_height } else { bottom.max(0) };
+
+    let x = x.max(0);
+    let luminance = region_luminance(canvas, x as u32, top as u32, label_width, label_height as u32);
+    let (text, background) = contrast_colors(luminance);
+    blend_pill(canvas, x, top, label_width, label_height as u32, background, LABEL_BACKGROUND_ALPHA);
+    draw_text_mut(canvas, Rgba([text[0], text[1], text[2], 255]), x + padding, top + padding, scale, font, label);
+}
+
+// 区域内的平均相对亮度（0~1），按间隔采样；区域在图片之外时返回 0
+pub fn region_luminance<I: GenericImageView<Pixel = Rgba<u8>>>(image: &I, x: u32, y: u32, width: u32, height: u32) -> f32 {
+    let (image_width, image_height) = image.dimensions();
+    let (x_end, y_end) = (x.saturating_add(width).min(image_width), y.saturating_add(height).min(image_height));
+    if x >= x_end || y >= y_end {
+        return 0.0;
+    }
+
+    let step = ((x_end - x).max(y_end - y) / LUMINANCE_SAMPLES).max(1);
+    let (mut sum, mut count) = (0.0, 0);
+    for py in (y..y_end).step_by(step as usize) {
+        for px in (x..x_end).step_by(step as usize) {
+            let Rgba([r, g, b, _]) = image.get_pixel(px, py);
+            sum += (0.2126 * r as f32 + 0.7152 * g as f32 + 0.0722 * b as f32) / 255.0;
+            count += 1;
+        }
+    }
+    sum / count as f32
+}
+
+// 根据底色亮度返回（文字颜色，标签底色）：亮处用黑字白底，暗处用白字黑底
+pub fn contrast_colors(luminance: f32) -> ([u8; 3], [u8; 3]) {
+    if luminance > 0.5 {
+        ([0, 0, 0], [255, 255, 255])
+    } else {
+        ([255, 255, 255], [0, 0, 0])
+    }
+}
+
+// 以两端为半圆的胶囊形状半透明填充
+fn blend_pill(canvas: &mut RgbaImage, x: i32, y: i32, width: u32, height: u32, color: [u8; 3], alpha: u8) {
+    let radius = height as f32 / 2.0;
+    let alpha = alpha as f32 / 255.0;
+    let (canvas_width, canvas_height) = canvas.dimensions();
+    for py in y.max(0)..(y + height as i32).min(canvas_height as i32) {
+        for px in x.max(0)..(x + width as i32).min(canvas_width as i32) {
+            // 到胶囊中轴线段的距离不超过半径即在形状内
+            let (cx, cy) = (px as f32 + 0.5, py as f32 + 0.5);
+            let axis_x = cx.clamp(x as f32 + radius, (x as f32 + width as f32 - radius).max(x as f32 + radius));
+            let axis_y = y as f32 + radius;
+            if (cx - axis_x).hypot(cy - axis_y) > radius {
+                continue;
+            }
+            let pixel = canvas.get_pixel_mut(px as u32, py as u32);
+            for channel in 0..3 {
+                pixel[channel] = (pixel[channel] as f32 * (1.0 - alpha) + color[channel] as f32 * alpha).round() as u8;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bbox(x: u32, y: u32, width: u32, height: u32) -> BoundingBox {
+        BoundingBox {
+            text: "text".to_string(),
+            confidence: 0.9,
+            x,
+            y,
+            width,
+            height,
+            edited: false,
+            language: None,
+        }
+    }
+
+    #[test]
+    fn test_boxes_are_drawn_without_font() {
+        let image = DynamicImage::new_rgb8(100, 100);
+        let annotated = annotate_image(&image, &[bbox(10, 10, 50, 20)], &OverlayStyle::default(), &AnnotateOptions::default(), None);
+
+        assert_eq!(*annotated.get_pixel(10, 10), BOX_COLOR);
+        assert_eq!(*annotated.get_pixel(30, 20), Rgba([0, 0, 0, 255]));
+    }
+
+    #[test]
+    fn test_style_colors_and_fills_boxes() {
+        let image = DynamicImage::new_rgb8(100, 100);
+        let mut low = bbox(10, 10, 50, 20);
+        low.confidence = 0.0;
+        let style = OverlayStyle { fill: true, color_mode: BoxColorMode::Confidence, ..Default::default() };
+        let annotated = annotate_image(&image, &[low], &style, &AnnotateOptions::default(), None);
+
+        let [r, g, b] = Colormap::RedGreen.color(0.0);
+        assert_eq!(*annotated.get_pixel(10, 10), Rgba([r, g, b, 255]));
+        // 框内半透明填充
+        let inside = annotated.get_pixel(30, 20);
+        assert!(inside[0] > 0 && inside[0] < r);
+    }
+
+    #[test]
+    fn test_thickness_scales_with_image_size() {
+        let image = DynamicImage::new_rgb8(2000, 2000);
+        let annotated = annotate_image(&image, &[bbox(100, 100, 500, 200)], &OverlayStyle::default(), &AnnotateOptions::default(), None);
+
+        // 2000 像素短边对应 4 像素线宽，向外扩展
+        assert_eq!(*annotated.get_pixel(97, 200), BOX_COLOR);
+        assert_eq!(*annotated.get_pixel(95, 200), Rgba([0, 0, 0, 255]));
+    }
+
+    #[test]
+    fn test_label_colors_contrast_with_background() {
+        let mut image = RgbaImage::from_pixel(40, 20, Rgba([250, 250, 250, 255]));
+        for x in 20..40 {
+            for y in 0..20 {
+                image.put_pixel(x, y, Rgba([10, 10, 10, 255]));
+            }
+        }
+        let light = region_luminance(&image, 0, 0, 20, 20);
+        let dark = region_luminance(&image, 20, 0, 20, 20);
+        assert!(light > 0.9 && dark < 0.1);
+        assert_eq!(contrast_colors(light).0, [0, 0, 0]);
+        assert_eq!(contrast_colors(dark).0, [255, 255, 255]);
+        assert_eq!(region_luminance(&image, 100, 100, 10, 10), 0.0);
+    }
+
+    #[test]
+    fn test_heatmap_tints_by_confidence() {
+        let image = DynamicImage::ImageRgb8(image::RgbImage::from_pixel(100, 50, image::Rgb([255, 255, 255])));
+        let mut low = bbox(0, 0, 40, 40);
+        low.confidence = 0.1;
+        let mut high = bbox(50, 0, 40, 40);
+        high.confidence = 1.0;
+
+        let opts = HeatmapOptions { opacity: 0.5, colormap: Colormap::RedGreen };
+        let heatmap = confidence_heatmap(&image, &[low.clone(), high.clone()], &opts);
+        let Rgba([r, g, _, _]) = *heatmap.get_pixel(20, 20);
+        assert!(r > g);
+        let Rgba([r, g, _, _]) = *heatmap.get_pixel(70, 20);
+        assert!(g > r);
+        // 检测框之外保持原图
+        assert_eq!(*heatmap.get_pixel(95, 45), Rgba([255, 255, 255, 255]));
+
+        let transparent = HeatmapOptions { opacity: 0.0, ..opts };
+        assert_eq!(*confidence_heatmap(&image, &[low], &transparent).get_pixel(20, 20), Rgba([255, 255, 255, 255]));
+    }
+
+    #[test]
+    fn test_colormap_endpoints() {
+        assert_eq!(Colormap::RedGreen.color(0.0), [220, 20, 60]);
+        assert_eq!(Colormap::RedGreen.color(1.0), [34, 170, 70]);
+        assert_eq!(Colormap::Viridis.color(0.5), [33, 145, 140]);
+        assert_eq!(Colormap::Viridis.color(2.0), [253, 231, 37]);
+    }
+
+    #[test]
+    fn test_pill_is_translucent_with_round_ends() {
+        let mut canvas = RgbaImage::from_pixel(40, 20, Rgba([0, 0, 0, 255]));
+        blend_pill(&mut canvas, 0, 0, 40, 20, [255, 255, 255], 128);
+        // 中心被半透明白色覆盖，角落在圆角之外保持不变
+        assert_eq!(*canvas.get_pixel(20, 10), Rgba([128, 128, 128, 255]));
+        assert_eq!(*canvas.get_pixel(0, 0), Rgba([0, 0, 0, 255]));
+    }
+}
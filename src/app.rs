@@ -1,17 +1,115 @@
 use eframe::egui;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Instant;
 use tokio::sync::mpsc;
 use image::{DynamicImage, GenericImageView};
 
-use crate::ocr::{OcrEngine, OcrResult};
-use crate::ui::{ImageDisplay, StatusDisplay, ResultPanel};
+use crate::annotate::{self, AnnotateOptions, BoxColorMode, Colormap, HeatmapOptions};
+use crate::archive;
+use crate::batch::{self, BatchQueue, BatchSummary, ExportAction, ExportPlan, BlankPageOptions, Manifest, ManifestEntry, ListSelection, RepeatedLine, SummaryColumn};
+use crate::clipboard::{ClipboardEvent, ClipboardMonitor};
+use crate::duplicate::{self, DuplicateOptions};
+use crate::evaluate::{self, Evaluation};
+use crate::export::{self, ExportMetadata, OcrExport, OutputFormat};
+use crate::file_config::{self, FileConfig};
+use crate::font::{self, GlyphCoverage};
+use crate::hook;
+use crate::live::{self, LiveCapture, LiveEvent, LiveLog};
+use crate::loader::{self, GrayWindow};
+use crate::metadata::{self, ImageInfo};
+use crate::ocr::{self, BoundingBox, ComputeDevice, EngineKind, EnginePreference, EngineStatus, PageSegMode, OcrEngine, OcrProgressEvent, OcrResult, ProcessOptions, StageTiming};
+use crate::palette::{self, Command, CommandPalette};
+use crate::postprocess::{postprocess_result, WidthMode};
+use crate::preprocess::{self, BinarizeMethod, HueFilter, HueFilterMode, Levels, PreprocessOptions};
+use crate::profile::ProfileStore;
+use crate::quality::{self, QualityGate, Suggestion};
+use crate::reading_order::TextDirection;
+use crate::recent::{RecentFiles, ViewState};
+use crate::scratch;
+use crate::settings::Settings;
+use crate::status_log::{self, LogLevel};
+use crate::tessdata;
+use crate::ui::{Backdrop, DebugOverlay, ImageDisplay, Interpolation, LoupeOptions, PreprocessPreview, ProgressIndicator, StatusDisplay, ResultPanel};
 
 #[derive(Debug)]
 pub enum AppMessage {
     ImageSelected(PathBuf),
+    BatchQueued(Vec<PathBuf>),
+    UrlImageLoaded {
+        url: String,
+        frames: Vec<DynamicImage>,
+        load_ms: f64,
+    },
+    UrlLoadFailed(String),
+    ClipboardImage(DynamicImage),
+    ClipboardError(String),
+    LiveText(String),
+    LiveError(String),
+    LayoutDetected {
+        boxes: Vec<BoundingBox>,
+        millis: f64,
+    },
+    // 单个检测框重新识别的结果
+    RegionRecognized {
+        index: usize,
+        text: String,
+        confidence: f32,
+    },
+    RegionFailed(String),
+    // 识别阶段的完成比例（0~1）
+    OcrProgress(f32),
     OcrCompleted(OcrResult),
-    OcrError(String),
+    OcrError(ocr::OcrError),
+    // 识别后命令执行失败
+    HookFailed(String),
+    // 预处理图像已保存（或保存失败的原因）
+    PreprocessedSaved(Result<PathBuf, String>),
+}
+
+impl AppMessage {
+    // 把识别引擎的事件转换为界面消息；界面在发起识别时已切换状态，开始事件只记录日志
+    fn from_ocr_event(event: OcrProgressEvent) -> Option<Self> {
+        match event {
+            OcrProgressEvent::Started { width, height } => {
+                log::debug!("开始识别 {}x{} 的图片", width, height);
+                None
+            }
+            OcrProgressEvent::Progress(fraction) => Some(Self::OcrProgress(fraction)),
+            OcrProgressEvent::Completed(result) => Some(Self::OcrCompleted(result)),
+            OcrProgressEvent::Failed(error) => Some(Self::OcrError(error)),
+        }
+    }
+}
+
+// 发送后台消息的同时唤醒界面，空闲时不持续重绘也能及时处理结果
+#[derive(Clone)]
+struct MessageSender {
+    tx: mpsc::UnboundedSender<AppMessage>,
+    ctx: egui::Context,
+}
+
+impl MessageSender {
+    // 返回消息是否送达（界面已关闭时失败）
+    fn send(&self, message: AppMessage) -> bool {
+        let sent = self.tx.send(message).is_ok();
+        self.ctx.request_repaint();
+        sent
+    }
+    
+    // 没有消息要发送时也唤醒界面，例如跳过了批量中的一张图片
+    fn wake(&self) {
+        self.ctx.request_repaint();
+    }
+}
+
+// 识别失败后在结果区域提供的恢复操作
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum RecoveryAction {
+    Retry,
+    ChooseImage,
+    CheckEngine,
+    ResetPreprocess,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -30,106 +128,747 @@ pub struct OcrApp {
     // 图像相关
     selected_image_path: Option<PathBuf>,
     current_image: Option<DynamicImage>,
+    // 当前图片的格式与元数据
+    image_info: Option<ImageInfo>,
     image_display: ImageDisplay,
+    preprocess_preview: PreprocessPreview,
+    // 动画 GIF 的所有帧（静态图片为空）
+    animation_frames: Vec<DynamicImage>,
+    current_frame: usize,
+    // 最近一次加载图片的耗时，识别完成后并入阶段耗时
+    load_time_ms: Option<f64>,
     
     // OCR相关
     ocr_result: Option<OcrResult>,
+    // 当前识别的进度，引擎尚未报告时为空
+    ocr_progress: Option<f32>,
     ocr_engine: Arc<OcrEngine>,
+    process_options: ProcessOptions,
+    annotate_options: AnnotateOptions,
+    heatmap_options: HeatmapOptions,
+    profiles: ProfileStore,
+    new_profile_name: String,
+    
+    // 批量识别队列
+    batch: BatchQueue,
+    // 批量结果列表中多选的页面
+    batch_selection: ListSelection,
+    strip_headers_footers: bool,
+    // 检测到的页眉/页脚缓存，键为结果数量
+    repeated_lines_cache: Option<(usize, Vec<RepeatedLine>)>,
+    // 批量识别汇总窗口（Some 时显示）及表格排序方式
+    batch_summary: Option<BatchSummary>,
+    // 估计内存占用过大、等待用户确认的批量图片及估计的字节数
+    batch_memory_warning: Option<(Vec<PathBuf>, u64)>,
+    // 批量导出前的预演结果，确认后才写入文件
+    export_plan: Option<ExportPlan>,
+    summary_sort: (SummaryColumn, bool),
+    batch_was_active: bool,
+    
+    // 剪贴板监视
+    clipboard_monitor: Option<ClipboardMonitor>,
+    // 识别进行中时收到的最新剪贴板图片
+    pending_clipboard_image: Option<DynamicImage>,
+    clipboard_ocr_pending: bool,
+    // 剪贴板识别记录：(时间, 文本)
+    clipboard_log: Vec<(String, String)>,
+    // 区域连续识别
+    live_capture: Option<LiveCapture>,
+    live_log: LiveLog,
+    // 与标准文本比较的识别准确率：(标准文本路径, 标准文本, 评估结果)
+    evaluation: Option<(PathBuf, String, Evaluation)>,
+    blank_page: BlankPageOptions,
+    // 置信度低于门槛时在结果上方提示重新识别；Some 为低质量结果的置信度
+    quality_gate: QualityGate,
+    low_quality: Option<f32>,
+    duplicate_options: DuplicateOptions,
+    // 界面字体链的字符覆盖范围；结果中有无法显示的字符时每次运行只提示一次
+    glyphs: GlyphCoverage,
+    glyph_hint_shown: bool,
+    // 追加模式：每个识别结果都追加写入该文件
+    append_target: Option<PathBuf>,
     
     // UI组件
     status_display: StatusDisplay,
+    show_status_log: bool,
     result_panel: ResultPanel,
     
     // 异步通信
-    tx: mpsc::UnboundedSender<AppMessage>,
+    tx: MessageSender,
     rx: mpsc::UnboundedReceiver<AppMessage>,
     rt: tokio::runtime::Runtime,
     
     // UI状态
     show_settings: bool,
     dark_mode: bool,
+    // 是否允许在没有真实引擎时使用演示模式
+    demo_mode: bool,
     show_image_viewer: bool,
     image_scale: f32,
+    // 查看器中顺时针旋转的 90° 次数
+    image_rotation: u8,
+    // 查看器当前滚动位置，以及打开图片后待恢复的滚动位置
+    viewer_offset: egui::Vec2,
+    restore_viewer_offset: Option<egui::Vec2>,
+    // 开启“自适应窗口”时上次计算缩放所用的可用区域与旋转，变化后重新适应
+    viewer_fit_key: Option<(egui::Vec2, u8)>,
+    recent_files: RecentFiles,
+    // “最近”菜单中按文件名、备注搜索与按标签筛选
+    recent_query: String,
+    recent_tag: Option<String>,
+    settings: Settings,
+    show_url_dialog: bool,
+    // Ctrl+P 打开的命令面板
+    command_palette: Option<CommandPalette>,
+    // 专注模式（F11）：只显示识别结果
+    focus_mode: bool,
+    url_input: String,
+    preview_backdrop: Backdrop,
+    loupe_options: LoupeOptions,
+    debug_overlay: DebugOverlay,
+    // 已安装的 Tesseract 语言包（首次打开设置时扫描）
+    tesseract_languages: Option<Vec<String>>,
+    // 失败状态下显示的标题与恢复操作，随错误类型不同
+    error_recovery: Option<(&'static str, RecoveryAction)>,
+    // 可执行文件旁的 ocr-rs.toml，写回时保留其中的模型路径
+    file_config: FileConfig,
+    // 正在进行的识别所用的选项，完成后随批量结果保存，供清单记录
+    recognizing_options: Option<ProcessOptions>,
 }
 
 impl OcrApp {
-    pub fn new(_cc: &eframe::CreationContext<'_>) -> Self {
+    pub fn new(cc: &eframe::CreationContext<'_>, glyphs: GlyphCoverage) -> Self {
         let (tx, rx) = mpsc::unbounded_channel();
         let rt = tokio::runtime::Runtime::new().unwrap();
-        let ocr_engine = Arc::new(OcrEngine::new());
+        let settings = Settings::load();
+        // 环境变量关闭演示模式时不受设置影响
+        let demo_mode = !settings.demo_mode_disabled && ocr::demo_mode_allowed();
+        // ocr-rs.toml 提供默认的识别选项与模型
+        let file_config = FileConfig::load().unwrap_or_default();
+        let ocr_engine = Arc::new(file_config.engine_with_demo_mode(demo_mode));
+        
+        // 启动时应用上次使用的配置方案，没有时使用配置文件中的选项
+        let profiles = ProfileStore::load();
+        let mut process_options: ProcessOptions = profiles
+            .active
+            .as_deref()
+            .and_then(|name| profiles.get(name))
+            .map(|profile| profile.options.clone())
+            .unwrap_or_else(|| file_config.options.clone());
+        process_options.preprocess.levels = settings.levels;
+        let mut result_panel = ResultPanel::new();
+        result_panel.set_copy_on_select(settings.copy_on_select);
+        result_panel.set_display(settings.result_display);
+        let mut status_display = StatusDisplay::new();
+        status_display.set_verbosity(settings.status_log.verbosity);
         
         Self {
             state: AppState::Idle,
             selected_image_path: None,
             current_image: None,
+            image_info: None,
             image_display: ImageDisplay::new(),
+            preprocess_preview: PreprocessPreview::new(),
+            animation_frames: Vec::new(),
+            current_frame: 0,
+            load_time_ms: None,
             ocr_result: None,
+            ocr_progress: None,
             ocr_engine,
-            status_display: StatusDisplay::new(),
-            result_panel: ResultPanel::new(),
-            tx,
+            process_options,
+            annotate_options: AnnotateOptions::default(),
+            heatmap_options: HeatmapOptions::default(),
+            profiles,
+            new_profile_name: String::new(),
+            batch: BatchQueue::new(),
+            batch_selection: ListSelection::default(),
+            strip_headers_footers: false,
+            repeated_lines_cache: None,
+            batch_summary: None,
+            batch_memory_warning: None,
+            export_plan: None,
+            summary_sort: (SummaryColumn::File, true),
+            batch_was_active: false,
+            clipboard_monitor: None,
+            pending_clipboard_image: None,
+            clipboard_ocr_pending: false,
+            clipboard_log: Vec::new(),
+            live_capture: None,
+            live_log: LiveLog::default(),
+            evaluation: None,
+            append_target: None,
+            blank_page: BlankPageOptions::default(),
+            quality_gate: QualityGate::default(),
+            low_quality: None,
+            duplicate_options: DuplicateOptions::default(),
+            glyphs,
+            glyph_hint_shown: false,
+            status_display,
+            show_status_log: false,
+            result_panel,
+            tx: MessageSender { tx, ctx: cc.egui_ctx.clone() },
             rx,
             rt,
             show_settings: false,
             dark_mode: true,
+            demo_mode,
             show_image_viewer: false,
             image_scale: 1.0,
+            image_rotation: 0,
+            viewer_offset: egui::Vec2::ZERO,
+            restore_viewer_offset: None,
+            viewer_fit_key: None,
+            recent_files: RecentFiles::load(),
+            recent_query: String::new(),
+            recent_tag: None,
+            settings,
+            show_url_dialog: false,
+            command_palette: None,
+            focus_mode: false,
+            url_input: String::new(),
+            preview_backdrop: Backdrop::Checkerboard,
+            loupe_options: LoupeOptions::default(),
+            debug_overlay: DebugOverlay::new(),
+            tesseract_languages: None,
+            error_recovery: None,
+            file_config,
+            recognizing_options: None,
+        }
+    }
+    
+    fn engine_available(&self) -> bool {
+        !matches!(self.ocr_engine.get_status(), EngineStatus::NoEngineAvailable)
+    }
+    
+    // 重新初始化 OCR 引擎（例如安装 Tesseract 或放入模型之后），仍加载 ocr-rs.toml 中配置的模型
+    fn reload_engine(&mut self) {
+        self.ocr_engine = Arc::new(self.file_config.engine_with_demo_mode(self.demo_mode));
+        if self.engine_available() {
+            self.status_display.set_success("OCR 引擎加载成功");
+        } else {
+            self.status_display.set_error("仍然没有可用的 OCR 引擎");
+        }
+    }
+    
+    // 加载拖入的模型文件替换 ocr-rs.toml 中配置的模型，其余按配置文件创建；正在进行的识别继续使用原来的引擎
+    fn load_model(&mut self, path: &std::path::Path) {
+        let mut engine = self.file_config.engine_with_demo_mode(self.demo_mode);
+        match engine.load_candle_model(path) {
+            Ok(()) => {
+                self.ocr_engine = Arc::new(engine);
+                let name = path.file_name().unwrap_or_default().to_string_lossy();
+                self.status_display.set_success(&format!("模型加载成功: {}", name));
+            }
+            Err(e) => self.status_display.set_error(&format!("模型加载失败: {}", e)),
+        }
+    }
+    
+    fn render_engine_banner(&mut self, ui: &mut egui::Ui) {
+        if self.engine_available() {
+            return;
+        }
+        
+        egui::Frame::group(ui.style())
+            .fill(egui::Color32::from_rgba_unmultiplied(220, 20, 60, 40))
+            .show(ui, |ui| {
+                ui.set_width(ui.available_width());
+                ui.colored_label(egui::Color32::from_rgb(220, 20, 60), egui::RichText::new("⚠ 没有可用的 OCR 引擎").strong().size(16.0));
+                ui.label("图片识别功能暂不可用，请通过以下任一方式提供识别引擎：");
+                ui.label("• 安装 Tesseract 及语言包（如 tesseract-ocr、tesseract-ocr-chi-sim），并使用 --features tesseract 编译");
+                ui.label("• 将训练好的模型文件（.safetensors / .onnx）拖入窗口，或放在 models/ocr_model.safetensors");
+                ui.add_space(4.0);
+                if ui.button("🔄 重试加载引擎").clicked() {
+                    self.reload_engine();
+                }
+            });
+        ui.add_space(8.0);
+    }
+    
+    // 低质量结果的提示：按当前设置给出可尝试的调整，选择后修改选项并重新识别
+    fn render_quality_banner(&mut self, ui: &mut egui::Ui) {
+        let Some(confidence) = self.low_quality else {
+            return;
+        };
+        let used = self.ocr_engine.primary_engine(self.process_options.engine);
+        let both_engines = matches!(self.ocr_engine.get_status(), EngineStatus::Ready);
+        let suggestions = quality::suggestions(&self.process_options, used, both_engines);
+        let mut chosen: Option<Suggestion> = None;
+        let mut dismiss = false;
+        
+        egui::Frame::group(ui.style())
+            .fill(egui::Color32::from_rgba_unmultiplied(230, 160, 40, 40))
+            .show(ui, |ui| {
+                ui.set_width(ui.available_width());
+                ui.colored_label(
+                    egui::Color32::from_rgb(200, 120, 0),
+                    egui::RichText::new(format!("⚠ 低质量：置信度 {:.1}%，识别结果可能有较多错误", confidence * 100.0)).strong(),
+                );
+                ui.horizontal_wrapped(|ui| {
+                    for suggestion in &suggestions {
+                        if ui.button(suggestion.label()).clicked() {
+                            chosen = Some(*suggestion);
+                        }
+                    }
+                    if suggestions.is_empty() {
+                        ui.weak("可尝试更换语言包或提高图片分辨率");
+                    }
+                    if ui.button("保留结果").clicked() {
+                        dismiss = true;
+                    }
+                });
+            });
+        ui.add_space(4.0);
+        
+        if let Some(suggestion) = chosen {
+            suggestion.apply(&mut self.process_options);
+            self.rerun_ocr();
+        } else if dismiss {
+            self.low_quality = None;
         }
     }
     
     fn reset_state(&mut self) {
         self.state = AppState::Idle;
         self.ocr_result = None;
+        self.low_quality = None;
         self.status_display.clear();
     }
     
     fn handle_file_selection(&mut self) {
         let tx = self.tx.clone();
         
-        if let Some(path) = rfd::FileDialog::new()
-            .add_filter("图片文件", &["png", "jpg", "jpeg", "bmp", "tiff", "webp", "gif"])
+        let all_extensions = [loader::IMAGE_EXTENSIONS, archive::ARCHIVE_EXTENSIONS].concat();
+        if let Some(paths) = rfd::FileDialog::new()
+            .add_filter("图片与压缩包", &all_extensions)
+            .add_filter("图片文件", loader::IMAGE_EXTENSIONS)
+            .add_filter("图片压缩包", archive::ARCHIVE_EXTENSIONS)
             .set_title("选择要识别的图片")
-            .pick_file()
+            .pick_files()
         {
-            let _ = tx.send(AppMessage::ImageSelected(path));
+            // 选择多张图片或压缩包时加入批量队列
+            let mut paths = self.expand_archives(paths);
+            if paths.len() == 1 {
+                let _ = tx.send(AppMessage::ImageSelected(paths.remove(0)));
+            } else if !paths.is_empty() {
+                let _ = tx.send(AppMessage::BatchQueued(paths));
+            }
         }
     }
     
+    // 把压缩包替换为其中的图片（按自然顺序），打不开的压缩包提示错误后跳过
+    fn expand_archives(&mut self, paths: Vec<PathBuf>) -> Vec<PathBuf> {
+        let mut expanded = Vec::new();
+        for path in paths {
+            if !archive::is_archive(&path) {
+                expanded.push(path);
+                continue;
+            }
+            match archive::list_images(&path) {
+                Ok(pages) => expanded.extend(pages),
+                Err(e) => self.status_display.set_error(&format!(
+                    "无法打开压缩包 {}: {}",
+                    path.file_name().unwrap_or_default().to_string_lossy(),
+                    e
+                )),
+            }
+        }
+        expanded
+    }
+    
     fn handle_image_selected(&mut self, path: PathBuf) {
+        if self.show_image_viewer {
+            self.remember_view_state();
+        }
+        self.restore_view_state(&path);
+        
         self.state = AppState::Loading;
         self.selected_image_path = Some(path.clone());
         self.status_display.set_message("正在加载图片...");
+        self.image_info = metadata::read_image_info(&path)
+            .inspect_err(|e| log::warn!("读取图像信息失败: {}", e))
+            .ok();
         
-        match image::open(&path) {
-            Ok(img) => {
-                self.current_image = Some(img.clone());
-                self.image_display.set_image(img.clone());
-                self.start_ocr_processing(img, path);
+        let load_start = Instant::now();
+        match loader::load_frames(&path, self.settings.gray_window) {
+            Ok(frames) => {
+                self.load_time_ms = Some(load_start.elapsed().as_secs_f64() * 1000.0);
+                if !self.skip_duplicate_page(&frames, &path) {
+                    self.show_frames_and_recognize(frames, path);
+                }
             }
             Err(e) => {
+                self.error_recovery = Some(("图片加载失败，请换一张图片", RecoveryAction::ChooseImage));
                 self.state = AppState::Error(format!("无法加载图片: {}", e));
                 self.status_display.set_error(&format!("图片加载失败: {}", e));
+                self.batch.fail(e.to_string());
+            }
+        }
+    }
+    
+    // 批量识别中与本批已处理页面几乎相同的图片不再识别，返回是否跳过
+    fn skip_duplicate_page(&mut self, frames: &[DynamicImage], path: &std::path::Path) -> bool {
+        if !self.duplicate_options.enabled || self.batch.current().map(PathBuf::as_path) != Some(path) {
+            return false;
+        }
+        let Some(image) = frames.first() else {
+            return false;
+        };
+        let Some(original) = self.batch.check_duplicate(duplicate::dhash(image), &self.duplicate_options) else {
+            return false;
+        };
+        let name = |path: &std::path::Path| path.file_name().unwrap_or_default().to_string_lossy().to_string();
+        self.status_display.set_message(&format!("{} 与 {} 几乎相同，已跳过", name(path), name(&original)));
+        // 空闲状态下由批量队列继续处理下一张；跳过时没有后台消息，需主动请求下一帧
+        self.state = AppState::Idle;
+        self.tx.wake();
+        true
+    }
+    
+    fn show_frames_and_recognize(&mut self, mut frames: Vec<DynamicImage>, path: PathBuf) {
+        if frames.is_empty() {
+            return;
+        }
+        let img = frames.remove(0);
+        self.animation_frames = if frames.is_empty() {
+            Vec::new()
+        } else {
+            frames.insert(0, img.clone());
+            frames
+        };
+        self.current_frame = 0;
+        self.current_image = Some(img.clone());
+        // 新图片的结果不与上一张图片的手动编辑比较
+        self.result_panel.discard_edits();
+        self.image_display.set_image(img.clone());
+        self.preprocess_preview.set_image(&img);
+        self.start_ocr_processing(img, path);
+    }
+    
+    // 恢复该文件上次的查看器缩放、旋转与滚动位置
+    fn restore_view_state(&mut self, path: &std::path::Path) {
+        let view = self.recent_files.open(path);
+        match self.recent_files.entry(path) {
+            Some(entry) => self.result_panel.set_annotation(&entry.note, &entry.tags),
+            None => self.result_panel.set_annotation("", &[]),
+        }
+        self.image_scale = view.scale;
+        self.image_rotation = view.rotation;
+        self.restore_viewer_offset = Some(egui::vec2(view.scroll[0], view.scroll[1]));
+        self.viewer_fit_key = None;
+        if let Err(e) = self.recent_files.save() {
+            log::warn!("保存最近文件失败: {}", e);
+        }
+    }
+    
+    fn remember_view_state(&mut self) {
+        let Some(path) = &self.selected_image_path else {
+            return;
+        };
+        let view = ViewState {
+            scale: self.image_scale,
+            rotation: self.image_rotation,
+            scroll: [self.viewer_offset.x, self.viewer_offset.y],
+        };
+        self.recent_files.set_view(path, view);
+        if let Err(e) = self.recent_files.save() {
+            log::warn!("保存最近文件失败: {}", e);
+        }
+    }
+    
+    // 在后台下载网络图片，完成后通过消息通知
+    fn open_url(&mut self, url: String) {
+        let url = url.trim().to_string();
+        if !loader::is_image_url(&url) {
+            self.status_display.set_error("请输入以 http:// 或 https:// 开头的图片链接");
+            return;
+        }
+        
+        self.reset_state();
+        self.image_info = None;
+        self.state = AppState::Loading;
+        self.status_display.set_message("正在下载图片...");
+        
+        let tx = self.tx.clone();
+        let window = self.settings.gray_window;
+        self.rt.spawn_blocking(move || {
+            let start = Instant::now();
+            let message = match loader::download_image(&url).and_then(|bytes| loader::load_frames_from_memory(&bytes, window)) {
+                Ok(frames) => AppMessage::UrlImageLoaded {
+                    url,
+                    frames,
+                    load_ms: start.elapsed().as_secs_f64() * 1000.0,
+                },
+                Err(e) => AppMessage::UrlLoadFailed(e.to_string()),
+            };
+            let _ = tx.send(message);
+        });
+    }
+    
+    // 切换动画帧，run_ocr 为 true 时对该帧重新识别
+    fn select_frame(&mut self, index: usize, run_ocr: bool) {
+        let Some(frame) = self.animation_frames.get(index).cloned() else {
+            return;
+        };
+        self.current_frame = index;
+        self.current_image = Some(frame.clone());
+        self.result_panel.discard_edits();
+        self.image_display.set_image(frame.clone());
+        self.preprocess_preview.set_image(&frame);
+        
+        if run_ocr {
+            if let Some(path) = self.selected_image_path.clone() {
+                self.ocr_result = None;
+                self.start_ocr_processing(frame, path);
+            }
+        }
+    }
+    
+    // PageUp/PageDown 切换上一页/下一页，Home/End 跳到首页/末页：多帧图片在帧之间切换，
+    // 否则在已完成的批量结果之间切换。文本框获得焦点时不响应，以免打断输入
+    fn handle_page_keys(&mut self, ctx: &egui::Context) {
+        if ctx.wants_keyboard_input() || matches!(self.state, AppState::Loading | AppState::Processing) {
+            return;
+        }
+        let keys = [egui::Key::PageUp, egui::Key::PageDown, egui::Key::Home, egui::Key::End];
+        let Some(key) = ctx.input(|i| keys.into_iter().find(|&key| i.key_pressed(key))) else {
+            return;
+        };
+        let target = |current: Option<usize>, count: usize| match (key, current) {
+            (egui::Key::Home, _) => 0,
+            (egui::Key::End, _) => count - 1,
+            (egui::Key::PageUp, Some(index)) => index.saturating_sub(1),
+            (egui::Key::PageDown, Some(index)) => (index + 1).min(count - 1),
+            _ => 0,
+        };
+        
+        let frame_count = self.animation_frames.len();
+        if frame_count > 1 {
+            let index = target(Some(self.current_frame), frame_count);
+            if index != self.current_frame {
+                self.select_frame(index, true);
+            }
+            return;
+        }
+        let pages = self.batch.visible_results(&self.blank_page);
+        let page_count = pages.len();
+        if page_count < 2 {
+            return;
+        }
+        let current = pages.iter().position(|(path, _)| Some(path) == self.selected_image_path.as_ref());
+        let index = target(current, page_count);
+        if Some(index) != current {
+            self.show_batch_page(index);
+        }
+    }
+    
+    // 显示批量结果（删除与筛选后）中的一页：重新加载图片并显示已保存的识别结果，不重新识别
+    fn show_batch_page(&mut self, index: usize) {
+        let pages = self.batch.visible_results(&self.blank_page);
+        let page_count = pages.len();
+        let Some((path, result)) = pages.get(index).map(|&page| page.clone()) else {
+            return;
+        };
+        let image = match loader::load_frames(&path, self.settings.gray_window) {
+            Ok(mut frames) => frames.remove(0),
+            Err(e) => {
+                self.status_display.set_error(&format!("图片加载失败: {}", e));
+                return;
+            }
+        };
+        if self.show_image_viewer {
+            self.remember_view_state();
+        }
+        self.restore_view_state(&path);
+        self.image_info = metadata::read_image_info(&path).ok();
+        self.selected_image_path = Some(path.clone());
+        self.animation_frames.clear();
+        self.current_frame = 0;
+        self.current_image = Some(image.clone());
+        self.result_panel.discard_edits();
+        self.image_display.set_image(image.clone());
+        self.image_display.set_boxes(result.bounding_boxes.clone());
+        self.preprocess_preview.set_image(&image);
+        self.result_panel.set_export_defaults(self.settings.export.clone(), Some(path.clone()));
+        self.result_panel.set_result(result.clone());
+        self.ocr_result = Some(result);
+        self.state = AppState::Completed;
+        self.status_display.set_message(&format!(
+            "第 {}/{} 页: {}",
+            index + 1,
+            page_count,
+            path.file_name().unwrap_or_default().to_string_lossy()
+        ));
+    }
+    
+    fn render_frame_slider(&mut self, ui: &mut egui::Ui) {
+        let frame_count = self.animation_frames.len();
+        if frame_count <= 1 {
+            return;
+        }
+        
+        let mut frame = self.current_frame + 1;
+        ui.horizontal(|ui| {
+            ui.label("帧:");
+            let response = ui.add(egui::Slider::new(&mut frame, 1..=frame_count));
+            
+            if response.changed() {
+                self.select_frame(frame - 1, false);
+            }
+            // 拖动结束或直接点击时才重新识别，避免拖动过程中频繁触发
+            if response.drag_stopped() || (response.changed() && !response.dragged()) {
+                self.select_frame(frame - 1, true);
+            }
+        });
+    }
+    
+    // 使用已加载的图片重新识别，无需重新选择文件
+    fn rerun_ocr(&mut self) {
+        let Some(image) = self.current_image.clone() else {
+            return;
+        };
+        let path = self.selected_image_path.clone().unwrap_or_default();
+        
+        self.reset_state();
+        self.load_time_ms = None;
+        self.image_display.set_boxes(Vec::new());
+        self.start_ocr_processing(image, path);
+    }
+    
+    // 只检测文本区域并叠加显示，不进行识别
+    fn detect_layout(&mut self) {
+        let Some(image) = self.current_image.clone() else {
+            return;
+        };
+        self.status_display.set_message("正在检测版面...");
+        
+        let tx = self.tx.clone();
+        let ocr_engine = self.ocr_engine.clone();
+        let options = self.process_options.clone();
+        self.rt.spawn_blocking(move || {
+            let start = Instant::now();
+            let boxes = ocr_engine.detect_layout(image, &options);
+            let millis = start.elapsed().as_secs_f64() * 1000.0;
+            let _ = tx.send(AppMessage::LayoutDetected { boxes, millis });
+        });
+    }
+    
+    // 按当前预处理参数处理原尺寸图片后保存，处理与编码在后台线程进行
+    fn save_preprocessed_image(&mut self) {
+        let Some(image) = self.current_image.clone() else {
+            return;
+        };
+        let stem = self.selected_image_path
+            .as_deref()
+            .and_then(|path| path.file_stem())
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_else(|| "ocr".to_string());
+        let mut dialog = rfd::FileDialog::new()
+            .set_file_name(format!("{}_preprocessed.png", stem))
+            .add_filter("PNG 图片", &["png"]);
+        if let Some(directory) = self.settings.export.dialog_directory() {
+            dialog = dialog.set_directory(directory);
+        }
+        let Some(path) = dialog.save_file() else {
+            return;
+        };
+        self.status_display.set_message("正在保存预处理图像...");
+        
+        let tx = self.tx.clone();
+        let options = self.process_options.preprocess.clone();
+        self.rt.spawn_blocking(move || {
+            let processed = preprocess::preprocess(image, &options);
+            let result = processed
+                .save_with_format(&path, image::ImageFormat::Png)
+                .map(|()| path)
+                .map_err(|e| e.to_string());
+            tx.send(AppMessage::PreprocessedSaved(result));
+        });
+    }
+    
+    // 裁剪出单个检测框重新识别，用于修正检测框位置之后
+    fn recognize_region(&mut self, index: usize) {
+        let (Some(result), Some(image)) = (&self.ocr_result, &self.current_image) else {
+            return;
+        };
+        let Some(bbox) = result.bounding_boxes.get(index) else {
+            return;
+        };
+        let (width, height) = image.dimensions();
+        let x = bbox.x.min(width.saturating_sub(1));
+        let y = bbox.y.min(height.saturating_sub(1));
+        let region = image.crop_imm(x, y, bbox.width.min(width - x).max(1), bbox.height.min(height - y).max(1));
+        self.status_display.set_message("正在重新识别选区...");
+        
+        let tx = self.tx.clone();
+        let ocr_engine = self.ocr_engine.clone();
+        let options = self.process_options.clone();
+        self.rt.spawn(async move {
+            let message = match ocr_engine.process_image(region, std::path::Path::new(""), &options).await {
+                Ok(result) => AppMessage::RegionRecognized {
+                    index,
+                    text: result.text,
+                    confidence: result.confidence,
+                },
+                Err(e) => AppMessage::RegionFailed(e.to_string()),
+            };
+            let _ = tx.send(message);
+        });
+    }
+    
+    // 拖动修改检测框后同步到识别结果，导出时使用修改后的检测框
+    fn apply_box_edits(&mut self, boxes: Vec<BoundingBox>) {
+        if let Some(result) = &mut self.ocr_result {
+            result.bounding_boxes = boxes.clone();
+            self.result_panel.update_boxes(boxes);
+        }
+    }
+    
+    fn recover_from_error(&mut self, action: RecoveryAction) {
+        match action {
+            RecoveryAction::ChooseImage => {
+                self.handle_file_selection();
+                return;
             }
+            RecoveryAction::CheckEngine => {
+                self.reload_engine();
+                if !self.engine_available() {
+                    return;
+                }
+            }
+            RecoveryAction::ResetPreprocess => {
+                self.process_options.preprocess = PreprocessOptions::default();
+            }
+            RecoveryAction::Retry => {}
+        }
+        if let Some(image) = self.current_image.clone() {
+            let path = self.selected_image_path.clone().unwrap_or_default();
+            self.start_ocr_processing(image, path);
         }
     }
     
     fn start_ocr_processing(&mut self, image: DynamicImage, path: PathBuf) {
         self.state = AppState::Processing;
+        self.error_recovery = None;
+        self.ocr_progress = None;
         self.status_display.set_message("正在识别文字...");
         
         let tx = self.tx.clone();
         let ocr_engine = self.ocr_engine.clone();
+        let options = self.process_options.clone();
+        self.recognizing_options = Some(options.clone());
         
-        self.rt.spawn(async move {
-            match ocr_engine.process_image(image, &path).await {
-                Ok(result) => {
-                    let _ = tx.send(AppMessage::OcrCompleted(result));
-                }
-                Err(e) => {
-                    let _ = tx.send(AppMessage::OcrError(e.to_string()));
+        // 识别事件在后台线程中转换为界面消息，识别结束、发送端释放后线程随之退出
+        let (events, receiver) = std::sync::mpsc::channel();
+        self.rt.spawn_blocking(move || {
+            for event in receiver {
+                if let Some(message) = AppMessage::from_ocr_event(event) {
+                    tx.send(message);
                 }
             }
         });
+        self.rt.spawn(async move {
+            ocr_engine.process_image_with_events(image, &path, &options, events).await;
+        });
     }
     
     fn handle_messages(&mut self) {
@@ -139,19 +878,1552 @@ impl OcrApp {
                     self.reset_state();
                     self.handle_image_selected(path);
                 }
-                AppMessage::OcrCompleted(result) => {
-                    self.state = AppState::Completed;
-                    self.status_display.set_success(&format!(
-                        "识别完成！置信度: {:.1}%, 用时: {:.0}ms", 
-                        result.confidence * 100.0, 
-                        result.processing_time
-                    ));
-                    self.result_panel.set_result(result.clone());
-                    self.ocr_result = Some(result);
+                AppMessage::BatchQueued(paths) => {
+                    // 已在低内存模式下运行的批量任务直接追加
+                    let estimate = batch::estimate_memory(&paths);
+                    if estimate > batch::MEMORY_WARNING_BYTES && !(self.batch.is_active() && self.batch.is_low_memory()) {
+                        self.batch_memory_warning = Some((paths, estimate));
+                    } else {
+                        self.enqueue_batch(paths, false);
+                    }
+                }
+                AppMessage::UrlImageLoaded { url, frames, load_ms } => {
+                    let path = PathBuf::from(url);
+                    self.selected_image_path = Some(path.clone());
+                    self.load_time_ms = Some(load_ms);
+                    self.show_frames_and_recognize(frames, path);
+                }
+                AppMessage::UrlLoadFailed(error) => {
+                    self.state = AppState::Error(format!("无法加载网络图片: {}", error));
+                    self.status_display.set_error(&format!("图片下载失败: {}", error));
+                }
+                AppMessage::ClipboardImage(image) => {
+                    self.pending_clipboard_image = Some(image);
+                }
+                AppMessage::ClipboardError(error) => {
+                    self.clipboard_monitor = None;
+                    self.status_display.set_error(&error);
+                }
+                AppMessage::LiveText(text) => {
+                    let time = chrono::Local::now().format("%H:%M:%S").to_string();
+                    self.live_log.push(time, &text);
+                }
+                AppMessage::LiveError(error) => {
+                    self.live_capture = None;
+                    self.status_display.set_error(&format!("区域连续识别已停止: {}", error));
+                }
+                AppMessage::OcrCompleted(mut result) => {
+                    if std::mem::take(&mut self.clipboard_ocr_pending) {
+                        let time = chrono::Local::now().format("%H:%M:%S").to_string();
+                        self.clipboard_log.push((time, result.text.clone()));
+                    }
+                    if let Some(load_ms) = self.load_time_ms.take() {
+                        result.timings.insert(0, StageTiming::new("加载", load_ms));
+                    }
+                    let options = self.recognizing_options.take().unwrap_or_else(|| self.process_options.clone());
+                    self.append_result(&result);
+                    self.auto_save_batch_result(&result, &options);
+                    self.run_post_ocr_hook(&result);
+                    let batch_item = self.batch.current().is_some();
+                    self.batch.complete(result.clone(), options);
+                    self.image_display.set_boxes(result.bounding_boxes.clone());
+                    self.state = AppState::Completed;
+                    // 批量识别与空白页不提示，避免打断
+                    let blank = self.blank_page.is_blank(&result);
+                    self.low_quality = (!batch_item && !blank && self.quality_gate.is_low(result.confidence)).then_some(result.confidence);
+                    if blank {
+                        self.status_display.set_message(&format!(
+                            "空白页/无文字（用时: {:.0}ms）",
+                            result.processing_time
+                        ));
+                    } else {
+                        self.status_display.set_success(&format!(
+                            "识别完成！置信度: {:.1}%, 用时: {:.0}ms", 
+                            result.confidence * 100.0, 
+                            result.processing_time
+                        ));
+                    }
+                    self.warn_missing_glyphs(&result.text);
+                    self.result_panel.set_export_defaults(self.settings.export.clone(), self.selected_image_path.clone());
+                    let language = if result.detected_language.is_empty() {
+                        &self.process_options.tesseract.language
+                    } else {
+                        &result.detected_language
+                    };
+                    let direction = TextDirection::for_language(language);
+                    self.result_panel.set_text_direction(direction);
+                    self.result_panel.set_language(language);
+                    self.image_display.set_text_direction(direction);
+                    self.result_panel.set_result(result.clone());
+                    self.ocr_result = Some(result);
+                    self.refresh_evaluation();
+                    if batch_item && self.batch.is_low_memory() {
+                        self.release_batch_image();
+                    }
+                }
+                AppMessage::LayoutDetected { boxes, millis } => {
+                    self.status_display.set_success(&format!(
+                        "检测到 {} 个文本区域，用时: {:.0}ms",
+                        boxes.len(),
+                        millis
+                    ));
+                    self.image_display.set_boxes(boxes);
+                }
+                AppMessage::RegionRecognized { index, text, confidence } => {
+                    let Some(result) = &mut self.ocr_result else {
+                        continue;
+                    };
+                    let Some(bbox) = result.bounding_boxes.get_mut(index) else {
+                        continue;
+                    };
+                    bbox.text = text.trim().to_string();
+                    bbox.language = tessdata::box_language(&bbox.text).map(str::to_string);
+                    bbox.confidence = confidence;
+                    bbox.edited = true;
+                    self.status_display.set_success(&format!("选区重新识别完成: {}", bbox.text));
+                    self.image_display.update_box(index, bbox.clone());
+                    self.result_panel.update_boxes(result.bounding_boxes.clone());
+                }
+                AppMessage::RegionFailed(error) => {
+                    self.status_display.set_error(&format!("选区识别失败: {}", error));
+                }
+                AppMessage::OcrProgress(fraction) => {
+                    self.ocr_progress = Some(fraction);
+                }
+                AppMessage::HookFailed(error) => {
+                    self.status_display.set_error(&format!("识别后命令执行失败: {}", error));
+                }
+                AppMessage::PreprocessedSaved(Ok(path)) => {
+                    self.status_display.set_success(&format!("预处理图像已保存: {}", path.display()));
+                }
+                AppMessage::PreprocessedSaved(Err(error)) => {
+                    self.status_display.set_error(&format!("预处理图像保存失败: {}", error));
+                }
+                AppMessage::OcrError(error) => {
+                    self.clipboard_ocr_pending = false;
+                    self.batch.fail(error.to_string());
+                    let (title, action) = match &error {
+                        ocr::OcrError::NoEngine(_) => ("没有可用的识别引擎", RecoveryAction::CheckEngine),
+                        ocr::OcrError::ImageLoad(_) => ("这张图片无法识别，请换一张图片", RecoveryAction::ChooseImage),
+                        ocr::OcrError::Preprocess(_) => ("预处理后没有可识别的内容", RecoveryAction::ResetPreprocess),
+                        ocr::OcrError::Timeout(_) => ("识别超时，请重试", RecoveryAction::Retry),
+                        ocr::OcrError::Cancelled => ("识别已取消", RecoveryAction::Retry),
+                        ocr::OcrError::Recognize(_) => ("识别失败，请重试", RecoveryAction::Retry),
+                    };
+                    self.error_recovery = Some((title, action));
+                    self.state = AppState::Error(error.to_string());
+                    self.status_display.set_error(&format!("识别失败: {}", error));
+                }
+            }
+        }
+    }
+    
+    fn append_result(&mut self, result: &OcrResult) {
+        let Some(target) = &self.append_target else {
+            return;
+        };
+        if self.blank_page.skip_in_exports && self.blank_page.is_blank(result) {
+            return;
+        }
+        let name = self.selected_image_path
+            .as_ref()
+            .and_then(|path| path.file_name())
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        if let Err(e) = batch::append_to_file(target, &name, result) {
+            self.status_display.set_error(&format!("追加到文件失败: {}", e));
+            self.append_target = None;
+        }
+    }
+    
+    // 批量识别时按文件名模板为每个文件保存识别文本
+    fn auto_save_batch_result(&mut self, result: &OcrResult, options: &ProcessOptions) {
+        if !self.settings.export.batch_auto_save {
+            return;
+        }
+        let Some(source) = self.batch.current() else {
+            return;
+        };
+        if self.blank_page.skip_in_exports && self.blank_page.is_blank(result) {
+            return;
+        }
+        let target = self.settings.export.auto_save_path(source, "txt");
+        if let Err(e) = std::fs::write(&target, self.export_text(result, Some(source))) {
+            self.status_display.set_error(&format!("自动保存失败 {}: {}", target.display(), e));
+            return;
+        }
+        if !self.settings.export.batch_manifest {
+            return;
+        }
+        // 每保存一个文件就合并进所在目录的清单；无法识别的同名清单不覆盖
+        let entry = ManifestEntry::new(&target, source, result, options, &batch::manifest_time());
+        let manifest = Manifest { version: batch::MANIFEST_VERSION, entries: vec![entry] };
+        let directory = target.parent().map(Path::to_path_buf).unwrap_or_default();
+        if let Err(e) = manifest.write(&directory, false) {
+            log::warn!("写入 {} 失败: {}", directory.join(batch::MANIFEST_FILE).display(), e);
+        }
+    }
+    
+    // 识别成功后在后台执行配置的命令，结果先按所选格式写入临时文件
+    fn run_post_ocr_hook(&mut self, result: &OcrResult) {
+        let hook = &self.settings.post_ocr_hook;
+        if !hook.enabled || hook.command.trim().is_empty() {
+            return;
+        }
+        let source = self.batch.current().or(self.selected_image_path.as_ref()).cloned();
+        let metadata = self.export_metadata(result, source.as_deref());
+        let content = export::render(result, hook.format, metadata.width, metadata.height, Some(self.effective_dpi()));
+        let result_path = match scratch::unique_path("result", hook.format.extension()) {
+            Ok(path) => path,
+            Err(e) => {
+                self.status_display.set_error(&format!("识别后命令执行失败: {}", e));
+                return;
+            }
+        };
+        let command = hook::expand(&hook.command, source.as_deref(), &result_path);
+        
+        let tx = self.tx.clone();
+        self.rt.spawn_blocking(move || {
+            let outcome = std::fs::write(&result_path, content)
+                .map_err(anyhow::Error::from)
+                .and_then(|_| hook::run(&command));
+            if let Err(e) = outcome {
+                log::warn!("识别后命令执行失败: {}", e);
+                tx.send(AppMessage::HookFailed(e.to_string()));
+            }
+        });
+    }
+    
+    fn toggle_append_mode(&mut self) {
+        if self.append_target.take().is_some() {
+            self.status_display.set_message("已停止追加到文件");
+            return;
+        }
+        
+        if let Some(path) = rfd::FileDialog::new()
+            .set_file_name("ocr_results.txt")
+            .add_filter("文本文件", &["txt"])
+            .save_file()
+        {
+            self.status_display.set_message(&format!("识别结果将追加到: {}", path.display()));
+            self.append_target = Some(path);
+        }
+    }
+    
+    fn toggle_clipboard_monitor(&mut self) {
+        if self.clipboard_monitor.take().is_some() {
+            self.status_display.set_message("已停止剪贴板监视");
+            return;
+        }
+        
+        let tx = self.tx.clone();
+        self.clipboard_monitor = Some(ClipboardMonitor::start(move |event| {
+            let message = match event {
+                ClipboardEvent::Image(image) => AppMessage::ClipboardImage(image),
+                ClipboardEvent::Error(error) => AppMessage::ClipboardError(error),
+            };
+            let _ = tx.send(message);
+        }));
+        self.status_display.set_message("正在监视剪贴板，复制图片后将自动识别");
+    }
+    
+    // 区域连续识别在独立线程中截图并等待识别结果，不影响当前打开的图片
+    fn toggle_live_capture(&mut self) {
+        if self.live_capture.take().is_some() {
+            self.status_display.set_message("已停止区域连续识别");
+            return;
+        }
+        
+        let options = self.settings.live_capture.clone();
+        let handle = self.rt.handle().clone();
+        let ocr_engine = self.ocr_engine.clone();
+        let process_options = self.process_options.clone();
+        let tx = self.tx.clone();
+        self.live_capture = Some(LiveCapture::start(
+            options.clone(),
+            move |image| {
+                handle
+                    .block_on(ocr_engine.process_image(image, std::path::Path::new(""), &process_options))
+                    .map(|result| result.text)
+                    .map_err(anyhow::Error::from)
+            },
+            move |event| {
+                let message = match event {
+                    LiveEvent::Text(text) => AppMessage::LiveText(text),
+                    LiveEvent::Error(error) => AppMessage::LiveError(error),
+                };
+                let _ = tx.send(message);
+            },
+        ));
+        self.status_display.set_message(&format!(
+            "正在连续识别屏幕区域 {}×{}+{}+{}，每 {:.1} 秒一次",
+            options.width, options.height, options.x, options.y, options.interval_secs
+        ));
+    }
+    
+    // 空闲时识别剪贴板中的新图片
+    fn drive_clipboard(&mut self) {
+        if matches!(self.state, AppState::Loading | AppState::Processing) {
+            return;
+        }
+        let Some(image) = self.pending_clipboard_image.take() else {
+            return;
+        };
+        
+        let path = PathBuf::from("剪贴板图片");
+        self.reset_state();
+        self.image_info = None;
+        self.selected_image_path = Some(path.clone());
+        self.load_time_ms = None;
+        self.clipboard_ocr_pending = true;
+        self.show_frames_and_recognize(vec![image], path);
+    }
+    
+    fn render_capture_logs(&mut self, ui: &mut egui::Ui) {
+        if render_text_log(ui, "📋 剪贴板识别记录", "clipboard_log", &self.clipboard_log) {
+            self.clipboard_log.clear();
+        }
+        if render_text_log(ui, "🎬 区域连续识别记录", "live_log", self.live_log.entries()) {
+            self.live_log.clear();
+        }
+    }
+    
+    fn enqueue_batch(&mut self, paths: Vec<PathBuf>, low_memory: bool) {
+        let requested = paths.len();
+        let added = self.batch.enqueue(paths);
+        if low_memory {
+            self.batch.set_low_memory(true);
+        }
+        if added < requested {
+            self.status_display.set_message(&format!(
+                "已添加 {} 张图片到队列（跳过 {} 张重复图片）",
+                added,
+                requested - added
+            ));
+        } else {
+            self.status_display.set_message(&format!("已添加 {} 张图片到队列", added));
+        }
+    }
+    
+    // 低内存模式下识别完成后只保留结果与缩略图
+    fn release_batch_image(&mut self) {
+        self.current_image = None;
+        self.animation_frames.clear();
+        self.current_frame = 0;
+        self.preprocess_preview.clear();
+        self.image_display.shrink_to_thumbnail();
+    }
+    
+    fn render_batch_memory_warning(&mut self, ctx: &egui::Context) {
+        let Some((paths, estimate)) = &self.batch_memory_warning else {
+            return;
+        };
+        let mut choice = None;
+        egui::Window::new("⚠ 批量识别内存占用较大")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "{} 张图片全部解码后预计占用约 {:.1} GB 内存，可能导致内存不足。",
+                    paths.len(),
+                    *estimate as f64 / (1024.0 * 1024.0 * 1024.0)
+                ));
+                ui.label("低内存模式会在每张图片识别完成后释放原图与纹理，只保留识别结果和缩略图。");
+                ui.add_space(8.0);
+                ui.horizontal(|ui| {
+                    if ui.button("低内存模式").clicked() {
+                        choice = Some(Some(true));
+                    }
+                    if ui.button("照常处理").clicked() {
+                        choice = Some(Some(false));
+                    }
+                    if ui.button("取消").clicked() {
+                        choice = Some(None);
+                    }
+                });
+            });
+        
+        if let Some(choice) = choice {
+            let (paths, _) = self.batch_memory_warning.take().unwrap_or_default();
+            match choice {
+                Some(low_memory) => self.enqueue_batch(paths, low_memory),
+                None => self.status_display.set_message("已取消批量识别"),
+            }
+        }
+    }
+    
+    // 当前没有识别任务时从队列取出下一张图片
+    fn drive_batch(&mut self) {
+        // 队列刚处理完时自动弹出汇总
+        let active = self.batch.is_active();
+        if self.batch_was_active && !active {
+            self.open_batch_summary();
+        }
+        self.batch_was_active = active;
+        
+        if matches!(self.state, AppState::Loading | AppState::Processing) {
+            return;
+        }
+        if let Some(path) = self.batch.next() {
+            self.reset_state();
+            self.handle_image_selected(path);
+        }
+    }
+    
+    fn toggle_batch_pause(&mut self) {
+        let paused = !self.batch.is_paused();
+        self.batch.set_paused(paused);
+        if paused {
+            let remaining = self.batch.pending().len();
+            self.status_display.set_message(&format!("批量识别已暂停，队列中还有 {} 张图片", remaining));
+        } else {
+            self.status_display.set_message("批量识别已继续");
+        }
+    }
+    
+    // 完成后重新打开的汇总包含重试的结果
+    fn retry_failed_batch(&mut self) {
+        let count = self.batch.retry_failed();
+        if count > 0 {
+            self.batch_summary = None;
+            self.status_display.set_message(&format!("已将 {} 张失败的图片重新加入队列", count));
+        }
+    }
+    
+    fn open_batch_summary(&mut self) {
+        let mut summary = BatchSummary::build(&self.batch, &self.blank_page);
+        let (column, ascending) = self.summary_sort;
+        summary.sort_by(column, ascending);
+        self.batch_summary = Some(summary);
+    }
+    
+    fn render_batch_queue(&mut self, ui: &mut egui::Ui) {
+        if !self.batch.is_active() {
+            self.render_batch_results(ui);
+            return;
+        }
+        
+        ui.add_space(8.0);
+        ui.separator();
+        
+        let mut progress = ProgressIndicator::new(self.batch.total(), "批量识别进度".to_string());
+        progress.set_progress(self.batch.finished_count());
+        progress.set_paused(self.batch.is_paused());
+        progress.show(ui);
+        
+        if let Some(current) = self.batch.current() {
+            ui.horizontal(|ui| {
+                ui.spinner();
+                ui.label(current.file_name().unwrap_or_default().to_string_lossy());
+            });
+        }
+        
+        let mut removed = None;
+        let mut moved = None;
+        
+        egui::ScrollArea::vertical()
+            .id_salt("batch_queue_scroll")
+            .max_height(200.0)
+            .show(ui, |ui| {
+                for (index, path) in self.batch.pending().iter().enumerate() {
+                    let row = ui.horizontal(|ui| {
+                        // 拖动手柄，拖到其他项上以调整顺序
+                        ui.dnd_drag_source(egui::Id::new(("batch_item", index)), index, |ui| {
+                            ui.label("☰");
+                        });
+                        ui.label(path.file_name().unwrap_or_default().to_string_lossy());
+                        
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            if ui.small_button("✕").on_hover_text("移出队列").clicked() {
+                                removed = Some(index);
+                            }
+                        });
+                    }).response;
+                    
+                    if row.dnd_hover_payload::<usize>().is_some() {
+                        ui.painter().hline(row.rect.x_range(), row.rect.top(), ui.visuals().selection.stroke);
+                    }
+                    if let Some(from) = row.dnd_release_payload::<usize>() {
+                        moved = Some((*from, index));
+                    }
+                }
+            });
+        
+        if let Some(index) = removed {
+            self.batch.remove(index);
+        }
+        if let Some((from, to)) = moved {
+            self.batch.move_item(from, to);
+        }
+        
+        if !self.batch.pending().is_empty() && ui.button("🗑 清空队列").clicked() {
+            self.batch.clear();
+        }
+    }
+    
+    // 批量识别完成后的合并导出
+    fn render_batch_results(&mut self, ui: &mut egui::Ui) {
+        if self.batch.finished_count() == 0 {
+            return;
+        }
+        
+        ui.add_space(8.0);
+        ui.separator();
+        ui.horizontal(|ui| {
+            if ui.button("📊 批量汇总").clicked() {
+                self.open_batch_summary();
+            }
+            let failed = self.batch.failed_count();
+            if failed > 0 && ui.button(format!("🔁 重试失败项（{}）", failed))
+                .on_hover_text("只重新识别失败的图片，成功的结果保留；可先在设置中调整预处理或引擎")
+                .clicked()
+            {
+                self.retry_failed_batch();
+            }
+            let page_count = self.batch.visible_results(&self.blank_page).len();
+            if page_count > 0 && ui.button("💾 逐个导出结果").on_hover_text("按导出命名设置为每张图片保存识别文本").clicked() {
+                self.open_export_plan();
+            }
+            if page_count > 1 {
+                ui.weak("PageUp/PageDown 翻页，Home/End 到首页/末页");
+            }
+        });
+        self.render_batch_result_list(ui);
+        
+        let page_count = self.batch.visible_results(&self.blank_page).len();
+        if page_count < 2 {
+            return;
+        }
+        
+        ui.strong(format!("📚 批量结果（{} 页）", page_count));
+        
+        let blank_pages: Vec<String> = self.batch.visible_results(&self.blank_page)
+            .into_iter()
+            .filter(|(_, result)| self.blank_page.is_blank(result))
+            .map(|(path, _)| path.file_name().unwrap_or_default().to_string_lossy().to_string())
+            .collect();
+        if !blank_pages.is_empty() {
+            ui.collapsing(format!("其中 {} 页为空白页/无文字", blank_pages.len()), |ui| {
+                for name in &blank_pages {
+                    ui.weak(name);
+                }
+            });
+        }
+        
+        ui.checkbox(&mut self.strip_headers_footers, "去除页眉页脚")
+            .on_hover_text("去除在多数页面相同位置重复出现的行");
+        
+        if self.strip_headers_footers {
+            let repeated = self.repeated_lines().to_vec();
+            if repeated.is_empty() {
+                ui.weak("未检测到页眉或页脚");
+            } else {
+                ui.collapsing(format!("将去除 {} 种重复行", repeated.len()), |ui| {
+                    for line in &repeated {
+                        ui.label(format!("“{}”（{} 页）", line.example, line.page_count));
+                    }
+                });
+            }
+        }
+        
+        if ui.button("📄 导出合并文本").clicked() {
+            self.export_merged_text();
+        }
+    }
+    
+    // 批量结果列表：逐项删除或按状态筛选，删除可撤销；翻页、导出与汇总只使用列表中显示的项
+    fn render_batch_result_list(&mut self, ui: &mut egui::Ui) {
+        let mut filter = self.batch.filter();
+        let mut deleted = None;
+        let mut shown = None;
+        let mut clicked = None;
+        let mut export_selected = None;
+        let mut undo = false;
+        let modifiers = ui.input(|i| i.modifiers);
+        let pages = self.batch.visible_results(&self.blank_page);
+        let items: Vec<&std::path::Path> = pages.iter().map(|(path, _)| path.as_path()).collect();
+        let failed = self.batch.visible_failed();
+        let deleted_count = self.batch.deleted_count();
+        let name = |path: &PathBuf| path.file_name().unwrap_or_default().to_string_lossy().to_string();
+        
+        ui.collapsing(format!("🗂 结果列表（{} 项）", pages.len() + failed.len()), |ui| {
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut filter.hide_blank, "隐藏空白页");
+                ui.checkbox(&mut filter.hide_failed, "隐藏失败项");
+                if ui.add_enabled(deleted_count > 0, egui::Button::new(format!("↩ 撤销删除（{}）", deleted_count)))
+                    .on_hover_text("恢复最近删除的一项")
+                    .clicked()
+                {
+                    undo = true;
+                }
+            });
+            // Ctrl/Shift 点击多选后复制或导出所选页面的合并文本
+            if !self.batch_selection.is_empty() {
+                ui.horizontal(|ui| {
+                    let selected_text = || -> String {
+                        pages.iter()
+                            .filter(|(path, _)| self.batch_selection.contains(path))
+                            .map(|(path, result)| batch::append_entry(&name(path), result))
+                            .collect()
+                    };
+                    ui.label(format!("已选 {} 项", self.batch_selection.len()));
+                    if ui.button("📋 复制所选").on_hover_text("以文件名分隔行合并所选页面的文本").clicked() {
+                        ui.ctx().copy_text(selected_text());
+                    }
+                    if ui.button("💾 导出所选…").clicked() {
+                        export_selected = Some(selected_text());
+                    }
+                    if ui.button("清除选择").clicked() {
+                        clicked = Some(None);
+                    }
+                });
+            } else {
+                ui.weak("按住 Ctrl 或 Shift 点击可多选");
+            }
+            egui::ScrollArea::vertical()
+                .id_salt("batch_result_list")
+                .max_height(200.0)
+                .show(ui, |ui| {
+                    for (index, (path, result)) in pages.iter().enumerate() {
+                        ui.horizontal(|ui| {
+                            let selected = if self.batch_selection.is_empty() {
+                                self.selected_image_path.as_ref() == Some(path)
+                            } else {
+                                self.batch_selection.contains(path)
+                            };
+                            if ui.selectable_label(selected, name(path))
+                                .on_hover_text(format!("置信度 {:.1}%，点击查看，Ctrl/Shift 点击多选", result.confidence * 100.0))
+                                .clicked()
+                            {
+                                clicked = Some(Some(index));
+                            }
+                            if self.blank_page.is_blank(result) {
+                                ui.weak("空白页");
+                            }
+                            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                                if ui.small_button("✕").on_hover_text("从结果中删除（可撤销）").clicked() {
+                                    deleted = Some(path.clone());
+                                }
+                            });
+                        });
+                    }
+                    let duplicate_groups = self.batch.duplicate_groups();
+                    if !duplicate_groups.is_empty() {
+                        ui.collapsing(format!("已跳过 {} 个重复页面", self.batch.duplicate_count()), |ui| {
+                            for (original, duplicates) in duplicate_groups {
+                                let names: Vec<String> = duplicates.into_iter().map(name).collect();
+                                ui.label(format!("{} ≈ {}", name(original), names.join("、")));
+                            }
+                        });
+                    }
+                    for (path, error) in &failed {
+                        ui.horizontal(|ui| {
+                            ui.label(name(path));
+                            ui.colored_label(egui::Color32::from_rgb(220, 20, 60), "失败").on_hover_text(error);
+                            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                                if ui.small_button("✕").on_hover_text("从结果中删除（可撤销）").clicked() {
+                                    deleted = Some(path.clone());
+                                }
+                            });
+                        });
+                    }
+                });
+        });
+        
+        match clicked {
+            Some(Some(index)) => {
+                self.batch_selection.click(&items, index, modifiers.command, modifiers.shift);
+                // 普通点击同时显示该页
+                if !modifiers.command && !modifiers.shift {
+                    shown = Some(index);
+                }
+            }
+            Some(None) => self.batch_selection.clear(),
+            None => {}
+        }
+        if let Some(text) = export_selected {
+            self.export_selected_pages(text);
+        }
+        
+        let changed = filter != self.batch.filter() || deleted.is_some() || undo;
+        self.batch.set_filter(filter);
+        if let Some(path) = deleted {
+            self.batch.delete(&path);
+            self.status_display.set_message(&format!("已从结果中删除 {}，可撤销", name(&path)));
+        }
+        if undo {
+            if let Some(path) = self.batch.undo_delete() {
+                self.status_display.set_message(&format!("已恢复 {}", name(&path)));
+            }
+        }
+        if changed {
+            self.repeated_lines_cache = None;
+            if self.batch_summary.is_some() {
+                self.open_batch_summary();
+            }
+        } else if let Some(index) = shown {
+            self.show_batch_page(index);
+        }
+    }
+    
+    fn open_export_plan(&mut self) {
+        let skip_blank = self.blank_page.skip_in_exports;
+        let items = self.batch.visible_results(&self.blank_page)
+            .into_iter()
+            .map(|(source, result)| {
+                let target = self.settings.export.auto_save_path(source, "txt");
+                let content = (!(skip_blank && self.blank_page.is_blank(result)))
+                    .then(|| self.export_text(result, Some(source)));
+                (source.clone(), target, content)
+            })
+            .collect();
+        let failed: Vec<(PathBuf, String)> = self.batch.visible_failed().into_iter().cloned().collect();
+        self.export_plan = Some(ExportPlan::build(items, &failed, false));
+    }
+    
+    fn render_export_plan(&mut self, ctx: &egui::Context) {
+        let Some(plan) = &mut self.export_plan else {
+            return;
+        };
+        let mut open = true;
+        let mut confirmed = false;
+        let mut cancelled = false;
+        let mut manifest_changed = false;
+        
+        egui::Window::new("💾 导出预览")
+            .open(&mut open)
+            .collapsible(false)
+            .default_width(520.0)
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "将写入 {} 个文件，共约 {:.1} KB；跳过 {} 个",
+                    plan.write_count(),
+                    plan.total_bytes() as f64 / 1024.0,
+                    plan.skipped_count()
+                ));
+                let overwrites = plan.count(ExportAction::Overwrite);
+                if overwrites > 0 {
+                    ui.colored_label(egui::Color32::from_rgb(230, 150, 30), format!("⚠ 其中 {} 个文件已存在，将被覆盖", overwrites));
+                }
+                for action in [
+                    ExportAction::SkipBlank,
+                    ExportAction::SkipFailed,
+                    ExportAction::SkipExisting,
+                    ExportAction::SkipDuplicate,
+                ] {
+                    let count = plan.count(action);
+                    if count > 0 {
+                        ui.weak(format!("{}: {} 个", action.label(), count));
+                    }
+                }
+                
+                let mut overwrite = plan.overwrite();
+                if ui.checkbox(&mut overwrite, "覆盖已存在的文件").changed() {
+                    plan.set_overwrite(overwrite);
+                }
+                manifest_changed = ui.checkbox(&mut self.settings.export.batch_manifest, format!("同时写入 {}", batch::MANIFEST_FILE))
+                    .on_hover_text("在结果文件所在目录记录每个文件对应的源图片、引擎、语言、置信度、导出时间与预处理设置")
+                    .changed();
+                
+                ui.collapsing("文件明细", |ui| {
+                    egui::ScrollArea::vertical().max_height(240.0).show(ui, |ui| {
+                        egui::Grid::new("export_plan_grid").striped(true).show(ui, |ui| {
+                            for entry in &plan.entries {
+                                ui.label(entry.source.file_name().unwrap_or_default().to_string_lossy());
+                                match &entry.target {
+                                    Some(target) => ui.label(target.display().to_string()),
+                                    None => ui.weak("-"),
+                                };
+                                if entry.action.writes() {
+                                    ui.label(entry.action.label());
+                                } else {
+                                    ui.weak(entry.action.label());
+                                }
+                                ui.end_row();
+                            }
+                        });
+                    });
+                });
+                
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui.add_enabled(plan.write_count() > 0, egui::Button::new("确认导出")).clicked() {
+                        confirmed = true;
+                    }
+                    if ui.button("取消").clicked() {
+                        cancelled = true;
+                    }
+                });
+            });
+        
+        if confirmed {
+            let (written, errors) = plan.execute();
+            if self.settings.export.batch_manifest {
+                self.write_manifests(&errors);
+            }
+            match errors.first() {
+                None => self.status_display.set_success(&format!("已导出 {} 个文件", written)),
+                Some((path, error)) => self.status_display.set_error(&format!(
+                    "已导出 {} 个文件，{} 个失败（{}: {}）",
+                    written,
+                    errors.len(),
+                    path.display(),
+                    error
+                )),
+            }
+        }
+        if manifest_changed {
+            self.save_settings();
+        }
+        if confirmed || cancelled || !open {
+            self.export_plan = None;
+        }
+    }
+    
+    // 按已写入的结果文件生成清单；清单写入失败只提示，不影响已导出的文件
+    fn write_manifests(&mut self, errors: &[(PathBuf, String)]) {
+        let Some(plan) = &self.export_plan else {
+            return;
+        };
+        for (directory, manifest) in plan.manifests(errors, &self.batch) {
+            if let Err(e) = manifest.write(&directory, plan.overwrite()) {
+                log::warn!("写入 {} 失败: {}", directory.join(batch::MANIFEST_FILE).display(), e);
+            }
+        }
+    }
+    
+    fn repeated_lines(&mut self) -> &[RepeatedLine] {
+        let visible = self.batch.visible_results(&self.blank_page);
+        let page_count = visible.len();
+        if !matches!(&self.repeated_lines_cache, Some((count, _)) if *count == page_count) {
+            let pages: Vec<&OcrResult> = visible.iter().map(|(_, r)| r).collect();
+            self.repeated_lines_cache = Some((page_count, batch::detect_repeated_lines(&pages)));
+        }
+        self.repeated_lines_cache.as_ref().map(|(_, lines)| lines.as_slice()).unwrap_or_default()
+    }
+    
+    fn export_merged_text(&mut self) {
+        let removed = if self.strip_headers_footers {
+            self.repeated_lines().to_vec()
+        } else {
+            Vec::new()
+        };
+        let pages: Vec<&OcrResult> = self.batch.visible_results(&self.blank_page)
+            .into_iter()
+            .map(|(_, r)| r)
+            .filter(|r| !(self.blank_page.skip_in_exports && self.blank_page.is_blank(r)))
+            .collect();
+        let merged = batch::merge_text(&pages, &removed);
+        
+        if let Some(path) = rfd::FileDialog::new()
+            .set_file_name("ocr_merged.txt")
+            .add_filter("文本文件", &["txt"])
+            .save_file()
+        {
+            match std::fs::write(&path, merged) {
+                Ok(()) => self.status_display.set_success(&format!("合并文本已保存: {}", path.display())),
+                Err(e) => self.status_display.set_error(&format!("合并文本保存失败: {}", e)),
+            }
+        }
+    }
+    
+    fn export_selected_pages(&mut self, text: String) {
+        if let Some(path) = rfd::FileDialog::new()
+            .set_file_name("ocr_selected.txt")
+            .add_filter("文本文件", &["txt"])
+            .save_file()
+        {
+            match std::fs::write(&path, text) {
+                Ok(()) => self.status_display.set_success(&format!("所选页面已保存: {}", path.display())),
+                Err(e) => self.status_display.set_error(&format!("所选页面保存失败: {}", e)),
+            }
+        }
+    }
+    
+    fn choose_ground_truth(&mut self) {
+        let Some(path) = rfd::FileDialog::new().add_filter("文本文件", &["txt"]).pick_file() else {
+            return;
+        };
+        match evaluate::read_ground_truth(&path) {
+            Ok(truth) => {
+                self.evaluation = Some((path, truth, Evaluation::default()));
+                self.refresh_evaluation();
+            }
+            Err(e) => self.status_display.set_error(&format!("无法读取标准文本 {}: {}", path.display(), e)),
+        }
+    }
+    
+    // 按当前识别结果重新计算；新的识别完成时自动调用，修改后处理选项后可手动重新计算
+    fn refresh_evaluation(&mut self) {
+        if let (Some((_, truth, evaluation)), Some(result)) = (&mut self.evaluation, &self.ocr_result) {
+            *evaluation = evaluate::evaluate(truth, &result.text);
+        }
+    }
+    
+    fn render_evaluation(&mut self, ctx: &egui::Context) {
+        let Some((path, _, evaluation)) = &self.evaluation else {
+            return;
+        };
+        let mut open = true;
+        let mut refresh = false;
+        let mut choose = false;
+        
+        egui::Window::new("📏 识别准确率评估")
+            .open(&mut open)
+            .default_width(520.0)
+            .show(ctx, |ui| {
+                ui.label(format!("标准文本: {}", path.display()));
+                ui.label(format!(
+                    "字符错误率 CER: {:.2}%（{} / {} 字符）",
+                    evaluation.cer * 100.0, evaluation.char_errors, evaluation.chars
+                ));
+                ui.label(format!(
+                    "词错误率 WER: {:.2}%（{} / {} 词）",
+                    evaluation.wer * 100.0, evaluation.word_errors, evaluation.words
+                ));
+                ui.horizontal(|ui| {
+                    if ui.button("重新计算").on_hover_text("与当前识别结果重新比较").clicked() {
+                        refresh = true;
+                    }
+                    if ui.button("更换标准文本").clicked() {
+                        choose = true;
+                    }
+                });
+                ui.separator();
+                
+                // 红色删除线为识别结果漏掉的内容，绿色为多出的内容
+                let mut job = egui::text::LayoutJob::default();
+                let font = egui::FontId::monospace(14.0);
+                for (tag, text) in &evaluation.segments {
+                    let format = match tag {
+                        similar::ChangeTag::Equal => egui::TextFormat::simple(font.clone(), ui.visuals().text_color()),
+                        similar::ChangeTag::Delete => egui::TextFormat {
+                            strikethrough: egui::Stroke::new(1.0, egui::Color32::from_rgb(220, 20, 60)),
+                            background: egui::Color32::from_rgba_unmultiplied(220, 20, 60, 40),
+                            ..egui::TextFormat::simple(font.clone(), egui::Color32::from_rgb(220, 20, 60))
+                        },
+                        similar::ChangeTag::Insert => egui::TextFormat {
+                            background: egui::Color32::from_rgba_unmultiplied(34, 139, 34, 40),
+                            ..egui::TextFormat::simple(font.clone(), egui::Color32::from_rgb(34, 139, 34))
+                        },
+                    };
+                    job.append(text, 0.0, format);
+                }
+                egui::ScrollArea::vertical().max_height(360.0).show(ui, |ui| {
+                    ui.label(job);
+                });
+            });
+        
+        if !open {
+            self.evaluation = None;
+        } else if choose {
+            self.choose_ground_truth();
+        } else if refresh {
+            self.refresh_evaluation();
+        }
+    }
+    
+    fn render_batch_summary(&mut self, ctx: &egui::Context) {
+        let Some(summary) = &mut self.batch_summary else {
+            return;
+        };
+        let mut open = true;
+        let mut export = None;
+        let mut retry = false;
+        
+        egui::Window::new("📊 批量识别汇总")
+            .open(&mut open)
+            .default_width(560.0)
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "共 {} 张：成功 {}，空白页 {}，失败 {}",
+                    summary.total, summary.succeeded, summary.blank, summary.failed
+                ));
+                if let (Some(avg), Some(min), Some(max)) =
+                    (summary.average_confidence, summary.min_confidence, summary.max_confidence)
+                {
+                    ui.label(format!(
+                        "置信度：平均 {:.1}%，最低 {:.1}%，最高 {:.1}%",
+                        avg * 100.0, min * 100.0, max * 100.0
+                    ));
+                }
+                ui.label(format!("总识别用时: {:.1}s", summary.total_time_ms / 1000.0));
+                if summary.hidden > 0 {
+                    ui.weak(format!("另有 {} 项已从结果列表中删除或被筛选隐藏，不计入统计与导出", summary.hidden));
+                }
+                if summary.duplicates > 0 {
+                    ui.weak(format!("跳过重复页面 {} 张（未识别，不计入统计与导出）", summary.duplicates));
+                }
+                
+                ui.horizontal(|ui| {
+                    if ui.button("导出 CSV").clicked() {
+                        export = Some("csv");
+                    }
+                    if ui.button("导出 JSON").clicked() {
+                        export = Some("json");
+                    }
+                    if summary.failed > 0 {
+                        retry = ui.button(format!("🔁 重试失败项（{}）", summary.failed))
+                            .on_hover_text("只重新识别失败的图片；可先在设置中调整预处理或引擎")
+                            .clicked();
+                    }
+                });
+                ui.separator();
+                
+                egui::ScrollArea::vertical().max_height(360.0).show(ui, |ui| {
+                    egui::Grid::new("batch_summary_grid").striped(true).show(ui, |ui| {
+                        // 点击表头排序，再次点击切换升降序
+                        for (column, title) in [
+                            (SummaryColumn::File, "文件"),
+                            (SummaryColumn::Status, "状态"),
+                            (SummaryColumn::Confidence, "置信度"),
+                            (SummaryColumn::Time, "用时"),
+                            (SummaryColumn::Chars, "字数"),
+                        ] {
+                            let (current, ascending) = self.summary_sort;
+                            let arrow = match (current == column, ascending) {
+                                (true, true) => " ⏶",
+                                (true, false) => " ⏷",
+                                _ => "",
+                            };
+                            if ui.button(format!("{}{}", title, arrow)).clicked() {
+                                self.summary_sort = (column, current != column || !ascending);
+                                summary.sort_by(column, self.summary_sort.1);
+                            }
+                        }
+                        ui.end_row();
+                        
+                        for row in &summary.rows {
+                            let name = row.path.file_name().unwrap_or_default().to_string_lossy();
+                            ui.label(name.as_ref()).on_hover_text(row.path.display().to_string());
+                            let status = ui.label(row.status.label());
+                            if let Some(error) = &row.error {
+                                status.on_hover_text(error);
+                            }
+                            ui.label(row.confidence.map(|c| format!("{:.1}%", c * 100.0)).unwrap_or_default());
+                            ui.label(format!("{:.0}ms", row.processing_time));
+                            ui.label(row.chars.to_string());
+                            ui.end_row();
+                        }
+                    });
+                });
+            });
+        
+        if let Some(kind) = export {
+            let content = match kind {
+                "csv" => Ok(summary.to_csv()),
+                _ => summary.to_json(),
+            };
+            if let Some(path) = rfd::FileDialog::new()
+                .set_file_name(format!("ocr_summary.{}", kind))
+                .add_filter(kind.to_uppercase(), &[kind])
+                .save_file()
+            {
+                let written = content
+                    .map_err(anyhow::Error::from)
+                    .and_then(|content| std::fs::write(&path, content).map_err(Into::into));
+                match written {
+                    Ok(()) => self.status_display.set_success(&format!("汇总已保存: {}", path.display())),
+                    Err(e) => self.status_display.set_error(&format!("汇总保存失败: {}", e)),
+                }
+            }
+        }
+        if !open {
+            self.batch_summary = None;
+        }
+        if retry {
+            self.retry_failed_batch();
+        }
+    }
+    
+    fn render_viewer_zoom_settings(&mut self, ui: &mut egui::Ui) {
+        let zoom = &mut self.settings.viewer_zoom;
+        let mut changed = false;
+        ui.horizontal(|ui| {
+            ui.label("查看器缩放:");
+            changed |= ui.add(egui::DragValue::new(&mut zoom.min).range(0.01..=1.0).speed(0.01).prefix("最小 ").suffix("×")).changed();
+            changed |= ui.add(egui::DragValue::new(&mut zoom.max).range(1.0..=50.0).speed(0.1).prefix("最大 ").suffix("×")).changed();
+            changed |= ui.add(egui::DragValue::new(&mut zoom.step).range(1.05..=3.0).speed(0.01).prefix("步进 ").suffix("×")).changed();
+        });
+        ui.horizontal(|ui| {
+            ui.label("缩放插值:");
+            changed |= ui.radio_value(&mut self.settings.interpolation, Interpolation::Linear, "平滑").changed();
+            changed |= ui.radio_value(&mut self.settings.interpolation, Interpolation::Nearest, "最近邻")
+                .on_hover_text("放大时保留像素边缘，便于检查锯齿和二值化结果")
+                .changed();
+        });
+        ui.horizontal(|ui| {
+            ui.label("预览渲染:");
+            let rendering = &mut self.settings.preview_rendering;
+            changed |= ui.checkbox(&mut rendering.mipmaps, "抗锯齿缩小")
+                .on_hover_text("缩小显示时使用 mipmap 过滤，细笔画不再断裂闪烁，更接近打印效果（部分渲染后端不支持）")
+                .changed();
+            changed |= ui.checkbox(&mut rendering.sharpen, "轻微锐化")
+                .on_hover_text("只锐化界面上显示的图片，识别使用的图片不受影响")
+                .changed();
+        });
+        changed |= ui.checkbox(&mut self.settings.viewer_minimap, "查看器导航图")
+            .on_hover_text("放大后图片超出窗口时，在右下角显示全图缩略图与当前可见区域，点击或拖动可跳转")
+            .changed();
+        if changed {
+            self.save_settings();
+        }
+    }
+    
+    // 检测框叠加层与标注图共用的样式
+    fn render_overlay_style_settings(&mut self, ui: &mut egui::Ui) {
+        let style = &mut self.settings.overlay_style;
+        let mut changed = false;
+        ui.horizontal(|ui| {
+            ui.label("线宽:");
+            changed |= ui.add(egui::Slider::new(&mut style.stroke_width, 0.5..=6.0).step_by(0.5))
+                .on_hover_text("标注图中的线宽会随图片尺寸等比放大")
+                .changed();
+            changed |= ui.checkbox(&mut style.fill, "半透明填充").changed();
+            changed |= ui.checkbox(&mut style.show_labels, "显示文字标签").changed();
+        });
+        ui.horizontal(|ui| {
+            ui.label("颜色:");
+            changed |= ui.radio_value(&mut style.color_mode, BoxColorMode::Fixed, "固定颜色").changed();
+            changed |= ui.add_enabled(
+                style.color_mode == BoxColorMode::Fixed,
+                |ui: &mut egui::Ui| ui.color_edit_button_srgb(&mut style.color),
+            )
+            .changed();
+            changed |= ui.radio_value(&mut style.color_mode, BoxColorMode::Confidence, "按置信度")
+                .on_hover_text("置信度低的检测框偏红，高的偏绿")
+                .changed();
+            changed |= ui.radio_value(&mut style.color_mode, BoxColorMode::Language, "按语言")
+                .on_hover_text("中文红色、英文蓝色、日文紫色、韩文绿色，无法判断语言的检测框为灰色")
+                .changed();
+        });
+        if changed {
+            self.save_settings();
+        }
+    }
+    
+    // 16 位灰度图降为 8 位的窗口，修改后重新加载当前的 16 位灰度图
+    fn render_gray_window_settings(&mut self, ui: &mut egui::Ui) {
+        let window = &mut self.settings.gray_window;
+        let mut changed = false;
+        ui.horizontal(|ui| {
+            ui.label("16 位灰度窗口:");
+            changed |= ui.radio_value(window, GrayWindow::Auto, "自动")
+                .on_hover_text("按像素值分布选择范围，忽略最暗和最亮的 0.5%")
+                .changed();
+            changed |= ui.radio_value(window, GrayWindow::Full, "完整范围")
+                .on_hover_text("按 0~65535 线性缩放")
+                .changed();
+            let manual = matches!(window, GrayWindow::Manual { .. });
+            if ui.radio(manual, "手动").clicked() && !manual {
+                *window = GrayWindow::Manual { min: 0, max: 4095 };
+                changed = true;
+            }
+        });
+        if let GrayWindow::Manual { min, max } = window {
+            ui.horizontal(|ui| {
+                let min_value = ui.add(egui::DragValue::new(min).range(0..=u16::MAX).speed(16.0).prefix("最小 "));
+                let max_value = ui.add(egui::DragValue::new(max).range(0..=u16::MAX).speed(16.0).prefix("最大 "));
+                // 拖动过程中不重新加载，松开后再应用
+                changed |= [min_value, max_value]
+                    .iter()
+                    .any(|response| response.drag_stopped() || response.lost_focus());
+            });
+        }
+        if !changed {
+            return;
+        }
+        self.save_settings();
+        
+        let gray16 = self.image_info
+            .as_ref()
+            .is_some_and(|info| info.bit_depth == 16 && info.color_type.starts_with('L'));
+        if let (true, Some(path)) = (gray16, self.selected_image_path.clone()) {
+            self.handle_image_selected(path);
+        }
+    }
+    
+    // 色阶与 gamma 调整，修改后记住参数供下次启动使用
+    fn render_levels_settings(&mut self, ui: &mut egui::Ui) {
+        let levels = &mut self.process_options.preprocess.levels;
+        let mut changed = false;
+        ui.horizontal(|ui| {
+            ui.label("Gamma:");
+            changed |= ui.add(egui::Slider::new(&mut levels.gamma, 0.2..=5.0).logarithmic(true))
+                .on_hover_text("小于 1 加深中间调，可以让浅淡的铅笔字和褪色墨迹更清晰")
+                .changed();
+        });
+        ui.horizontal(|ui| {
+            ui.label("色阶:");
+            changed |= ui.add(egui::DragValue::new(&mut levels.black).range(0..=254).prefix("黑场 ")).changed();
+            changed |= ui.add(egui::DragValue::new(&mut levels.white).range(1..=255).prefix("白场 ")).changed();
+            if ui.add_enabled(!levels.is_identity(), egui::Button::new("重置")).clicked() {
+                *levels = Levels::default();
+                changed = true;
+            }
+        });
+        
+        if changed {
+            levels.white = levels.white.max(levels.black + 1);
+            self.settings.levels = *levels;
+            self.save_settings();
+        }
+    }
+    
+    fn save_settings(&mut self) {
+        if let Err(e) = self.settings.save() {
+            self.status_display.set_error(&format!("设置保存失败: {}", e));
+        }
+    }
+    
+    // 识别结果中有界面字体无法显示的字符（会显示为方框）时提示更换字体，复制与导出的文字不受影响
+    fn warn_missing_glyphs(&mut self, text: &str) {
+        if self.glyph_hint_shown {
+            return;
+        }
+        let missing = self.glyphs.missing(text);
+        if missing.is_empty() {
+            return;
+        }
+        self.glyph_hint_shown = true;
+        let codepoints: Vec<String> = missing.iter().map(|c| format!("U+{:04X}", *c as u32)).collect();
+        log::warn!("当前字体无法显示 {} 个字符: {}", missing.len(), codepoints.join(" "));
+        self.status_display.append_note(&format!(
+            "⚠ 有 {} 个字符当前字体无法显示（{}{}），复制和导出不受影响；可将字形更全的字体放到 {} 后重启",
+            missing.len(),
+            codepoints.iter().take(3).cloned().collect::<Vec<_>>().join(" "),
+            if missing.len() > 3 { " 等" } else { "" },
+            font::EXTERNAL_FONT_PATH,
+        ));
+    }
+    
+    // 后处理选项变化后立即作用于当前结果，无需重新识别
+    fn reapply_postprocess(&mut self) {
+        let Some(result) = &mut self.ocr_result else {
+            return;
+        };
+        if result.raw_text.is_empty() {
+            return;
+        }
+        result.text = postprocess_result(&result.raw_text, &result.bounding_boxes, &self.process_options.postprocess);
+        self.result_panel.set_result(result.clone());
+    }
+    
+    // 导出的默认目录、文件名模板与批量自动保存
+    fn render_export_settings(&mut self, ui: &mut egui::Ui) {
+        let mut changed = false;
+        ui.horizontal(|ui| {
+            ui.label("导出目录:");
+            let directory = self.settings.export.directory
+                .as_ref()
+                .map(|dir| dir.display().to_string())
+                .unwrap_or_else(|| "（系统默认）".to_string());
+            ui.label(directory);
+            if ui.button("📁 选择").clicked() {
+                if let Some(dir) = rfd::FileDialog::new().pick_folder() {
+                    self.settings.export.directory = Some(dir);
+                    changed = true;
+                }
+            }
+            if self.settings.export.directory.is_some() && ui.button("清除").clicked() {
+                self.settings.export.directory = None;
+                changed = true;
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("文件名模板:");
+            changed |= ui.text_edit_singleline(&mut self.settings.export.template)
+                .on_hover_text("{name} 源文件名，{ext} 扩展名，{date} 当天日期")
+                .lost_focus();
+        });
+        changed |= ui.checkbox(&mut self.settings.export.batch_auto_save, "批量识别时自动保存每个文件的结果")
+            .on_hover_text("未设置导出目录时保存在源文件旁边")
+            .changed();
+        changed |= ui.checkbox(&mut self.settings.export.batch_manifest, format!("同时写入 {}", batch::MANIFEST_FILE))
+            .on_hover_text("自动保存或逐个导出批量结果时，在结果文件所在目录记录对应的源图片与识别设置")
+            .changed();
+        changed |= ui.checkbox(&mut self.settings.export.text_header, "导出文本时添加来源信息")
+            .on_hover_text("在文本开头以 # 注释行写入源文件、图片尺寸、引擎、语言与导出时间")
+            .changed();
+        changed |= ui.checkbox(&mut self.settings.export.direction_marks, "导出文本时标记书写方向")
+            .on_hover_text("阿拉伯文、希伯来文等从右向左的行以方向控制符开头，其他编辑器中也按从右向左显示；竖排结果在开头注明原文方向。从左向右的横排文本不受影响")
+            .changed();
+        changed |= ui.checkbox(&mut self.settings.export.embed_thumbnail, "导出 JSON 时嵌入缩略图")
+            .on_hover_text(format!("在 metadata.thumbnail 中写入最长边 {} 像素的 base64 JPEG，原图不在时也能看出结果对应的图片", export::THUMBNAIL_MAX_SIZE))
+            .changed();
+        if changed {
+            self.save_settings();
+            self.result_panel.set_export_defaults(self.settings.export.clone(), self.selected_image_path.clone());
+        }
+    }
+    
+    // 识别成功后执行的命令，默认关闭
+    fn render_post_ocr_hook_settings(&mut self, ui: &mut egui::Ui) {
+        let hook = &mut self.settings.post_ocr_hook;
+        let mut changed = ui.checkbox(&mut hook.enabled, "识别成功后执行命令")
+            .on_hover_text("命令通过系统 shell 执行，请只填写信任的命令")
+            .changed();
+        ui.add_enabled_ui(hook.enabled, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("命令:");
+                changed |= ui.add(
+                    egui::TextEdit::singleline(&mut hook.command)
+                        .hint_text("notify-send 识别完成 {image}")
+                        .desired_width(260.0),
+                )
+                .on_hover_text("{image} 源图片路径，{result} 识别结果文件路径")
+                .lost_focus();
+            });
+            ui.horizontal(|ui| {
+                ui.label("结果文件格式:");
+                for format in OutputFormat::ALL {
+                    changed |= ui.radio_value(&mut hook.format, format, format.extension()).changed();
+                }
+            });
+        });
+        if changed {
+            self.save_settings();
+        }
+    }
+    
+    // 区域连续识别的屏幕区域、间隔与截图命令，修改后下次开始时生效
+    fn render_live_capture_settings(&mut self, ui: &mut egui::Ui) {
+        let live = &mut self.settings.live_capture;
+        let mut changed = false;
+        ui.horizontal(|ui| {
+            ui.label("连续识别区域:");
+            changed |= ui.add(egui::DragValue::new(&mut live.x).prefix("x ")).changed();
+            changed |= ui.add(egui::DragValue::new(&mut live.y).prefix("y ")).changed();
+            changed |= ui.add(egui::DragValue::new(&mut live.width).range(8..=10000).prefix("宽 ")).changed();
+            changed |= ui.add(egui::DragValue::new(&mut live.height).range(8..=10000).prefix("高 ")).changed();
+        });
+        changed |= ui.add(egui::Slider::new(&mut live.interval_secs, 0.2..=10.0).suffix(" 秒").text("截图间隔")).changed();
+        ui.horizontal(|ui| {
+            ui.label("截图命令:");
+            changed |= ui.add(egui::TextEdit::singleline(&mut live.command).desired_width(260.0))
+                .on_hover_text("{x} {y} {width} {height} 屏幕区域，{output} 截图保存路径（不含引号）。Wayland 可使用 grim -g '{x},{y} {width}x{height}' '{output}'")
+                .lost_focus();
+            if ui.small_button("默认").clicked() {
+                live.command = live::DEFAULT_CAPTURE_COMMAND.to_string();
+                changed = true;
+            }
+        });
+        if changed {
+            self.save_settings();
+        }
+    }
+    
+    // 配置方案的切换、保存与删除
+    fn render_profile_settings(&mut self, ui: &mut egui::Ui) {
+        ui.strong("配置方案");
+        
+        let active = self.profiles.active.clone();
+        let mut selected = active.clone();
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_salt("profile_select")
+                .selected_text(active.as_deref().unwrap_or("（未使用）"))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut selected, None, "（未使用）");
+                    for profile in &self.profiles.profiles {
+                        ui.selectable_value(&mut selected, Some(profile.name.clone()), &profile.name);
+                    }
+                });
+            
+            if let Some(name) = &active {
+                let modified = self.profiles.get(name).is_some_and(|p| p.options != self.process_options);
+                if ui.add_enabled(modified, egui::Button::new("保存")).on_hover_text("用当前设置覆盖该方案").clicked() {
+                    self.profiles.upsert(name, self.process_options.clone());
+                    self.save_profiles();
+                }
+                if ui.button("删除").clicked() {
+                    self.profiles.remove(name);
+                    self.save_profiles();
+                }
+            }
+        });
+        
+        if selected != active {
+            if let Some(profile) = selected.as_deref().and_then(|name| self.profiles.get(name)) {
+                self.process_options = profile.options.clone();
+            }
+            self.profiles.active = selected;
+            self.save_profiles();
+        }
+        
+        ui.horizontal(|ui| {
+            ui.add(egui::TextEdit::singleline(&mut self.new_profile_name).hint_text("新方案名称").desired_width(140.0));
+            let name = self.new_profile_name.trim().to_string();
+            if ui.add_enabled(!name.is_empty(), egui::Button::new("另存为方案")).clicked() {
+                self.profiles.upsert(&name, self.process_options.clone());
+                self.profiles.active = Some(name);
+                self.new_profile_name.clear();
+                self.save_profiles();
+            }
+        });
+        
+        ui.horizontal(|ui| {
+            let modified = self.file_config.options != self.process_options;
+            if ui
+                .add_enabled(modified, egui::Button::new(format!("写入 {}", file_config::FILE_NAME)))
+                .on_hover_text("把当前设置保存到程序所在目录的配置文件，作为下次启动及命令行、HTTP 服务的默认值")
+                .clicked()
+            {
+                self.save_file_config();
+            }
+        });
+    }
+    
+    fn save_file_config(&mut self) {
+        self.file_config.options = self.process_options.clone();
+        match self.file_config.save() {
+            Ok(path) => self.status_display.set_success(&format!("已写入 {}", path.display())),
+            Err(e) => self.status_display.set_error(&format!("配置文件保存失败: {}", e)),
+        }
+    }
+    
+    fn save_profiles(&mut self) {
+        if let Err(e) = self.profiles.save() {
+            self.status_display.set_error(&format!("配置方案保存失败: {}", e));
+        }
+    }
+    
+    // 从 tessdata 目录中发现的语言里勾选识别语言
+    fn render_language_settings(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.strong("识别语言");
+            if ui.small_button("🔄").on_hover_text("重新扫描已安装的语言包").clicked() {
+                self.tesseract_languages = None;
+            }
+        });
+        
+        let available = self.tesseract_languages.get_or_insert_with(tessdata::discover_languages);
+        if available.is_empty() {
+            ui.weak("未在 tessdata 目录中找到语言包，请安装 Tesseract 语言数据或设置 TESSDATA_PREFIX");
+            return;
+        }
+        
+        // 常用语言组合一键设置，未安装的语言包不可选
+        ui.horizontal_wrapped(|ui| {
+            ui.label("快速选择:");
+            for preset in tessdata::LANGUAGE_PRESETS {
+                let missing = preset.missing_languages(available);
+                let current = self.process_options.tesseract.language == preset.language;
+                let response = ui.add_enabled(missing.is_empty(), egui::Button::new(preset.name).selected(current));
+                let response = response.on_disabled_hover_text(format!("未安装语言包: {}", missing.join(", ")));
+                if response.on_hover_text(preset.language).clicked() {
+                    self.process_options.tesseract.apply_preset(preset);
+                }
+            }
+        });
+        let page_seg_mode = &mut self.process_options.tesseract.page_seg_mode;
+        egui::ComboBox::from_label("版面分析模式")
+            .selected_text(page_seg_mode.label())
+            .show_ui(ui, |ui| {
+                for mode in PageSegMode::ALL {
+                    ui.selectable_value(page_seg_mode, mode, mode.label());
+                }
+            });
+        
+        let mut selected = tessdata::split_languages(&self.process_options.tesseract.language);
+        let mut changed = false;
+        ui.horizontal_wrapped(|ui| {
+            for language in available.iter() {
+                let mut checked = selected.contains(language);
+                if ui.checkbox(&mut checked, language).changed() {
+                    if checked {
+                        selected.push(language.clone());
+                    } else if selected.len() > 1 {
+                        // 至少保留一种语言
+                        selected.retain(|l| l != language);
+                    }
+                    changed = true;
+                }
+            }
+        });
+        
+        if changed {
+            self.process_options.tesseract.language = tessdata::join_languages(&selected);
+        }
+        
+        // 提示已选择但未安装的语言，避免初始化失败
+        let missing: Vec<&String> = selected.iter().filter(|l| !available.contains(l)).collect();
+        if !missing.is_empty() {
+            ui.colored_label(
+                egui::Color32::from_rgb(220, 20, 60),
+                format!("未安装的语言: {}", missing.iter().map(|l| l.as_str()).collect::<Vec<_>>().join(", ")),
+            );
+        }
+        ui.weak(format!("当前: {}", self.process_options.tesseract.language));
+    }
+    
+    // 只显示当前引擎用得到的选项，避免修改了另一引擎的选项却看不到效果
+    fn render_engine_settings(&mut self, ui: &mut egui::Ui) {
+        let Some(engine) = self.ocr_engine.primary_engine(self.process_options.engine) else {
+            ui.weak("没有可用的识别引擎");
+            return;
+        };
+        // 两个引擎都可用时才需要选择
+        if matches!(self.ocr_engine.get_status(), EngineStatus::Ready) {
+            ui.horizontal(|ui| {
+                ui.label("首选引擎:");
+                egui::ComboBox::from_id_salt("engine_preference")
+                    .selected_text(self.process_options.engine.label())
+                    .show_ui(ui, |ui| {
+                        for preference in EnginePreference::ALL {
+                            ui.selectable_value(&mut self.process_options.engine, preference, preference.label());
+                        }
+                    });
+            });
+        }
+        let has_tesseract = cfg!(feature = "tesseract") && self.ocr_engine.has_tesseract();
+        match engine {
+            EngineKind::Tesseract => {
+                ui.strong("Tesseract 选项");
+                if cfg!(feature = "tesseract") {
+                    self.render_language_settings(ui);
+                }
+            }
+            EngineKind::Candle => {
+                ui.strong("Candle 选项");
+                let candle = &mut self.process_options.candle;
+                let available = ocr::available_devices();
+                let (device, warning) = candle.device.resolve(&available);
+                ui.horizontal(|ui| {
+                    ui.label("计算设备:");
+                    egui::ComboBox::from_id_salt("compute_device")
+                        .selected_text(candle.device.label())
+                        .show_ui(ui, |ui| {
+                            // 不可用的加速器只显示、不能选择
+                            for option in ComputeDevice::ALL {
+                                let usable = matches!(option, ComputeDevice::Auto | ComputeDevice::Cpu) || available.contains(&option);
+                                if ui
+                                    .add_enabled(usable, egui::SelectableLabel::new(candle.device == option, option.label()))
+                                    .on_disabled_hover_text("本机或当前版本不支持该设备")
+                                    .clicked()
+                                {
+                                    candle.device = option;
+                                }
+                            }
+                        });
+                    ui.weak(format!("当前使用: {}", device.label()))
+                        .on_hover_text("每张图片的推理耗时见识别详情中的“识别”阶段与批量汇总的耗时列");
+                });
+                if let Some(warning) = warning {
+                    ui.colored_label(egui::Color32::from_rgb(230, 160, 40), format!("⚠ {}", warning));
                 }
-                AppMessage::OcrError(error) => {
-                    self.state = AppState::Error(error.clone());
-                    self.status_display.set_error(&format!("识别失败: {}", error));
+                ui.add_enabled_ui(has_tesseract, |ui| {
+                    ui.checkbox(&mut candle.low_confidence_fallback, "低置信度自动回退")
+                        .on_hover_text("Candle 识别置信度低于阈值时使用 Tesseract 重新识别，保留置信度较高的结果")
+                        .on_disabled_hover_text("需要 Tesseract 可用（使用 --features tesseract 编译并安装语言包）");
+                    ui.add_enabled(
+                        candle.low_confidence_fallback,
+                        egui::Slider::new(&mut candle.fallback_threshold, 0.0..=1.0).text("置信度阈值"),
+                    );
+                });
+                // 回退时由 Tesseract 识别，才需要选择语言包
+                if has_tesseract && candle.low_confidence_fallback {
+                    self.render_language_settings(ui);
                 }
             }
         }
@@ -176,9 +2448,13 @@ impl OcrApp {
                 // 新建/重置按钮
                 if ui.button("🆕 新建").clicked() {
                     self.reset_state();
+                    self.image_info = None;
                     self.selected_image_path = None;
                     self.current_image = None;
                     self.image_display = ImageDisplay::new();
+                    self.preprocess_preview.clear();
+                    self.animation_frames.clear();
+                    self.current_frame = 0;
                 }
             });
         });
@@ -186,9 +2462,116 @@ impl OcrApp {
     
     fn render_toolbar(&mut self, ui: &mut egui::Ui) {
         ui.horizontal(|ui| {
-            if ui.button("📁 选择图片").clicked() {
-                self.handle_file_selection();
-            }
+            // 没有可用引擎时禁用所有输入操作
+            let engine_ready = self.engine_available();
+            ui.add_enabled_ui(engine_ready, |ui| {
+                if ui.button("📁 选择图片").clicked() {
+                    self.handle_file_selection();
+                }
+                
+                ui.add_enabled_ui(!self.recent_files.entries().is_empty(), |ui| {
+                    ui.menu_button("🕘 最近", |ui| {
+                        ui.add(
+                            egui::TextEdit::singleline(&mut self.recent_query)
+                                .hint_text("搜索文件名、备注或标签")
+                                .desired_width(200.0),
+                        );
+                        let tags = self.recent_files.tags();
+                        if !tags.is_empty() {
+                            ui.horizontal_wrapped(|ui| {
+                                ui.selectable_value(&mut self.recent_tag, None, "全部");
+                                for tag in tags {
+                                    ui.selectable_value(&mut self.recent_tag, Some(tag.to_string()), format!("🏷 {}", tag));
+                                }
+                            });
+                        }
+                        ui.separator();
+                        
+                        let mut selected = None;
+                        let entries = self.recent_files.entries().iter()
+                            .filter(|entry| entry.matches(&self.recent_query, self.recent_tag.as_deref()));
+                        for entry in entries {
+                            let name = entry.path.file_name().unwrap_or_default().to_string_lossy();
+                            let label = if entry.tags.is_empty() {
+                                name.to_string()
+                            } else {
+                                format!("{}  🏷 {}", name, entry.tags.join(", "))
+                            };
+                            let mut hover = entry.path.display().to_string();
+                            if !entry.note.is_empty() {
+                                hover = format!("{}\n{}", hover, entry.note);
+                            }
+                            if ui.button(label).on_hover_text(hover).clicked() {
+                                selected = Some(entry.path.clone());
+                            }
+                        }
+                        if let Some(path) = selected {
+                            let _ = self.tx.send(AppMessage::ImageSelected(path));
+                            ui.close_menu();
+                        }
+                    });
+                });
+                
+                if ui.button("🌐 从 URL 打开").on_hover_text("也可以直接粘贴图片链接").clicked() {
+                    self.show_url_dialog = true;
+                }
+                
+                let monitoring = self.clipboard_monitor.is_some();
+                if ui.selectable_label(monitoring, "📋 剪贴板监视")
+                    .on_hover_text("自动识别复制到剪贴板的新图片")
+                    .clicked()
+                {
+                    self.toggle_clipboard_monitor();
+                }
+                
+                let live = self.live_capture.is_some();
+                if ui.selectable_label(live, "🎬 区域连续识别")
+                    .on_hover_text("按设置中的屏幕区域与间隔反复截图识别，文字变化时追加到记录（适合字幕、滚动文字）")
+                    .clicked()
+                {
+                    self.toggle_live_capture();
+                }
+                
+                if self.batch.is_active() {
+                    let paused = self.batch.is_paused();
+                    let label = if paused { "▶ 继续队列" } else { "⏸ 暂停队列" };
+                    if ui.selectable_label(paused, label)
+                        .on_hover_text("暂停后当前图片照常识别完成，之后不再处理队列中的图片")
+                        .clicked()
+                    {
+                        self.toggle_batch_pause();
+                    }
+                }
+                
+                let idle = !matches!(self.state, AppState::Loading | AppState::Processing);
+                // 识别进行中或批量未完成时重新识别会与之争用同一个当前项
+                if ui.add_enabled(self.current_image.is_some() && idle && !self.batch.is_active(), egui::Button::new("🔄 重新识别"))
+                    .on_hover_text("使用当前设置重新识别当前图片")
+                    .on_disabled_hover_text("识别进行中或批量识别未完成")
+                    .clicked()
+                {
+                    self.rerun_ocr();
+                }
+                
+                if ui.add_enabled(self.current_image.is_some() && idle, egui::Button::new("🧱 仅检测布局"))
+                    .on_hover_text("只检测文本区域并显示检测框，不进行识别")
+                    .clicked()
+                {
+                    self.detect_layout();
+                }
+                if ui.add_enabled(self.current_image.is_some(), egui::Button::new("🧪 保存预处理图像"))
+                    .on_hover_text("按当前预处理参数处理原尺寸图片（颜色过滤、色阶、二值化）并保存为 PNG，即送入识别引擎的图像")
+                    .clicked()
+                {
+                    self.save_preprocessed_image();
+                }
+                if ui.add_enabled(self.ocr_result.is_some(), egui::Button::new("📏 评估"))
+                    .on_hover_text("选择人工校对的标准文本（.txt），计算当前识别结果的字符错误率（CER）与词错误率（WER）")
+                    .clicked()
+                {
+                    self.choose_ground_truth();
+                }
+            });
             
             ui.separator();
             
@@ -201,34 +2584,126 @@ impl OcrApp {
             }
             
             ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                let appending = self.append_target.is_some();
+                let append_hint = match &self.append_target {
+                    Some(path) => format!("正在追加到 {}，点击停止", path.display()),
+                    None => "将之后的每个识别结果追加到同一个文本文件".to_string(),
+                };
+                if ui.selectable_label(appending, "📝 追加到文件").on_hover_text(append_hint).clicked() {
+                    self.toggle_append_mode();
+                }
+                
                 // 导出按钮
                 if let Some(_result) = &self.ocr_result {
                     if ui.button("💾 导出结果").clicked() {
                         self.export_result();
                     }
+                    if ui.button("🖼 导出标注图").on_hover_text("导出绘制了检测框的图片").clicked() {
+                        self.export_annotated_image();
+                    }
+                    if ui.button("🌡 导出置信度热力图")
+                        .on_hover_text("按置信度为每个检测区域着色，查看整页中识别较差的位置")
+                        .clicked()
+                    {
+                        self.export_confidence_heatmap();
+                    }
+                }
+            });
+        });
+    }
+    
+    // 当前图片的扫描分辨率：手动指定优先，其次为图片记录的分辨率
+    fn effective_dpi(&self) -> (f32, f32) {
+        self.settings.dpi_override.resolve(self.image_info.as_ref().and_then(|info| info.dpi))
+    }
+    
+    fn render_image_info(&mut self, ui: &mut egui::Ui) {
+        let effective_dpi = self.effective_dpi();
+        let Some(info) = &self.image_info else {
+            return;
+        };
+        let mut dpi_changed = false;
+        ui.collapsing("ℹ 图像信息", |ui| {
+            egui::Grid::new("image_info_grid").num_columns(2).show(ui, |ui| {
+                let unknown = || "未知".to_string();
+                let rows = [
+                    ("格式", info.format.clone().unwrap_or_else(unknown)),
+                    ("尺寸", format!("{} × {}", info.width, info.height)),
+                    ("颜色类型", info.color_type.clone()),
+                    ("位深", format!("{} 位/通道", info.bit_depth)),
+                    ("文件大小", format!("{:.1} KB", info.file_size as f64 / 1024.0)),
+                    ("分辨率", info.dpi.map(|(x, y)| format!("{:.0} × {:.0} DPI", x, y)).unwrap_or_else(unknown)),
+                    ("打印尺寸", {
+                        let (width, height) = metadata::print_size_cm((info.width, info.height), effective_dpi);
+                        format!("{:.1} × {:.1} cm（按 {:.0} DPI）", width, height, effective_dpi.0)
+                    }),
+                    ("相机", info.camera.clone().unwrap_or_else(unknown)),
+                    ("拍摄时间", info.date_taken.clone().unwrap_or_else(unknown)),
+                    (
+                        "ICC 配置文件",
+                        info.icc_profile.map(|size| format!("有（{} 字节）", size)).unwrap_or_else(|| "无".to_string()),
+                    ),
+                ];
+                for (label, value) in rows {
+                    ui.label(format!("{}:", label));
+                    ui.label(value);
+                    ui.end_row();
                 }
             });
+            let dpi = &mut self.settings.dpi_override;
+            ui.horizontal(|ui| {
+                dpi_changed |= ui.checkbox(&mut dpi.enabled, "指定扫描 DPI")
+                    .on_hover_text(format!(
+                        "图片没有记录分辨率或记录有误时使用，影响打印尺寸与 hOCR 输出的 scan_res；未指定且图片没有记录时按 {:.0} DPI",
+                        metadata::DEFAULT_DPI
+                    ))
+                    .changed();
+                dpi_changed |= ui.add_enabled(dpi.enabled, egui::DragValue::new(&mut dpi.dpi).range(10.0..=4800.0).suffix(" DPI"))
+                    .changed();
+            });
         });
+        if dpi_changed {
+            self.save_settings();
+        }
     }
     
     fn render_main_content(&mut self, ui: &mut egui::Ui) {
-        // 使用可调整大小的面板布局
+        // 使用可调整大小的面板布局；专注模式下结果区域占满窗口
         egui::SidePanel::left("image_panel")
             .resizable(true)
             .default_width(500.0)
             .min_width(300.0)
             .max_width(800.0)
-            .show_inside(ui, |ui| {
+            .show_animated_inside(ui, !self.focus_mode, |ui| {
                 ui.group(|ui| {
                     ui.vertical(|ui| {
                         ui.strong("📸 图片预览");
                         ui.separator();
                         
-                        if self.image_display.has_image() {
+                        self.preprocess_preview.set_interpolation(self.settings.interpolation);
+                        if self.preprocess_preview.is_enabled() {
+                            self.preprocess_preview.show(ui, &self.process_options.preprocess);
+                            self.preprocess_preview.show_toggle(ui);
+                            self.render_frame_slider(ui);
+                        } else if self.image_display.has_image() {
+                            self.image_display.set_backdrop(self.preview_backdrop);
+                            self.image_display.set_loupe(self.loupe_options);
+                            self.image_display.set_interpolation(self.settings.interpolation);
+                            self.image_display.set_rendering(self.settings.preview_rendering);
+                            self.image_display.set_overlay_style(self.settings.overlay_style);
                             let clicked = self.image_display.show(ui);
                             if clicked {
                                 self.show_image_viewer = true;
                             }
+                            if let Some(boxes) = self.image_display.take_edited_boxes() {
+                                self.apply_box_edits(boxes);
+                            }
+                            if let Some(index) = self.image_display.take_reocr_request() {
+                                self.recognize_region(index);
+                            }
+                            self.preprocess_preview.show_toggle(ui);
+                            self.render_frame_slider(ui);
+                            self.render_image_info(ui);
                         } else {
                             ui.vertical_centered(|ui| {
                                 ui.add_space(50.0);
@@ -239,6 +2714,8 @@ impl OcrApp {
                                 ui.add_space(50.0);
                             });
                         }
+                        
+                        self.render_batch_queue(ui);
                     });
                 });
             });
@@ -271,51 +2748,383 @@ impl OcrApp {
                                 ui.add_space(30.0);
                                 ui.spinner();
                                 ui.label("正在识别文字...");
-                                ui.weak("请稍候");
+                                match self.ocr_progress {
+                                    Some(fraction) => {
+                                        ui.add(
+                                            egui::ProgressBar::new(fraction)
+                                                .desired_width(200.0)
+                                                .show_percentage(),
+                                        );
+                                    }
+                                    None => {
+                                        ui.weak("请稍候");
+                                    }
+                                }
                                 ui.add_space(30.0);
                             });
                         }
                         AppState::Completed => {
+                            self.render_quality_banner(ui);
                             self.result_panel.show(ui);
+                            if let Some(display) = self.result_panel.take_display_change() {
+                                self.settings.result_display = display;
+                                self.save_settings();
+                            }
+                            if let Some(naming) = self.result_panel.take_export_change() {
+                                self.settings.export = naming;
+                                self.save_settings();
+                            }
+                            if let (Some((note, tags)), Some(path)) = (self.result_panel.take_annotation(), &self.selected_image_path) {
+                                self.recent_files.set_annotation(path, note, tags);
+                                if let Err(e) = self.recent_files.save() {
+                                    log::warn!("保存最近文件失败: {}", e);
+                                }
+                            }
+                            if let Some(index) = self.result_panel.take_flash_request() {
+                                let now = ui.input(|i| i.time);
+                                self.image_display.flash_box(index, now);
+                            }
+                            self.image_display.set_active_box(self.result_panel.active_box());
+                            let (search_boxes, search_current) = self.result_panel.search_highlight();
+                            let now = ui.input(|i| i.time);
+                            self.image_display.set_search_highlight(search_boxes, search_current, now);
                         }
                         AppState::Error(error) => {
+                            let (title, action) = self.error_recovery.unwrap_or(("识别失败，请重试", RecoveryAction::Retry));
+                            let mut recover = false;
                             ui.vertical_centered(|ui| {
                                 ui.add_space(30.0);
-                                ui.weak("识别失败，请重试");
+                                ui.weak(title);
+                                ui.add_space(10.0);
+                                let label = match action {
+                                    RecoveryAction::Retry => "🔄 重试",
+                                    RecoveryAction::ChooseImage => "📁 选择其他图片",
+                                    RecoveryAction::CheckEngine => "🔄 重新加载引擎",
+                                    RecoveryAction::ResetPreprocess => "↺ 重置预处理并重试",
+                                };
+                                recover = ui.button(label).clicked();
                                 ui.add_space(10.0);
                                 ui.collapsing("查看详情", |ui| {
                                     ui.weak(error);
                                 });
                                 ui.add_space(30.0);
                             });
+                            if recover {
+                                self.recover_from_error(action);
+                            }
                         }
                     }
+                    
+                    self.render_capture_logs(ui);
                 });
             });
         });
     }
     
+    fn render_status_log_settings(&mut self, ui: &mut egui::Ui) {
+        let options = &mut self.settings.status_log;
+        let mut changed = false;
+        ui.horizontal(|ui| {
+            ui.label("状态栏:");
+            egui::ComboBox::from_id_salt("status_verbosity")
+                .selected_text(options.verbosity.label())
+                .show_ui(ui, |ui| {
+                    for level in LogLevel::ALL {
+                        changed |= ui.selectable_value(&mut options.verbosity, level, level.label()).changed();
+                    }
+                })
+                .response
+                .on_hover_text("状态栏与日志面板显示的消息级别；所有消息仍会记入日志");
+            changed |= ui.checkbox(&mut options.mirror_log, "记录程序日志")
+                .on_hover_text("把程序内部的运行日志也记入日志面板，无需在终端中设置 RUST_LOG")
+                .changed();
+        });
+        if changed {
+            self.status_display.set_verbosity(self.settings.status_log.verbosity);
+            self.save_settings();
+        }
+    }
+    
     fn render_status_bar(&mut self, ui: &mut egui::Ui) {
         ui.horizontal(|ui| {
             self.status_display.show(ui);
             
             ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                ui.toggle_value(&mut self.show_status_log, format!("📜 {}", self.status_display.log_count()))
+                    .on_hover_text("显示消息日志");
                 if let Some(image) = &self.current_image {
                     ui.weak(format!("{}×{}", image.width(), image.height()));
                 }
+                if self.animation_frames.len() > 1 {
+                    ui.weak(format!("帧 {}/{}", self.current_frame + 1, self.animation_frames.len()));
+                }
             });
         });
     }
     
-    fn export_result(&self) {
+    // 保存为 .json 时导出完整结果（包括手动修改过的检测框），否则只导出文本
+    fn export_result(&mut self) {
         if let Some(result) = &self.ocr_result {
-            if let Some(path) = rfd::FileDialog::new()
-                .set_file_name("ocr_result.txt")
-                .add_filter("文本文件", &["txt"])
-                .save_file()
-            {
-                let _ = std::fs::write(path, &result.text);
+            let naming = &self.settings.export;
+            let extension = naming.preferred_extension(&["txt", "json"]);
+            let mut dialog = rfd::FileDialog::new().set_file_name(naming.file_name(self.selected_image_path.as_deref(), extension));
+            // 上次使用的格式排在前面，作为对话框默认选中的类型
+            let filters = [("文本文件", "txt"), ("JSON（含检测框）", "json")];
+            for (name, ext) in filters.iter().filter(|(_, ext)| *ext == extension).chain(filters.iter().filter(|(_, ext)| *ext != extension)) {
+                dialog = dialog.add_filter(*name, &[*ext]);
+            }
+            if let Some(directory) = naming.dialog_directory() {
+                dialog = dialog.set_directory(directory);
+            }
+            if let Some(path) = dialog.save_file() {
+                let is_json = path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+                let source = self.selected_image_path.as_deref();
+                let content = if is_json {
+                    let mut metadata = self.export_metadata(result, source);
+                    if let (true, Some(image)) = (self.settings.export.embed_thumbnail, &self.current_image) {
+                        metadata = metadata.with_thumbnail(image);
+                    }
+                    let export = OcrExport { metadata, result };
+                    serde_json::to_string_pretty(&export).unwrap_or_default()
+                } else {
+                    self.export_text(result, source)
+                };
+                let _ = std::fs::write(&path, content);
+                if self.settings.export.remember(&path) {
+                    self.save_settings();
+                    self.result_panel.set_export_defaults(self.settings.export.clone(), self.selected_image_path.clone());
+                }
+            }
+        }
+    }
+    
+    fn export_metadata(&self, result: &OcrResult, source: Option<&std::path::Path>) -> ExportMetadata {
+        // 批量导出其他页面时从文件头读取尺寸
+        let current = source == self.selected_image_path.as_deref();
+        let size = match (&self.current_image, source) {
+            (Some(image), _) if current => image.dimensions(),
+            (_, Some(path)) => image::image_dimensions(path).unwrap_or_default(),
+            _ => (0, 0),
+        };
+        let source = source.map(|path| path.display().to_string()).unwrap_or_default();
+        ExportMetadata::new(&source, size, result, &self.process_options.tesseract.language)
+    }
+    
+    // 导出的文本，按设置添加书写方向标记并在开头加上来源信息注释
+    fn export_text(&self, result: &OcrResult, source: Option<&std::path::Path>) -> String {
+        let export = &self.settings.export;
+        if !export.text_header && !export.direction_marks {
+            return result.text.clone();
+        }
+        let metadata = self.export_metadata(result, source);
+        let text = if export.direction_marks {
+            export::mark_direction(&result.text, &metadata.language)
+        } else {
+            result.text.clone()
+        };
+        if export.text_header {
+            metadata.text_header() + &text
+        } else {
+            text
+        }
+    }
+    
+    fn export_annotated_image(&mut self) {
+        let (Some(result), Some(image)) = (&self.ocr_result, &self.current_image) else {
+            return;
+        };
+        let Some(path) = rfd::FileDialog::new()
+            .set_file_name("ocr_annotated.png")
+            .add_filter("PNG 图片", &["png"])
+            .save_file()
+        else {
+            return;
+        };
+        
+        let font = annotate::load_label_font();
+        let style = &self.settings.overlay_style;
+        if font.is_none() && (style.show_labels || self.annotate_options.include_confidence) {
+            log::warn!("无法加载标注字体，导出的标注图将不包含文字标签");
+        }
+        let annotated = annotate::annotate_image(image, &result.bounding_boxes, style, &self.annotate_options, font.as_ref());
+        match annotated.save_with_format(&path, image::ImageFormat::Png) {
+            Ok(()) => self.status_display.set_success(&format!("标注图已保存: {}", path.display())),
+            Err(e) => self.status_display.set_error(&format!("标注图保存失败: {}", e)),
+        }
+    }
+    
+    fn export_confidence_heatmap(&mut self) {
+        let (Some(result), Some(image)) = (&self.ocr_result, &self.current_image) else {
+            return;
+        };
+        if result.bounding_boxes.is_empty() {
+            self.status_display.set_error("识别结果没有检测框，无法生成热力图");
+            return;
+        }
+        let Some(path) = rfd::FileDialog::new()
+            .set_file_name("ocr_heatmap.png")
+            .add_filter("PNG 图片", &["png"])
+            .save_file()
+        else {
+            return;
+        };
+        
+        let heatmap = annotate::confidence_heatmap(image, &result.bounding_boxes, &self.heatmap_options);
+        match heatmap.save_with_format(&path, image::ImageFormat::Png) {
+            Ok(()) => self.status_display.set_success(&format!("热力图已保存: {}", path.display())),
+            Err(e) => self.status_display.set_error(&format!("热力图保存失败: {}", e)),
+        }
+    }
+    
+    // 没有输入框获得焦点时，粘贴图片链接直接打开
+    fn handle_paste_url(&mut self, ctx: &egui::Context) {
+        if ctx.wants_keyboard_input() || !self.engine_available() {
+            return;
+        }
+        let pasted_url = ctx.input(|i| {
+            i.events.iter().find_map(|event| match event {
+                egui::Event::Paste(text) if loader::is_image_url(text) => Some(text.clone()),
+                _ => None,
+            })
+        });
+        if let Some(url) = pasted_url {
+            self.open_url(url);
+        }
+    }
+    
+    fn render_url_dialog(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_url_dialog;
+        let mut submitted = false;
+        
+        egui::Window::new("🌐 从 URL 打开")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label("图片链接:");
+                let response = ui.add(
+                    egui::TextEdit::singleline(&mut self.url_input)
+                        .hint_text("https://example.com/image.png")
+                        .desired_width(360.0)
+                );
+                let enter_pressed = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                
+                ui.horizontal(|ui| {
+                    if ui.button("打开").clicked() || enter_pressed {
+                        submitted = true;
+                    }
+                });
+            });
+        
+        if submitted {
+            open = false;
+            let url = std::mem::take(&mut self.url_input);
+            self.open_url(url);
+        }
+        self.show_url_dialog = open;
+    }
+    
+    // 命令面板：输入关键字筛选命令，上下键选择，回车执行，Esc 关闭
+    fn render_command_palette(&mut self, ctx: &egui::Context) {
+        let Some(palette) = &mut self.command_palette else {
+            return;
+        };
+        let entries = palette::commands();
+        let matches = palette::filter(&entries, &palette.query);
+        let (up, down, enter, escape) = ctx.input_mut(|i| {
+            (
+                i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowUp),
+                i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowDown),
+                i.consume_key(egui::Modifiers::NONE, egui::Key::Enter),
+                i.consume_key(egui::Modifiers::NONE, egui::Key::Escape),
+            )
+        });
+        if down {
+            palette.selected += 1;
+        }
+        if up {
+            palette.selected = palette.selected.saturating_sub(1);
+        }
+        palette.selected = palette.selected.min(matches.len().saturating_sub(1));
+        let mut chosen = enter.then(|| matches.get(palette.selected).map(|entry| entry.command)).flatten();
+        
+        egui::Window::new("命令面板")
+            .title_bar(false)
+            .anchor(egui::Align2::CENTER_TOP, egui::vec2(0.0, 80.0))
+            .fixed_size(egui::vec2(420.0, 0.0))
+            .show(ctx, |ui| {
+                let response = ui.add(
+                    egui::TextEdit::singleline(&mut palette.query)
+                        .hint_text("输入命令，如“导出”“语言”“export”")
+                        .desired_width(f32::INFINITY),
+                );
+                response.request_focus();
+                if response.changed() {
+                    palette.selected = 0;
+                }
+                ui.separator();
+                egui::ScrollArea::vertical().max_height(320.0).show(ui, |ui| {
+                    if matches.is_empty() {
+                        ui.weak("没有匹配的命令");
+                    }
+                    for (index, entry) in matches.iter().enumerate() {
+                        let label = ui.selectable_label(index == palette.selected, &entry.title);
+                        if index == palette.selected && (up || down) {
+                            label.scroll_to_me(None);
+                        }
+                        if label.clicked() {
+                            chosen = Some(entry.command);
+                        }
+                    }
+                });
+            });
+        
+        if escape || chosen.is_some() {
+            self.command_palette = None;
+        }
+        if let Some(command) = chosen {
+            self.run_command(ctx, command);
+        }
+    }
+    
+    fn run_command(&mut self, ctx: &egui::Context, command: Command) {
+        let busy = matches!(self.state, AppState::Loading | AppState::Processing);
+        match command {
+            Command::OpenImage => self.handle_file_selection(),
+            Command::OpenUrl => self.show_url_dialog = true,
+            Command::RerunOcr if !busy && !self.batch.is_active() => self.rerun_ocr(),
+            Command::CopyText => {
+                if let Some(result) = &self.ocr_result {
+                    ctx.copy_text(result.text.clone());
+                    self.status_display.set_success("识别结果已复制");
+                }
+            }
+            Command::ExportResult => self.export_result(),
+            Command::ExportAnnotated => self.export_annotated_image(),
+            Command::ExportHeatmap => self.export_confidence_heatmap(),
+            Command::TogglePreprocessCompare => self.preprocess_preview.toggle(),
+            Command::ToggleImageViewer if self.current_image.is_some() => {
+                if self.show_image_viewer {
+                    self.remember_view_state();
+                }
+                self.show_image_viewer = !self.show_image_viewer;
             }
+            Command::ToggleClipboardMonitor => self.toggle_clipboard_monitor(),
+            Command::ToggleLiveCapture => self.toggle_live_capture(),
+            Command::OpenBatchSummary if self.batch.finished_count() > 0 => self.open_batch_summary(),
+            Command::ExportMergedText if self.batch.finished_count() > 0 => self.export_merged_text(),
+            Command::Evaluate => self.choose_ground_truth(),
+            Command::ToggleSettings => self.show_settings = !self.show_settings,
+            Command::ToggleDarkMode => self.dark_mode = !self.dark_mode,
+            Command::ToggleDebugOverlay => self.debug_overlay.toggle(),
+            Command::ToggleFocusMode => self.focus_mode = !self.focus_mode,
+            Command::Language(index) => {
+                if let Some(preset) = tessdata::LANGUAGE_PRESETS.get(index) {
+                    self.process_options.tesseract.apply_preset(preset);
+                    self.status_display.set_message(&format!("识别语言已切换为 {}，重新识别后生效", preset.name));
+                }
+            }
+            _ => self.status_display.set_message("当前状态下无法执行该命令"),
         }
     }
     
@@ -324,33 +3133,112 @@ impl OcrApp {
         if !ctx.input(|i| i.raw.dropped_files.is_empty()) {
             let dropped_files = ctx.input(|i| i.raw.dropped_files.clone());
             
-            for file in dropped_files {
-                if let Some(path) = &file.path {
-                    // 检查是否为图片文件
-                    if let Some(extension) = path.extension() {
-                        let ext = extension.to_string_lossy().to_lowercase();
-                        if matches!(ext.as_str(), "png" | "jpg" | "jpeg" | "bmp" | "tiff" | "webp" | "gif") {
-                            let _ = self.tx.send(AppMessage::ImageSelected(path.clone()));
-                            break; // 只处理第一个图片文件
-                        }
-                    }
+            // 按拖入顺序收集图片文件与压缩包，忽略重复项；模型文件直接加载
+            let mut paths: Vec<PathBuf> = Vec::new();
+            for path in dropped_files.into_iter().filter_map(|file| file.path) {
+                if ocr::is_model_file(&path) {
+                    self.load_model(&path);
+                } else if (loader::is_supported_image(&path) || archive::is_archive(&path)) && !paths.contains(&path) {
+                    paths.push(path);
                 }
             }
+            let mut paths = self.expand_archives(paths);
+            if !self.engine_available() {
+                return;
+            }
+            
+            // 只拖入一张图片且没有进行中的批量任务时直接识别
+            if paths.len() == 1 && !self.batch.is_active() {
+                let _ = self.tx.send(AppMessage::ImageSelected(paths.remove(0)));
+            } else if !paths.is_empty() {
+                let _ = self.tx.send(AppMessage::BatchQueued(paths));
+            }
+        }
+    }
+    
+    fn set_viewer_fit_lock(&mut self, locked: bool) {
+        if self.settings.viewer_fit_to_window != locked {
+            self.settings.viewer_fit_to_window = locked;
+            self.viewer_fit_key = None;
+            self.save_settings();
+        }
+    }
+    
+    // 在查看区域右下角绘制全图缩略图与当前可见区域，点击或拖动缩略图时把该处移到视图中央
+    fn show_viewer_minimap(
+        &mut self,
+        ui: &egui::Ui,
+        scroll_output: &egui::scroll_area::ScrollAreaOutput<()>,
+        (img_width, img_height): (u32, u32),
+        (rotated_width, rotated_height): (f32, f32),
+    ) {
+        const MINIMAP_MAX_SIDE: f32 = 160.0;
+        const MINIMAP_MARGIN: f32 = 12.0;
+        
+        let content = scroll_output.content_size;
+        let visible = scroll_output.inner_rect.size();
+        if content.x <= visible.x && content.y <= visible.y {
+            return;
+        }
+        let Some(texture) = self.image_display.get_texture() else {
+            return;
+        };
+        
+        let minimap_scale = MINIMAP_MAX_SIDE / rotated_width.max(rotated_height);
+        let size = egui::vec2(rotated_width, rotated_height) * minimap_scale;
+        let rect = egui::Rect::from_min_size(
+            scroll_output.inner_rect.right_bottom() - size - egui::Vec2::splat(MINIMAP_MARGIN),
+            size,
+        );
+        
+        let painter = ui.painter().with_clip_rect(scroll_output.inner_rect);
+        painter.rect_filled(rect.expand(2.0), 2.0, ui.visuals().extreme_bg_color);
+        egui::Image::from_texture(texture)
+            .rotate(self.image_rotation as f32 * std::f32::consts::FRAC_PI_2, egui::Vec2::splat(0.5))
+            .paint_at(ui, egui::Rect::from_center_size(rect.center(), egui::vec2(img_width as f32, img_height as f32) * minimap_scale));
+        
+        // 内容坐标到缩略图坐标的比例
+        let to_minimap = rect.width() / content.x;
+        let view = egui::Rect::from_min_size(
+            rect.min + scroll_output.state.offset * to_minimap,
+            visible * to_minimap,
+        )
+        .intersect(rect);
+        let accent = egui::Color32::from_rgb(255, 165, 0);
+        painter.rect_filled(view, 0.0, accent.gamma_multiply(0.15));
+        painter.rect_stroke(view, 0.0, egui::Stroke::new(1.5, accent), egui::StrokeKind::Inside);
+        
+        let response = ui
+            .interact(rect, ui.id().with("viewer_minimap"), egui::Sense::click_and_drag())
+            .on_hover_cursor(egui::CursorIcon::Crosshair);
+        if let Some(pointer) = response.interact_pointer_pos().filter(|_| response.clicked() || response.dragged()) {
+            let center = (pointer - rect.min) / to_minimap;
+            let max_offset = (content - visible).max(egui::Vec2::ZERO);
+            let offset = (center - visible / 2.0).clamp(egui::Vec2::ZERO, max_offset);
+            self.restore_viewer_offset = Some(offset);
+            ui.ctx().request_repaint();
         }
     }
     
     fn render_image_viewer(&mut self, ctx: &egui::Context) {
         if let Some(image) = &self.current_image {
             let (img_width, img_height) = image.dimensions();
+            let (rotated_width, rotated_height) = if self.image_rotation % 2 == 1 {
+                (img_height as f32, img_width as f32)
+            } else {
+                (img_width as f32, img_height as f32)
+            };
             
             egui::Window::new("🖼️ 图片查看器")
                 .default_size(egui::vec2(
-                    (img_width as f32 * 0.8).min(1200.0).max(600.0),
-                    (img_height as f32 * 0.8).min(800.0).max(400.0)
+                    (img_width as f32 * 0.8).clamp(600.0, 1200.0),
+                    (img_height as f32 * 0.8).clamp(400.0, 800.0)
                 ))
                 .resizable(true)
                 .collapsible(false)
                 .show(ctx, |ui| {
+                    let mut fit_now = false;
+                    
                     // 顶部控制栏
                     ui.horizontal(|ui| {
                         ui.label(format!("尺寸: {}×{}", img_width, img_height));
@@ -358,21 +3246,38 @@ impl OcrApp {
                         
                         // 缩放控制
                         ui.label("缩放:");
+                        let scale_before = self.image_scale;
                         if ui.button("🔍−").on_hover_text("缩小").clicked() {
-                            self.image_scale = (self.image_scale * 0.8).max(0.1);
+                            self.image_scale = self.settings.viewer_zoom.zoom_out(self.image_scale);
                         }
                         ui.label(format!("{:.0}%", self.image_scale * 100.0));
                         if ui.button("🔍+").on_hover_text("放大").clicked() {
-                            self.image_scale = (self.image_scale * 1.25).min(10.0);
+                            self.image_scale = self.settings.viewer_zoom.zoom_in(self.image_scale);
                         }
                         if ui.button("1:1").on_hover_text("原始大小").clicked() {
                             self.image_scale = 1.0;
                         }
+                        if self.image_scale != scale_before {
+                            // 手动缩放后不再自动适应窗口
+                            self.set_viewer_fit_lock(false);
+                        }
                         if ui.button("适应").on_hover_text("适应窗口").clicked() {
-                            let available_size = ui.available_size();
-                            let scale_x = (available_size.x - 40.0) / img_width as f32;
-                            let scale_y = (available_size.y - 100.0) / img_height as f32;
-                            self.image_scale = scale_x.min(scale_y).min(1.0);
+                            fit_now = true;
+                        }
+                        let mut fit_lock = self.settings.viewer_fit_to_window;
+                        if ui.checkbox(&mut fit_lock, "自适应窗口")
+                            .on_hover_text("调整窗口大小或旋转图片时自动重新适应窗口")
+                            .changed()
+                        {
+                            self.set_viewer_fit_lock(fit_lock);
+                        }
+                        
+                        ui.separator();
+                        if ui.button("⟲").on_hover_text("逆时针旋转 90°").clicked() {
+                            self.image_rotation = (self.image_rotation + 3) % 4;
+                        }
+                        if ui.button("⟳").on_hover_text("顺时针旋转 90°").clicked() {
+                            self.image_rotation = (self.image_rotation + 1) % 4;
                         }
                         
                         ui.separator();
@@ -381,39 +3286,64 @@ impl OcrApp {
                         }
                         
                         ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            // 关闭时保留缩放与滚动位置，重新打开时回到原处；加载新图片时才恢复该图片记录的状态
                             if ui.button("❌ 关闭").clicked() {
+                                self.remember_view_state();
                                 self.show_image_viewer = false;
-                                self.image_scale = 1.0; // 重置缩放
+                                self.restore_viewer_offset = Some(self.viewer_offset);
+                                self.viewer_fit_key = None;
                             }
                         });
                     });
                     
                     ui.separator();
                     
+                    // 为滚动条和底部提示留出空间后按可用区域适应
+                    let viewport = ui.available_size();
+                    let fit_key = (viewport, self.image_rotation);
+                    let locked = self.settings.viewer_fit_to_window;
+                    if fit_now || (locked && self.viewer_fit_key != Some(fit_key)) {
+                        let scale_x = (viewport.x - 20.0) / rotated_width;
+                        let scale_y = (viewport.y - 40.0) / rotated_height;
+                        self.image_scale = self.settings.viewer_zoom.clamp(scale_x.min(scale_y).min(1.0));
+                    }
+                    self.viewer_fit_key = locked.then_some(fit_key);
+                    
                     // 显示可缩放的图片
-                    egui::ScrollArea::both()
+                    let mut scroll_area = egui::ScrollArea::both()
                         .id_salt("image_viewer_scroll")
-                        .auto_shrink([false, false])
-                        .show(ui, |ui| {
+                        .auto_shrink([false, false]);
+                    if let Some(offset) = self.restore_viewer_offset.take() {
+                        scroll_area = scroll_area.scroll_offset(offset);
+                    }
+                    let scroll_output = scroll_area.show(ui, |ui| {
                             // 处理鼠标滚轮缩放
                             if ui.rect_contains_pointer(ui.max_rect()) {
                                 let scroll_delta = ui.ctx().input(|i| i.smooth_scroll_delta.y);
                                 if scroll_delta != 0.0 && ui.ctx().input(|i| i.modifiers.ctrl) {
                                     let zoom_factor = if scroll_delta > 0.0 { 1.1 } else { 0.9 };
-                                    self.image_scale = (self.image_scale * zoom_factor).clamp(0.1, 10.0);
+                                    self.image_scale = self.settings.viewer_zoom.clamp(self.image_scale * zoom_factor);
+                                    self.set_viewer_fit_lock(false);
                                 }
                             }
                             
                             if let Some(texture) = self.image_display.get_texture() {
-                                let scaled_width = img_width as f32 * self.image_scale;
-                                let scaled_height = img_height as f32 * self.image_scale;
+                                let scaled_size = egui::vec2(img_width as f32, img_height as f32) * self.image_scale;
                                 
-                                ui.add(
-                                    egui::Image::from_texture(texture)
-                                        .fit_to_exact_size(egui::vec2(scaled_width, scaled_height))
+                                // 按旋转后的尺寸占位，再绕中心旋转绘制
+                                let (rect, _) = ui.allocate_exact_size(
+                                    egui::vec2(rotated_width, rotated_height) * self.image_scale,
+                                    egui::Sense::hover(),
                                 );
+                                egui::Image::from_texture(texture)
+                                    .rotate(self.image_rotation as f32 * std::f32::consts::FRAC_PI_2, egui::Vec2::splat(0.5))
+                                    .paint_at(ui, egui::Rect::from_center_size(rect.center(), scaled_size));
                             }
                         });
+                    self.viewer_offset = scroll_output.state.offset;
+                    if self.settings.viewer_minimap {
+                        self.show_viewer_minimap(ui, &scroll_output, (img_width, img_height), (rotated_width, rotated_height));
+                    }
                         
                     // 底部提示
                     ui.horizontal(|ui| {
@@ -425,17 +3355,47 @@ impl OcrApp {
 }
 
 impl eframe::App for OcrApp {
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+    fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
         // 禁用egui的调试信息和警告显示
         ctx.options_mut(|opt| {
             opt.warn_on_id_clash = false;
         });
         
+        // F12 切换调试浮层
+        if ctx.input(|i| i.key_pressed(egui::Key::F12)) {
+            self.debug_overlay.toggle();
+        }
+        // F11 切换专注模式
+        if ctx.input(|i| i.key_pressed(egui::Key::F11)) {
+            self.focus_mode = !self.focus_mode;
+        }
+        self.debug_overlay.record(frame.info().cpu_usage);
+        
         // 处理异步消息
         self.handle_messages();
+        for entry in status_log::drain_mirrored() {
+            if self.settings.status_log.mirror_log {
+                self.status_display.push_log(entry);
+            }
+        }
+        
+        // 推进批量队列
+        self.drive_batch();
+        self.drive_clipboard();
         
         // 处理拖拽文件
         self.handle_drag_and_drop(ctx);
+        self.handle_page_keys(ctx);
+        // Ctrl+P（macOS 上为 Cmd+P）打开或关闭命令面板
+        if ctx.input_mut(|i| i.consume_key(egui::Modifiers::COMMAND, egui::Key::P)) {
+            self.command_palette = match self.command_palette {
+                Some(_) => None,
+                None => Some(CommandPalette::default()),
+            };
+        }
+        
+        // 处理粘贴的图片链接
+        self.handle_paste_url(ctx);
         
         // 设置主题
         if self.dark_mode {
@@ -444,29 +3404,51 @@ impl eframe::App for OcrApp {
             ctx.set_visuals(egui::Visuals::light());
         }
         
-        // 顶部面板
-        egui::TopBottomPanel::top("header").show(ctx, |ui| {
-            ui.add_space(8.0);
-            self.render_header(ui);
-            ui.add_space(4.0);
-            ui.separator();
-            ui.add_space(4.0);
-            self.render_toolbar(ui);
-            ui.add_space(8.0);
-        });
-        
-        // 底部状态栏
-        egui::TopBottomPanel::bottom("status").show(ctx, |ui| {
-            ui.add_space(4.0);
-            ui.separator();
-            ui.add_space(4.0);
-            self.render_status_bar(ui);
-            ui.add_space(8.0);
-        });
+        // 专注模式下不显示顶部面板、状态栏与图片区域
+        if !self.focus_mode {
+            // 顶部面板
+            egui::TopBottomPanel::top("header").show(ctx, |ui| {
+                ui.add_space(8.0);
+                self.render_header(ui);
+                ui.add_space(4.0);
+                ui.separator();
+                ui.add_space(4.0);
+                self.render_toolbar(ui);
+                ui.add_space(8.0);
+            });
+            
+            // 底部状态栏
+            egui::TopBottomPanel::bottom("status").show(ctx, |ui| {
+                ui.add_space(4.0);
+                ui.separator();
+                ui.add_space(4.0);
+                self.render_status_bar(ui);
+                ui.add_space(8.0);
+            });
+            
+            // 状态栏上方的日志面板
+            if self.show_status_log {
+                egui::TopBottomPanel::bottom("status_log")
+                    .resizable(true)
+                    .default_height(160.0)
+                    .show(ctx, |ui| {
+                        self.status_display.show_log(ui);
+                    });
+            }
+        }
         
         // 主内容区域
         egui::CentralPanel::default().show(ctx, |ui| {
-            ui.add_space(10.0);
+            if self.focus_mode {
+                ui.horizontal(|ui| {
+                    if ui.small_button("退出专注模式 (F11)").clicked() {
+                        self.focus_mode = false;
+                    }
+                });
+            } else {
+                ui.add_space(10.0);
+                self.render_engine_banner(ui);
+            }
             egui::ScrollArea::both()
                 .id_salt("main_content_scroll")
                 .auto_shrink([false, false])
@@ -481,7 +3463,170 @@ impl eframe::App for OcrApp {
                 .collapsible(false)
                 .resizable(false)
                 .show(ctx, |ui| {
-                    ui.checkbox(&mut self.dark_mode, "深色主题");
+                    // 设置项较多，超出屏幕高度时滚动，关闭按钮始终可见
+                    egui::ScrollArea::vertical()
+                        .id_salt("settings_scroll")
+                        .max_height(ctx.screen_rect().height() * 0.75)
+                        .show(ui, |ui| {
+                            ui.checkbox(&mut self.dark_mode, "深色主题");
+                            if ui.checkbox(&mut self.settings.continuous_repaint, "性能模式")
+                                .on_hover_text("每帧持续刷新界面，响应更及时但会一直占用 CPU；关闭时空闲状态下不重绘")
+                                .changed()
+                            {
+                                self.save_settings();
+                            }
+                            if ui.checkbox(&mut self.settings.copy_on_select, "选中即复制")
+                                .on_hover_text("在可编辑的识别结果中选中文字后松开鼠标，自动复制到剪贴板")
+                                .changed()
+                            {
+                                self.result_panel.set_copy_on_select(self.settings.copy_on_select);
+                                self.save_settings();
+                            }
+                            self.render_status_log_settings(ui);
+                            self.render_export_settings(ui);
+                            self.render_post_ocr_hook_settings(ui);
+                            self.render_live_capture_settings(ui);
+                            ui.horizontal(|ui| {
+                                ui.label("预览衬底:");
+                                ui.radio_value(&mut self.preview_backdrop, Backdrop::None, "无");
+                                ui.radio_value(&mut self.preview_backdrop, Backdrop::Checkerboard, "棋盘格");
+                                ui.radio_value(&mut self.preview_backdrop, Backdrop::NeutralGray, "中性灰");
+                            });
+                            ui.horizontal(|ui| {
+                                ui.label("放大镜:");
+                                ui.add(egui::Slider::new(&mut self.loupe_options.diameter, 80.0..=320.0).text("直径"));
+                                ui.add(egui::Slider::new(&mut self.loupe_options.zoom, 1.5..=8.0).text("倍数"));
+                            });
+                            self.render_viewer_zoom_settings(ui);
+                            self.render_gray_window_settings(ui);
+                            // 环境变量关闭演示模式时优先于设置，不能在界面中重新打开
+                            if ui.add_enabled(ocr::demo_mode_allowed(), egui::Checkbox::new(&mut self.demo_mode, "允许演示模式"))
+                                .on_hover_text(format!(
+                                    "没有真实引擎时输出模拟文本；关闭后识别将直接报错（也可设置环境变量 {}=1）",
+                                    ocr::DISABLE_DEMO_ENV
+                                ))
+                                .on_disabled_hover_text(format!("已由环境变量 {} 关闭", ocr::DISABLE_DEMO_ENV))
+                                .changed()
+                            {
+                                self.settings.demo_mode_disabled = !self.demo_mode;
+                                self.save_settings();
+                                self.reload_engine();
+                            }
+                            ui.separator();
+                    
+                                    self.render_profile_settings(ui);
+                            ui.separator();
+                    
+                                    self.render_engine_settings(ui);
+                            ui.checkbox(&mut self.process_options.auto_language, "自动检测语言")
+                                .on_hover_text("根据初步识别结果判断文字语言；使用 Tesseract 时会用检测到的语言包重新识别，无法判断时沿用当前语言");
+                            ui.checkbox(&mut self.process_options.tiling.enabled, "分块识别")
+                                .on_hover_text("长宽比超过 3:1 的图片（全景图、长截图、收据）切成相互重叠的小块分别识别后合并");
+                            let panels = &mut self.process_options.panels;
+                            ui.horizontal(|ui| {
+                                ui.checkbox(&mut panels.enabled, "自动分割文本块")
+                                    .on_hover_text("截图中被大片空白隔开的多个面板（仪表盘、对比截图）分别识别，结果按“区块 n”分节显示");
+                                ui.add_enabled(panels.enabled, egui::Slider::new(&mut panels.min_gap, 10..=300).suffix(" px").text("分隔空白"))
+                                    .on_hover_text("空白宽度达到该值才视为文本块之间的分隔，块内的行距与列间距应小于该值");
+                            });
+                            let box_filter = &mut self.process_options.box_filter;
+                            ui.checkbox(&mut box_filter.enabled, "去除噪点检测框")
+                                .on_hover_text("丢弃高度或面积过小的检测框（多为扫描斑点），并按剩余检测框重建文本");
+                            ui.add_enabled_ui(box_filter.enabled, |ui| {
+                                ui.add(egui::Slider::new(&mut box_filter.min_box_height, 0.0..=0.05).text("最小高度"))
+                                    .on_hover_text("检测框高度低于图片高度的该比例时丢弃");
+                                ui.add(egui::Slider::new(&mut box_filter.min_box_area, 0.0..=0.001).text("最小面积"))
+                                    .on_hover_text("检测框面积低于图片高度平方的该比例时丢弃");
+                            });
+                            ui.separator();
+                    
+                                    // 图像预处理
+                            let preprocess = &mut self.process_options.preprocess;
+                            ui.strong("图像预处理");
+                            ui.horizontal(|ui| {
+                                ui.label("二值化:");
+                                ui.radio_value(&mut preprocess.binarize, BinarizeMethod::None, "无");
+                                ui.radio_value(&mut preprocess.binarize, BinarizeMethod::Otsu, "Otsu 全局阈值");
+                                ui.radio_value(&mut preprocess.binarize, BinarizeMethod::Adaptive, "自适应阈值");
+                            });
+                            if preprocess.binarize == BinarizeMethod::Adaptive {
+                                ui.horizontal(|ui| {
+                                    ui.label("邻域大小:");
+                                    ui.add(egui::Slider::new(&mut preprocess.block_size, 3..=101).step_by(2.0));
+                                });
+                                ui.horizontal(|ui| {
+                                    ui.label("阈值偏移:");
+                                    ui.add(egui::Slider::new(&mut preprocess.adaptive_c, -20..=40));
+                                });
+                            }
+                            self.render_levels_settings(ui);
+                            render_hue_filter_settings(ui, &mut self.process_options.preprocess.hue_filter);
+                            ui.separator();
+                    
+                                    // 检测框样式与标注图导出
+                            ui.strong("检测框样式");
+                            self.render_overlay_style_settings(ui);
+                            ui.strong("标注图导出");
+                            ui.checkbox(&mut self.annotate_options.include_confidence, "包含置信度");
+                            ui.horizontal(|ui| {
+                                ui.label("热力图:");
+                                ui.add(egui::Slider::new(&mut self.heatmap_options.opacity, 0.1..=1.0).text("不透明度"));
+                                for colormap in Colormap::ALL {
+                                    ui.radio_value(&mut self.heatmap_options.colormap, colormap, colormap.label());
+                                }
+                            });
+                            ui.separator();
+                    
+                                    // 空白页检测
+                            ui.strong("空白页检测");
+                            ui.add(egui::Slider::new(&mut self.blank_page.min_chars, 0..=50).text("字符数"))
+                                .on_hover_text("识别出的非空白字符少于该数量时视为空白页，0 表示不检测");
+                            ui.checkbox(&mut self.blank_page.skip_in_exports, "导出时跳过空白页");
+                            ui.separator();
+                    
+                                    // 低质量结果提示
+                            ui.strong("低质量提示");
+                            ui.checkbox(&mut self.quality_gate.enabled, "置信度过低时提示重新识别")
+                                .on_hover_text("单张识别的置信度低于门槛时，在结果上方给出调整预处理或引擎后重试的选项");
+                            ui.add_enabled(
+                                self.quality_gate.enabled,
+                                egui::Slider::new(&mut self.quality_gate.min_confidence, 0.0..=1.0).text("置信度门槛"),
+                            );
+                            ui.separator();
+                    
+                                    // 重复页面检测
+                            ui.strong("重复页面检测");
+                            ui.checkbox(&mut self.duplicate_options.enabled, "批量识别时跳过重复页面")
+                                .on_hover_text("按感知哈希比较图片，与本批已处理页面几乎相同的图片不识别也不导出");
+                            ui.add_enabled(
+                                self.duplicate_options.enabled,
+                                egui::Slider::new(&mut self.duplicate_options.max_distance, 0..=16).text("允许差异（位）"),
+                            )
+                            .on_hover_text("64 位哈希中允许不同的位数，越大越容易判为重复；0 表示只跳过几乎完全相同的图片");
+                            ui.separator();
+                    
+                                    // 文本后处理
+                            let previous_postprocess = self.process_options.postprocess.clone();
+                            let postprocess = &mut self.process_options.postprocess;
+                            ui.strong("文本后处理");
+                            ui.checkbox(&mut postprocess.enabled, "启用后处理");
+                            ui.add_enabled_ui(postprocess.enabled, |ui| {
+                                ui.checkbox(&mut postprocess.strip_trailing_spaces, "去除行尾空格");
+                                ui.checkbox(&mut postprocess.collapse_blank_lines, "合并连续空行");
+                                ui.checkbox(&mut postprocess.normalize_width, "规范全角/半角标点与数字");
+                                ui.checkbox(&mut postprocess.merge_paragraphs, "合并为段落")
+                                    .on_hover_text("把扫描时的硬换行合并为段落，行末是句末标点或与下一行间距较大时分段；结果面板中勾选“显示原始文本”可查看逐行版本");
+                                let width = &mut postprocess.width;
+                                ui.horizontal(|ui| {
+                                    width_mode_combo(ui, "width_digits", "数字", &mut width.digits);
+                                    width_mode_combo(ui, "width_letters", "字母", &mut width.letters);
+                                    width_mode_combo(ui, "width_punctuation", "标点", &mut width.punctuation);
+                                });
+                            });
+                            if self.process_options.postprocess != previous_postprocess {
+                                self.reapply_postprocess();
+                            }
+                        });
                     ui.separator();
                     if ui.button("关闭").clicked() {
                         self.show_settings = false;
@@ -489,12 +3634,141 @@ impl eframe::App for OcrApp {
                 });
         }
         
+        // 批量识别汇总窗口
+        self.render_batch_summary(ctx);
+        self.render_evaluation(ctx);
+        self.render_batch_memory_warning(ctx);
+        self.render_export_plan(ctx);
+        
+        // URL 输入窗口
+        if self.show_url_dialog {
+            self.render_url_dialog(ctx);
+        }
+        
+        // 命令面板
+        if self.command_palette.is_some() {
+            self.render_command_palette(ctx);
+        }
+        
         // 图片查看器窗口
         if self.show_image_viewer {
             self.render_image_viewer(ctx);
         }
         
-        // 请求重绘
-        ctx.request_repaint();
+        let state = format!("{:?}", self.state);
+        self.debug_overlay.show(ctx, &state);
+        
+        // 仅在识别进行中、批量队列未处理完（或开启性能模式）时持续重绘，空闲时由输入事件和后台消息触发重绘。
+        // 批量中的图片加载失败时不会发出后台消息，需要下一帧由 drive_batch 取出下一张；队列刚处理完时再绘制一帧以弹出汇总
+        let batch_running = (self.batch.is_active() || self.batch_was_active) && !self.batch.is_paused();
+        if self.settings.continuous_repaint || batch_running || matches!(self.state, AppState::Loading | AppState::Processing) {
+            ctx.request_repaint();
+        }
+    }
+}
+
+// 全角/半角转换方向的下拉框
+fn width_mode_combo(ui: &mut egui::Ui, id: &str, label: &str, mode: &mut WidthMode) {
+    ui.label(format!("{}:", label));
+    egui::ComboBox::from_id_salt(id)
+        .selected_text(mode.label())
+        .width(60.0)
+        .show_ui(ui, |ui| {
+            for option in WidthMode::ALL {
+                ui.selectable_value(mode, option, option.label());
+            }
+        });
+}
+
+// 带时间的识别记录，可逐条或全部复制；返回是否点击了清空
+fn render_text_log(ui: &mut egui::Ui, title: &str, id: &str, log: &[(String, String)]) -> bool {
+    if log.is_empty() {
+        return false;
+    }
+
+    ui.add_space(8.0);
+    let mut clear = false;
+    ui.push_id(id, |ui| {
+        ui.collapsing(format!("{}（{}）", title, log.len()), |ui| {
+            egui::ScrollArea::vertical()
+                .id_salt(id)
+                .max_height(200.0)
+                .stick_to_bottom(true)
+                .show(ui, |ui| {
+                    for (time, text) in log {
+                        ui.horizontal(|ui| {
+                            ui.weak(time);
+                            if ui.small_button("📋").on_hover_text("复制").clicked() {
+                                ui.ctx().copy_text(text.clone());
+                            }
+                        });
+                        ui.label(text);
+                        ui.separator();
+                    }
+                });
+            ui.horizontal(|ui| {
+                if ui.button("复制全部").clicked() {
+                    let all = log.iter().map(|(_, text)| text.as_str()).collect::<Vec<_>>().join("\n\n");
+                    ui.ctx().copy_text(all);
+                }
+                if ui.button("清空").clicked() {
+                    clear = true;
+                }
+            });
+        });
+    });
+    clear
+}
+
+// 颜色过滤：去掉或单独保留某一色相范围（如红色印章）
+fn render_hue_filter_settings(ui: &mut egui::Ui, filter: &mut HueFilter) {
+    ui.horizontal(|ui| {
+        ui.label("颜色过滤:");
+        ui.radio_value(&mut filter.mode, HueFilterMode::Off, "无");
+        ui.radio_value(&mut filter.mode, HueFilterMode::Remove, "去除该颜色")
+            .on_hover_text("例如去掉压在黑色文字上的红色公章");
+        ui.radio_value(&mut filter.mode, HueFilterMode::Keep, "只保留该颜色");
+        if ui.button("🖊 只识别蓝色墨迹")
+            .on_hover_text("去掉表格中预印的黑色线条和文字，只识别用蓝色笔填写的内容")
+            .clicked()
+        {
+            *filter = HueFilter::blue_ink();
+        }
+        if ui.add_enabled(filter.mode != HueFilterMode::Off, egui::Button::new("重置")).clicked() {
+            *filter = HueFilter::default();
+        }
+    });
+    if filter.mode == HueFilterMode::Off {
+        return;
+    }
+    
+    let swatch = |ui: &mut egui::Ui, hue: f32| {
+        let color = egui::ecolor::Hsva::new(hue / 360.0, 1.0, 1.0, 1.0);
+        let (rect, _) = ui.allocate_exact_size(egui::vec2(14.0, 14.0), egui::Sense::hover());
+        ui.painter().rect_filled(rect, 2.0, color);
+    };
+    // 以目标色相和容差调整，内部仍保存为色相起点与终点
+    let mut center = filter.center();
+    let mut tolerance = filter.tolerance();
+    let mut changed = false;
+    ui.horizontal(|ui| {
+        ui.label("目标色相:");
+        changed |= ui.add(egui::Slider::new(&mut center, 0.0..=360.0).suffix("°")).changed();
+        swatch(ui, center);
+    });
+    ui.horizontal(|ui| {
+        ui.label("容差:");
+        changed |= ui.add(egui::Slider::new(&mut tolerance, 1.0..=179.0).suffix("°")).changed();
+        swatch(ui, filter.hue_start);
+        ui.weak("~");
+        swatch(ui, filter.hue_end);
+    });
+    if changed {
+        filter.set_center(center, tolerance);
     }
-} 
\ No newline at end of file
+    ui.horizontal(|ui| {
+        ui.label("最低饱和度:");
+        ui.add(egui::Slider::new(&mut filter.min_saturation, 0.0..=1.0))
+            .on_hover_text("饱和度低于该值的黑、白、灰像素不受颜色过滤影响");
+    });
+}
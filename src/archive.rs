@@ -0,0 +1,208 @@
+use std::cmp::Ordering;
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::{Path, PathBuf};
+use anyhow::{anyhow, bail, Result};
+use zip::result::ZipError;
+use zip::ZipArchive;
+
+use crate::loader;
+
+// 可直接打开的图片压缩包：漫画常用的 cbz 即 zip
+pub const ARCHIVE_EXTENSIONS: &[&str] = &["zip", "cbz"];
+
+// 预先分配的上限：大小字段来自文件本身，不能直接信任
+const MAX_PREALLOCATE: u64 = 16 * 1024 * 1024;
+
+pub fn is_archive(path: &Path) -> bool {
+    path.extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .is_some_and(|ext| ARCHIVE_EXTENSIONS.contains(&ext.as_str()))
+}
+
+fn open(archive: &Path) -> Result<ZipArchive<BufReader<File>>> {
+    ZipArchive::new(BufReader::new(File::open(archive)?)).map_err(|e| anyhow!("不是有效的 zip 压缩包: {}", e))
+}
+
+// 列出压缩包中的图片，按自然顺序排序（page2 在 page10 之前）。
+// 返回的路径形如 book.cbz/001.jpg，加载时从压缩包中直接读取该项，无需先解压
+pub fn list_images(archive: &Path) -> Result<Vec<PathBuf>> {
+    let zip = open(archive)?;
+    let mut names: Vec<String> = zip
+        .file_names()
+        .filter_map(|name| name.ok())
+        .filter(|name| !name.ends_with('/') && !name.starts_with("__MACOSX/"))
+        .filter(|name| loader::is_supported_image(Path::new(name.as_ref())))
+        .map(|name| name.into_owned())
+        .collect();
+    names.sort_by(|a, b| natural_cmp(a, b));
+    if names.is_empty() {
+        bail!("压缩包中没有可识别的图片");
+    }
+    Ok(names.into_iter().map(|name| archive.join(name)).collect())
+}
+
+// 把 list_images 返回的路径拆分为压缩包路径与包内名称；普通文件返回 None
+pub fn split(path: &Path) -> Option<(&Path, String)> {
+    if path.exists() {
+        return None;
+    }
+    let archive = path.ancestors().skip(1).find(|ancestor| is_archive(ancestor) && ancestor.is_file())?;
+    let name = path
+        .strip_prefix(archive)
+        .ok()?
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/");
+    Some((archive, name))
+}
+
+// 读取文件内容；压缩包中的图片从压缩包读出
+pub fn read(path: &Path) -> Result<Vec<u8>> {
+    match split(path) {
+        Some((archive, name)) => read_entry(archive, &name),
+        None => Ok(std::fs::read(path)?),
+    }
+}
+
+// 实际存在于磁盘上的文件：压缩包中的图片为压缩包本身
+pub fn source_file(path: &Path) -> &Path {
+    split(path).map_or(path, |(archive, _)| archive)
+}
+
+// 图片所在的目录；压缩包中的图片为压缩包所在的目录，用于在“源文件旁边”保存结果
+pub fn containing_dir(path: &Path) -> Option<&Path> {
+    match split(path) {
+        Some((archive, _)) => archive.parent(),
+        None => path.parent(),
+    }
+}
+
+fn read_entry(archive: &Path, name: &str) -> Result<Vec<u8>> {
+    let mut zip = open(archive)?;
+    let mut entry = zip.by_name(name).map_err(|e| match e {
+        ZipError::FileNotFound => anyhow!("压缩包中找不到 {}", name),
+        e => anyhow!("无法读取压缩包中的 {}: {}", name, e),
+    })?;
+    // 最多读出声明的大小再多 1 字节，超出即视为损坏，避免解压炸弹占满内存
+    let size = entry.size();
+    let mut data = Vec::with_capacity(size.min(MAX_PREALLOCATE) as usize);
+    (&mut entry).take(size.saturating_add(1)).read_to_end(&mut data)?;
+    if data.len() as u64 != size {
+        bail!("压缩包已损坏：{} 不完整", name);
+    }
+    Ok(data)
+}
+
+// 自然排序：连续数字按数值比较，其余字符不区分大小写
+fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let mut a = a.chars().peekable();
+    let mut b = b.chars().peekable();
+    loop {
+        match (a.peek().copied(), b.peek().copied()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let take_number = |chars: &mut std::iter::Peekable<std::str::Chars>| {
+                    let mut digits = String::new();
+                    while let Some(c) = chars.next_if(char::is_ascii_digit) {
+                        digits.push(c);
+                    }
+                    digits.trim_start_matches('0').to_string()
+                };
+                let (x, y) = (take_number(&mut a), take_number(&mut b));
+                let ordering = x.len().cmp(&y.len()).then_with(|| x.cmp(&y));
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+            (Some(x), Some(y)) => {
+                let ordering = x.to_lowercase().cmp(y.to_lowercase());
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+                a.next();
+                b.next();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+    use zip::{CompressionMethod, ZipWriter};
+
+    // deflate 为 true 时压缩存储
+    fn write_zip(path: &Path, files: &[(&str, &[u8], bool)]) {
+        let mut writer = ZipWriter::new(File::create(path).unwrap());
+        for (name, content, deflate) in files {
+            let method = if *deflate { CompressionMethod::Deflated } else { CompressionMethod::Stored };
+            writer.start_file(*name, SimpleFileOptions::default().compression_method(method)).unwrap();
+            writer.write_all(content).unwrap();
+        }
+        writer.finish().unwrap();
+    }
+
+    #[test]
+    fn test_natural_order() {
+        let mut names = vec!["page10.png", "Page2.png", "page1.png", "cover.jpg", "page02b.png"];
+        names.sort_by(|a, b| natural_cmp(a, b));
+        assert_eq!(names, vec!["cover.jpg", "page1.png", "Page2.png", "page02b.png", "page10.png"]);
+    }
+
+    #[test]
+    fn test_list_and_read_images_in_archive() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join("book.cbz");
+        let mut png = Vec::new();
+        image::DynamicImage::new_rgb8(3, 2)
+            .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+            .unwrap();
+        write_zip(&archive, &[
+            ("p10.png", &png, true),
+            ("notes.txt", b"not an image", false),
+            ("ch1/p2.png", &png, false),
+        ]);
+
+        let pages = list_images(&archive).unwrap();
+        assert_eq!(pages, vec![archive.join("ch1/p2.png"), archive.join("p10.png")]);
+        assert_eq!(split(&pages[0]), Some((archive.as_path(), "ch1/p2.png".to_string())));
+        assert_eq!(containing_dir(&pages[0]), Some(dir.path()));
+        assert_eq!(source_file(&pages[0]), archive.as_path());
+        assert_eq!(source_file(&archive), archive.as_path());
+        assert_eq!(split(&archive), None);
+
+        for page in &pages {
+            assert_eq!(read(page).unwrap(), png);
+        }
+        assert!(read(&archive.join("missing.png")).is_err());
+    }
+
+    #[test]
+    fn test_corrupt_archives_are_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join("book.cbz");
+        let content = vec![7u8; 1000];
+        write_zip(&archive, &[("p1.png", &content, false)]);
+        let data = std::fs::read(&archive).unwrap();
+
+        // 截断后找不到中央目录
+        std::fs::write(&archive, &data[..data.len() / 2]).unwrap();
+        assert!(list_images(&archive).is_err());
+
+        // 内容被改动时校验和不符
+        let mut corrupted = data.clone();
+        let offset = corrupted.windows(4).position(|window| window == [7, 7, 7, 7]).unwrap();
+        corrupted[offset] = 8;
+        std::fs::write(&archive, &corrupted).unwrap();
+        assert!(read(&archive.join("p1.png")).is_err());
+
+        std::fs::write(&archive, b"not a zip").unwrap();
+        assert!(list_images(&archive).is_err());
+    }
+}
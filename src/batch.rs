@@ -0,0 +1,1216 @@
+use std::collections::HashMap;
+use std::io::{Cursor, Write};
+use std::path::{Path, PathBuf};
+use image::ImageReader;
+use serde::{Deserialize, Serialize};
+
+use crate::archive;
+use crate::duplicate::DuplicateOptions;
+use crate::ocr::{OcrResult, ProcessOptions};
+use crate::preprocess::PreprocessOptions;
+
+// 出现在多少比例的页面上才视为页眉/页脚
+const REPEATED_LINE_MIN_RATIO: f32 = 0.6;
+// 每张图片在内存中的解码副本数：原图、显示用副本与纹理
+const COPIES_PER_IMAGE: u64 = 3;
+// 估计的批量内存占用超过该值时提示使用低内存模式
+pub const MEMORY_WARNING_BYTES: u64 = 2 * 1024 * 1024 * 1024;
+
+// 空白页检测：识别出的非空白字符少于 min_chars 时视为空白页
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BlankPageOptions {
+    pub min_chars: usize,
+    // 导出时跳过空白页
+    pub skip_in_exports: bool,
+}
+
+impl Default for BlankPageOptions {
+    fn default() -> Self {
+        Self {
+            min_chars: 5,
+            skip_in_exports: true,
+        }
+    }
+}
+
+impl BlankPageOptions {
+    pub fn is_blank(&self, result: &OcrResult) -> bool {
+        result.text.chars().filter(|c| !c.is_whitespace()).take(self.min_chars).count() < self.min_chars
+    }
+}
+
+// 批量结果列表的显示筛选
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ResultFilter {
+    pub hide_blank: bool,
+    pub hide_failed: bool,
+}
+
+// 批量结果列表中的多选：Ctrl 点击切换单项，Shift 点击选中从上次点击项到当前项的范围，
+// 普通点击只选中该项。按路径记录，列表筛选或删除后仍然有效
+#[derive(Debug, Clone, Default)]
+pub struct ListSelection {
+    selected: Vec<PathBuf>,
+    // 上次普通点击或 Ctrl 点击的项，作为 Shift 范围选择的起点
+    anchor: Option<PathBuf>,
+}
+
+impl ListSelection {
+    // items 为列表中当前显示的项，按显示顺序
+    pub fn click(&mut self, items: &[&Path], index: usize, ctrl: bool, shift: bool) {
+        let Some(&path) = items.get(index) else {
+            return;
+        };
+        let anchor = self.anchor.as_deref().and_then(|anchor| items.iter().position(|item| *item == anchor));
+        match (shift, anchor) {
+            (true, Some(anchor)) => {
+                if !ctrl {
+                    self.selected.clear();
+                }
+                for item in &items[anchor.min(index)..=anchor.max(index)] {
+                    if !self.contains(item) {
+                        self.selected.push(item.to_path_buf());
+                    }
+                }
+                return;
+            }
+            _ if ctrl => match self.selected.iter().position(|selected| selected == path) {
+                Some(position) => {
+                    self.selected.remove(position);
+                }
+                None => self.selected.push(path.to_path_buf()),
+            },
+            _ => self.selected = vec![path.to_path_buf()],
+        }
+        self.anchor = Some(path.to_path_buf());
+    }
+
+    pub fn contains(&self, path: &Path) -> bool {
+        self.selected.iter().any(|selected| selected == path)
+    }
+
+    pub fn len(&self) -> usize {
+        self.selected.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.selected.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.selected.clear();
+        self.anchor = None;
+    }
+}
+
+// 批量识别队列：按顺序逐个处理待识别图片
+pub struct BatchQueue {
+    pending: Vec<PathBuf>,
+    current: Option<PathBuf>,
+    results: Vec<(PathBuf, OcrResult)>,
+    // 每个结果识别时实际使用的选项，写入清单时以此为准而非导出时的设置
+    options: HashMap<PathBuf, ProcessOptions>,
+    failed: Vec<(PathBuf, String)>,
+    // 暂停后当前项照常完成，但不再取出新的待处理项
+    paused: bool,
+    // 低内存模式：每张图片识别完成后立即释放图片与纹理，只保留识别结果
+    low_memory: bool,
+    // 从结果列表中删除的项，按删除顺序保存以便撤销。删除与筛选只影响视图（翻页、导出与汇总），
+    // results 与 failed 保持不变，开始新的一批之前都可以恢复
+    deleted: Vec<PathBuf>,
+    filter: ResultFilter,
+    // 本批已处理页面的差异哈希，以及被跳过的重复页面与其对应的原页面
+    hashes: Vec<(PathBuf, u64)>,
+    duplicates: Vec<(PathBuf, PathBuf)>,
+}
+
+impl BatchQueue {
+    pub fn new() -> Self {
+        Self {
+            pending: Vec::new(),
+            current: None,
+            results: Vec::new(),
+            options: HashMap::new(),
+            failed: Vec::new(),
+            paused: false,
+            low_memory: false,
+            deleted: Vec::new(),
+            filter: ResultFilter::default(),
+            hashes: Vec::new(),
+            duplicates: Vec::new(),
+        }
+    }
+
+    // 加入队列并跳过已在队列中的图片，返回实际添加的数量
+    pub fn enqueue(&mut self, paths: impl IntoIterator<Item = PathBuf>) -> usize {
+        // 上一批已全部完成时开始新的一批
+        if !self.is_active() {
+            self.results.clear();
+            self.options.clear();
+            self.failed.clear();
+            self.deleted.clear();
+            self.hashes.clear();
+            self.duplicates.clear();
+            self.paused = false;
+            self.low_memory = false;
+        }
+
+        let mut added = 0;
+        for path in paths {
+            if self.current.as_ref() == Some(&path) || self.pending.contains(&path) {
+                continue;
+            }
+            self.pending.push(path);
+            added += 1;
+        }
+        added
+    }
+
+    // 取出下一个待处理项作为当前项
+    pub fn next(&mut self) -> Option<PathBuf> {
+        if self.paused || self.current.is_some() || self.pending.is_empty() {
+            return None;
+        }
+        let path = self.pending.remove(0);
+        self.current = Some(path.clone());
+        Some(path)
+    }
+
+    pub fn complete(&mut self, result: OcrResult, options: ProcessOptions) {
+        if let Some(path) = self.current.take() {
+            self.options.insert(path.clone(), options);
+            self.results.push((path, result));
+        }
+    }
+
+    // 某个源文件的识别结果与识别时使用的选项
+    pub fn result_with_options(&self, path: &Path) -> Option<(&OcrResult, &ProcessOptions)> {
+        let (_, result) = self.results.iter().find(|(source, _)| source == path)?;
+        Some((result, self.options.get(path)?))
+    }
+
+    pub fn fail(&mut self, error: String) {
+        if let Some(path) = self.current.take() {
+            self.failed.push((path, error));
+        }
+    }
+
+    // 当前项与本批已处理的某页几乎相同时跳过当前项（不识别、不导出）并返回原页面，
+    // 否则记录当前项的哈希供后续页面比较
+    pub fn check_duplicate(&mut self, hash: u64, options: &DuplicateOptions) -> Option<PathBuf> {
+        let current = self.current.as_ref()?;
+        match self.hashes.iter().find(|(_, seen)| options.is_duplicate(*seen, hash)) {
+            Some((original, _)) => {
+                let original = original.clone();
+                self.duplicates.push((self.current.take()?, original.clone()));
+                Some(original)
+            }
+            None => {
+                self.hashes.push((current.clone(), hash));
+                None
+            }
+        }
+    }
+
+    // 跳过的重复页面，按原页面分组，保持原页面的处理顺序
+    pub fn duplicate_groups(&self) -> Vec<(&PathBuf, Vec<&PathBuf>)> {
+        let mut groups: Vec<(&PathBuf, Vec<&PathBuf>)> = Vec::new();
+        for (duplicate, original) in &self.duplicates {
+            match groups.iter_mut().find(|(path, _)| *path == original) {
+                Some((_, duplicates)) => duplicates.push(duplicate),
+                None => groups.push((original, vec![duplicate])),
+            }
+        }
+        groups
+    }
+
+    pub fn duplicate_count(&self) -> usize {
+        self.duplicates.len()
+    }
+
+    pub fn failed_count(&self) -> usize {
+        self.failed.len()
+    }
+
+    // 本批完成后只把失败项重新放入队列，成功的结果保留；已从列表中删除的失败项不重试。
+    // 返回重新排队的数量
+    pub fn retry_failed(&mut self) -> usize {
+        if self.is_active() {
+            return 0;
+        }
+        let (retry, kept): (Vec<_>, Vec<_>) = std::mem::take(&mut self.failed)
+            .into_iter()
+            .partition(|(path, _)| !self.deleted.contains(path));
+        self.failed = kept;
+        // 识别前已记录的哈希会让重试项被当作自身的重复页面
+        self.hashes.retain(|(path, _)| !retry.iter().any(|(failed, _)| failed == path));
+        self.paused = false;
+        self.pending.extend(retry.into_iter().map(|(path, _)| path));
+        self.pending.len()
+    }
+
+    pub fn remove(&mut self, index: usize) {
+        if index < self.pending.len() {
+            self.pending.remove(index);
+        }
+    }
+
+    // 调整待处理项的顺序，to 为移动后的位置
+    pub fn move_item(&mut self, from: usize, to: usize) {
+        if from >= self.pending.len() || from == to {
+            return;
+        }
+        let path = self.pending.remove(from);
+        let to = to.min(self.pending.len());
+        self.pending.insert(to, path);
+    }
+
+    pub fn clear(&mut self) {
+        self.pending.clear();
+        self.paused = false;
+    }
+
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn set_low_memory(&mut self, low_memory: bool) {
+        self.low_memory = low_memory;
+    }
+
+    pub fn is_low_memory(&self) -> bool {
+        self.low_memory
+    }
+
+    // 未删除且未被筛选隐藏的识别结果
+    pub fn visible_results(&self, blank: &BlankPageOptions) -> Vec<&(PathBuf, OcrResult)> {
+        self.results
+            .iter()
+            .filter(|(path, result)| !(self.deleted.contains(path) || self.filter.hide_blank && blank.is_blank(result)))
+            .collect()
+    }
+
+    pub fn visible_failed(&self) -> Vec<&(PathBuf, String)> {
+        if self.filter.hide_failed {
+            return Vec::new();
+        }
+        self.failed.iter().filter(|(path, _)| !self.deleted.contains(path)).collect()
+    }
+
+    // 已完成但因删除或筛选不显示的项数，不含跳过的重复页面
+    pub fn hidden_count(&self, blank: &BlankPageOptions) -> usize {
+        self.finished_count() - self.duplicates.len() - self.visible_results(blank).len() - self.visible_failed().len()
+    }
+
+    pub fn delete(&mut self, path: &Path) {
+        if !self.deleted.iter().any(|deleted| deleted == path) {
+            self.deleted.push(path.to_path_buf());
+        }
+    }
+
+    // 恢复最近删除的一项
+    pub fn undo_delete(&mut self) -> Option<PathBuf> {
+        self.deleted.pop()
+    }
+
+    pub fn deleted_count(&self) -> usize {
+        self.deleted.len()
+    }
+
+    pub fn filter(&self) -> ResultFilter {
+        self.filter
+    }
+
+    pub fn set_filter(&mut self, filter: ResultFilter) {
+        self.filter = filter;
+    }
+
+    pub fn pending(&self) -> &[PathBuf] {
+        &self.pending
+    }
+
+    pub fn current(&self) -> Option<&PathBuf> {
+        self.current.as_ref()
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.current.is_some() || !self.pending.is_empty()
+    }
+
+    pub fn finished_count(&self) -> usize {
+        self.results.len() + self.failed.len() + self.duplicates.len()
+    }
+
+    pub fn total(&self) -> usize {
+        self.finished_count() + self.pending.len() + usize::from(self.current.is_some())
+    }
+}
+
+// 在多数页面相近位置重复出现的行（页眉、页脚、页码等）
+#[derive(Debug, Clone, PartialEq)]
+pub struct RepeatedLine {
+    // 匹配用的规范化文本（数字统一替换为 #，以便匹配页码）
+    pub pattern: String,
+    // 第一次出现时的原文
+    pub example: String,
+    pub page_count: usize,
+}
+
+fn line_pattern(text: &str) -> String {
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .chars()
+        .map(|c| if c.is_numeric() { '#' } else { c })
+        .collect()
+}
+
+// 根据检测框的文字和纵坐标找出跨页重复的行
+pub fn detect_repeated_lines(pages: &[&OcrResult]) -> Vec<RepeatedLine> {
+    if pages.len() < 2 {
+        return Vec::new();
+    }
+
+    // 按规范化文本分组，记录每页出现的位置
+    let mut groups: HashMap<String, Vec<(usize, u32, u32, String)>> = HashMap::new();
+    for (page, result) in pages.iter().enumerate() {
+        for bbox in &result.bounding_boxes {
+            let pattern = line_pattern(&bbox.text);
+            if pattern.is_empty() {
+                continue;
+            }
+            groups.entry(pattern).or_default().push((page, bbox.y, bbox.height, bbox.text.trim().to_string()));
+        }
+    }
+
+    let min_pages = ((pages.len() as f32 * REPEATED_LINE_MIN_RATIO).ceil() as usize).max(2);
+    let mut repeated: Vec<RepeatedLine> = groups
+        .into_iter()
+        .filter_map(|(pattern, occurrences)| {
+            let (_, first_y, first_height, example) = occurrences.first()?.clone();
+            // 位置相近：与首次出现的纵坐标差不超过一个行高
+            let tolerance = first_height.max(1);
+            let mut pages_at_position: Vec<usize> = occurrences
+                .iter()
+                .filter(|(_, y, _, _)| y.abs_diff(first_y) <= tolerance)
+                .map(|(page, _, _, _)| *page)
+                .collect();
+            pages_at_position.dedup();
+
+            (pages_at_position.len() >= min_pages).then_some(RepeatedLine {
+                pattern,
+                example,
+                page_count: pages_at_position.len(),
+            })
+        })
+        .collect();
+
+    repeated.sort_by(|a, b| a.example.cmp(&b.example));
+    repeated
+}
+
+// 合并各页文本，可选去除重复的页眉/页脚行
+pub fn merge_text(pages: &[&OcrResult], removed: &[RepeatedLine]) -> String {
+    pages
+        .iter()
+        .map(|result| {
+            result
+                .text
+                .lines()
+                .filter(|line| {
+                    let pattern = line_pattern(line);
+                    !removed.iter().any(|r| r.pattern == pattern)
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PageStatus {
+    Success,
+    Blank,
+    Failed,
+}
+
+impl PageStatus {
+    pub fn label(self) -> &'static str {
+        match self {
+            PageStatus::Success => "成功",
+            PageStatus::Blank => "空白页",
+            PageStatus::Failed => "失败",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SummaryRow {
+    pub path: PathBuf,
+    pub status: PageStatus,
+    pub confidence: Option<f32>,
+    pub processing_time: f64,
+    pub chars: usize,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SummaryColumn {
+    File,
+    Status,
+    Confidence,
+    Time,
+    Chars,
+}
+
+// 批量识别的汇总统计
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchSummary {
+    pub total: usize,
+    pub succeeded: usize,
+    pub blank: usize,
+    pub failed: usize,
+    pub average_confidence: Option<f32>,
+    pub min_confidence: Option<f32>,
+    pub max_confidence: Option<f32>,
+    pub total_time_ms: f64,
+    // 被删除或筛选隐藏、不计入统计的项数
+    pub hidden: usize,
+    // 作为重复页面跳过、未识别的项数
+    pub duplicates: usize,
+    pub rows: Vec<SummaryRow>,
+}
+
+impl BatchSummary {
+    pub fn build(queue: &BatchQueue, blank: &BlankPageOptions) -> Self {
+        let mut rows: Vec<SummaryRow> = queue
+            .visible_results(blank)
+            .into_iter()
+            .map(|(path, result)| SummaryRow {
+                path: path.clone(),
+                status: if blank.is_blank(result) { PageStatus::Blank } else { PageStatus::Success },
+                confidence: Some(result.confidence),
+                processing_time: result.processing_time,
+                chars: result.text.chars().filter(|c| !c.is_whitespace()).count(),
+                error: None,
+            })
+            .collect();
+        rows.extend(queue.visible_failed().into_iter().map(|(path, error)| SummaryRow {
+            path: path.clone(),
+            status: PageStatus::Failed,
+            confidence: None,
+            processing_time: 0.0,
+            chars: 0,
+            error: Some(error.clone()),
+        }));
+
+        let confidences: Vec<f32> = rows.iter().filter_map(|row| row.confidence).collect();
+        let count = |status| rows.iter().filter(|row| row.status == status).count();
+
+        Self {
+            total: rows.len(),
+            succeeded: count(PageStatus::Success),
+            blank: count(PageStatus::Blank),
+            failed: count(PageStatus::Failed),
+            average_confidence: (!confidences.is_empty())
+                .then(|| confidences.iter().sum::<f32>() / confidences.len() as f32),
+            min_confidence: confidences.iter().copied().reduce(f32::min),
+            max_confidence: confidences.iter().copied().reduce(f32::max),
+            total_time_ms: rows.iter().map(|row| row.processing_time).sum(),
+            hidden: queue.hidden_count(blank),
+            duplicates: queue.duplicate_count(),
+            rows,
+        }
+    }
+
+    pub fn sort_by(&mut self, column: SummaryColumn, ascending: bool) {
+        self.rows.sort_by(|a, b| {
+            let ordering = match column {
+                SummaryColumn::File => a.path.cmp(&b.path),
+                SummaryColumn::Status => a.status.cmp(&b.status),
+                SummaryColumn::Confidence => a.confidence.unwrap_or(-1.0).total_cmp(&b.confidence.unwrap_or(-1.0)),
+                SummaryColumn::Time => a.processing_time.total_cmp(&b.processing_time),
+                SummaryColumn::Chars => a.chars.cmp(&b.chars),
+            };
+            if ascending { ordering } else { ordering.reverse() }
+        });
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("file,status,confidence,processing_time_ms,chars,error\n");
+        for row in &self.rows {
+            csv.push_str(&format!(
+                "{},{},{},{:.0},{},{}\n",
+                csv_field(&row.path.display().to_string()),
+                row.status.label(),
+                row.confidence.map(|c| format!("{:.3}", c)).unwrap_or_default(),
+                row.processing_time,
+                row.chars,
+                csv_field(row.error.as_deref().unwrap_or("")),
+            ));
+        }
+        csv
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+// 含逗号、引号或换行的字段需要加引号，内部引号加倍
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+// 追加模式下每条结果的格式：分隔行包含文件名与置信度
+pub fn append_entry(name: &str, result: &OcrResult) -> String {
+    format!(
+        "---- {} (置信度 {:.1}%) ----\n{}\n",
+        name,
+        result.confidence * 100.0,
+        result.text
+    )
+}
+
+// 批量导出时每个文件的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportAction {
+    Write,
+    // 目标文件已存在，将被覆盖
+    Overwrite,
+    SkipBlank,
+    SkipFailed,
+    // 目标文件已存在且不允许覆盖
+    SkipExisting,
+    // 与前面的文件生成了相同的目标文件名
+    SkipDuplicate,
+}
+
+impl ExportAction {
+    pub fn label(self) -> &'static str {
+        match self {
+            ExportAction::Write => "写入",
+            ExportAction::Overwrite => "覆盖",
+            ExportAction::SkipBlank => "跳过（空白页）",
+            ExportAction::SkipFailed => "跳过（识别失败）",
+            ExportAction::SkipExisting => "跳过（文件已存在）",
+            ExportAction::SkipDuplicate => "跳过（文件名重复）",
+        }
+    }
+
+    pub fn writes(self) -> bool {
+        matches!(self, ExportAction::Write | ExportAction::Overwrite)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ExportPlanEntry {
+    pub source: PathBuf,
+    pub target: Option<PathBuf>,
+    pub action: ExportAction,
+    content: String,
+}
+
+// 批量导出前的预演：列出将写入和跳过的文件，确认后才真正写入
+#[derive(Debug, Clone)]
+pub struct ExportPlan {
+    pub entries: Vec<ExportPlanEntry>,
+    overwrite: bool,
+}
+
+impl ExportPlan {
+    // items 为（源文件、目标文件、导出内容），内容为空表示空白页需跳过
+    pub fn build(items: Vec<(PathBuf, PathBuf, Option<String>)>, failed: &[(PathBuf, String)], overwrite: bool) -> Self {
+        let mut targets: Vec<PathBuf> = Vec::new();
+        let mut entries: Vec<ExportPlanEntry> = items
+            .into_iter()
+            .map(|(source, target, content)| {
+                let action = if content.is_none() {
+                    ExportAction::SkipBlank
+                } else if targets.contains(&target) {
+                    ExportAction::SkipDuplicate
+                } else if target.exists() {
+                    ExportAction::Overwrite
+                } else {
+                    ExportAction::Write
+                };
+                if content.is_some() {
+                    targets.push(target.clone());
+                }
+                ExportPlanEntry { source, target: Some(target), action, content: content.unwrap_or_default() }
+            })
+            .collect();
+        entries.extend(failed.iter().map(|(source, _)| ExportPlanEntry {
+            source: source.clone(),
+            target: None,
+            action: ExportAction::SkipFailed,
+            content: String::new(),
+        }));
+
+        let mut plan = Self { entries, overwrite: true };
+        plan.set_overwrite(overwrite);
+        plan
+    }
+
+    pub fn overwrite(&self) -> bool {
+        self.overwrite
+    }
+
+    pub fn set_overwrite(&mut self, overwrite: bool) {
+        self.overwrite = overwrite;
+        for entry in &mut self.entries {
+            entry.action = match entry.action {
+                ExportAction::Overwrite | ExportAction::SkipExisting if overwrite => ExportAction::Overwrite,
+                ExportAction::Overwrite | ExportAction::SkipExisting => ExportAction::SkipExisting,
+                action => action,
+            };
+        }
+    }
+
+    pub fn count(&self, action: ExportAction) -> usize {
+        self.entries.iter().filter(|entry| entry.action == action).count()
+    }
+
+    pub fn write_count(&self) -> usize {
+        self.entries.iter().filter(|entry| entry.action.writes()).count()
+    }
+
+    pub fn skipped_count(&self) -> usize {
+        self.entries.len() - self.write_count()
+    }
+
+    // 将写入的总字节数
+    pub fn total_bytes(&self) -> usize {
+        self.entries
+            .iter()
+            .filter(|entry| entry.action.writes())
+            .map(|entry| entry.content.len())
+            .sum()
+    }
+
+    // 写入所有需要写入的文件，返回成功数量与失败的文件
+    pub fn execute(&self) -> (usize, Vec<(PathBuf, String)>) {
+        let mut written = 0;
+        let mut errors = Vec::new();
+        for entry in self.entries.iter().filter(|entry| entry.action.writes()) {
+            let Some(target) = &entry.target else {
+                continue;
+            };
+            match std::fs::write(target, &entry.content) {
+                Ok(()) => written += 1,
+                Err(e) => errors.push((target.clone(), e.to_string())),
+            }
+        }
+        (written, errors)
+    }
+
+    // 已写入的结果文件按所在目录分组生成清单，errors 为写入失败的文件。
+    // 引擎、语言与预处理选项取自批量中各项识别时的记录
+    pub fn manifests(&self, errors: &[(PathBuf, String)], batch: &BatchQueue) -> Vec<(PathBuf, Manifest)> {
+        let exported_at = manifest_time();
+        let mut manifests: Vec<(PathBuf, Manifest)> = Vec::new();
+        for entry in self.entries.iter().filter(|entry| entry.action.writes()) {
+            let Some(target) = entry.target.as_ref().filter(|target| !errors.iter().any(|(failed, _)| failed == *target)) else {
+                continue;
+            };
+            let Some((result, options)) = batch.result_with_options(&entry.source) else {
+                continue;
+            };
+            let directory = target.parent().map(Path::to_path_buf).unwrap_or_default();
+            let manifest_entry = ManifestEntry::new(target, &entry.source, result, options, &exported_at);
+            match manifests.iter_mut().find(|(dir, _)| *dir == directory) {
+                Some((_, manifest)) => manifest.entries.push(manifest_entry),
+                None => manifests.push((directory, Manifest { version: MANIFEST_VERSION, entries: vec![manifest_entry] })),
+            }
+        }
+        manifests
+    }
+}
+
+// 批量导出时写在结果文件旁的清单文件
+pub const MANIFEST_FILE: &str = "manifest.json";
+// 清单格式的版本。字段名即对外格式，供下游工具读取，增删或改名字段时需提高版本号
+pub const MANIFEST_VERSION: u32 = 1;
+
+// 记录同一目录中每个结果文件对应的源图片与识别信息
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Manifest {
+    pub version: u32,
+    pub entries: Vec<ManifestEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    // 结果文件名，相对于清单所在目录
+    pub output: String,
+    // 源图片路径；压缩包中的图片为压缩包路径加包内名称，如 book.cbz/001.jpg
+    pub source: String,
+    pub engine: String,
+    pub language: String,
+    pub confidence: f32,
+    // ISO-8601 格式的导出时间
+    pub exported_at: String,
+    // 识别时使用的预处理选项
+    pub preprocess: PreprocessOptions,
+}
+
+impl ManifestEntry {
+    // language 为识别时设置的语言，结果检测到语言时以检测结果为准
+    pub fn new(output: &Path, source: &Path, result: &OcrResult, options: &ProcessOptions, exported_at: &str) -> Self {
+        let language = if result.detected_language.is_empty() {
+            &options.tesseract.language
+        } else {
+            &result.detected_language
+        };
+        Self {
+            output: output.file_name().unwrap_or_default().to_string_lossy().to_string(),
+            source: source.display().to_string(),
+            engine: result.engine.clone(),
+            language: language.clone(),
+            confidence: result.confidence,
+            exported_at: exported_at.to_string(),
+            preprocess: options.preprocess.clone(),
+        }
+    }
+}
+
+// 清单中的导出时间
+pub fn manifest_time() -> String {
+    chrono::Local::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, false)
+}
+
+impl Manifest {
+    // 合并进目录中已有的清单：同名结果文件的记录被替换，其余保留。
+    // 已有的清单无法识别（格式或版本不同）时，只有 overwrite 为 true 才替换，否则不写入并返回错误
+    pub fn write(&self, directory: &Path, overwrite: bool) -> anyhow::Result<PathBuf> {
+        let path = directory.join(MANIFEST_FILE);
+        let mut merged = self.clone();
+        if path.exists() {
+            let existing = std::fs::read_to_string(&path)
+                .ok()
+                .and_then(|content| serde_json::from_str::<Manifest>(&content).ok())
+                .filter(|existing| existing.version == MANIFEST_VERSION);
+            match existing {
+                Some(existing) => {
+                    let mut entries: Vec<ManifestEntry> = existing
+                        .entries
+                        .into_iter()
+                        .filter(|old| !self.entries.iter().any(|new| new.output == old.output))
+                        .collect();
+                    entries.extend(merged.entries);
+                    merged.entries = entries;
+                }
+                None if overwrite => {}
+                None => anyhow::bail!("{} 已存在且无法识别，未覆盖", path.display()),
+            }
+        }
+        std::fs::write(&path, serde_json::to_string_pretty(&merged)?)?;
+        Ok(path)
+    }
+}
+
+// 估计批量识别所有图片解码后占用的内存（字节）。只读取文件头中的尺寸，
+// 无法读取尺寸的文件不计入
+pub fn estimate_memory(paths: &[PathBuf]) -> u64 {
+    paths
+        .iter()
+        .filter_map(|path| dimensions(path))
+        .map(|(width, height)| decoded_size(width, height))
+        .sum()
+}
+
+// 压缩包中的图片无法按路径打开，需先读出该项再解析文件头
+fn dimensions(path: &Path) -> Option<(u32, u32)> {
+    if archive::split(path).is_none() {
+        return image::image_dimensions(path).ok();
+    }
+    let bytes = archive::read(path).ok()?;
+    ImageReader::new(Cursor::new(bytes)).with_guessed_format().ok()?.into_dimensions().ok()
+}
+
+fn decoded_size(width: u32, height: u32) -> u64 {
+    width as u64 * height as u64 * 4 * COPIES_PER_IMAGE
+}
+
+pub fn append_to_file(path: &Path, name: &str, result: &OcrResult) -> std::io::Result<()> {
+    let mut file = std::fs::OpenOptions::new().create(true).append(true).open(path)?;
+    file.write_all(append_entry(name, result).as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ocr::BoundingBox;
+
+    fn queue_of(names: &[&str]) -> BatchQueue {
+        let mut queue = BatchQueue::new();
+        queue.enqueue(names.iter().map(PathBuf::from));
+        queue
+    }
+
+    #[test]
+    fn test_estimate_memory_reads_dimensions() {
+        let path = std::env::temp_dir().join(format!("ocr_rs_estimate_{}.png", std::process::id()));
+        image::DynamicImage::new_rgb8(40, 25).save(&path).unwrap();
+        let missing = PathBuf::from("does_not_exist.png");
+
+        let estimate = estimate_memory(&[path.clone(), missing, path.clone()]);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(estimate, 2 * decoded_size(40, 25));
+        assert_eq!(decoded_size(40, 25), 40 * 25 * 4 * COPIES_PER_IMAGE);
+    }
+
+    #[test]
+    fn test_export_plan_classifies_files() {
+        let dir = std::env::temp_dir().join(format!("ocr_rs_export_plan_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let existing = dir.join("b_ocr.txt");
+        std::fs::write(&existing, "old").unwrap();
+
+        let items = vec![
+            (PathBuf::from("a.png"), dir.join("a_ocr.txt"), Some("hello".to_string())),
+            (PathBuf::from("b.png"), existing.clone(), Some("world!".to_string())),
+            (PathBuf::from("c.png"), dir.join("c_ocr.txt"), None),
+            (PathBuf::from("other/a.png"), dir.join("a_ocr.txt"), Some("again".to_string())),
+        ];
+        let failed = vec![(PathBuf::from("d.png"), "error".to_string())];
+        let mut plan = ExportPlan::build(items, &failed, false);
+
+        let actions: Vec<ExportAction> = plan.entries.iter().map(|entry| entry.action).collect();
+        assert_eq!(actions, vec![
+            ExportAction::Write,
+            ExportAction::SkipExisting,
+            ExportAction::SkipBlank,
+            ExportAction::SkipDuplicate,
+            ExportAction::SkipFailed,
+        ]);
+        assert_eq!((plan.write_count(), plan.skipped_count(), plan.total_bytes()), (1, 4, 5));
+
+        plan.set_overwrite(true);
+        assert_eq!(plan.count(ExportAction::Overwrite), 1);
+        assert_eq!(plan.total_bytes(), 11);
+        assert_eq!(plan.execute(), (2, Vec::new()));
+        assert_eq!(std::fs::read_to_string(&existing).unwrap(), "world!");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_manifest_groups_written_files_by_directory() {
+        let mut batch = queue_of(&["/scans/a.png", "/scans/b.png", "/photos/c.png"]);
+        for (index, text) in ["a", "b", "c"].into_iter().enumerate() {
+            let mut options = ProcessOptions::default();
+            options.tesseract.language = if index == 0 { "chi_sim" } else { "eng" }.to_string();
+            batch.next();
+            batch.complete(page(&[(text, 90)]), options);
+        }
+        let items = vec![
+            (PathBuf::from("/scans/a.png"), PathBuf::from("/nonexistent/out/a_ocr.txt"), Some("a".to_string())),
+            (PathBuf::from("/scans/b.png"), PathBuf::from("/nonexistent/out/b_ocr.txt"), Some("b".to_string())),
+            (PathBuf::from("/photos/c.png"), PathBuf::from("/nonexistent/other/c_ocr.txt"), Some("c".to_string())),
+        ];
+        let plan = ExportPlan::build(items, &[(PathBuf::from("/scans/d.png"), "error".to_string())], false);
+        let errors = vec![(PathBuf::from("/nonexistent/out/b_ocr.txt"), "denied".to_string())];
+        let manifests = plan.manifests(&errors, &batch);
+
+        assert_eq!(manifests.len(), 2);
+        let (directory, manifest) = &manifests[0];
+        assert_eq!(directory, Path::new("/nonexistent/out"));
+        assert_eq!(manifest.entries.len(), 1);
+        assert_eq!(manifest.entries[0].output, "a_ocr.txt");
+        assert_eq!(manifest.entries[0].source, "/scans/a.png");
+        assert_eq!(manifest.entries[0].language, "chi_sim");
+        assert_eq!(manifests[1].1.entries[0].language, "eng");
+
+        // 字段名是对外格式的一部分
+        let json: serde_json::Value = serde_json::to_value(manifest).unwrap();
+        assert_eq!(json["version"], MANIFEST_VERSION);
+        let keys: Vec<&str> = json["entries"][0].as_object().unwrap().keys().map(String::as_str).collect();
+        for key in ["output", "source", "engine", "language", "confidence", "exported_at", "preprocess"] {
+            assert!(keys.contains(&key), "缺少字段 {}", key);
+        }
+    }
+
+    #[test]
+    fn test_manifest_merges_into_existing_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let entry = |output: &str, language: &str| {
+            let mut options = ProcessOptions::default();
+            options.tesseract.language = language.to_string();
+            ManifestEntry::new(&dir.path().join(output), Path::new("/scans/x.png"), &page(&[]), &options, "2024-01-01T00:00:00+08:00")
+        };
+        let first = Manifest { version: MANIFEST_VERSION, entries: vec![entry("a.txt", "eng"), entry("b.txt", "eng")] };
+        let path = first.write(dir.path(), false).unwrap();
+        let second = Manifest { version: MANIFEST_VERSION, entries: vec![entry("b.txt", "jpn"), entry("c.txt", "jpn")] };
+        second.write(dir.path(), false).unwrap();
+
+        let merged: Manifest = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        let outputs: Vec<(&str, &str)> = merged.entries.iter().map(|e| (e.output.as_str(), e.language.as_str())).collect();
+        assert_eq!(outputs, vec![("a.txt", "eng"), ("b.txt", "jpn"), ("c.txt", "jpn")]);
+
+        // 无法识别的同名文件只在允许覆盖时替换
+        std::fs::write(&path, "{\"other\": true}").unwrap();
+        assert!(second.write(dir.path(), false).is_err());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "{\"other\": true}");
+        second.write(dir.path(), true).unwrap();
+        let replaced: Manifest = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(replaced, second);
+    }
+
+    #[test]
+    fn test_new_batch_resets_low_memory() {
+        let mut queue = queue_of(&["a.png"]);
+        queue.set_low_memory(true);
+        queue.enqueue([PathBuf::from("b.png")]);
+        assert!(queue.is_low_memory());
+
+        queue.next();
+        queue.complete(page(&[]), ProcessOptions::default());
+        queue.next();
+        queue.complete(page(&[]), ProcessOptions::default());
+        queue.enqueue([PathBuf::from("c.png")]);
+        assert!(!queue.is_low_memory());
+    }
+
+    #[test]
+    fn test_retry_failed_keeps_results() {
+        let mut queue = queue_of(&["a.png", "b.png", "c.png"]);
+        queue.next();
+        queue.complete(page(&[]), ProcessOptions::default());
+        queue.next();
+        queue.check_duplicate(0, &DuplicateOptions { enabled: true, max_distance: 0 });
+        queue.fail("error".to_string());
+        queue.next();
+        queue.fail("error".to_string());
+        queue.delete(Path::new("c.png"));
+
+        assert_eq!(queue.retry_failed(), 1);
+        assert_eq!(queue.pending(), &[PathBuf::from("b.png")]);
+        assert_eq!(queue.failed_count(), 1);
+        assert_eq!(queue.finished_count(), 2);
+        // 重试时不会与自身的哈希比较而被跳过
+        assert_eq!(queue.next(), Some(PathBuf::from("b.png")));
+        assert_eq!(queue.check_duplicate(0, &DuplicateOptions { enabled: true, max_distance: 0 }), None);
+        queue.complete(page(&[]), ProcessOptions::default());
+        assert_eq!(queue.visible_results(&BlankPageOptions::default()).len(), 2);
+        assert_eq!(queue.retry_failed(), 0);
+    }
+
+    #[test]
+    fn test_move_and_remove() {
+        let mut queue = queue_of(&["a.png", "b.png", "c.png"]);
+        queue.move_item(2, 0);
+        assert_eq!(queue.pending(), &[PathBuf::from("c.png"), PathBuf::from("a.png"), PathBuf::from("b.png")]);
+        queue.remove(1);
+        assert_eq!(queue.pending(), &[PathBuf::from("c.png"), PathBuf::from("b.png")]);
+        assert_eq!(queue.total(), 2);
+    }
+
+    #[test]
+    fn test_enqueue_skips_duplicates() {
+        let mut queue = queue_of(&["a.png", "b.png"]);
+        assert_eq!(queue.next(), Some(PathBuf::from("a.png")));
+        let added = queue.enqueue(["a.png", "b.png", "c.png", "c.png"].iter().map(PathBuf::from));
+        assert_eq!(added, 1);
+        assert_eq!(queue.pending(), &[PathBuf::from("b.png"), PathBuf::from("c.png")]);
+    }
+
+    #[test]
+    fn test_processing_order_and_total() {
+        let mut queue = queue_of(&["a.png", "b.png"]);
+        assert_eq!(queue.next(), Some(PathBuf::from("a.png")));
+        // 当前项未完成时不会取出下一项
+        assert_eq!(queue.next(), None);
+        queue.fail("error".to_string());
+        assert_eq!(queue.next(), Some(PathBuf::from("b.png")));
+        assert_eq!(queue.total(), 2);
+        assert_eq!(queue.finished_count(), 1);
+    }
+
+    #[test]
+    fn test_pause_stops_pulling_new_items() {
+        let mut queue = queue_of(&["a.png", "b.png"]);
+        assert_eq!(queue.next(), Some(PathBuf::from("a.png")));
+        queue.set_paused(true);
+        // 暂停时当前项仍可完成，但不会取出下一项
+        queue.fail("error".to_string());
+        assert_eq!(queue.next(), None);
+        assert!(queue.is_active());
+
+        queue.set_paused(false);
+        assert_eq!(queue.next(), Some(PathBuf::from("b.png")));
+    }
+
+    fn page(lines: &[(&str, u32)]) -> OcrResult {
+        OcrResult {
+            text: lines.iter().map(|(text, _)| *text).collect::<Vec<_>>().join("\n"),
+            raw_text: String::new(),
+            confidence: 0.9,
+            weighted_confidence: 0.9,
+            processing_time: 0.0,
+            bounding_boxes: lines
+                .iter()
+                .map(|(text, y)| BoundingBox {
+                    text: text.to_string(),
+                    confidence: 0.9,
+                    x: 10,
+                    y: *y,
+                    width: 200,
+                    height: 20,
+                    edited: false,
+                    language: None,
+                })
+                .collect(),
+            timings: Vec::new(),
+            engine: String::new(),
+            attempts: Vec::new(),
+            detected_language: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_detect_and_strip_headers_footers() {
+        let pages = [
+            page(&[("公司年度报告", 10), ("第一页正文", 100), ("第 1 页", 900)]),
+            page(&[("公司年度报告", 12), ("第二页正文", 100), ("第 2 页", 905)]),
+            page(&[("公司年度报告", 9), ("第三页正文", 100), ("第 3 页", 898)]),
+        ];
+        let refs: Vec<&OcrResult> = pages.iter().collect();
+
+        let repeated = detect_repeated_lines(&refs);
+        let examples: Vec<&str> = repeated.iter().map(|r| r.example.as_str()).collect();
+        assert_eq!(examples, vec!["公司年度报告", "第 1 页"]);
+
+        let merged = merge_text(&refs, &repeated);
+        assert_eq!(merged, "第一页正文\n\n第二页正文\n\n第三页正文");
+    }
+
+    #[test]
+    fn test_blank_page_detection() {
+        let options = BlankPageOptions { min_chars: 3, ..Default::default() };
+        assert!(options.is_blank(&page(&[])));
+        assert!(options.is_blank(&page(&[(" 。 ", 0), ("\t.", 20)])));
+        assert!(!options.is_blank(&page(&[("第一页", 0)])));
+    }
+
+    #[test]
+    fn test_batch_summary() {
+        let mut queue = queue_of(&["a.png", "b.png", "c, d.png"]);
+        queue.next();
+        queue.complete(page(&[("第一页正文", 0)]), ProcessOptions::default());
+        queue.next();
+        queue.complete(OcrResult { confidence: 0.5, ..page(&[]) }, ProcessOptions::default());
+        queue.next();
+        queue.fail("无法解码".to_string());
+
+        let mut summary = BatchSummary::build(&queue, &BlankPageOptions::default());
+        assert_eq!((summary.total, summary.succeeded, summary.blank, summary.failed), (3, 1, 1, 1));
+        assert_eq!(summary.min_confidence, Some(0.5));
+        assert_eq!(summary.max_confidence, Some(0.9));
+
+        summary.sort_by(SummaryColumn::Confidence, true);
+        let order: Vec<PageStatus> = summary.rows.iter().map(|row| row.status).collect();
+        assert_eq!(order, vec![PageStatus::Failed, PageStatus::Blank, PageStatus::Success]);
+        assert!(summary.to_csv().contains("\"c, d.png\",失败,,0,0,无法解码"));
+    }
+
+    #[test]
+    fn test_delete_and_filter_are_undoable_views() {
+        let mut queue = queue_of(&["a.png", "b.png", "c.png"]);
+        queue.next();
+        queue.complete(page(&[("第一页正文", 0)]), ProcessOptions::default());
+        queue.next();
+        queue.complete(page(&[]), ProcessOptions::default());
+        queue.next();
+        queue.fail("无法解码".to_string());
+        let blank = BlankPageOptions::default();
+
+        queue.set_filter(ResultFilter { hide_blank: true, hide_failed: true });
+        assert_eq!(queue.visible_results(&blank).len(), 1);
+        assert!(queue.visible_failed().is_empty());
+        let summary = BatchSummary::build(&queue, &blank);
+        assert_eq!((summary.total, summary.hidden), (1, 2));
+
+        queue.set_filter(ResultFilter::default());
+        queue.delete(Path::new("a.png"));
+        queue.delete(Path::new("c.png"));
+        let visible: Vec<&PathBuf> = queue.visible_results(&blank).into_iter().map(|(path, _)| path).collect();
+        assert_eq!(visible, vec![&PathBuf::from("b.png")]);
+        assert_eq!(queue.hidden_count(&blank), 2);
+
+        assert_eq!(queue.undo_delete(), Some(PathBuf::from("c.png")));
+        assert_eq!(queue.visible_failed().len(), 1);
+        assert_eq!(queue.finished_count(), 3);
+        assert_eq!(queue.deleted_count(), 1);
+    }
+
+    #[test]
+    fn test_duplicates_are_skipped() {
+        let mut queue = queue_of(&["a.png", "b.png", "c.png", "d.png"]);
+        let options = DuplicateOptions::default();
+        let hashes = [0xFF00_FF00_FF00_FF00u64, 0x0F0F_0F0F_0F0F_0F0F, 0xFF00_FF00_FF00_FF01, 0xFF00_FF00_FF00_FF03];
+        for hash in hashes {
+            queue.next();
+            if queue.check_duplicate(hash, &options).is_none() {
+                queue.complete(page(&[("正文", 0)]), ProcessOptions::default());
+            }
+        }
+
+        assert_eq!(queue.duplicate_count(), 2);
+        assert_eq!(queue.finished_count(), 4);
+        let groups = queue.duplicate_groups();
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].0, &PathBuf::from("a.png"));
+        assert_eq!(groups[0].1, vec![&PathBuf::from("c.png"), &PathBuf::from("d.png")]);
+        assert_eq!(queue.visible_results(&BlankPageOptions::default()).len(), 2);
+        // 重复页面单独统计，不算作被删除或筛选隐藏
+        assert_eq!(queue.hidden_count(&BlankPageOptions::default()), 0);
+        assert_eq!(BatchSummary::build(&queue, &BlankPageOptions::default()).duplicates, 2);
+    }
+
+    #[test]
+    fn test_list_selection() {
+        let paths: Vec<PathBuf> = ["a.png", "b.png", "c.png", "d.png"].iter().map(PathBuf::from).collect();
+        let items: Vec<&Path> = paths.iter().map(PathBuf::as_path).collect();
+        let selected = |selection: &ListSelection| -> Vec<bool> { items.iter().map(|item| selection.contains(item)).collect() };
+
+        let mut selection = ListSelection::default();
+        selection.click(&items, 1, false, false);
+        selection.click(&items, 3, false, true);
+        assert_eq!(selected(&selection), vec![false, true, true, true]);
+
+        // Ctrl 点击切换单项，并成为新的范围起点
+        selection.click(&items, 2, true, false);
+        selection.click(&items, 0, true, false);
+        assert_eq!(selected(&selection), vec![true, true, false, true]);
+        selection.click(&items, 1, false, true);
+        assert_eq!(selected(&selection), vec![true, true, false, false]);
+
+        selection.click(&items, 2, false, false);
+        assert_eq!((selection.len(), selected(&selection)[2]), (1, true));
+    }
+
+    #[test]
+    fn test_append_to_file() {
+        let path = std::env::temp_dir().join(format!("ocr_rs_append_{}.txt", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        append_to_file(&path, "a.png", &page(&[("第一页", 0)])).unwrap();
+        append_to_file(&path, "b.png", &page(&[("第二页", 0)])).unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(
+            content,
+            "---- a.png (置信度 90.0%) ----\n第一页\n---- b.png (置信度 90.0%) ----\n第二页\n"
+        );
+    }
+}
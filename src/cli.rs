@@ -0,0 +1,311 @@
+use std::cell::RefCell;
+use std::io::{Read, Write};
+use std::path::Path;
+use std::time::Duration;
+use anyhow::{anyhow, Result};
+use futures::StreamExt;
+use image::{DynamicImage, GenericImageView};
+
+use crate::export::{self, OutputFormat};
+use crate::file_config::FileConfig;
+use crate::loader::{self, GrayWindow};
+use crate::metadata;
+use crate::ocr::{EngineStatus, OcrEngine, OcrError, OcrResult, ProcessOptions};
+use crate::server;
+
+const USAGE: &str = "用法:
+  ocr-rs                                  启动图形界面
+  ocr-rs recognize <图片...|-> [--lang 语言] [--format json|text|hocr] [--timeout 秒]
+                                          识别图片并输出到标准输出，- 表示从标准输入读取；
+                                          多张图片并发识别，按输入顺序输出，超时限制整个过程
+  ocr-rs --serve <端口> [--timeout 秒]    启动本地 HTTP 服务，单个请求默认限时 120 秒";
+
+// 识别超时的退出码，与 coreutils 的 timeout 命令一致
+const EXIT_TIMEOUT: i32 = 124;
+
+// 解析命令行参数并执行无界面命令。没有命令时返回 None，由调用方启动图形界面
+pub fn run(args: &[String]) -> Option<i32> {
+    if let Some(index) = args.iter().position(|arg| arg == "--serve") {
+        let Some(port) = args.get(index + 1).and_then(|port| port.parse::<u16>().ok()) else {
+            eprintln!("{}", USAGE);
+            return Some(2);
+        };
+        let timeout = match args.iter().position(|arg| arg == "--timeout") {
+            Some(index) => match parse_timeout(args.get(index + 1)) {
+                Ok(timeout) => timeout,
+                Err(e) => {
+                    eprintln!("{}\n\n{}", e, USAGE);
+                    return Some(2);
+                }
+            },
+            None => server::DEFAULT_REQUEST_TIMEOUT,
+        };
+        return Some(match server::run(port, timeout) {
+            Ok(()) => 0,
+            Err(e) => {
+                eprintln!("服务启动失败: {}", e);
+                1
+            }
+        });
+    }
+
+    match args.first().map(String::as_str) {
+        Some("recognize") => Some(recognize_command(&args[1..])),
+        Some("-h" | "--help" | "help") => {
+            println!("{}", USAGE);
+            Some(0)
+        }
+        _ => None,
+    }
+}
+
+struct RecognizeArgs {
+    inputs: Vec<String>,
+    language: Option<String>,
+    format: OutputFormat,
+    timeout: Option<Duration>,
+}
+
+fn parse_timeout(value: Option<&String>) -> Result<Duration> {
+    let value = value.ok_or_else(|| anyhow!("--timeout 需要指定秒数"))?;
+    value
+        .parse::<f64>()
+        .ok()
+        .filter(|secs| secs.is_finite() && *secs > 0.0)
+        .map(Duration::from_secs_f64)
+        .ok_or_else(|| anyhow!("无效的超时时间: {}", value))
+}
+
+fn parse_recognize_args(args: &[String]) -> Result<RecognizeArgs> {
+    let mut inputs = Vec::new();
+    let mut language = None;
+    let mut format = OutputFormat::Text;
+    let mut timeout = None;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--timeout" => timeout = Some(parse_timeout(iter.next())?),
+            "--lang" => language = Some(iter.next().ok_or_else(|| anyhow!("--lang 需要指定语言"))?.clone()),
+            "--format" => {
+                let name = iter.next().ok_or_else(|| anyhow!("--format 需要指定格式"))?;
+                format = OutputFormat::parse(name).ok_or_else(|| anyhow!("不支持的输出格式: {}", name))?;
+            }
+            // 单独的 - 表示标准输入
+            "-" => inputs.push(arg.clone()),
+            flag if flag.starts_with("--") => return Err(anyhow!("未知参数: {}", flag)),
+            path => inputs.push(path.to_string()),
+        }
+    }
+    if inputs.is_empty() {
+        return Err(anyhow!("缺少输入图片"));
+    }
+    if inputs.len() > 1 && inputs.iter().any(|input| input == "-") {
+        return Err(anyhow!("标准输入（-）只能单独使用"));
+    }
+
+    Ok(RecognizeArgs {
+        inputs,
+        language,
+        format,
+        timeout,
+    })
+}
+
+fn recognize_command(args: &[String]) -> i32 {
+    let args = match parse_recognize_args(args) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            return 2;
+        }
+    };
+
+    if args.inputs.len() > 1 {
+        return recognize_many(&args);
+    }
+    match recognize(&args) {
+        Ok(output) => {
+            let mut stdout = std::io::stdout().lock();
+            let _ = writeln!(stdout, "{}", output);
+            0
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            exit_code(&e)
+        }
+    }
+}
+
+fn exit_code(error: &anyhow::Error) -> i32 {
+    match error.downcast_ref::<OcrError>() {
+        Some(OcrError::Timeout(_)) => EXIT_TIMEOUT,
+        _ => 1,
+    }
+}
+
+// 只识别第一帧
+fn load_image(input: &str) -> Result<DynamicImage> {
+    let frames = if input == "-" {
+        let mut bytes = Vec::new();
+        std::io::stdin().read_to_end(&mut bytes).map_err(|e| anyhow!("读取标准输入失败: {}", e))?;
+        if bytes.is_empty() {
+            return Err(anyhow!("标准输入中没有图片数据"));
+        }
+        loader::load_frames_from_memory(&bytes, GrayWindow::default())
+    } else {
+        loader::load_frames(Path::new(input), GrayWindow::default())
+    };
+    frames
+        .map_err(|e| anyhow!("无法解码图片: {}", e))?
+        .into_iter()
+        .next()
+        .ok_or_else(|| anyhow!("图片中没有可识别的帧"))
+}
+
+// 可执行文件旁 ocr-rs.toml 中的选项作为默认值，命令行参数优先
+fn prepare_engine(args: &RecognizeArgs) -> Result<(OcrEngine, ProcessOptions)> {
+    let config = FileConfig::load().unwrap_or_default();
+    let engine = config.engine();
+    if matches!(engine.get_status(), EngineStatus::NoEngineAvailable) {
+        return Err(anyhow!("没有可用的 OCR 引擎"));
+    }
+
+    let mut options = config.options;
+    if let Some(language) = &args.language {
+        options.tesseract.language = language.clone();
+    }
+    Ok((engine, options))
+}
+
+fn recognize(args: &RecognizeArgs) -> Result<String> {
+    let input = &args.inputs[0];
+    let image = load_image(input)?;
+    let (engine, options) = prepare_engine(args)?;
+
+    let (width, height) = image.dimensions();
+    let rt = tokio::runtime::Runtime::new()?;
+    let result = rt.block_on(engine.process_image_with_timeout(image, Path::new(input), &options, args.timeout));
+    // 超时后后台线程中的识别可能仍在运行，不等待其结束
+    rt.shutdown_background();
+    Ok(render(input, &result?, args.format, width, height))
+}
+
+// hOCR 的 scan_res 使用图片记录的分辨率；标准输入的图片不读取（当前目录中可能恰有名为 - 的文件）
+fn render(input: &str, result: &OcrResult, format: OutputFormat, width: u32, height: u32) -> String {
+    let dpi = if input == "-" {
+        None
+    } else {
+        metadata::read_image_info(Path::new(input)).ok().and_then(|info| info.dpi)
+    };
+    export::render(result, format, width, height, dpi)
+}
+
+// 多张图片经 process_stream 并发识别，图片在识别前才逐个读取。结果按输入顺序输出，
+// 每张之前加上 ==> 文件名 <== 标题行；失败的图片在标准错误中说明，其余结果照常输出
+fn recognize_many(args: &RecognizeArgs) -> i32 {
+    let (engine, options) = match prepare_engine(args) {
+        Ok(prepared) => prepared,
+        Err(e) => {
+            eprintln!("{}", e);
+            return 1;
+        }
+    };
+
+    // 成功读取的图片在输入中的序号与尺寸，按送入识别的顺序排列
+    let loaded = RefCell::new(Vec::new());
+    let mut outputs: Vec<Result<String>> = args.inputs.iter().map(|_| Err(anyhow!("未识别"))).collect();
+    let load_errors = RefCell::new(Vec::new());
+    let images = futures::stream::iter(args.inputs.iter().enumerate()).filter_map(|(index, input)| {
+        let image = match load_image(input) {
+            Ok(image) => {
+                loaded.borrow_mut().push((index, image.dimensions()));
+                Some(image)
+            }
+            Err(e) => {
+                load_errors.borrow_mut().push((index, e));
+                None
+            }
+        };
+        std::future::ready(image)
+    });
+
+    let rt = match tokio::runtime::Runtime::new() {
+        Ok(rt) => rt,
+        Err(e) => {
+            eprintln!("{}", e);
+            return 1;
+        }
+    };
+    let results = rt.block_on(async {
+        let recognition = engine.process_stream(images, &options).collect::<Vec<_>>();
+        match args.timeout {
+            Some(limit) => tokio::time::timeout(limit, recognition).await.map_err(|_| OcrError::Timeout(limit)),
+            None => Ok(recognition.await),
+        }
+    });
+    rt.shutdown_background();
+    let results = match results {
+        Ok(results) => results,
+        Err(e) => {
+            eprintln!("{}", e);
+            return EXIT_TIMEOUT;
+        }
+    };
+
+    for (index, error) in load_errors.into_inner() {
+        outputs[index] = Err(error);
+    }
+    let loaded = loaded.into_inner();
+    for (position, result) in results {
+        let (index, (width, height)) = loaded[position];
+        outputs[index] = result
+            .map(|result| render(&args.inputs[index], &result, args.format, width, height))
+            .map_err(anyhow::Error::from);
+    }
+
+    let mut code = 0;
+    let mut stdout = std::io::stdout().lock();
+    for (input, output) in args.inputs.iter().zip(outputs) {
+        match output {
+            Ok(output) => {
+                let _ = writeln!(stdout, "==> {} <==\n{}\n", input, output);
+            }
+            Err(e) => {
+                eprintln!("{}: {}", input, e);
+                code = code.max(exit_code(&e));
+            }
+        }
+    }
+    code
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_stdin_sentinel_and_options() {
+        let parsed = parse_recognize_args(&args(&["-", "--lang", "eng", "--format", "json"])).unwrap();
+        assert_eq!(parsed.inputs, vec!["-"]);
+        assert_eq!(parsed.language.as_deref(), Some("eng"));
+        assert_eq!(parsed.format, OutputFormat::Json);
+
+        assert_eq!(parsed.timeout, None);
+
+        let parsed = parse_recognize_args(&args(&["a.png", "--timeout", "2.5"])).unwrap();
+        assert_eq!(parsed.timeout, Some(Duration::from_millis(2500)));
+        assert!(parse_recognize_args(&args(&["a.png", "--timeout", "0"])).is_err());
+        assert!(parse_recognize_args(&args(&["a.png", "--timeout"])).is_err());
+
+        assert!(parse_recognize_args(&args(&["--lang", "eng"])).is_err());
+        let parsed = parse_recognize_args(&args(&["a.png", "--lang", "eng", "b.png"])).unwrap();
+        assert_eq!(parsed.inputs, vec!["a.png", "b.png"]);
+        assert!(parse_recognize_args(&args(&["a.png", "-"])).is_err());
+        assert!(parse_recognize_args(&args(&["a.png", "--format", "pdf"])).is_err());
+    }
+}
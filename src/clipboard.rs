@@ -0,0 +1,94 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use anyhow::{anyhow, Result};
+use image::{DynamicImage, RgbaImage};
+
+// 剪贴板轮询间隔
+const POLL_INTERVAL: Duration = Duration::from_millis(800);
+
+pub enum ClipboardEvent {
+    Image(DynamicImage),
+    Error(String),
+}
+
+// 剪贴板监视：在后台线程轮询剪贴板，发现新图片时回调
+pub struct ClipboardMonitor {
+    running: Arc<AtomicBool>,
+}
+
+impl ClipboardMonitor {
+    pub fn start<F>(on_event: F) -> Self
+    where
+        F: Fn(ClipboardEvent) + Send + 'static,
+    {
+        let running = Arc::new(AtomicBool::new(true));
+        let flag = running.clone();
+
+        std::thread::spawn(move || {
+            let mut clipboard = match arboard::Clipboard::new() {
+                Ok(clipboard) => clipboard,
+                Err(e) => {
+                    on_event(ClipboardEvent::Error(format!("无法访问剪贴板: {}", e)));
+                    return;
+                }
+            };
+
+            // 开始监视时剪贴板中已有的图片不处理
+            let mut last_hash = clipboard.get_image().ok().map(|image| hash_bytes(&image.bytes));
+
+            while flag.load(Ordering::Relaxed) {
+                std::thread::sleep(POLL_INTERVAL);
+
+                let Ok(data) = clipboard.get_image() else {
+                    continue;
+                };
+                // 相同内容只处理一次
+                let hash = hash_bytes(&data.bytes);
+                if last_hash == Some(hash) {
+                    continue;
+                }
+                last_hash = Some(hash);
+
+                let buffer = RgbaImage::from_raw(data.width as u32, data.height as u32, data.bytes.into_owned());
+                if let Some(buffer) = buffer {
+                    on_event(ClipboardEvent::Image(DynamicImage::ImageRgba8(buffer)));
+                }
+            }
+        });
+
+        Self { running }
+    }
+
+    pub fn stop(&self) {
+        self.running.store(false, Ordering::Relaxed);
+    }
+}
+
+impl Drop for ClipboardMonitor {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+// 复制富文本时使用的剪贴板对象，保持存活以便 Linux 上其他程序仍能读取内容
+static RICH_CLIPBOARD: Mutex<Option<arboard::Clipboard>> = Mutex::new(None);
+
+// 以 HTML 写入剪贴板，plain 为不支持富文本的程序粘贴时得到的纯文本
+pub fn copy_html(html: &str, plain: &str) -> Result<()> {
+    let mut guard = RICH_CLIPBOARD.lock().map_err(|_| anyhow!("剪贴板被占用"))?;
+    let clipboard = match guard.as_mut() {
+        Some(clipboard) => clipboard,
+        None => guard.insert(arboard::Clipboard::new()?),
+    };
+    clipboard.set_html(html, Some(plain))?;
+    Ok(())
+}
+
+fn hash_bytes(bytes: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    bytes.hash(&mut hasher);
+    hasher.finish()
+}
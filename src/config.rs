@@ -0,0 +1,28 @@
+use std::path::{Path, PathBuf};
+use anyhow::{anyhow, Result};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+// 应用配置目录，例如 Linux 下的 ~/.config/ocr-rs
+pub fn config_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("ocr-rs"))
+}
+
+pub fn config_file(name: &str) -> Result<PathBuf> {
+    config_dir()
+        .map(|dir| dir.join(name))
+        .ok_or_else(|| anyhow!("无法确定配置目录"))
+}
+
+pub fn load_json<T: DeserializeOwned>(path: &Path) -> Result<T> {
+    let content = std::fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&content)?)
+}
+
+pub fn save_json<T: Serialize>(path: &Path, value: &T) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, serde_json::to_string_pretty(value)?)?;
+    Ok(())
+}
@@ -0,0 +1,80 @@
+use similar::{ChangeTag, TextDiff};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineChangeKind {
+    Unchanged,
+    // 仅存在于手动编辑后的文本中
+    Removed,
+    // 仅存在于新的识别结果中
+    Added,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LineChange {
+    pub kind: LineChangeKind,
+    pub text: String,
+    // 是否接受这处变化：接受删除即去掉该行，接受新增即保留该行
+    pub accepted: bool,
+}
+
+// 按行比较手动编辑的文本与新的识别结果，默认接受所有变化
+pub fn line_diff(edited: &str, recognized: &str) -> Vec<LineChange> {
+    // 统一末尾换行，避免最后一行仅因换行符不同被判为修改
+    let with_newline = |text: &str| if text.ends_with('\n') { text.to_string() } else { format!("{}\n", text) };
+    let (edited, recognized) = (with_newline(edited), with_newline(recognized));
+    TextDiff::from_lines(&edited, &recognized)
+        .iter_all_changes()
+        .map(|change| LineChange {
+            kind: match change.tag() {
+                ChangeTag::Equal => LineChangeKind::Unchanged,
+                ChangeTag::Delete => LineChangeKind::Removed,
+                ChangeTag::Insert => LineChangeKind::Added,
+            },
+            text: change.value().trim_end_matches(['\n', '\r']).to_string(),
+            accepted: true,
+        })
+        .collect()
+}
+
+pub fn has_changes(changes: &[LineChange]) -> bool {
+    changes.iter().any(|change| change.kind != LineChangeKind::Unchanged)
+}
+
+// 根据每行的接受/拒绝状态合并出最终文本
+pub fn merge(changes: &[LineChange]) -> String {
+    changes
+        .iter()
+        .filter(|change| match change.kind {
+            LineChangeKind::Unchanged => true,
+            LineChangeKind::Removed => !change.accepted,
+            LineChangeKind::Added => change.accepted,
+        })
+        .map(|change| change.text.as_str())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_respects_accepted_lines() {
+        let mut changes = line_diff("标题\n我改正的行\n结尾", "标题\n识别错误的行\n新增一行\n结尾");
+        assert!(has_changes(&changes));
+        assert_eq!(merge(&changes), "标题\n识别错误的行\n新增一行\n结尾");
+
+        // 拒绝替换，保留手动修改，但接受新增的行
+        for change in changes.iter_mut() {
+            if change.text == "我改正的行" || change.text == "识别错误的行" {
+                change.accepted = false;
+            }
+        }
+        assert_eq!(merge(&changes), "标题\n我改正的行\n新增一行\n结尾");
+    }
+
+    #[test]
+    fn test_identical_text_has_no_changes() {
+        assert!(!has_changes(&line_diff("a\nb", "a\nb\n")));
+    }
+}
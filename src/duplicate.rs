@@ -0,0 +1,65 @@
+use image::imageops::FilterType;
+use image::DynamicImage;
+
+// 重复页面检测：扫描时同一页被拍了两次，批量识别时跳过与已处理页面几乎相同的图片
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DuplicateOptions {
+    pub enabled: bool,
+    // 64 位差异哈希允许不同的最多位数，越大越宽松
+    pub max_distance: u32,
+}
+
+impl Default for DuplicateOptions {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_distance: 6,
+        }
+    }
+}
+
+impl DuplicateOptions {
+    pub fn is_duplicate(&self, a: u64, b: u64) -> bool {
+        distance(a, b) <= self.max_distance
+    }
+}
+
+// 差异哈希（dHash）：缩小为 9×8 的灰度图，每行相邻像素比较亮度得到 64 位。
+// 对缩放、轻微的亮度与压缩差异不敏感，内容不同的页面通常相差 20 位以上
+pub fn dhash(image: &DynamicImage) -> u64 {
+    let small = image.resize_exact(9, 8, FilterType::Triangle).to_luma8();
+    let mut hash = 0u64;
+    for y in 0..8 {
+        for x in 0..8 {
+            hash <<= 1;
+            hash |= u64::from(small.get_pixel(x, y)[0] < small.get_pixel(x + 1, y)[0]);
+        }
+    }
+    hash
+}
+
+// 两个哈希不同的位数
+pub fn distance(a: u64, b: u64) -> u32 {
+    (a ^ b).count_ones()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{GrayImage, Luma};
+
+    fn page(seed: u32) -> DynamicImage {
+        DynamicImage::ImageLuma8(GrayImage::from_fn(180, 240, |x, y| {
+            Luma([((x * (seed + 3) + y * y * seed / 7) % 251) as u8])
+        }))
+    }
+
+    #[test]
+    fn test_rescaled_copy_is_duplicate() {
+        let original = page(5);
+        let rescan = original.resize(120, 160, FilterType::Lanczos3).brighten(4);
+        let options = DuplicateOptions::default();
+        assert!(options.is_duplicate(dhash(&original), dhash(&rescan)));
+        assert!(!options.is_duplicate(dhash(&original), dhash(&page(11))));
+    }
+}
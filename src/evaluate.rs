@@ -0,0 +1,145 @@
+use std::path::Path;
+use anyhow::Result;
+use similar::{ChangeTag, TextDiff};
+
+// 识别结果与人工校对的标准文本之间的差异统计
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Evaluation {
+    // 字符错误率：字符级编辑距离 / 标准文本字符数
+    pub cer: f32,
+    pub char_errors: usize,
+    pub chars: usize,
+    // 词错误率：词级编辑距离 / 标准文本词数，汉字与假名每个字算一个词
+    pub wer: f32,
+    pub word_errors: usize,
+    pub words: usize,
+    // 字符级对齐结果：Delete 为识别结果漏掉的，Insert 为识别结果多出的
+    pub segments: Vec<(ChangeTag, String)>,
+}
+
+// 比较前统一空白：连续空白视为一个空格，汉字之间的空白忽略，识别引擎在汉字间插入的空格不计为错误
+pub fn evaluate(ground_truth: &str, recognized: &str) -> Evaluation {
+    let truth_words = words(ground_truth);
+    let recognized_words = words(recognized);
+    let truth = join_words(&truth_words);
+    let recognized = join_words(&recognized_words);
+
+    let truth_chars: Vec<char> = truth.chars().collect();
+    let recognized_chars: Vec<char> = recognized.chars().collect();
+    let char_errors = edit_distance(&truth_chars, &recognized_chars);
+    let word_errors = edit_distance(&truth_words, &recognized_words);
+
+    let mut segments: Vec<(ChangeTag, String)> = Vec::new();
+    for change in TextDiff::from_chars(truth.as_str(), recognized.as_str()).iter_all_changes() {
+        match segments.last_mut() {
+            Some((tag, text)) if *tag == change.tag() => text.push_str(change.value()),
+            _ => segments.push((change.tag(), change.value().to_string())),
+        }
+    }
+
+    Evaluation {
+        cer: char_errors as f32 / truth_chars.len().max(1) as f32,
+        char_errors,
+        chars: truth_chars.len(),
+        wer: word_errors as f32 / truth_words.len().max(1) as f32,
+        word_errors,
+        words: truth_words.len(),
+        segments,
+    }
+}
+
+// 读取标准文本：UTF-8（可带 BOM），否则按 GBK 解码
+pub fn read_ground_truth(path: &Path) -> Result<String> {
+    let bytes = std::fs::read(path)?;
+    let bytes = bytes.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(&bytes);
+    Ok(match std::str::from_utf8(bytes) {
+        Ok(text) => text.to_string(),
+        Err(_) => encoding_rs::GBK.decode(bytes).0.into_owned(),
+    })
+}
+
+fn is_cjk(c: char) -> bool {
+    matches!(c as u32, 0x3040..=0x30FF | 0x3400..=0x4DBF | 0x4E00..=0x9FFF | 0xF900..=0xFAFF)
+}
+
+// 按空白分词，汉字与假名单独成词
+fn words(text: &str) -> Vec<&str> {
+    let mut words = Vec::new();
+    for chunk in text.split_whitespace() {
+        let mut start = 0;
+        for (offset, c) in chunk.char_indices().filter(|&(_, c)| is_cjk(c)) {
+            if start < offset {
+                words.push(&chunk[start..offset]);
+            }
+            words.push(&chunk[offset..offset + c.len_utf8()]);
+            start = offset + c.len_utf8();
+        }
+        if start < chunk.len() {
+            words.push(&chunk[start..]);
+        }
+    }
+    words
+}
+
+// 非汉字的相邻词之间用一个空格连接
+fn join_words(words: &[&str]) -> String {
+    let mut text = String::new();
+    let mut previous_cjk = true;
+    for word in words {
+        let cjk = word.chars().all(is_cjk);
+        if !previous_cjk && !cjk {
+            text.push(' ');
+        }
+        text.push_str(word);
+        previous_cjk = cjk;
+    }
+    text
+}
+
+// 编辑距离（替换、插入、删除各计 1）
+fn edit_distance<T: PartialEq>(a: &[T], b: &[T]) -> usize {
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for (i, x) in a.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, y) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(x != y);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_rates() {
+        let evaluation = evaluate("the quick brown fox", "the quick  brovvn\nfox");
+        assert_eq!((evaluation.char_errors, evaluation.chars), (2, 19));
+        assert_eq!((evaluation.word_errors, evaluation.words), (1, 4));
+        assert!((evaluation.wer - 0.25).abs() < 1e-6);
+
+        // 汉字间的空格不计为错误，每个汉字算一个词
+        let evaluation = evaluate("你好世界，OCR 测试", "你 好世届，OCR测试");
+        assert_eq!((evaluation.char_errors, evaluation.chars), (1, 10));
+        assert_eq!((evaluation.word_errors, evaluation.words), (1, 7));
+
+        let evaluation = evaluate("", "");
+        assert_eq!(evaluation.cer, 0.0);
+    }
+
+    #[test]
+    fn test_aligned_segments() {
+        let evaluation = evaluate("识别结果", "识别洁果果");
+        let segments: Vec<(ChangeTag, &str)> = evaluation.segments.iter().map(|(tag, text)| (*tag, text.as_str())).collect();
+        assert_eq!(segments[0], (ChangeTag::Equal, "识别"));
+        let missing: String = segments.iter().filter(|(tag, _)| *tag == ChangeTag::Delete).map(|(_, text)| *text).collect();
+        let extra: String = segments.iter().filter(|(tag, _)| *tag == ChangeTag::Insert).map(|(_, text)| *text).collect();
+        assert_eq!(missing, "结");
+        assert_eq!(extra.chars().count(), 2);
+        assert_eq!(evaluation.char_errors, 2);
+    }
+}
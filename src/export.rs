@@ -0,0 +1,354 @@
+use std::io::Cursor;
+use base64::Engine;
+use image::DynamicImage;
+use serde::{Deserialize, Serialize};
+
+use crate::ocr::{BoundingBox, OcrResult};
+use crate::tessdata;
+
+// 命令行、HTTP 服务与识别后命令的输出格式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    #[default]
+    Json,
+    Text,
+    Hocr,
+}
+
+impl OutputFormat {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "json" => Some(Self::Json),
+            "text" | "txt" => Some(Self::Text),
+            "hocr" => Some(Self::Hocr),
+            _ => None,
+        }
+    }
+
+    pub const ALL: [Self; 3] = [Self::Text, Self::Json, Self::Hocr];
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Text => "txt",
+            Self::Hocr => "hocr",
+        }
+    }
+}
+
+// 按输出格式渲染识别结果，width/height 为原图尺寸，dpi 为扫描分辨率（hOCR 需要）
+pub fn render(result: &OcrResult, format: OutputFormat, width: u32, height: u32, dpi: Option<(f32, f32)>) -> String {
+    match format {
+        OutputFormat::Json => serde_json::to_string_pretty(result).unwrap_or_default(),
+        OutputFormat::Text => result.text.clone(),
+        OutputFormat::Hocr => to_hocr(result, width, height, dpi),
+    }
+}
+
+// 将识别结果渲染为 hOCR（每个检测框输出为一行）。已知分辨率时写入 scan_res，
+// hocr-pdf 等工具据此确定可搜索 PDF 的页面尺寸与文字层位置
+pub fn to_hocr(result: &OcrResult, width: u32, height: u32, dpi: Option<(f32, f32)>) -> String {
+    let scan_res = dpi.map(|(x, y)| format!("; scan_res {:.0} {:.0}", x, y)).unwrap_or_default();
+    let mut body = String::new();
+    for (i, bbox) in result.bounding_boxes.iter().enumerate() {
+        body.push_str(&format!(
+            "   <span class='ocr_line' id='line_1_{}' title='bbox {} {} {} {}; x_wconf {:.0}'>{}</span>\n",
+            i + 1,
+            bbox.x,
+            bbox.y,
+            bbox.x + bbox.width,
+            bbox.y + bbox.height,
+            bbox.confidence * 100.0,
+            escape_xml(&bbox.text),
+        ));
+    }
+
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>
+<!DOCTYPE html PUBLIC \"-//W3C//DTD XHTML 1.0 Transitional//EN\" \"http://www.w3.org/TR/xhtml1/DTD/xhtml1-transitional.dtd\">
+<html xmlns=\"http://www.w3.org/1999/xhtml\">
+ <head>
+  <title></title>
+  <meta http-equiv=\"Content-Type\" content=\"text/html;charset=utf-8\"/>
+  <meta name=\"ocr-system\" content=\"ocr-rs {}\"/>
+  <meta name=\"ocr-capabilities\" content=\"ocr_page ocr_line\"/>
+ </head>
+ <body>
+  <div class='ocr_page' id='page_1' title='bbox 0 0 {} {}{}'>
+{}  </div>
+ </body>
+</html>
+",
+        env!("CARGO_PKG_VERSION"),
+        width,
+        height,
+        scan_res,
+        body,
+    )
+}
+
+// 每个检测框一行的 TSV，便于粘贴到电子表格
+pub fn to_tsv(boxes: &[BoundingBox]) -> String {
+    let mut tsv = String::from("text\tconfidence\tx\ty\twidth\theight\n");
+    for bbox in boxes {
+        tsv.push_str(&format!(
+            "{}\t{:.3}\t{}\t{}\t{}\t{}\n",
+            escape_tsv(&bbox.text),
+            bbox.confidence,
+            bbox.x,
+            bbox.y,
+            bbox.width,
+            bbox.height,
+        ));
+    }
+    tsv
+}
+
+// 转义单元格中的制表符和换行，避免破坏行列结构
+fn escape_tsv(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\t' => escaped.push_str("\\t"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// 导出结果的来源信息
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportMetadata {
+    pub source: String,
+    pub width: u32,
+    pub height: u32,
+    pub engine: String,
+    pub language: String,
+    // ISO-8601 格式的导出时间
+    pub exported_at: String,
+    // 可选的源图片缩略图（JPEG data URI），原图不在时也能看出结果对应哪张图
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thumbnail: Option<String>,
+}
+
+impl ExportMetadata {
+    pub fn new(source: &str, (width, height): (u32, u32), result: &OcrResult, language: &str) -> Self {
+        let language = if result.detected_language.is_empty() {
+            language
+        } else {
+            &result.detected_language
+        };
+        Self {
+            source: source.to_string(),
+            width,
+            height,
+            engine: result.engine.clone(),
+            language: language.to_string(),
+            exported_at: chrono::Local::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, false),
+            thumbnail: None,
+        }
+    }
+
+    pub fn with_thumbnail(mut self, image: &DynamicImage) -> Self {
+        self.thumbnail = thumbnail_data_uri(image, THUMBNAIL_MAX_SIZE);
+        self
+    }
+
+    // 文本导出时置于开头的注释行
+    pub fn text_header(&self) -> String {
+        format!(
+            "# 来源: {}\n# 尺寸: {}x{}\n# 引擎: {}\n# 语言: {}\n# 时间: {}\n\n",
+            self.source, self.width, self.height, self.engine, self.language, self.exported_at
+        )
+    }
+}
+
+// 从右向左标记（RLM），置于行首时编辑器按从右向左显示该段
+const RIGHT_TO_LEFT_MARK: char = '\u{200F}';
+
+// 按识别语言的书写方向标记导出文本：从右向左书写时每个非空行以 RLM 开头，
+// 避免行首的数字或拉丁字母使编辑器按从左向右显示；竖排时在开头说明原文方向。
+// 从左向右的横排文本原样返回
+pub fn mark_direction(text: &str, language: &str) -> String {
+    if tessdata::is_rtl_language(language) {
+        text.split('\n')
+            .map(|line| {
+                if line.trim().is_empty() || line.starts_with(RIGHT_TO_LEFT_MARK) {
+                    line.to_string()
+                } else {
+                    format!("{}{}", RIGHT_TO_LEFT_MARK, line)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    } else if tessdata::is_vertical_language(language) {
+        format!("# 原文为竖排（从上到下、从右到左），以下每行对应原文一列\n\n{}", text)
+    } else {
+        text.to_string()
+    }
+}
+
+// 嵌入导出结果的缩略图最长边（像素），base64 后通常只有几 KB
+pub const THUMBNAIL_MAX_SIZE: u32 = 160;
+
+// 按比例缩小到最长边不超过 max_size 并编码为 JPEG data URI，小图不放大
+pub fn thumbnail_data_uri(image: &DynamicImage, max_size: u32) -> Option<String> {
+    let thumbnail = if image.width() > max_size || image.height() > max_size {
+        image.thumbnail(max_size, max_size)
+    } else {
+        image.clone()
+    };
+    // JPEG 不支持透明通道
+    let mut jpeg = Vec::new();
+    DynamicImage::ImageRgb8(thumbnail.to_rgb8())
+        .write_to(&mut Cursor::new(&mut jpeg), image::ImageFormat::Jpeg)
+        .ok()?;
+    Some(format!("data:image/jpeg;base64,{}", base64::engine::general_purpose::STANDARD.encode(jpeg)))
+}
+
+// JSON 导出的内容：识别结果及其来源信息
+#[derive(Debug, Serialize)]
+pub struct OcrExport<'a> {
+    pub metadata: ExportMetadata,
+    pub result: &'a OcrResult,
+}
+
+// 富文本剪贴板使用的 HTML 片段：等宽字体并保留空格与换行，粘贴到文档中时版面不变
+pub fn to_html_fragment(text: &str) -> String {
+    format!(
+        "<pre style=\"font-family: Consolas, 'Courier New', monospace; white-space: pre-wrap; margin: 0\">{}</pre>",
+        escape_xml(text)
+    )
+}
+
+pub fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ocr::BoundingBox;
+
+    #[test]
+    fn test_tsv_escapes_cells() {
+        let boxes = vec![BoundingBox {
+            text: "a\tb\nc\\".to_string(),
+            confidence: 0.5,
+            x: 1,
+            y: 2,
+            width: 3,
+            height: 4,
+            edited: false,
+            language: None,
+        }];
+        assert_eq!(
+            to_tsv(&boxes),
+            "text\tconfidence\tx\ty\twidth\theight\na\\tb\\nc\\\\\t0.500\t1\t2\t3\t4\n"
+        );
+    }
+
+    #[test]
+    fn test_html_fragment_escapes_text() {
+        assert_eq!(
+            to_html_fragment("a < b\n  c"),
+            "<pre style=\"font-family: Consolas, 'Courier New', monospace; white-space: pre-wrap; margin: 0\">a &lt; b\n  c</pre>"
+        );
+    }
+
+    fn sample_result() -> OcrResult {
+        OcrResult {
+            text: "a<b".to_string(),
+            raw_text: String::new(),
+            confidence: 0.9,
+            weighted_confidence: 0.9,
+            processing_time: 0.0,
+            bounding_boxes: vec![BoundingBox {
+                text: "a<b".to_string(),
+                confidence: 0.87,
+                x: 10,
+                y: 20,
+                width: 100,
+                height: 30,
+                edited: false,
+                language: None,
+            }],
+            timings: Vec::new(),
+            engine: String::new(),
+            attempts: Vec::new(),
+            detected_language: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_hocr_lines() {
+        let result = sample_result();
+        let hocr = to_hocr(&result, 640, 480, None);
+        assert!(hocr.contains("title='bbox 0 0 640 480'"));
+        assert!(to_hocr(&result, 640, 480, Some((300.0, 300.0))).contains("title='bbox 0 0 640 480; scan_res 300 300'"));
+        assert!(hocr.contains("title='bbox 10 20 110 50; x_wconf 87'>a&lt;b</span>"));
+    }
+
+    #[test]
+    fn test_export_includes_metadata() {
+        let mut result = sample_result();
+        result.engine = "Candle".to_string();
+        let metadata = ExportMetadata::new("/scans/page.png", (640, 480), &result, "chi_sim+eng");
+        let export = OcrExport { metadata, result: &result };
+
+        let json: serde_json::Value = serde_json::to_value(&export).unwrap();
+        assert_eq!(json["metadata"]["source"], "/scans/page.png");
+        assert_eq!(json["metadata"]["width"], 640);
+        assert_eq!(json["metadata"]["engine"], "Candle");
+        assert_eq!(json["metadata"]["language"], "chi_sim+eng");
+        assert!(chrono::DateTime::parse_from_rfc3339(json["metadata"]["exported_at"].as_str().unwrap()).is_ok());
+        assert_eq!(json["result"]["text"], "a<b");
+
+        assert!(json["metadata"].get("thumbnail").is_none());
+
+        let header = export.metadata.text_header();
+        assert!(header.starts_with("# 来源: /scans/page.png\n# 尺寸: 640x480\n"));
+        assert!(header.ends_with("\n\n"));
+    }
+
+    #[test]
+    fn test_mark_direction() {
+        assert_eq!(mark_direction("مرحبا 123\n\n42 شارع", "ara+eng"), "\u{200F}مرحبا 123\n\n\u{200F}42 شارع");
+        // 已有标记的行不重复添加
+        let marked = mark_direction("שלום", "heb");
+        assert_eq!(mark_direction(&marked, "heb"), marked);
+        assert!(mark_direction("縦書き", "jpn_vert").starts_with("# "));
+        assert_eq!(mark_direction("hello", "eng"), "hello");
+    }
+
+    #[test]
+    fn test_thumbnail_is_bounded() {
+        let result = sample_result();
+        let image = DynamicImage::new_rgba8(1200, 300);
+        let metadata = ExportMetadata::new("page.png", (1200, 300), &result, "eng").with_thumbnail(&image);
+        let uri = metadata.thumbnail.unwrap();
+        let encoded = uri.strip_prefix("data:image/jpeg;base64,").unwrap();
+        let bytes = base64::engine::general_purpose::STANDARD.decode(encoded).unwrap();
+        let thumbnail = image::load_from_memory(&bytes).unwrap();
+        assert_eq!((thumbnail.width(), thumbnail.height()), (THUMBNAIL_MAX_SIZE, 40));
+
+        let small = thumbnail_data_uri(&DynamicImage::new_rgb8(20, 10), THUMBNAIL_MAX_SIZE).unwrap();
+        let bytes = base64::engine::general_purpose::STANDARD.decode(&small["data:image/jpeg;base64,".len()..]).unwrap();
+        assert_eq!(image::load_from_memory(&bytes).unwrap().width(), 20);
+    }
+}
@@ -0,0 +1,326 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use anyhow::{anyhow, bail, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::ocr::{OcrEngine, ProcessOptions};
+
+// 可执行文件旁的配置文件名
+pub const FILE_NAME: &str = "ocr-rs.toml";
+
+// 供高级用户手动编辑的配置文件：启动时作为默认的识别选项与模型路径，
+// 界面、命令行参数与配置方案可以覆盖其中的选项。例如：
+//
+// model_path = "models/ocr.safetensors"
+//
+// [options]
+// language = "chi_sim+eng"
+// engine = "tesseract"
+// low_confidence_fallback = true
+// fallback_threshold = 0.5
+//
+// [options.preprocess]
+// binarize = "Adaptive"
+// block_size = 41
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FileConfig {
+    // 启动时加载的 Candle 模型，相对路径相对于配置文件所在目录；保持文件中的写法，创建引擎时才解析
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model_path: Option<PathBuf>,
+    pub options: ProcessOptions,
+}
+
+// 可执行文件所在目录下的配置文件
+pub fn path() -> Option<PathBuf> {
+    let exe = std::env::current_exe().ok()?;
+    Some(exe.parent()?.join(FILE_NAME))
+}
+
+impl FileConfig {
+    // 文件不存在时返回 None；格式错误时记录警告并忽略，不影响启动
+    pub fn load() -> Option<Self> {
+        let path = path()?;
+        if !path.exists() {
+            return None;
+        }
+        match Self::load_from(&path) {
+            Ok(config) => {
+                log::info!("已读取配置文件 {}", path.display());
+                Some(config)
+            }
+            Err(e) => {
+                log::warn!("配置文件 {} 无效: {}", path.display(), e);
+                None
+            }
+        }
+    }
+
+    pub fn load_from(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        Ok(toml::from_str(&content)?)
+    }
+
+    // 写回可执行文件旁的配置文件，返回写入的路径
+    pub fn save(&self) -> Result<PathBuf> {
+        let path = path().ok_or_else(|| anyhow!("无法确定可执行文件所在目录"))?;
+        self.save_to(&path)?;
+        Ok(path)
+    }
+
+    // 已有的文件只改写变化的键，保留其中的注释与未变化的写法
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        // 先转换为 JSON 值（f32 保留简短的十进制表示），再按 TOML 格式写出
+        let value: Value = serde_json::from_str(&serde_json::to_string(self)?)?;
+        let mut tables = Vec::new();
+        if let Value::Object(map) = &value {
+            collect_tables(&mut tables, Vec::new(), map)?;
+        }
+        let existing = if path.exists() { std::fs::read_to_string(path)? } else { String::new() };
+        std::fs::write(path, merge(&existing, &tables))?;
+        Ok(())
+    }
+
+    // 模型文件的实际路径，相对路径相对于 config_dir
+    pub fn model_file(&self, config_dir: &Path) -> Option<PathBuf> {
+        self.model_path.as_ref().map(|model| config_dir.join(model))
+    }
+
+    // 创建识别引擎并加载配置的模型；模型加载失败时仍使用默认引擎
+    pub fn engine(&self) -> OcrEngine {
+        self.with_model(OcrEngine::new())
+    }
+
+    // 图形界面按设置决定是否允许演示模式
+    pub fn engine_with_demo_mode(&self, allow_demo: bool) -> OcrEngine {
+        self.with_model(OcrEngine::with_demo_mode(allow_demo))
+    }
+
+    fn with_model(&self, mut engine: OcrEngine) -> OcrEngine {
+        let config_dir = path().and_then(|path| path.parent().map(Path::to_path_buf)).unwrap_or_default();
+        if let Some(model) = self.model_file(&config_dir) {
+            if let Err(e) = engine.load_candle_model(&model) {
+                log::warn!("无法加载配置文件中的模型 {}: {}", model.display(), e);
+            }
+        }
+        engine
+    }
+}
+
+// 要写出的一个表：表的路径（根表为空）与其中按 TOML 写好的键值，不含子表
+type Table = (Vec<String>, Vec<(String, String)>);
+
+// 先收集本表的键值，再依次收集子表；值为空的键不写出
+fn collect_tables(tables: &mut Vec<Table>, path: Vec<String>, map: &Map<String, Value>) -> Result<()> {
+    let mut entries = Vec::new();
+    for (key, value) in map {
+        if !value.is_object() && !value.is_null() {
+            entries.push((key.clone(), toml_value(value)?));
+        }
+    }
+    tables.push((path.clone(), entries));
+    for (key, value) in map {
+        if let Value::Object(table) = value {
+            let mut child = path.clone();
+            child.push(key.clone());
+            collect_tables(tables, child, table)?;
+        }
+    }
+    Ok(())
+}
+
+// 把要写出的表合并进已有的文件内容：注释、空行与值未变化的键原样保留，
+// 值变化的键改写，不再设置的键删除，缺少的键追加到所在表的末尾，缺少的表追加到文件末尾
+fn merge(existing: &str, tables: &[Table]) -> String {
+    let mut out: Vec<String> = Vec::new();
+    let mut written: HashSet<(Vec<String>, String)> = HashSet::new();
+    // 各表最后一个键之后的位置，缺少的键插入到这里
+    let mut section_ends: Vec<(Vec<String>, usize)> = vec![(Vec::new(), 0)];
+    // 当前所在的表；数组表等无法识别的表为 None，其中的内容原样保留
+    let mut current = Some(Vec::new());
+    // 跨行的值（如多行数组）读完整后再一起处理
+    let mut pending = String::new();
+
+    for line in existing.lines() {
+        let trimmed = line.trim();
+        if pending.is_empty() {
+            if trimmed.starts_with('[') {
+                current = header_path(trimmed);
+                out.push(line.to_string());
+                if let Some(path) = &current {
+                    section_ends.push((path.clone(), out.len()));
+                }
+                continue;
+            }
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                out.push(line.to_string());
+                continue;
+            }
+        }
+        pending.push_str(line);
+        pending.push('\n');
+        let Ok(parsed) = toml::from_str::<toml::Table>(&pending) else { continue };
+        let unit = std::mem::take(&mut pending);
+        let (Some(path), Some((key, old))) = (&current, parsed.into_iter().next()) else {
+            out.extend(unit.lines().map(str::to_string));
+            continue;
+        };
+        let rendered = tables
+            .iter()
+            .find(|(table, _)| table == path)
+            .and_then(|(_, entries)| entries.iter().find(|(name, _)| *name == key))
+            .map(|(_, value)| value);
+        if let Some(value) = rendered {
+            let line = format!("{} = {}", toml_key(&key), value);
+            let unchanged = toml::from_str::<toml::Table>(&line).ok().and_then(|new| new.get(&key).cloned()) == Some(old);
+            if unchanged {
+                out.extend(unit.lines().map(str::to_string));
+            } else {
+                out.push(line);
+            }
+            written.insert((path.clone(), key));
+        }
+        if let Some(end) = section_ends.iter_mut().rev().find(|(table, _)| table == path) {
+            end.1 = out.len();
+        }
+    }
+    // 未能解析的残余内容原样保留
+    out.extend(pending.lines().map(str::to_string));
+
+    let mut inserts = Vec::new();
+    let mut appended = Vec::new();
+    for (path, entries) in tables {
+        let missing: Vec<String> = entries
+            .iter()
+            .filter(|(key, _)| !written.contains(&(path.clone(), key.clone())))
+            .map(|(key, value)| format!("{} = {}", toml_key(key), value))
+            .collect();
+        if missing.is_empty() {
+            continue;
+        }
+        match section_ends.iter().rev().find(|(table, _)| table == path) {
+            Some((_, end)) => inserts.push((*end, missing)),
+            None => {
+                appended.push(String::new());
+                appended.push(format!("[{}]", path.iter().map(|key| toml_key(key)).collect::<Vec<_>>().join(".")));
+                appended.extend(missing);
+            }
+        }
+    }
+    // 从后往前插入，前面的位置不受影响
+    inserts.sort_by_key(|(end, _)| std::cmp::Reverse(*end));
+    for (end, lines) in inserts {
+        out.splice(end..end, lines);
+    }
+    out.extend(appended);
+
+    let mut content = out.join("\n").trim_start().to_string();
+    content.push('\n');
+    content
+}
+
+// 表头对应的表路径，如 [options . "pre process"] 为 ["options", "pre process"]；数组表返回 None
+fn header_path(header: &str) -> Option<Vec<String>> {
+    if header.starts_with("[[") {
+        return None;
+    }
+    let mut table = toml::from_str::<toml::Table>(header).ok()?;
+    let mut path = Vec::new();
+    while let Some((key, value)) = table.into_iter().next() {
+        path.push(key);
+        match value {
+            toml::Value::Table(child) => table = child,
+            _ => return None,
+        }
+    }
+    Some(path)
+}
+
+fn toml_key(key: &str) -> String {
+    if !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+        key.to_string()
+    } else {
+        Value::String(key.to_string()).to_string()
+    }
+}
+
+// JSON 字符串的转义写法在 TOML 中同样有效；数组中的表写为行内表。
+// TOML 没有空值：表中的空值省略，数组中的空值无法表示，返回错误
+fn toml_value(value: &Value) -> Result<String> {
+    Ok(match value {
+        Value::Null => bail!("配置中的数组不能包含空值"),
+        Value::Array(items) => format!("[{}]", items.iter().map(toml_value).collect::<Result<Vec<_>>>()?.join(", ")),
+        Value::Object(map) => format!(
+            "{{ {} }}",
+            map.iter()
+                .filter(|(_, value)| !value.is_null())
+                .map(|(key, value)| Ok(format!("{} = {}", toml_key(key), toml_value(value)?)))
+                .collect::<Result<Vec<_>>>()?
+                .join(", ")
+        ),
+        other => other.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ocr::EnginePreference;
+    use crate::preprocess::BinarizeMethod;
+
+    #[test]
+    fn test_roundtrip_and_relative_model_path() {
+        let dir = std::env::temp_dir().join(format!("ocr-rs-file-config-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(FILE_NAME);
+
+        let content = "model_path = \"models/ocr.safetensors\"\n\n[options]\nlanguage = \"jpn\"\nengine = \"tesseract\"\n\n[options.preprocess]\nbinarize = \"Adaptive\"\n";
+        std::fs::write(&path, content).unwrap();
+        let config = FileConfig::load_from(&path).unwrap();
+        assert_eq!(config.model_path, Some(PathBuf::from("models/ocr.safetensors")));
+        assert_eq!(config.model_file(&dir), Some(dir.join("models/ocr.safetensors")));
+        assert_eq!(config.options.tesseract.language, "jpn");
+        assert_eq!(config.options.engine, EnginePreference::Tesseract);
+        assert_eq!(config.options.preprocess.binarize, BinarizeMethod::Adaptive);
+        // 未写出的选项使用默认值
+        assert_eq!(config.options.tiling, ProcessOptions::default().tiling);
+
+        // 写回后能读出相同的设置
+        let mut saved = FileConfig { model_path: None, options: config.options.clone() };
+        saved.options.candle.fallback_threshold = 0.35;
+        saved.save_to(&path).unwrap();
+        assert_eq!(FileConfig::load_from(&path).unwrap(), saved);
+        assert!(std::fs::read_to_string(&path).unwrap().contains("fallback_threshold = 0.35"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_save_keeps_comments_and_relative_model_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(FILE_NAME);
+        let content = "# 本机配置\nmodel_path = \"models/ocr.safetensors\"\n\n[options]\n# 日文为主\nlanguage = \"jpn\" # 竖排\nengine = \"tesseract\"\n";
+        std::fs::write(&path, content).unwrap();
+
+        let mut config = FileConfig::load_from(&path).unwrap();
+        config.options.engine = EnginePreference::Candle;
+        config.save_to(&path).unwrap();
+
+        let saved = std::fs::read_to_string(&path).unwrap();
+        assert!(saved.starts_with("# 本机配置\nmodel_path = \"models/ocr.safetensors\"\n"));
+        assert!(saved.contains("# 日文为主\nlanguage = \"jpn\" # 竖排\nengine = \"candle\"\n"));
+        assert_eq!(saved.matches("[options]").count(), 1);
+        assert_eq!(FileConfig::load_from(&path).unwrap(), config);
+
+        // 再次保存不改变文件
+        config.save_to(&path).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), saved);
+    }
+
+    #[test]
+    fn test_null_in_array_is_rejected() {
+        assert_eq!(toml_value(&serde_json::json!([1, 2])).unwrap(), "[1, 2]");
+        assert!(toml_value(&serde_json::json!([1, null])).is_err());
+        assert_eq!(toml_value(&serde_json::json!([{ "a": 1, "b": null }])).unwrap(), "[{ a = 1 }]");
+    }
+}
@@ -0,0 +1,101 @@
+use std::borrow::Cow;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use ab_glyph::{Font, FontRef};
+use eframe::egui::FontData;
+
+// 外部字体文件，存在时优先使用，便于替换为其他字体
+pub const EXTERNAL_FONT_PATH: &str = "assets/font/hei.ttf";
+
+// 编译时内嵌的中文字体，外部文件缺失时使用，保证中文始终能正常显示
+const EMBEDDED_FONT: &[u8] = include_bytes!("../assets/font/hei.ttf");
+
+// 中文字体缺字时依次尝试的系统字体：符号、表情与 CJK 扩展区汉字，不存在的跳过
+const FALLBACK_FONT_PATHS: &[&str] = &[
+    "C:/Windows/Fonts/seguisym.ttf",
+    "C:/Windows/Fonts/seguiemj.ttf",
+    "C:/Windows/Fonts/simsunb.ttf",
+    "/System/Library/Fonts/Apple Symbols.ttf",
+    "/Library/Fonts/Arial Unicode.ttf",
+    "/System/Library/Fonts/Supplemental/Arial Unicode.ttf",
+    "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf",
+    "/usr/share/fonts/truetype/noto/NotoSansSymbols2-Regular.ttf",
+    "/usr/share/fonts/opentype/noto/NotoSansSymbols2-Regular.otf",
+    "/usr/share/fonts/truetype/noto/NotoSansMath-Regular.ttf",
+];
+
+// 依次查找当前目录和可执行文件所在目录下的外部字体
+fn external_font_candidates() -> Vec<PathBuf> {
+    let mut candidates = vec![PathBuf::from(EXTERNAL_FONT_PATH)];
+    if let Some(dir) = std::env::current_exe().ok().and_then(|exe| exe.parent().map(PathBuf::from)) {
+        candidates.push(dir.join(EXTERNAL_FONT_PATH));
+    }
+    candidates
+}
+
+// 读取中文字体数据，返回字体数据及其来源说明
+pub fn load_cjk_font() -> (Cow<'static, [u8]>, String) {
+    for path in external_font_candidates() {
+        if let Ok(data) = std::fs::read(&path) {
+            return (Cow::Owned(data), path.display().to_string());
+        }
+    }
+    (Cow::Borrowed(EMBEDDED_FONT), "内置字体".to_string())
+}
+
+// 读取存在的后备字体，返回字体名与字体数据
+pub fn load_fallback_fonts() -> Vec<(String, Vec<u8>)> {
+    FALLBACK_FONT_PATHS
+        .iter()
+        .map(Path::new)
+        .filter_map(|path| {
+            let data = std::fs::read(path).ok()?;
+            let name = path.file_stem()?.to_string_lossy().to_string();
+            Some((name, data))
+        })
+        .collect()
+}
+
+// 界面字体链的字符覆盖范围，用于找出会显示为方框（豆腐块）的字符
+pub struct GlyphCoverage {
+    fonts: Vec<Arc<FontData>>,
+}
+
+impl GlyphCoverage {
+    pub fn new(fonts: Vec<Arc<FontData>>) -> Self {
+        Self { fonts }
+    }
+
+    // 字体链中所有字体都没有字形的字符（去重，按出现顺序），不含空白与控制字符
+    pub fn missing(&self, text: &str) -> Vec<char> {
+        let fonts: Vec<FontRef> = self
+            .fonts
+            .iter()
+            .filter_map(|data| FontRef::try_from_slice_and_index(&data.font, data.index).ok())
+            .collect();
+        let mut missing = Vec::new();
+        for c in text.chars() {
+            if c.is_whitespace() || c.is_control() || missing.contains(&c) {
+                continue;
+            }
+            if fonts.iter().all(|font| font.glyph_id(c).0 == 0) {
+                missing.push(c);
+            }
+        }
+        missing
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_glyphs() {
+        let coverage = GlyphCoverage::new(vec![Arc::new(FontData::from_static(EMBEDDED_FONT))]);
+        assert!(coverage.missing("识别结果 OCR 123").is_empty());
+        // 私用区字符与 CJK 扩展 B 区的汉字不在内置字体中
+        assert_eq!(coverage.missing("甲\u{F0000}乙\u{F0000}𠀀"), vec!['\u{F0000}', '𠀀']);
+        assert_eq!(GlyphCoverage::new(Vec::new()).missing("a"), vec!['a']);
+    }
+}
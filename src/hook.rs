@@ -0,0 +1,65 @@
+use std::path::Path;
+use std::process::Command;
+use anyhow::{bail, Result};
+
+// 识别成功后执行的命令，例如 notify-send 或自定义脚本。
+// 命令中的 {image} 替换为源图片路径，{result} 替换为按导出格式写出的结果文件路径
+pub fn expand(command: &str, image: Option<&Path>, result: &Path) -> String {
+    let image = image.map(|path| shell_quote(&path.to_string_lossy())).unwrap_or_else(|| shell_quote(""));
+    command
+        .replace("{image}", &image)
+        .replace("{result}", &shell_quote(&result.to_string_lossy()))
+}
+
+// 为路径加上引号，路径中的空格和特殊字符不会被 shell 拆分或解释
+#[cfg(not(windows))]
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+#[cfg(windows)]
+fn shell_quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('"', ""))
+}
+
+// 通过系统 shell 执行命令并等待结束，退出码非零时返回包含标准错误输出的错误
+pub fn run(command: &str) -> Result<()> {
+    #[cfg(not(windows))]
+    let output = Command::new("sh").arg("-c").arg(command).output()?;
+    #[cfg(windows)]
+    let output = Command::new("cmd").arg("/C").arg(command).output()?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        match output.status.code() {
+            Some(code) => bail!("命令退出码 {}: {}", code, stderr.trim()),
+            None => bail!("命令被中止: {}", stderr.trim()),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(not(windows))]
+    fn test_expand_quotes_paths() {
+        let command = expand(
+            "notify-send 完成 {image} && cat {result}",
+            Some(Path::new("/tmp/my scan's.png")),
+            Path::new("/tmp/result 1.txt"),
+        );
+        assert_eq!(command, r"notify-send 完成 '/tmp/my scan'\''s.png' && cat '/tmp/result 1.txt'");
+        assert_eq!(expand("echo {image}", None, Path::new("r.txt")), "echo ''");
+    }
+
+    #[test]
+    #[cfg(not(windows))]
+    fn test_run_reports_failure() {
+        assert!(run("true").is_ok());
+        let error = run("echo oops >&2; exit 3").unwrap_err().to_string();
+        assert!(error.contains('3') && error.contains("oops"));
+    }
+}
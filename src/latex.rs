@@ -0,0 +1,246 @@
+// 实验性的“复制为 LaTeX”：把看起来像公式的行转换为行内公式，其余行原样保留。
+// 只处理常见写法（上下标、a/b 分数、根号、希腊字母与运算符），结果仍需人工检查
+
+// 公式中常见、普通文字里少见的符号
+const MATH_SYMBOLS: &str = "=<>^√²³±×÷≤≥≠≈∑∫π∞∂→·";
+
+// 转换为命令的符号
+const SYMBOL_COMMANDS: &[(char, &str)] = &[
+    ('×', "\\times"),
+    ('÷', "\\div"),
+    ('±', "\\pm"),
+    ('≤', "\\leq"),
+    ('≥', "\\geq"),
+    ('≠', "\\neq"),
+    ('≈', "\\approx"),
+    ('∞', "\\infty"),
+    ('∑', "\\sum"),
+    ('∫', "\\int"),
+    ('∂', "\\partial"),
+    ('→', "\\to"),
+    ('·', "\\cdot"),
+    ('α', "\\alpha"),
+    ('β', "\\beta"),
+    ('γ', "\\gamma"),
+    ('δ', "\\delta"),
+    ('θ', "\\theta"),
+    ('λ', "\\lambda"),
+    ('μ', "\\mu"),
+    ('π', "\\pi"),
+    ('σ', "\\sigma"),
+    ('ω', "\\omega"),
+    ('Δ', "\\Delta"),
+    ('%', "\\%"),
+];
+
+// 以命令形式输出的函数名
+const FUNCTIONS: &[&str] = &["sin", "cos", "tan", "cot", "log", "ln", "exp", "lim", "max", "min"];
+
+const SUPERSCRIPTS: &str = "⁰¹²³⁴⁵⁶⁷⁸⁹⁺⁻ⁿ";
+const SUBSCRIPTS: &str = "₀₁₂₃₄₅₆₇₈₉₊₋";
+const PLAIN: &str = "0123456789+-n";
+
+// 把文本中像公式的行包成 $...$ 并转换常见写法
+pub fn to_latex(text: &str) -> String {
+    text.lines()
+        .map(|line| {
+            if is_formula_line(line) {
+                let indent = &line[..line.len() - line.trim_start().len()];
+                format!("{}${}$", indent, convert_line(line.trim()))
+            } else {
+                line.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// 含公式符号、没有汉字，且三个字母以上的普通单词不超过一个（函数名除外）。
+// 只有斜杠时须恰好一个且含数字，避免把 and/or、日期当作分数
+fn is_formula_line(line: &str) -> bool {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('$') {
+        return false;
+    }
+    if !line.chars().any(|c| MATH_SYMBOLS.contains(c))
+        && (line.matches('/').count() != 1 || !line.chars().any(|c| c.is_ascii_digit()))
+    {
+        return false;
+    }
+    if line.chars().any(|c| ('\u{4E00}'..='\u{9FFF}').contains(&c) || ('\u{3040}'..='\u{30FF}').contains(&c)) {
+        return false;
+    }
+    let words = line
+        .split(|c: char| !c.is_ascii_alphabetic())
+        .filter(|word| word.len() >= 3 && !FUNCTIONS.contains(word))
+        .count();
+    words <= 1
+}
+
+fn convert_line(line: &str) -> String {
+    let chars: Vec<char> = line.chars().collect();
+    let mut out = String::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if SUPERSCRIPTS.contains(c) || SUBSCRIPTS.contains(c) {
+            let table = if SUPERSCRIPTS.contains(c) { SUPERSCRIPTS } else { SUBSCRIPTS };
+            let mut run = String::new();
+            while i < chars.len() && table.contains(chars[i]) {
+                let position = table.chars().position(|s| s == chars[i]).unwrap_or_default();
+                run.extend(PLAIN.chars().nth(position));
+                i += 1;
+            }
+            out.push(if table == SUPERSCRIPTS { '^' } else { '_' });
+            push_group(&mut out, &run);
+            continue;
+        }
+        match c {
+            // x^10 → x^{10}
+            '^' | '_' => {
+                let run = take_operand(&chars, i + 1);
+                out.push(c);
+                push_group(&mut out, &run.iter().collect::<String>());
+                i += 1 + run.len();
+            }
+            '√' => {
+                let run = take_operand(&chars, i + 1);
+                let inner: String = run.iter().collect();
+                out.push_str(&format!("\\sqrt{{{}}}", strip_parens(&inner)));
+                i += 1 + run.len();
+            }
+            '/' => {
+                let numerator = split_operand_tail(&mut out);
+                let denominator = take_operand(&chars, i + 1);
+                if numerator.is_empty() || denominator.is_empty() {
+                    out.push_str(&numerator);
+                    out.push('/');
+                    i += 1;
+                } else {
+                    let denominator: String = denominator.iter().collect();
+                    out.push_str(&format!("\\frac{{{}}}{{{}}}", strip_parens(&numerator), strip_parens(&denominator)));
+                    i += 1 + denominator.chars().count();
+                }
+            }
+            c if c.is_ascii_alphabetic() => {
+                let start = i;
+                while i < chars.len() && chars[i].is_ascii_alphabetic() {
+                    i += 1;
+                }
+                let word: String = chars[start..i].iter().collect();
+                if FUNCTIONS.contains(&word.as_str()) {
+                    out.push('\\');
+                }
+                out.push_str(&word);
+            }
+            c => {
+                match SYMBOL_COMMANDS.iter().find(|(symbol, _)| *symbol == c) {
+                    Some((_, command)) => {
+                        out.push_str(command);
+                        // 命令后紧跟字母时需要空格分隔
+                        if chars.get(i + 1).is_some_and(|next| next.is_ascii_alphabetic()) {
+                            out.push(' ');
+                        }
+                    }
+                    None => out.push(c),
+                }
+                i += 1;
+            }
+        }
+    }
+    out
+}
+
+// 单个字符的上下标不加花括号
+fn push_group(out: &mut String, run: &str) {
+    if run.chars().count() == 1 {
+        out.push_str(run);
+    } else {
+        out.push('{');
+        out.push_str(run);
+        out.push('}');
+    }
+}
+
+// 从 start 开始的操作数：括号括起的整体，或连续的字母、数字与小数点
+fn take_operand(chars: &[char], start: usize) -> &[char] {
+    if chars.get(start) == Some(&'(') {
+        let mut depth = 0;
+        for (offset, &c) in chars[start..].iter().enumerate() {
+            match c {
+                '(' => depth += 1,
+                ')' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return &chars[start..=start + offset];
+                    }
+                }
+                _ => {}
+            }
+        }
+        return &[];
+    }
+    let end = chars[start.min(chars.len())..]
+        .iter()
+        .position(|c| !(c.is_ascii_alphanumeric() || *c == '.'))
+        .map_or(chars.len(), |offset| start + offset);
+    &chars[start.min(end)..end]
+}
+
+// 从已输出的文本末尾取下分子：括号括起的整体，或连续的字母、数字、小数点（含前面的命令反斜杠）
+fn split_operand_tail(out: &mut String) -> String {
+    let chars: Vec<char> = out.chars().collect();
+    let mut start = chars.len();
+    if chars.last() == Some(&')') {
+        let mut depth = 0;
+        for (index, &c) in chars.iter().enumerate().rev() {
+            match c {
+                ')' => depth += 1,
+                '(' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        start = index;
+                        break;
+                    }
+                }
+                _ => {}
+            }
+        }
+    } else {
+        while start > 0 && (chars[start - 1].is_ascii_alphanumeric() || chars[start - 1] == '.') {
+            start -= 1;
+        }
+        if start > 0 && start < chars.len() && chars[start - 1] == '\\' {
+            start -= 1;
+        }
+    }
+    let tail: String = chars[start..].iter().collect();
+    out.truncate(out.len() - tail.len());
+    tail
+}
+
+fn strip_parens(operand: &str) -> &str {
+    operand.strip_prefix('(').and_then(|inner| inner.strip_suffix(')')).unwrap_or(operand)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_formula_lines_are_converted() {
+        assert_eq!(to_latex("E = mc²"), "$E = mc^2$");
+        assert_eq!(to_latex("x₁₀ + y^10 ≤ 1/2"), "$x_{10} + y^{10} \\leq \\frac{1}{2}$");
+        assert_eq!(to_latex("(a+b)/c = √(x+1)"), "$\\frac{a+b}{c} = \\sqrt{x+1}$");
+        assert_eq!(to_latex("sin x × πr"), "$\\sin x \\times \\pi r$");
+    }
+
+    #[test]
+    fn test_prose_is_kept() {
+        let text = "第一章 勾股定理\nThe theorem states that a² + b² = c²\n\n  a² + b² = c²";
+        assert_eq!(to_latex(text), "第一章 勾股定理\nThe theorem states that a² + b² = c²\n\n  $a^2 + b^2 = c^2$");
+        assert_eq!(to_latex("and/or"), "and/or");
+        assert_eq!(to_latex("2024/01/05"), "2024/01/05");
+        assert_eq!(to_latex("3/4"), "$\\frac{3}{4}$");
+    }
+}
@@ -0,0 +1,335 @@
+use image::{DynamicImage, GenericImageView, GrayImage};
+use serde::{Deserialize, Serialize};
+
+use crate::ocr::{self, BoundingBox, OcrResult};
+use crate::preprocess::otsu_threshold;
+use crate::tiling::Tile;
+
+// 版面检测时图片的最长边，超过时先缩小以保证速度
+const DETECT_MAX_SIDE: u32 = 2000;
+// 高度小于该值（像素）的行带视为噪点
+const MIN_LINE_HEIGHT: u32 = 3;
+// 分割出的文本块向外留出的边距（检测尺寸下的像素），避免裁掉笔画边缘
+const PANEL_PADDING: u32 = 4;
+
+// 自动分割文本块：截图中由大片空白隔开的多个面板（仪表盘、对比截图）分别识别，结果按块分节显示
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PanelOptions {
+    pub enabled: bool,
+    // 空白宽度达到该值（原图像素）才视为文本块之间的分隔
+    pub min_gap: u32,
+}
+
+impl Default for PanelOptions {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_gap: 40,
+        }
+    }
+}
+
+// 仅检测文本区域（不识别文字）：基于投影分析，先按行切分，再在行内按较大的空白切分为块
+pub fn detect_text_regions(image: &DynamicImage) -> Vec<BoundingBox> {
+    let (width, height) = image.dimensions();
+    if width == 0 || height == 0 {
+        return Vec::new();
+    }
+
+    let scale = (DETECT_MAX_SIDE as f32 / width.max(height) as f32).min(1.0);
+    let gray = if scale < 1.0 {
+        image.thumbnail(DETECT_MAX_SIDE, DETECT_MAX_SIDE).to_luma8()
+    } else {
+        image.to_luma8()
+    };
+    let ink = ink_mask(&gray);
+
+    let mut boxes = Vec::new();
+    for (top, bottom) in row_bands(&ink) {
+        let line_height = bottom - top;
+        for (left, right) in column_segments(&ink, top, bottom, line_height) {
+            boxes.push(BoundingBox {
+                text: String::new(),
+                confidence: 0.0,
+                x: (left as f32 / scale) as u32,
+                y: (top as f32 / scale) as u32,
+                width: ((right - left) as f32 / scale).ceil() as u32,
+                height: (line_height as f32 / scale).ceil() as u32,
+                edited: false,
+                language: None,
+            });
+        }
+    }
+    boxes
+}
+
+// 按空白分割文本块：把墨迹向四周扩张 min_gap 的一半后做连通域分析，间距小于 min_gap 的内容连成一块。
+// 返回各块在原图中的范围，按从上到下、同一排从左到右排列；只有一块时返回一个覆盖内容的范围
+pub fn split_panels(image: &DynamicImage, options: &PanelOptions) -> Vec<Tile> {
+    let (width, height) = image.dimensions();
+    if width == 0 || height == 0 {
+        return Vec::new();
+    }
+    let scale = (DETECT_MAX_SIDE as f32 / width.max(height) as f32).min(1.0);
+    let gray = if scale < 1.0 {
+        image.thumbnail(DETECT_MAX_SIDE, DETECT_MAX_SIDE).to_luma8()
+    } else {
+        image.to_luma8()
+    };
+    let ink = ink_mask(&gray);
+    let radius = ((options.min_gap as f32 * scale / 2.0).round() as u32).max(1);
+    let joined = dilate(&ink, radius);
+
+    // 连通域标记，同时记录每个连通域中真实墨迹的外接矩形
+    let (w, h) = (ink.width as usize, ink.height as usize);
+    let mut visited = vec![false; w * h];
+    let mut panels: Vec<(u32, u32, u32, u32)> = Vec::new();
+    for start in 0..w * h {
+        if visited[start] || !joined[start] {
+            continue;
+        }
+        visited[start] = true;
+        let mut stack = vec![start];
+        let mut bounds: Option<(u32, u32, u32, u32)> = None;
+        while let Some(index) = stack.pop() {
+            let (x, y) = (index % w, index / w);
+            if ink.pixels[index] {
+                let (x, y) = (x as u32, y as u32);
+                bounds = Some(match bounds {
+                    Some((left, top, right, bottom)) => (left.min(x), top.min(y), right.max(x + 1), bottom.max(y + 1)),
+                    None => (x, y, x + 1, y + 1),
+                });
+            }
+            let neighbors = [
+                (x > 0).then(|| index - 1),
+                (x + 1 < w).then(|| index + 1),
+                (y > 0).then(|| index - w),
+                (y + 1 < h).then(|| index + w),
+            ];
+            for neighbor in neighbors.into_iter().flatten() {
+                if joined[neighbor] && !visited[neighbor] {
+                    visited[neighbor] = true;
+                    stack.push(neighbor);
+                }
+            }
+        }
+        if let Some((left, top, right, bottom)) = bounds {
+            if right - left >= MIN_LINE_HEIGHT || bottom - top >= MIN_LINE_HEIGHT {
+                panels.push((left, top, right, bottom));
+            }
+        }
+    }
+
+    let mut tiles: Vec<Tile> = panels
+        .into_iter()
+        .map(|(left, top, right, bottom)| {
+            let to_original = |value: u32, limit: u32| ((value as f32 / scale).round() as u32).min(limit);
+            let x = to_original(left.saturating_sub(PANEL_PADDING), width);
+            let y = to_original(top.saturating_sub(PANEL_PADDING), height);
+            let right = to_original((right + PANEL_PADDING).min(ink.width), width);
+            let bottom = to_original((bottom + PANEL_PADDING).min(ink.height), height);
+            Tile { x, y, width: (right - x).max(1), height: (bottom - y).max(1) }
+        })
+        .collect();
+    sort_panels(&mut tiles);
+    tiles
+}
+
+// 纵向范围与本排第一块重叠的文本块归为同一排，排内从左到右
+fn sort_panels(tiles: &mut [Tile]) {
+    tiles.sort_by_key(|tile| (tile.y, tile.x));
+    let mut row_start = 0;
+    for index in 1..=tiles.len() {
+        let first = tiles[row_start];
+        let same_row = index < tiles.len() && tiles[index].y < first.y + first.height;
+        if !same_row {
+            tiles[row_start..index].sort_by_key(|tile| tile.x);
+            row_start = index;
+        }
+    }
+}
+
+// 墨迹向四周扩张 radius：先逐行再逐列，用前缀和判断窗口内是否有墨迹
+fn dilate(ink: &InkMask, radius: u32) -> Vec<bool> {
+    let (w, h) = (ink.width as usize, ink.height as usize);
+    let r = radius as usize;
+    let spread = |line: &[bool]| -> Vec<bool> {
+        let mut prefix = vec![0u32; line.len() + 1];
+        for (i, &value) in line.iter().enumerate() {
+            prefix[i + 1] = prefix[i] + value as u32;
+        }
+        (0..line.len())
+            .map(|i| prefix[(i + r + 1).min(line.len())] > prefix[i.saturating_sub(r)])
+            .collect()
+    };
+    let mut rows = Vec::with_capacity(w * h);
+    for line in ink.pixels.chunks(w) {
+        rows.extend(spread(line));
+    }
+    let mut output = vec![false; w * h];
+    for x in 0..w {
+        let column: Vec<bool> = (0..h).map(|y| rows[y * w + x]).collect();
+        for (y, value) in spread(&column).into_iter().enumerate() {
+            output[y * w + x] = value;
+        }
+    }
+    output
+}
+
+// 合并各文本块的识别结果：检测框换算到原图坐标，文本按块分节并加上“区块 n”标题
+pub fn merge_panel_results(panels: Vec<(Tile, OcrResult)>) -> Option<OcrResult> {
+    let mut merged = panels.first()?.1.clone();
+    let mut sections = Vec::new();
+    let mut boxes = Vec::new();
+    for (tile, result) in &panels {
+        let text = result.text.trim();
+        if !text.is_empty() {
+            sections.push(format!("【区块 {}】\n{}", sections.len() + 1, text));
+        }
+        boxes.extend(result.bounding_boxes.iter().map(|bbox| BoundingBox {
+            x: bbox.x + tile.x,
+            y: bbox.y + tile.y,
+            ..bbox.clone()
+        }));
+    }
+    let mean_confidence = panels.iter().map(|(_, result)| result.confidence).sum::<f32>() / panels.len() as f32;
+    merged.text = sections.join("\n\n");
+    merged.confidence = ocr::weighted_confidence(&boxes).unwrap_or(mean_confidence);
+    merged.detected_language = panels
+        .iter()
+        .map(|(_, result)| result.detected_language.clone())
+        .find(|language| !language.is_empty())
+        .unwrap_or_default();
+    merged.bounding_boxes = boxes;
+    Some(merged)
+}
+
+// 墨迹掩码：根据 Otsu 阈值判断前景，背景较暗（反色文档）时取亮像素为前景
+struct InkMask {
+    width: u32,
+    height: u32,
+    pixels: Vec<bool>,
+}
+
+impl InkMask {
+    fn get(&self, x: u32, y: u32) -> bool {
+        self.pixels[(y * self.width + x) as usize]
+    }
+}
+
+fn ink_mask(gray: &GrayImage) -> InkMask {
+    let threshold = otsu_threshold(gray);
+    let dark_count = gray.pixels().filter(|p| p[0] <= threshold).count();
+    let dark_is_ink = dark_count * 2 <= gray.pixels().len();
+
+    InkMask {
+        width: gray.width(),
+        height: gray.height(),
+        pixels: gray
+            .pixels()
+            .map(|p| (p[0] <= threshold) == dark_is_ink)
+            .collect(),
+    }
+}
+
+// 水平投影：连续含有墨迹的行组成一个行带
+fn row_bands(ink: &InkMask) -> Vec<(u32, u32)> {
+    let mut bands = Vec::new();
+    let mut start = None;
+    for y in 0..=ink.height {
+        let has_ink = y < ink.height && (0..ink.width).any(|x| ink.get(x, y));
+        match (has_ink, start) {
+            (true, None) => start = Some(y),
+            (false, Some(top)) => {
+                if y - top >= MIN_LINE_HEIGHT {
+                    bands.push((top, y));
+                }
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    bands
+}
+
+// 行内的垂直投影：空白宽度超过行高时切分为不同区块（如表格的列）
+fn column_segments(ink: &InkMask, top: u32, bottom: u32, line_height: u32) -> Vec<(u32, u32)> {
+    let mut segments = Vec::new();
+    let mut current: Option<(u32, u32)> = None;
+    for x in 0..ink.width {
+        if !(top..bottom).any(|y| ink.get(x, y)) {
+            continue;
+        }
+        current = match current {
+            Some((left, right)) if x - right <= line_height => Some((left, x + 1)),
+            Some(segment) => {
+                segments.push(segment);
+                Some((x, x + 1))
+            }
+            None => Some((x, x + 1)),
+        };
+    }
+    segments.extend(current);
+    segments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Luma;
+
+    #[test]
+    fn test_detect_lines_and_columns() {
+        // 两行“文字”，第二行有两列
+        let gray = GrayImage::from_fn(300, 120, |x, y| {
+            let first_line = (20..35).contains(&y) && (10..150).contains(&x);
+            let second_line = (70..85).contains(&y) && ((10..80).contains(&x) || (200..280).contains(&x));
+            if first_line || second_line { Luma([0]) } else { Luma([255]) }
+        });
+
+        let boxes = detect_text_regions(&DynamicImage::ImageLuma8(gray));
+        let rects: Vec<(u32, u32, u32, u32)> = boxes.iter().map(|b| (b.x, b.y, b.width, b.height)).collect();
+        assert_eq!(rects, vec![(10, 20, 140, 15), (10, 70, 70, 15), (200, 70, 80, 15)]);
+    }
+
+    #[test]
+    fn test_split_panels_by_whitespace() {
+        // 左上两行相距 10 像素属于同一块；右侧与下方的块之间隔着大片空白
+        let gray = GrayImage::from_fn(500, 300, |x, y| {
+            let left = ((20..35).contains(&y) || (45..60).contains(&y)) && (10..150).contains(&x);
+            let right = (30..50).contains(&y) && (300..450).contains(&x);
+            let bottom = (200..215).contains(&y) && (10..400).contains(&x);
+            if left || right || bottom { Luma([0]) } else { Luma([255]) }
+        });
+        let image = DynamicImage::ImageLuma8(gray);
+
+        let panels = split_panels(&image, &PanelOptions { enabled: true, min_gap: 40 });
+        let rects: Vec<(u32, u32, u32, u32)> = panels.iter().map(|t| (t.x, t.y, t.width, t.height)).collect();
+        assert_eq!(rects, vec![(6, 16, 148, 48), (296, 26, 158, 28), (6, 196, 398, 23)]);
+
+        // 分隔阈值大于块间空白时合为一块
+        assert_eq!(split_panels(&image, &PanelOptions { enabled: true, min_gap: 400 }).len(), 1);
+    }
+
+    #[test]
+    fn test_merge_panel_results_labels_sections() {
+        let result = |text: &str, x: u32| OcrResult {
+            text: text.to_string(),
+            raw_text: String::new(),
+            confidence: 0.8,
+            weighted_confidence: 0.0,
+            processing_time: 0.0,
+            bounding_boxes: vec![BoundingBox { x, y: 0, width: 10, height: 10, text: text.to_string(), confidence: 0.8, edited: false, language: None }],
+            timings: Vec::new(),
+            engine: "Candle".to_string(),
+            attempts: Vec::new(),
+            detected_language: String::new(),
+        };
+        let tile = |x: u32| Tile { x, y: 5, width: 100, height: 50 };
+        let merged = merge_panel_results(vec![(tile(0), result("左侧", 1)), (tile(200), result(" ", 2)), (tile(300), result("右侧", 3))]).unwrap();
+        assert_eq!(merged.text, "【区块 1】\n左侧\n\n【区块 2】\n右侧");
+        let xs: Vec<(u32, u32)> = merged.bounding_boxes.iter().map(|b| (b.x, b.y)).collect();
+        assert_eq!(xs, vec![(1, 5), (202, 5), (303, 5)]);
+    }
+}
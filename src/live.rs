@@ -0,0 +1,191 @@
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use anyhow::{Context, Result};
+use image::DynamicImage;
+use serde::{Deserialize, Serialize};
+
+use crate::{hook, scratch};
+
+// 区域连续识别：按固定间隔截取屏幕区域（如视频字幕区）并识别
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LiveCaptureOptions {
+    // 屏幕区域，单位为屏幕像素
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    // 两次截图之间的间隔（秒），识别耗时更长时识别完成后立即截取下一帧
+    pub interval_secs: f32,
+    // 截图命令，支持 {x}、{y}、{width}、{height} 和 {output}（截图保存路径，不含引号）
+    pub command: String,
+}
+
+impl Default for LiveCaptureOptions {
+    fn default() -> Self {
+        Self {
+            x: 0,
+            y: 0,
+            width: 800,
+            height: 120,
+            interval_secs: 1.0,
+            command: DEFAULT_CAPTURE_COMMAND.to_string(),
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+pub const DEFAULT_CAPTURE_COMMAND: &str = "screencapture -x -R{x},{y},{width},{height} '{output}'";
+#[cfg(windows)]
+pub const DEFAULT_CAPTURE_COMMAND: &str = "powershell -NoProfile -Command \"Add-Type -AssemblyName System.Drawing; $b = New-Object System.Drawing.Bitmap {width},{height}; [System.Drawing.Graphics]::FromImage($b).CopyFromScreen({x},{y},0,0,$b.Size); $b.Save('{output}')\"";
+// X11 使用 ImageMagick；Wayland 可改为 grim -g '{x},{y} {width}x{height}' '{output}'
+#[cfg(not(any(target_os = "macos", windows)))]
+pub const DEFAULT_CAPTURE_COMMAND: &str = "import -window root -crop {width}x{height}+{x}+{y} '{output}'";
+
+impl LiveCaptureOptions {
+    pub fn expand_command(&self, output: &Path) -> String {
+        self.command
+            .replace("{x}", &self.x.to_string())
+            .replace("{y}", &self.y.to_string())
+            .replace("{width}", &self.width.to_string())
+            .replace("{height}", &self.height.to_string())
+            .replace("{output}", &output.to_string_lossy())
+    }
+
+    fn interval(&self) -> Duration {
+        Duration::from_secs_f32(self.interval_secs.clamp(0.2, 60.0))
+    }
+}
+
+// 执行截图命令并读取截图
+pub fn capture(options: &LiveCaptureOptions) -> Result<DynamicImage> {
+    let output = scratch::unique_path("live", "png")?;
+    hook::run(&options.expand_command(&output))?;
+    let image = image::open(&output).with_context(|| format!("无法读取截图 {}", output.display()));
+    let _ = std::fs::remove_file(&output);
+    image
+}
+
+pub enum LiveEvent {
+    // 一帧的识别文本（未去重）
+    Text(String),
+    // 截图失败，连续识别随之停止
+    Error(String),
+}
+
+// 在后台线程中循环截图并调用 recognize 识别，直到停止或截图失败
+pub struct LiveCapture {
+    running: Arc<AtomicBool>,
+}
+
+impl LiveCapture {
+    pub fn start<R, F>(options: LiveCaptureOptions, recognize: R, on_event: F) -> Self
+    where
+        R: Fn(DynamicImage) -> Result<String> + Send + 'static,
+        F: Fn(LiveEvent) + Send + 'static,
+    {
+        let running = Arc::new(AtomicBool::new(true));
+        let flag = running.clone();
+
+        std::thread::spawn(move || {
+            while flag.load(Ordering::Relaxed) {
+                let started = Instant::now();
+                let image = match capture(&options) {
+                    Ok(image) => image,
+                    Err(e) => {
+                        on_event(LiveEvent::Error(format!("截图失败: {:#}", e)));
+                        return;
+                    }
+                };
+                // 单帧识别失败（如画面中暂时没有文字）不中断
+                match recognize(image) {
+                    Ok(text) if flag.load(Ordering::Relaxed) => on_event(LiveEvent::Text(text)),
+                    Ok(_) => {}
+                    Err(e) => log::debug!("连续识别单帧失败: {}", e),
+                }
+                std::thread::sleep(options.interval().saturating_sub(started.elapsed()));
+            }
+        });
+
+        Self { running }
+    }
+
+    pub fn stop(&self) {
+        self.running.store(false, Ordering::Relaxed);
+    }
+}
+
+impl Drop for LiveCapture {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+// 连续识别记录：只追加与上一帧不同的非空文本
+#[derive(Debug, Default)]
+pub struct LiveLog {
+    entries: Vec<(String, String)>,
+    // 上一帧去掉空白后的文本，空白差异不视为变化
+    previous: String,
+}
+
+impl LiveLog {
+    // 文本有变化时带时间追加，返回是否追加
+    pub fn push(&mut self, time: String, text: &str) -> bool {
+        let normalized: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+        if normalized == self.previous {
+            return false;
+        }
+        // 画面暂时没有文字时只更新上一帧，同一句字幕重新出现时照常记录
+        self.previous = normalized;
+        if self.previous.is_empty() {
+            return false;
+        }
+        self.entries.push((time, text.trim().to_string()));
+        true
+    }
+
+    pub fn entries(&self) -> &[(String, String)] {
+        &self.entries
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.previous.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_command() {
+        let options = LiveCaptureOptions {
+            x: 10,
+            y: -20,
+            width: 300,
+            height: 40,
+            command: "grim -g '{x},{y} {width}x{height}' '{output}'".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(
+            options.expand_command(Path::new("/tmp/live_0.png")),
+            "grim -g '10,-20 300x40' '/tmp/live_0.png'"
+        );
+    }
+
+    #[test]
+    fn test_log_skips_unchanged_frames() {
+        let mut log = LiveLog::default();
+        assert!(log.push("10:00:00".to_string(), "你好 世界\n"));
+        assert!(!log.push("10:00:01".to_string(), "你好世界"));
+        assert!(!log.push("10:00:02".to_string(), "  "));
+        assert!(log.push("10:00:03".to_string(), "你好世界"));
+        assert!(log.push("10:00:04".to_string(), "下一句"));
+        let texts: Vec<&str> = log.entries().iter().map(|(_, text)| text.as_str()).collect();
+        assert_eq!(texts, vec!["你好 世界", "你好世界", "下一句"]);
+    }
+}
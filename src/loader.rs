@@ -0,0 +1,356 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader, Cursor, Read, Seek};
+use std::path::Path;
+use std::time::Duration;
+use anyhow::{anyhow, bail, Result};
+use image::codecs::gif::GifDecoder;
+use image::codecs::webp::WebPDecoder;
+use image::{AnimationDecoder, DynamicImage, GrayAlphaImage, GrayImage, ImageFormat, ImageReader, Rgba, Rgba32FImage, RgbaImage};
+use serde::{Deserialize, Serialize};
+
+use crate::archive;
+
+// 支持打开的图片扩展名
+pub const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "bmp", "tif", "tiff", "webp", "gif", "hdr", "exr"];
+
+// 网络图片的大小与超时限制
+const MAX_DOWNLOAD_BYTES: u64 = 50 * 1024 * 1024;
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(30);
+
+// 自动窗口忽略最暗和最亮的这部分像素，避免个别坏点或饱和像素拉宽窗口
+const AUTO_WINDOW_CLIP: f64 = 0.005;
+
+// 16 位灰度图（医学、科学仪器图像）降为 8 位时映射到 0~255 的取值范围。
+// 这类图像的有效数据常只占 0~65535 中的一小段（如 12 位探测器），整体线性缩放后几乎全黑
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GrayWindow {
+    // 按完整的 0~65535 线性缩放
+    Full,
+    // 按图中像素值的分布自动选择范围
+    #[default]
+    Auto,
+    Manual { min: u16, max: u16 },
+}
+
+impl GrayWindow {
+    // 该窗口在给定图像上的实际范围，保证 min < max
+    pub fn range(self, values: &[u16]) -> (u16, u16) {
+        let (min, max) = match self {
+            Self::Full => (0, u16::MAX),
+            Self::Manual { min, max } => (min.min(max), min.max(max)),
+            Self::Auto => auto_window(values),
+        };
+        if min < max {
+            (min, max)
+        } else if max < u16::MAX {
+            (min, max + 1)
+        } else {
+            (min - 1, max)
+        }
+    }
+}
+
+// 按直方图取分位数作为窗口
+fn auto_window(values: &[u16]) -> (u16, u16) {
+    if values.is_empty() {
+        return (0, u16::MAX);
+    }
+    let mut histogram = vec![0usize; u16::MAX as usize + 1];
+    for &value in values {
+        histogram[value as usize] += 1;
+    }
+    let clip = (values.len() as f64 * AUTO_WINDOW_CLIP) as usize;
+    // 从一端累计像素数，超过裁剪数量处即为窗口边界
+    let percentile = |bins: &mut dyn Iterator<Item = usize>| {
+        let mut seen = 0;
+        for value in bins {
+            seen += histogram[value];
+            if seen > clip {
+                return value as u16;
+            }
+        }
+        0
+    };
+    let low = percentile(&mut (0..histogram.len()));
+    let high = percentile(&mut (0..histogram.len()).rev());
+    (low, high)
+}
+
+fn apply_window(value: u16, (min, max): (u16, u16)) -> u8 {
+    let scaled = (value.clamp(min, max) - min) as f32 / (max - min) as f32;
+    (scaled * 255.0).round() as u8
+}
+
+// 加载图片的所有帧：动画 GIF/WebP 返回每一帧（已合成为完整画面），其他格式只返回一帧。
+// 返回的图片均为 8 位，16 位和 HDR 图片在这里完成转换，16 位灰度图按 window 映射
+pub fn load_frames(path: &Path, window: GrayWindow) -> Result<Vec<DynamicImage>> {
+    // 压缩包中的图片直接从压缩包读出后解码
+    if archive::split(path).is_some() {
+        return load_frames_from_memory(&archive::read(path)?, window);
+    }
+    let mut reader = BufReader::new(File::open(path)?);
+    // 优先按文件内容判断格式，扩展名与内容不符时也能打开
+    let format = image::guess_format(reader.fill_buf()?)
+        .ok()
+        .or_else(|| ImageFormat::from_path(path).ok());
+    decode_frames(reader, format, window)
+}
+
+pub fn load_frames_from_memory(bytes: &[u8], window: GrayWindow) -> Result<Vec<DynamicImage>> {
+    decode_frames(Cursor::new(bytes), image::guess_format(bytes).ok(), window)
+}
+
+// 解码内存中图片的第一帧；format 为空时按内容判断格式
+pub fn load_first_frame_from_memory(bytes: &[u8], format: Option<ImageFormat>, window: GrayWindow) -> Result<DynamicImage> {
+    let format = format.or_else(|| image::guess_format(bytes).ok());
+    Ok(decode_frames(Cursor::new(bytes), format, window)?.remove(0))
+}
+
+fn decode_frames<R: BufRead + Seek>(reader: R, format: Option<ImageFormat>, window: GrayWindow) -> Result<Vec<DynamicImage>> {
+    let frames = match format {
+        Some(ImageFormat::Avif) => bail!("暂不支持解码 AVIF 图片，请先转换为 PNG 或 JPEG"),
+        Some(ImageFormat::Gif) => collect_frames(GifDecoder::new(reader)?)?,
+        Some(ImageFormat::WebP) => {
+            let decoder = WebPDecoder::new(reader)?;
+            if decoder.has_animation() {
+                collect_frames(decoder)?
+            } else {
+                vec![DynamicImage::from_decoder(decoder)?]
+            }
+        }
+        Some(format) => vec![ImageReader::with_format(reader, format).decode()?],
+        None => vec![ImageReader::new(reader).with_guessed_format()?.decode()?],
+    };
+
+    if frames.is_empty() {
+        bail!("图片中没有可显示的帧");
+    }
+    Ok(frames.into_iter().map(|frame| to_8bit(frame, window)).collect())
+}
+
+fn collect_frames<'a>(decoder: impl AnimationDecoder<'a>) -> Result<Vec<DynamicImage>> {
+    let frames = decoder.into_frames().collect_frames()?;
+    Ok(frames
+        .into_iter()
+        .map(|frame| DynamicImage::ImageRgba8(frame.into_buffer()))
+        .collect())
+}
+
+// 16 位彩色图片直接降为 8 位，16 位灰度图按窗口映射；HDR（浮点）图片先做色调映射，避免高光被直接截断
+fn to_8bit(image: DynamicImage, window: GrayWindow) -> DynamicImage {
+    match image {
+        DynamicImage::ImageRgb32F(_) | DynamicImage::ImageRgba32F(_) => tone_map(&image.into_rgba32f()),
+        DynamicImage::ImageLuma16(gray) => {
+            let range = window.range(gray.as_raw());
+            DynamicImage::ImageLuma8(GrayImage::from_fn(gray.width(), gray.height(), |x, y| {
+                image::Luma([apply_window(gray.get_pixel(x, y)[0], range)])
+            }))
+        }
+        DynamicImage::ImageLumaA16(gray) => {
+            let values: Vec<u16> = gray.pixels().map(|p| p[0]).collect();
+            let range = window.range(&values);
+            DynamicImage::ImageLumaA8(GrayAlphaImage::from_fn(gray.width(), gray.height(), |x, y| {
+                let [value, alpha] = gray.get_pixel(x, y).0;
+                image::LumaA([apply_window(value, range), (alpha >> 8) as u8])
+            }))
+        }
+        DynamicImage::ImageRgb16(_) => DynamicImage::ImageRgb8(image.to_rgb8()),
+        DynamicImage::ImageRgba16(_) => DynamicImage::ImageRgba8(image.to_rgba8()),
+        image => image,
+    }
+}
+
+// 扩展 Reinhard 色调映射（以图中最亮值为白点），再做 gamma 编码
+fn tone_map(image: &Rgba32FImage) -> DynamicImage {
+    let white = image
+        .pixels()
+        .flat_map(|p| p.0[..3].to_vec())
+        .filter(|v| v.is_finite())
+        .fold(f32::EPSILON, f32::max);
+    let map = |v: f32| {
+        let v = if v.is_finite() { v.max(0.0) } else { 0.0 };
+        let mapped = v * (1.0 + v / (white * white)) / (1.0 + v);
+        (mapped.clamp(0.0, 1.0).powf(1.0 / 2.2) * 255.0).round() as u8
+    };
+
+    DynamicImage::ImageRgba8(RgbaImage::from_fn(image.width(), image.height(), |x, y| {
+        let p = image.get_pixel(x, y);
+        Rgba([map(p[0]), map(p[1]), map(p[2]), (p[3].clamp(0.0, 1.0) * 255.0).round() as u8])
+    }))
+}
+
+pub fn is_supported_image(path: &Path) -> bool {
+    path.extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .is_some_and(|ext| IMAGE_EXTENSIONS.contains(&ext.as_str()))
+}
+
+pub fn is_image_url(text: &str) -> bool {
+    let text = text.trim();
+    (text.starts_with("http://") || text.starts_with("https://")) && !text.contains(char::is_whitespace)
+}
+
+// 下载网络图片（阻塞调用，需在后台线程执行）
+pub fn download_image(url: &str) -> Result<Vec<u8>> {
+    let agent = ureq::AgentBuilder::new().timeout(DOWNLOAD_TIMEOUT).build();
+    let response = agent.get(url.trim()).call().map_err(|e| match e {
+        ureq::Error::Status(code, _) => anyhow!("服务器返回错误状态码 {}", code),
+        ureq::Error::Transport(t) => anyhow!("网络错误: {}", t),
+    })?;
+
+    let content_type = response.content_type().to_string();
+    if !content_type.starts_with("image/") && content_type != "application/octet-stream" {
+        return Err(anyhow!("链接内容不是图片（{}）", content_type));
+    }
+
+    if let Some(length) = response.header("Content-Length").and_then(|v| v.parse::<u64>().ok()) {
+        if length > MAX_DOWNLOAD_BYTES {
+            return Err(anyhow!("图片过大（{} MB），上限为 {} MB", length / 1024 / 1024, MAX_DOWNLOAD_BYTES / 1024 / 1024));
+        }
+    }
+
+    // 没有 Content-Length 时边读边检查大小
+    let mut bytes = Vec::new();
+    response
+        .into_reader()
+        .take(MAX_DOWNLOAD_BYTES + 1)
+        .read_to_end(&mut bytes)?;
+    if bytes.len() as u64 > MAX_DOWNLOAD_BYTES {
+        return Err(anyhow!("图片过大，上限为 {} MB", MAX_DOWNLOAD_BYTES / 1024 / 1024));
+    }
+
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::codecs::gif::GifEncoder;
+    use image::codecs::webp::WebPEncoder;
+    use image::{ExtendedColorType, Frame, ImageBuffer, ImageEncoder, Luma, Rgb};
+
+    // 用无损编码的单帧拼出动画 WebP 容器（image 不支持编码动画 WebP）
+    fn animated_webp(shades: &[u8]) -> Vec<u8> {
+        fn chunk(name: &[u8], payload: &[u8]) -> Vec<u8> {
+            let mut bytes = name.to_vec();
+            bytes.extend((payload.len() as u32).to_le_bytes());
+            bytes.extend(payload);
+            if payload.len() % 2 == 1 {
+                bytes.push(0);
+            }
+            bytes
+        }
+        let u24 = |v: u32| v.to_le_bytes()[..3].to_vec();
+
+        let mut body = b"WEBP".to_vec();
+        body.extend(chunk(b"VP8X", &[&[0x02, 0, 0, 0][..], &u24(7), &u24(7)].concat()));
+        body.extend(chunk(b"ANIM", &[0, 0, 0, 0, 0, 0]));
+        for &shade in shades {
+            let mut still = Vec::new();
+            let pixels = [shade; 8 * 8 * 3];
+            WebPEncoder::new_lossless(&mut still)
+                .write_image(&pixels, 8, 8, ExtendedColorType::Rgb8)
+                .unwrap();
+            // 单帧文件为 RIFF 头 + VP8L 块，取出 VP8L 块放入 ANMF；标志 0x02 表示不与上一帧混合
+            let vp8l = &still[12..];
+            let frame = [&u24(0)[..], &u24(0), &u24(7), &u24(7), &u24(100), &[0x02], vp8l].concat();
+            body.extend(chunk(b"ANMF", &frame));
+        }
+
+        let mut riff = b"RIFF".to_vec();
+        riff.extend((body.len() as u32).to_le_bytes());
+        riff.extend(body);
+        riff
+    }
+
+    #[test]
+    fn test_load_animated_webp_frames() {
+        let frames = load_frames_from_memory(&animated_webp(&[0, 255]), GrayWindow::Auto).unwrap();
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].to_rgba8().get_pixel(3, 3)[0], 0);
+        assert_eq!(frames[1].to_rgba8().get_pixel(3, 3)[0], 255);
+    }
+
+    #[test]
+    fn test_sixteen_bit_image_converted_to_8bit() {
+        let image: ImageBuffer<Rgb<u16>, Vec<u16>> = ImageBuffer::from_pixel(4, 4, Rgb([65535, 32896, 0]));
+        let mut png = Vec::new();
+        DynamicImage::ImageRgb16(image)
+            .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+            .unwrap();
+
+        let frames = load_frames_from_memory(&png, GrayWindow::Auto).unwrap();
+        assert!(matches!(frames[0], DynamicImage::ImageRgb8(_)));
+        assert_eq!(frames[0].to_rgb8().get_pixel(0, 0).0, [255, 128, 0]);
+    }
+
+    #[test]
+    fn test_sixteen_bit_gray_windowed() {
+        // 12 位探测器的数据只占 0~4095
+        let image: ImageBuffer<Luma<u16>, Vec<u16>> = ImageBuffer::from_fn(64, 64, |x, _| Luma([x as u16 * 64]));
+        let mut png = Vec::new();
+        DynamicImage::ImageLuma16(image)
+            .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+            .unwrap();
+
+        let full = load_frames_from_memory(&png, GrayWindow::Full).unwrap()[0].to_luma8();
+        assert!(full.get_pixel(63, 0)[0] < 20);
+
+        let auto = load_frames_from_memory(&png, GrayWindow::Auto).unwrap()[0].to_luma8();
+        assert_eq!(auto.get_pixel(0, 0)[0], 0);
+        assert_eq!(auto.get_pixel(63, 0)[0], 255);
+
+        let manual = load_frames_from_memory(&png, GrayWindow::Manual { min: 1024, max: 2048 }).unwrap()[0].to_luma8();
+        assert_eq!(manual.get_pixel(15, 0)[0], 0);
+        assert_eq!(manual.get_pixel(24, 0)[0], 128);
+        assert_eq!(manual.get_pixel(40, 0)[0], 255);
+
+        assert_eq!(GrayWindow::Manual { min: 7, max: 7 }.range(&[]), (7, 8));
+    }
+
+    #[test]
+    fn test_hdr_image_tone_mapped() {
+        let image = Rgba32FImage::from_fn(2, 1, |x, _| {
+            if x == 0 { Rgba([8.0, 8.0, 8.0, 1.0]) } else { Rgba([0.5, 0.5, 0.5, 1.0]) }
+        });
+        let mapped = to_8bit(DynamicImage::ImageRgba32F(image), GrayWindow::Auto).to_rgba8();
+        // 最亮处映射为白色，较暗处保留层次而不是被截断
+        assert_eq!(mapped.get_pixel(0, 0).0, [255, 255, 255, 255]);
+        assert!((100..200).contains(&mapped.get_pixel(1, 0)[0]));
+    }
+
+    #[test]
+    fn test_load_animated_gif_frames() {
+        let path = std::env::temp_dir().join(format!("ocr_rs_test_{}.gif", std::process::id()));
+        {
+            let mut encoder = GifEncoder::new(File::create(&path).unwrap());
+            for shade in [0u8, 128, 255] {
+                let buffer = RgbaImage::from_pixel(8, 8, image::Rgba([shade, shade, shade, 255]));
+                encoder.encode_frame(Frame::new(buffer)).unwrap();
+            }
+        }
+
+        let frames = load_frames(&path, GrayWindow::Auto).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(frames.len(), 3);
+        assert_eq!(frames[2].to_rgba8().get_pixel(0, 0)[0], 255);
+    }
+
+    #[test]
+    fn test_is_supported_image() {
+        assert!(is_supported_image(Path::new("scan.PNG")));
+        assert!(is_supported_image(Path::new("/tmp/photo.jpeg")));
+        assert!(!is_supported_image(Path::new("notes.txt")));
+        assert!(!is_supported_image(Path::new("README")));
+    }
+
+    #[test]
+    fn test_is_image_url() {
+        assert!(is_image_url("https://example.com/a.png"));
+        assert!(is_image_url("  http://example.com/a.png\n"));
+        assert!(!is_image_url("ftp://example.com/a.png"));
+        assert!(!is_image_url("https://example.com/a b.png"));
+    }
+}
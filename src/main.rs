@@ -1,15 +1,57 @@
 use eframe::egui;
 use std::sync::Arc;
 
+mod annotate;
+mod archive;
 mod app;
+mod batch;
+mod cli;
+mod clipboard;
+mod config;
+mod diff;
+mod duplicate;
+mod evaluate;
+mod export;
+mod file_config;
+mod font;
+mod hook;
+mod latex;
+mod layout;
+mod live;
+mod loader;
+mod metadata;
 mod ocr;
+mod palette;
+mod postprocess;
+mod preprocess;
+mod profile;
+mod quality;
+mod reading_order;
+mod recent;
+mod scratch;
+mod search;
+mod server;
+mod settings;
+mod spellcheck;
+mod status_log;
+mod table;
+mod tessdata;
+mod tiling;
 mod ui;
 
 use app::OcrApp;
 use ui::setup_custom_style;
 
 fn main() -> Result<(), eframe::Error> {
-    env_logger::init();
+    status_log::init_logger();
+    
+    // 命令行模式（recognize、--serve）不启动界面
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some(code) = cli::run(&args) {
+        // process::exit 不会运行析构函数，需先手动清理临时目录
+        scratch::cleanup();
+        std::process::exit(code);
+    }
     
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
@@ -20,49 +62,60 @@ fn main() -> Result<(), eframe::Error> {
         ..Default::default()
     };
     
-    eframe::run_native(
+    let result = eframe::run_native(
         "OCR文字识别工具",
         options,
         Box::new(|cc| {
-            setup_custom_fonts(&cc.egui_ctx);
+            let glyphs = setup_custom_fonts(&cc.egui_ctx);
             setup_custom_style(&cc.egui_ctx);
-            Ok(Box::new(OcrApp::new(cc)))
+            Ok(Box::new(OcrApp::new(cc, glyphs)))
         }),
-    )
+    );
+    scratch::cleanup();
+    result
 }
 
-fn setup_custom_fonts(ctx: &egui::Context) {
+// 设置界面字体，返回字体链的字符覆盖范围，用于提示识别结果中无法显示的字符
+fn setup_custom_fonts(ctx: &egui::Context) -> font::GlyphCoverage {
     let mut fonts = egui::FontDefinitions::default();
     
-    // 尝试加载中文字体
-    match std::fs::read("assets/font/hei.ttf") {
-        Ok(font_data) => {
-            log::info!("成功加载中文字体文件: assets/font/hei.ttf");
-            
-            // 添加中文字体
-            fonts.font_data.insert(
-                "hei".to_owned(),
-                Arc::new(egui::FontData::from_owned(font_data)),
-            );
-            
-            // 设置字体族，优先使用中文字体
-            fonts
-                .families
-                .entry(egui::FontFamily::Proportional)
-                .or_default()
-                .insert(0, "hei".to_owned());
-            
-            fonts
-                .families
-                .entry(egui::FontFamily::Monospace)
-                .or_default()
-                .insert(0, "hei".to_owned());
-        }
-        Err(e) => {
-            log::warn!("无法加载中文字体文件: {}", e);
-            log::info!("将使用系统默认字体，中文可能显示为方块");
+    // 加载中文字体，外部文件缺失时使用内置字体
+    let (font_data, source) = font::load_cjk_font();
+    log::info!("成功加载中文字体: {}", source);
+    
+    // 添加中文字体
+    let font_data = match font_data {
+        std::borrow::Cow::Borrowed(data) => egui::FontData::from_static(data),
+        std::borrow::Cow::Owned(data) => egui::FontData::from_owned(data),
+    };
+    fonts.font_data.insert("hei".to_owned(), Arc::new(font_data));
+    
+    // 设置字体族，优先使用中文字体
+    fonts
+        .families
+        .entry(egui::FontFamily::Proportional)
+        .or_default()
+        .insert(0, "hei".to_owned());
+    
+    fonts
+        .families
+        .entry(egui::FontFamily::Monospace)
+        .or_default()
+        .insert(0, "hei".to_owned());
+    
+    // 中文字体与 egui 自带字体都缺字时，再依次尝试系统中的符号字体
+    for (name, data) in font::load_fallback_fonts() {
+        log::info!("加载后备字体: {}", name);
+        fonts.font_data.insert(name.clone(), Arc::new(egui::FontData::from_owned(data)));
+        for family in [egui::FontFamily::Proportional, egui::FontFamily::Monospace] {
+            fonts.families.entry(family).or_default().push(name.clone());
         }
     }
     
+    let chain = fonts.families[&egui::FontFamily::Proportional]
+        .iter()
+        .filter_map(|name| fonts.font_data.get(name).cloned())
+        .collect();
     ctx.set_fonts(fonts);
+    font::GlyphCoverage::new(chain)
 }
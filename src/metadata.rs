@@ -0,0 +1,168 @@
+use std::io::{BufReader, Cursor};
+use std::path::Path;
+use anyhow::Result;
+use exif::{In, Tag, Value};
+use image::{ImageDecoder, ImageReader};
+
+use crate::archive;
+
+// 图片没有记录分辨率时按此值计算
+pub const DEFAULT_DPI: f32 = 72.0;
+
+// 图片的格式与元数据，各字段在图片未携带相应信息时为空
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImageInfo {
+    pub format: Option<String>,
+    pub width: u32,
+    pub height: u32,
+    pub color_type: String,
+    pub bit_depth: u16,
+    pub file_size: u64,
+    // 水平/垂直分辨率（DPI）
+    pub dpi: Option<(f32, f32)>,
+    pub camera: Option<String>,
+    pub date_taken: Option<String>,
+    // 内嵌 ICC 色彩配置文件的大小（字节）
+    pub icc_profile: Option<usize>,
+}
+
+pub fn read_image_info(path: &Path) -> Result<ImageInfo> {
+    read_image_info_from_bytes(&archive::read(path)?)
+}
+
+pub fn read_image_info_from_bytes(bytes: &[u8]) -> Result<ImageInfo> {
+    let reader = ImageReader::new(Cursor::new(bytes)).with_guessed_format()?;
+    let format = reader.format().map(|format| format!("{:?}", format).to_uppercase());
+    let mut decoder = reader.into_decoder()?;
+    let (width, height) = decoder.dimensions();
+    let color = decoder.color_type();
+    // 部分格式读取色彩配置文件会失败，视为没有
+    let icc_profile = decoder.icc_profile().ok().flatten().map(|profile| profile.len());
+
+    let mut info = ImageInfo {
+        format,
+        width,
+        height,
+        color_type: format!("{:?}", color),
+        bit_depth: color.bits_per_pixel() / color.channel_count() as u16,
+        file_size: bytes.len() as u64,
+        icc_profile,
+        ..Default::default()
+    };
+
+    // 没有 EXIF 的图片（截图、PNG 等）很常见，读取失败时保持空值
+    if let Ok(exif) = exif::Reader::new().read_from_container(&mut BufReader::new(Cursor::new(bytes))) {
+        let make = ascii_field(&exif, Tag::Make);
+        let model = ascii_field(&exif, Tag::Model);
+        info.camera = match (make, model) {
+            (Some(make), Some(model)) if model.starts_with(&make) => Some(model),
+            (Some(make), Some(model)) => Some(format!("{} {}", make, model)),
+            (make, model) => make.or(model),
+        };
+        info.date_taken = ascii_field(&exif, Tag::DateTimeOriginal).or_else(|| ascii_field(&exif, Tag::DateTime));
+        info.dpi = exif_dpi(&exif);
+    }
+    Ok(info)
+}
+
+fn ascii_field(exif: &exif::Exif, tag: Tag) -> Option<String> {
+    match &exif.get_field(tag, In::PRIMARY)?.value {
+        Value::Ascii(values) => values
+            .first()
+            .map(|value| String::from_utf8_lossy(value).trim_end_matches('\0').trim().to_string())
+            .filter(|value| !value.is_empty()),
+        _ => None,
+    }
+}
+
+fn rational_field(exif: &exif::Exif, tag: Tag) -> Option<f32> {
+    match &exif.get_field(tag, In::PRIMARY)?.value {
+        Value::Rational(values) => values.first().map(|value| value.to_f32()),
+        _ => None,
+    }
+}
+
+// 按分辨率折算的打印尺寸（厘米）
+pub fn print_size_cm((width, height): (u32, u32), (dpi_x, dpi_y): (f32, f32)) -> (f32, f32) {
+    (width as f32 / dpi_x * 2.54, height as f32 / dpi_y * 2.54)
+}
+
+// EXIF 中的分辨率单位：2 为英寸（默认），3 为厘米
+fn exif_dpi(exif: &exif::Exif) -> Option<(f32, f32)> {
+    let x = rational_field(exif, Tag::XResolution)?;
+    let y = rational_field(exif, Tag::YResolution).unwrap_or(x);
+    let unit = exif
+        .get_field(Tag::ResolutionUnit, In::PRIMARY)
+        .and_then(|field| field.value.get_uint(0))
+        .unwrap_or(2);
+    let per_inch = if unit == 3 { 2.54 } else { 1.0 };
+    Some((x * per_inch, y * per_inch))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{DynamicImage, ImageFormat};
+
+    fn encode(image: &DynamicImage, format: ImageFormat) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        image.write_to(&mut Cursor::new(&mut bytes), format).unwrap();
+        bytes
+    }
+
+    // 构造只含 Make 与 DateTime 两项的小端 TIFF 结构 EXIF 数据
+    fn exif_segment() -> Vec<u8> {
+        let make = b"Canon\0";
+        let date = b"2024:01:15 10:30:00\0";
+        let entries = 4u16;
+        let data_offset = 8 + 2 + entries as u32 * 12 + 4;
+        let mut tiff = b"II*\0".to_vec();
+        tiff.extend(8u32.to_le_bytes());
+        tiff.extend(entries.to_le_bytes());
+        let mut entry = |tag: u16, kind: u16, count: u32, value: u32| {
+            tiff.extend(tag.to_le_bytes());
+            tiff.extend(kind.to_le_bytes());
+            tiff.extend(count.to_le_bytes());
+            tiff.extend(value.to_le_bytes());
+        };
+        entry(0x010F, 2, make.len() as u32, data_offset);
+        entry(0x011A, 5, 1, data_offset + make.len() as u32 + date.len() as u32);
+        entry(0x0128, 3, 1, 2);
+        entry(0x0132, 2, date.len() as u32, data_offset + make.len() as u32);
+        tiff.extend(0u32.to_le_bytes());
+        tiff.extend(make);
+        tiff.extend(date);
+        tiff.extend(300u32.to_le_bytes());
+        tiff.extend(1u32.to_le_bytes());
+
+        let mut segment = vec![0xFF, 0xE1];
+        segment.extend((tiff.len() as u16 + 8).to_be_bytes());
+        segment.extend(b"Exif\0\0");
+        segment.extend(tiff);
+        segment
+    }
+
+    #[test]
+    fn test_png_without_metadata() {
+        let bytes = encode(&DynamicImage::new_rgba8(4, 3), ImageFormat::Png);
+        let info = read_image_info_from_bytes(&bytes).unwrap();
+        assert_eq!(info.format.as_deref(), Some("PNG"));
+        assert_eq!((info.width, info.height, info.bit_depth), (4, 3, 8));
+        assert_eq!(info.color_type, "Rgba8");
+        assert_eq!((info.camera, info.date_taken, info.dpi, info.icc_profile), (None, None, None, None));
+    }
+
+    #[test]
+    fn test_jpeg_exif_fields() {
+        let mut bytes = encode(&DynamicImage::new_rgb8(8, 8), ImageFormat::Jpeg);
+        // EXIF 段插在 SOI 标记之后
+        let segment = exif_segment();
+        bytes.splice(2..2, segment);
+
+        let info = read_image_info_from_bytes(&bytes).unwrap();
+        assert_eq!(info.format.as_deref(), Some("JPEG"));
+        assert_eq!(info.camera.as_deref(), Some("Canon"));
+        assert_eq!(info.date_taken.as_deref(), Some("2024:01:15 10:30:00"));
+        assert_eq!(info.dpi, Some((300.0, 300.0)));
+    }
+}
@@ -1,15 +1,81 @@
 use std::path::Path;
-use std::time::Instant;
-use image::{DynamicImage, GenericImageView};
+use std::sync::mpsc::Sender;
+use std::time::{Duration, Instant};
+use futures::stream::{Stream, StreamExt};
+use image::{DynamicImage, GenericImageView, ImageFormat};
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
+use tokio::sync::Semaphore;
+
+use crate::layout::{self, PanelOptions};
+use crate::loader::{self, GrayWindow};
+use crate::postprocess::{self, postprocess_result, BoxFilterOptions, PostprocessOptions};
+use crate::preprocess::{preprocess, PreprocessOptions};
+use crate::reading_order::{self, TextDirection};
+use crate::tessdata::{self, LanguagePreset};
+use crate::tiling::{self, TilingOptions};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OcrResult {
     pub text: String,
+    // 后处理前的原始识别文本
+    #[serde(default)]
+    pub raw_text: String,
     pub confidence: f32,
+    // 按各检测框字符数加权的平均置信度
+    #[serde(default)]
+    pub weighted_confidence: f32,
     pub processing_time: f64, // 毫秒
     pub bounding_boxes: Vec<BoundingBox>,
+    // 各处理阶段耗时
+    #[serde(default)]
+    pub timings: Vec<StageTiming>,
+    // 最终采用的识别引擎
+    #[serde(default)]
+    pub engine: String,
+    // 低置信度回退时各引擎的识别尝试
+    #[serde(default)]
+    pub attempts: Vec<EngineAttempt>,
+    // 自动检测到的语言（Tesseract 语言代码），未检测或无法判断时为空
+    #[serde(default)]
+    pub detected_language: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EngineAttempt {
+    pub engine: String,
+    pub confidence: f32,
+}
+
+impl EngineAttempt {
+    #[cfg_attr(not(feature = "tesseract"), allow(dead_code))]
+    fn of(result: &OcrResult) -> Self {
+        Self {
+            engine: result.engine.clone(),
+            confidence: result.confidence,
+        }
+    }
+}
+
+#[cfg_attr(not(feature = "tesseract"), allow(dead_code))]
+const ENGINE_TESSERACT: &str = "Tesseract";
+const ENGINE_CANDLE: &str = "Candle";
+#[cfg(any(test, feature = "test-engine"))]
+const ENGINE_TEST: &str = "Test";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StageTiming {
+    pub stage: String,
+    pub millis: f64,
+}
+
+impl StageTiming {
+    pub fn new(stage: &str, millis: f64) -> Self {
+        Self {
+            stage: stage.to_string(),
+            millis,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -20,16 +86,314 @@ pub struct BoundingBox {
     pub y: u32,
     pub width: u32,
     pub height: u32,
+    // 检测框位置或文字被手动修改过
+    #[serde(default)]
+    pub edited: bool,
+    // 检测框文字的语言（Tesseract 语言代码），按文字系统推测，中英混排时用于核对与着色
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+}
+
+// Tesseract 默认识别语言
+pub const DEFAULT_LANGUAGE: &str = "chi_sim+eng";
+
+// 设置为 1/true 时禁用演示模式，没有真实引擎时直接报错
+pub const DISABLE_DEMO_ENV: &str = "OCR_RS_DISABLE_DEMO";
+
+// 识别进度回调，参数为 0~1 的完成比例
+pub type Progress<'a> = &'a (dyn Fn(f32) + Send + Sync);
+
+// 不关心进度时使用的空回调
+pub fn no_progress(_fraction: f32) {}
+
+// 识别接口对外返回的错误。内部仍使用 anyhow，在 process_image 等公开接口处归类，
+// 界面、CLI 与 HTTP 服务据此给出不同的提示、退出码和状态码
+#[derive(Debug)]
+pub enum OcrError {
+    // 没有可用的识别引擎
+    NoEngine(String),
+    // 图片本身无法识别（如尺寸为 0）
+    ImageLoad(String),
+    // 预处理后没有可识别的内容
+    Preprocess(String),
+    // 识别引擎出错
+    Recognize(anyhow::Error),
+    Timeout(Duration),
+    // 识别任务在完成前被中止
+    Cancelled,
+}
+
+impl std::fmt::Display for OcrError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NoEngine(message) | Self::ImageLoad(message) | Self::Preprocess(message) => write!(f, "{}", message),
+            Self::Recognize(error) => write!(f, "{}", error),
+            Self::Timeout(limit) => write!(f, "识别超时（{:.1} 秒）", limit.as_secs_f64()),
+            Self::Cancelled => write!(f, "识别已取消"),
+        }
+    }
+}
+
+impl std::error::Error for OcrError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Recognize(error) => error.source(),
+            _ => None,
+        }
+    }
+}
+
+impl From<anyhow::Error> for OcrError {
+    // 内部已归类的错误原样取出，其余视为识别失败
+    fn from(error: anyhow::Error) -> Self {
+        error.downcast::<OcrError>().unwrap_or_else(Self::Recognize)
+    }
+}
+
+// 识别过程中的事件，不依赖界面类型，供嵌入调用方、CLI 与 HTTP 服务观察状态
+#[derive(Debug)]
+pub enum OcrProgressEvent {
+    // 已取得图片，开始识别（宽, 高）
+    Started { width: u32, height: u32 },
+    // 识别阶段的完成比例（0~1）
+    Progress(f32),
+    Completed(OcrResult),
+    Failed(OcrError),
+}
+
+// 可拖入窗口加载的 Candle 模型文件扩展名
+pub const MODEL_EXTENSIONS: &[&str] = &["safetensors", "onnx"];
+// safetensors 文件头（JSON）的长度上限，与 safetensors 库一致
+const MAX_SAFETENSORS_HEADER: u64 = 100_000_000;
+
+pub fn is_model_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| MODEL_EXTENSIONS.iter().any(|m| ext.eq_ignore_ascii_case(m)))
+}
+
+// 启用 test-engine 功能时，设置该环境变量即使用测试引擎（仅用于测试/CI）
+#[cfg(feature = "test-engine")]
+pub const TEST_ENGINE_ENV: &str = "OCR_RS_TEST_ENGINE";
+
+// 根据环境变量判断是否允许使用演示模式
+pub fn demo_mode_allowed() -> bool {
+    match std::env::var(DISABLE_DEMO_ENV) {
+        Ok(value) => !matches!(value.trim().to_ascii_lowercase().as_str(), "1" | "true" | "yes" | "on"),
+        Err(_) => true,
+    }
+}
+
+// Tesseract 的版面分析模式（PSM）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PageSegMode {
+    #[default]
+    Auto,
+    SingleColumn,
+    SingleBlock,
+    // 竖排文字块，配合 *_vert 语言包使用
+    SingleBlockVertical,
+}
+
+impl PageSegMode {
+    pub const ALL: [PageSegMode; 4] = [
+        PageSegMode::Auto,
+        PageSegMode::SingleColumn,
+        PageSegMode::SingleBlock,
+        PageSegMode::SingleBlockVertical,
+    ];
+    
+    pub fn label(self) -> &'static str {
+        match self {
+            PageSegMode::Auto => "自动",
+            PageSegMode::SingleColumn => "单栏",
+            PageSegMode::SingleBlock => "单个文本块",
+            PageSegMode::SingleBlockVertical => "竖排文本块",
+        }
+    }
+    
+    #[cfg(feature = "tesseract")]
+    fn to_tesseract(self) -> tesseract::PageSegMode {
+        match self {
+            PageSegMode::Auto => tesseract::PageSegMode::PsmAuto,
+            PageSegMode::SingleColumn => tesseract::PageSegMode::PsmSingleColumn,
+            PageSegMode::SingleBlock => tesseract::PageSegMode::PsmSingleBlock,
+            PageSegMode::SingleBlockVertical => tesseract::PageSegMode::PsmSingleBlockVertText,
+        }
+    }
+}
+
+// 只对 Tesseract 生效的选项
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TesseractOptions {
+    // Tesseract 语言，多个语言用 + 连接
+    pub language: String,
+    pub page_seg_mode: PageSegMode,
+}
+
+impl Default for TesseractOptions {
+    fn default() -> Self {
+        Self {
+            language: DEFAULT_LANGUAGE.to_string(),
+            page_seg_mode: PageSegMode::default(),
+        }
+    }
+}
+
+impl TesseractOptions {
+    // 一次设置预设的语言与对应的版面分析模式
+    pub fn apply_preset(&mut self, preset: &LanguagePreset) {
+        self.language = preset.language.to_string();
+        self.page_seg_mode = if preset.vertical {
+            PageSegMode::SingleBlockVertical
+        } else {
+            PageSegMode::Auto
+        };
+    }
+}
+
+// 只对 Candle 生效的选项
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CandleOptions {
+    // Candle 置信度低于阈值时使用 Tesseract 重新识别，保留置信度较高的结果
+    pub low_confidence_fallback: bool,
+    pub fallback_threshold: f32,
+    // 推理使用的计算设备
+    pub device: ComputeDevice,
+}
+
+impl Default for CandleOptions {
+    fn default() -> Self {
+        Self {
+            low_confidence_fallback: false,
+            fallback_threshold: 0.6,
+            device: ComputeDevice::Auto,
+        }
+    }
+}
+
+// Candle 推理的计算设备，对应 candle_core::Device。Auto 时优先使用可用的 GPU
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ComputeDevice {
+    #[default]
+    Auto,
+    Cpu,
+    Cuda,
+    Metal,
+}
+
+impl ComputeDevice {
+    pub const ALL: [ComputeDevice; 4] = [ComputeDevice::Auto, ComputeDevice::Cpu, ComputeDevice::Cuda, ComputeDevice::Metal];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ComputeDevice::Auto => "自动",
+            ComputeDevice::Cpu => "CPU",
+            ComputeDevice::Cuda => "CUDA",
+            ComputeDevice::Metal => "Metal",
+        }
+    }
+
+    // 实际使用的设备；选择的加速器不可用时回退到 CPU，并返回提示
+    pub fn resolve(self, available: &[ComputeDevice]) -> (ComputeDevice, Option<String>) {
+        match self {
+            ComputeDevice::Auto => {
+                let device = [ComputeDevice::Cuda, ComputeDevice::Metal]
+                    .into_iter()
+                    .find(|device| available.contains(device))
+                    .unwrap_or(ComputeDevice::Cpu);
+                (device, None)
+            }
+            ComputeDevice::Cpu => (ComputeDevice::Cpu, None),
+            accelerator if available.contains(&accelerator) => (accelerator, None),
+            accelerator => (
+                ComputeDevice::Cpu,
+                Some(format!("{} 不可用，已回退到 CPU", accelerator.label())),
+            ),
+        }
+    }
+}
+
+// 本程序可用的计算设备。当前版本的 Candle 推理尚未接入 candle_core 的 GPU 后端，
+// 接入后在此用 candle_core::utils::cuda_is_available / metal_is_available 检测
+pub fn available_devices() -> Vec<ComputeDevice> {
+    vec![ComputeDevice::Cpu]
+}
+
+// 单次识别的处理选项。各引擎专用的选项分开存放，展开保存以兼容旧的配置文件
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProcessOptions {
+    #[serde(flatten)]
+    pub tesseract: TesseractOptions,
+    #[serde(flatten)]
+    pub candle: CandleOptions,
+    pub preprocess: PreprocessOptions,
+    pub postprocess: PostprocessOptions,
+    // 根据初步识别结果检测语言，并在需要时用检测到的语言包重新识别
+    pub auto_language: bool,
+    // 超宽/超长图片分块识别
+    pub tiling: TilingOptions,
+    // 按空白自动分割文本块
+    pub panels: PanelOptions,
+    // 去除过小的噪点检测框
+    pub box_filter: BoxFilterOptions,
+    // 两个引擎都可用时优先使用哪一个
+    pub engine: EnginePreference,
+}
+
+// 首选识别引擎。首选的引擎不可用时使用另一个
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EnginePreference {
+    #[default]
+    Auto,
+    Candle,
+    Tesseract,
+}
+
+impl EnginePreference {
+    pub const ALL: [EnginePreference; 3] = [EnginePreference::Auto, EnginePreference::Candle, EnginePreference::Tesseract];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            EnginePreference::Auto => "自动（优先 Candle）",
+            EnginePreference::Candle => "Candle",
+            EnginePreference::Tesseract => "Tesseract",
+        }
+    }
 }
 
 pub struct OcrEngine {
     #[cfg(feature = "tesseract")]
     tesseract_available: bool,
     candle_model: Option<CandleOcrModel>,
+    #[cfg(any(test, feature = "test-engine"))]
+    test_engine: Option<TestEngine>,
     engine_status: EngineStatus,
+    // 是否允许演示输出；Candle 推理尚未集成，加载的模型也只能输出演示文本
+    allow_demo: bool,
+    // 限制同时进行的识别数量，避免大量并发请求占满内存
+    permits: Semaphore,
+}
+
+// 同时识别的最大图片数
+fn max_concurrency() -> usize {
+    std::thread::available_parallelism().map(|n| n.get()).unwrap_or(2)
+}
+
+// 识别时实际使用的引擎：两者都可用时按首选引擎决定，默认优先使用 Candle
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EngineKind {
+    Candle,
+    Tesseract,
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(not(feature = "tesseract"), allow(dead_code))]
 pub enum EngineStatus {
     Ready,
     NoEngineAvailable,
@@ -39,17 +403,44 @@ pub enum EngineStatus {
 
 impl OcrEngine {
     pub fn new() -> Self {
+        #[cfg(feature = "test-engine")]
+        if std::env::var_os(TEST_ENGINE_ENV).is_some() {
+            return Self::with_test_engine();
+        }
+        Self::with_demo_mode(demo_mode_allowed())
+    }
+    
+    // 仅用于测试：不加载任何真实引擎，识别结果只由图片内容决定
+    #[cfg(any(test, feature = "test-engine"))]
+    pub fn with_test_engine() -> Self {
+        Self {
+            #[cfg(feature = "tesseract")]
+            tesseract_available: false,
+            candle_model: None,
+            test_engine: Some(TestEngine),
+            engine_status: EngineStatus::CandleOnly,
+            allow_demo: false,
+            permits: Semaphore::new(max_concurrency()),
+        }
+    }
+    
+    // allow_demo 为 false 时不加载演示模型，避免模拟文本被当作真实结果
+    pub fn with_demo_mode(allow_demo: bool) -> Self {
         let mut engine = Self {
             #[cfg(feature = "tesseract")]
             tesseract_available: false,
             candle_model: None,
+            #[cfg(any(test, feature = "test-engine"))]
+            test_engine: None,
             engine_status: EngineStatus::NoEngineAvailable,
+            allow_demo,
+            permits: Semaphore::new(max_concurrency()),
         };
         
         // 检查Tesseract是否可用（如果启用）
         #[cfg(feature = "tesseract")]
         {
-            match tesseract::Tesseract::new(None, Some("chi_sim+eng")) {
+            match tesseract::Tesseract::new(None, Some(DEFAULT_LANGUAGE)) {
                 Ok(_) => {
                     log::info!("Tesseract initialized successfully");
                     engine.tesseract_available = true;
@@ -63,7 +454,7 @@ impl OcrEngine {
         }
         
         // 尝试加载Candle模型
-        match CandleOcrModel::new() {
+        match CandleOcrModel::new(allow_demo) {
             Ok(model) => {
                 log::info!("Candle OCR model loaded successfully");
                 engine.candle_model = Some(model);
@@ -80,70 +471,366 @@ impl OcrEngine {
         engine
     }
     
+    // 加载指定的模型文件替换当前的 Candle 模型，并更新引擎状态。
+    // 推理尚未集成，识别仍输出演示文本，因此禁用演示模式时拒绝加载，避免模拟文本被当作真实结果
+    pub fn load_candle_model(&mut self, path: &Path) -> Result<()> {
+        let model = CandleOcrModel::from_path(path)?;
+        if !self.allow_demo {
+            anyhow::bail!("当前版本尚未集成 Candle 推理，模型只能在演示模式下使用（演示模式已禁用）");
+        }
+        log::warn!("Candle 推理尚未集成，{} 将以演示模式运行", path.display());
+        self.candle_model = Some(model);
+        self.engine_status = match self.engine_status {
+            EngineStatus::TesseractOnly | EngineStatus::Ready => EngineStatus::Ready,
+            _ => EngineStatus::CandleOnly,
+        };
+        Ok(())
+    }
+    
     pub fn get_status(&self) -> &EngineStatus {
         &self.engine_status
     }
     
-    pub async fn process_image(&self, image: DynamicImage, _path: &Path) -> Result<OcrResult> {
+    pub fn primary_engine(&self, preference: EnginePreference) -> Option<EngineKind> {
+        match self.engine_status {
+            EngineStatus::Ready if preference == EnginePreference::Tesseract => Some(EngineKind::Tesseract),
+            EngineStatus::Ready | EngineStatus::CandleOnly => Some(EngineKind::Candle),
+            EngineStatus::TesseractOnly => Some(EngineKind::Tesseract),
+            EngineStatus::NoEngineAvailable => None,
+        }
+    }
+    
+    // Tesseract 可用时 Candle 才能回退
+    pub fn has_tesseract(&self) -> bool {
+        matches!(self.engine_status, EngineStatus::Ready | EngineStatus::TesseractOnly)
+    }
+    
+    // 仅检测文本区域，不进行识别，用于在完整识别前快速确认版面。
+    // tesseract crate 未暴露版面分析接口，因此所有引擎都使用内置的投影分析
+    pub fn detect_layout(&self, image: DynamicImage, options: &ProcessOptions) -> Vec<BoundingBox> {
+        let image = preprocess(image, &options.preprocess);
+        layout::detect_text_regions(&image)
+    }
+    
+    // 流式识别：按完成顺序输出（输入中的序号, 结果）。同一时刻最多从输入中取出 max_concurrency 张图片，
+    // 下游消费变慢时不再继续读取输入
+    pub fn process_stream<'a, S>(
+        &'a self,
+        images: S,
+        options: &'a ProcessOptions,
+    ) -> impl Stream<Item = (usize, Result<OcrResult, OcrError>)> + 'a
+    where
+        S: Stream<Item = DynamicImage> + 'a,
+    {
+        images
+            .enumerate()
+            .map(move |(index, image)| async move { (index, self.process_image(image, Path::new(""), options).await) })
+            .buffer_unordered(max_concurrency())
+    }
+    
+    pub async fn process_image(
+        &self,
+        image: DynamicImage,
+        path: &Path,
+        options: &ProcessOptions,
+    ) -> Result<OcrResult, OcrError> {
+        self.process_image_with_progress(image, path, options, &no_progress).await
+    }
+    
+    // 限时识别，超时返回 OcrError::Timeout；timeout 为空时不限时。
+    // 已在后台线程中运行的 Tesseract 调用无法中止，只是不再等待其结果
+    pub async fn process_image_with_timeout(
+        &self,
+        image: DynamicImage,
+        path: &Path,
+        options: &ProcessOptions,
+        timeout: Option<Duration>,
+    ) -> Result<OcrResult, OcrError> {
+        with_timeout(self.process_image(image, path, options), timeout).await
+    }
+    
+    // 直接识别内存中的图片数据（HTTP 上传、剪贴板），不必先写入临时文件。
+    // format_hint 为空时按内容判断格式，多帧图片只识别第一帧；无法解码时返回 OcrError::ImageLoad
+    pub async fn recognize_bytes(
+        &self,
+        bytes: &[u8],
+        format_hint: Option<ImageFormat>,
+        options: &ProcessOptions,
+    ) -> Result<OcrResult, OcrError> {
+        let image = loader::load_first_frame_from_memory(bytes, format_hint, GrayWindow::default())
+            .map_err(|e| OcrError::ImageLoad(format!("无法解码图片: {}", e)))?;
+        self.process_image(image, Path::new(""), options).await
+    }
+    
+    // 通过 events 依次报告开始、进度与结果（Completed 或 Failed 总是最后一个事件）。
+    // 接收端已被丢弃时忽略发送失败，识别照常完成
+    pub async fn process_image_with_events(
+        &self,
+        image: DynamicImage,
+        path: &Path,
+        options: &ProcessOptions,
+        events: Sender<OcrProgressEvent>,
+    ) {
+        let (width, height) = image.dimensions();
+        let _ = events.send(OcrProgressEvent::Started { width, height });
+        let progress_events = events.clone();
+        let progress = move |fraction: f32| {
+            let _ = progress_events.send(OcrProgressEvent::Progress(fraction));
+        };
+        let event = match self.process_image_with_progress(image, path, options, &progress).await {
+            Ok(result) => OcrProgressEvent::Completed(result),
+            Err(error) => OcrProgressEvent::Failed(error),
+        };
+        let _ = events.send(event);
+    }
+    
+    // 识别过程中通过 progress 报告识别阶段的完成比例（0~1）
+    pub async fn process_image_with_progress(
+        &self,
+        image: DynamicImage,
+        _path: &Path,
+        options: &ProcessOptions,
+        progress: Progress<'_>,
+    ) -> Result<OcrResult, OcrError> {
+        let (width, height) = image.dimensions();
+        if width == 0 || height == 0 {
+            return Err(OcrError::ImageLoad(format!("图片尺寸为 {}x{}，无法识别", width, height)));
+        }
+        
+        // 信号量只会在引擎被销毁时关闭
+        let _permit = self.permits.acquire().await.map_err(|_| OcrError::Cancelled)?;
         let start_time = Instant::now();
+        let image = preprocess(image, &options.preprocess);
+        let preprocess_ms = elapsed_ms(start_time);
+        if image.width() == 0 || image.height() == 0 {
+            return Err(OcrError::Preprocess("预处理后图片为空，请检查裁剪等预处理选项".to_string()));
+        }
         
         // 优先使用Candle模型，其次使用Tesseract
-        let result = if let Some(candle_model) = &self.candle_model {
-            self.process_with_candle(candle_model, &image).await
+        let recognize_start = Instant::now();
+        let panels = if options.panels.enabled {
+            layout::split_panels(&image, &options.panels)
         } else {
-            #[cfg(feature = "tesseract")]
-            {
-                if self.tesseract_available {
-                    self.process_with_tesseract(&image).await
-                } else {
-                    Err(anyhow::anyhow!("没有可用的OCR引擎。请检查系统依赖或启用相应功能。"))
-                }
-            }
-            #[cfg(not(feature = "tesseract"))]
-            {
-                Err(anyhow::anyhow!("没有可用的OCR引擎。当前版本仅支持Candle模型，Tesseract功能未启用。"))
-            }
+            Vec::new()
+        };
+        let result = if panels.len() > 1 {
+            self.recognize_panels(&image, panels, options, progress).await
+        } else {
+            self.recognize_tiled(&image, options, progress).await
         };
         
         match result {
             Ok(mut ocr_result) => {
+                let recognize_ms = elapsed_ms(recognize_start);
+                
+                if options.auto_language && ocr_result.detected_language.is_empty() {
+                    ocr_result.detected_language = tessdata::detect_language(&ocr_result.text)
+                        .unwrap_or_default()
+                        .to_string();
+                }
+                
+                let postprocess_start = Instant::now();
+                for bbox in ocr_result.bounding_boxes.iter_mut().filter(|bbox| bbox.language.is_none()) {
+                    bbox.language = tessdata::box_language(&bbox.text).map(str::to_string);
+                }
+                ocr_result.weighted_confidence = weighted_confidence(&ocr_result.bounding_boxes)
+                    .unwrap_or(ocr_result.confidence);
+                ocr_result.raw_text = ocr_result.text.clone();
+                ocr_result.text = postprocess_result(&ocr_result.raw_text, &ocr_result.bounding_boxes, &options.postprocess);
+                let postprocess_ms = elapsed_ms(postprocess_start);
+                
+                ocr_result.timings = vec![
+                    StageTiming::new("预处理", preprocess_ms),
+                    StageTiming::new("识别", recognize_ms),
+                    StageTiming::new("后处理", postprocess_ms),
+                ];
                 ocr_result.processing_time = start_time.elapsed().as_millis() as f64;
                 Ok(ocr_result)
             }
-            Err(e) => Err(e),
+            Err(e) => Err(e.into()),
         }
     }
     
-    #[cfg(feature = "tesseract")]
-    async fn process_with_tesseract(
+    #[cfg_attr(not(feature = "tesseract"), allow(unused_variables))]
+    async fn recognize(&self, image: &DynamicImage, options: &ProcessOptions, progress: Progress<'_>) -> Result<OcrResult> {
+        #[cfg(any(test, feature = "test-engine"))]
+        if let Some(test_engine) = &self.test_engine {
+            progress(1.0);
+            return Ok(test_engine.recognize(image));
+        }
+        
+        let prefers_tesseract = self.primary_engine(options.engine) == Some(EngineKind::Tesseract);
+        if let Some(candle_model) = self.candle_model.as_ref().filter(|_| !prefers_tesseract) {
+            let result = self.process_with_candle(candle_model, image, options, progress).await?;
+            #[cfg(feature = "tesseract")]
+            let result = self.retry_low_confidence(result, image, options).await;
+            return Ok(result);
+        }
+        
+        #[cfg(feature = "tesseract")]
+        {
+            if self.tesseract_available {
+                // Tesseract 不提供中间进度，只在完成时报告
+                let result = self.recognize_with_tesseract(image, options).await;
+                progress(1.0);
+                result
+            } else {
+                Err(OcrError::NoEngine("没有可用的OCR引擎。请检查系统依赖或启用相应功能。".to_string()).into())
+            }
+        }
+        #[cfg(not(feature = "tesseract"))]
+        {
+            Err(OcrError::NoEngine("没有可用的OCR引擎。当前版本仅支持Candle模型，Tesseract功能未启用。".to_string()).into())
+        }
+    }
+    
+    // 超宽/超长图片分块识别，否则整体识别
+    async fn recognize_tiled(&self, image: &DynamicImage, options: &ProcessOptions, progress: Progress<'_>) -> Result<OcrResult> {
+        let tiles = tiling::plan_tiles(image.width(), image.height(), &options.tiling);
+        if tiles.is_empty() {
+            self.recognize_denoised(image, options, progress).await
+        } else {
+            self.recognize_tiles(image, tiles, options, progress).await
+        }
+    }
+    
+    // 各文本块分别识别后按块分节合并
+    async fn recognize_panels(
         &self,
         image: &DynamicImage,
+        panels: Vec<tiling::Tile>,
+        options: &ProcessOptions,
+        progress: Progress<'_>,
     ) -> Result<OcrResult> {
-        // 保存临时图像文件用于tesseract处理
-        let temp_path = format!("/tmp/ocr_temp_{}.png", std::process::id());
-        image.save(&temp_path)?;
-        
-        // 使用新的tesseract API
-        let tesseract = tesseract::Tesseract::new(None, Some("chi_sim+eng"))?
-            .set_image(&temp_path)?
-            .recognize()?;
+        let count = panels.len();
+        let mut results = Vec::with_capacity(count);
+        for (index, panel) in panels.into_iter().enumerate() {
+            let panel_progress = |fraction: f32| progress((index as f32 + fraction) / count as f32);
+            let result = self.recognize_tiled(&panel.crop(image), options, &panel_progress).await?;
+            results.push((panel, result));
+        }
+        layout::merge_panel_results(results).ok_or_else(|| anyhow::anyhow!("文本块识别没有结果"))
+    }
+    
+    // 去掉过小的检测框后按阅读顺序重建文本；阈值按本次识别的图片（或分块）高度折算
+    async fn recognize_denoised(&self, image: &DynamicImage, options: &ProcessOptions, progress: Progress<'_>) -> Result<OcrResult> {
+        let mut result = self.recognize(image, options, progress).await?;
+        if postprocess::drop_small_boxes(&mut result.bounding_boxes, image.height(), &options.box_filter) {
+            let direction = TextDirection::for_language(&options.tesseract.language);
+            result.text = reading_order::reorder_text(&result.bounding_boxes, direction);
+        }
+        Ok(result)
+    }
+    
+    // 逐块识别后合并，去除重叠区中重复的文字
+    async fn recognize_tiles(
+        &self,
+        image: &DynamicImage,
+        tiles: Vec<tiling::Tile>,
+        options: &ProcessOptions,
+        progress: Progress<'_>,
+    ) -> Result<OcrResult> {
+        let count = tiles.len();
+        let mut results = Vec::with_capacity(count);
+        for (index, tile) in tiles.into_iter().enumerate() {
+            // 各分块的进度折算为整体进度
+            let tile_progress = |fraction: f32| progress((index as f32 + fraction) / count as f32);
+            let result = self.recognize_denoised(&tile.crop(image), options, &tile_progress).await?;
+            results.push((tile, result));
+        }
+        let direction = TextDirection::for_language(&options.tesseract.language);
+        tiling::merge_results(results, direction).ok_or_else(|| anyhow::anyhow!("分块识别没有结果"))
+    }
+    
+    // 置信度过低时用 Tesseract 再识别一次，保留置信度较高的结果，两次尝试都记录下来
+    #[cfg(feature = "tesseract")]
+    async fn retry_low_confidence(
+        &self,
+        result: OcrResult,
+        image: &DynamicImage,
+        options: &ProcessOptions,
+    ) -> OcrResult {
+        if !options.candle.low_confidence_fallback
+            || !self.tesseract_available
+            || result.confidence >= options.candle.fallback_threshold
+        {
+            return result;
+        }
         
-        // 需要将tesseract实例设为可变来获取文本
-        let mut tess = tesseract;
-        let text = tess.get_text()?;
-        let confidence = tess.mean_text_conf() as f32 / 100.0;
+        match self.recognize_with_tesseract(image, options).await {
+            Ok(fallback) => {
+                let attempts = vec![EngineAttempt::of(&result), EngineAttempt::of(&fallback)];
+                let mut chosen = if fallback.confidence > result.confidence { fallback } else { result };
+                chosen.attempts = attempts;
+                chosen
+            }
+            Err(e) => {
+                log::warn!("Tesseract 回退识别失败: {}", e);
+                result
+            }
+        }
+    }
+    
+    // 自动检测语言时，若检测结果不在当前语言中且已安装对应语言包，则用该语言重新识别
+    #[cfg(feature = "tesseract")]
+    async fn recognize_with_tesseract(
+        &self,
+        image: &DynamicImage,
+        options: &ProcessOptions,
+    ) -> Result<OcrResult> {
+        let mut result = self.process_with_tesseract(image, &options.tesseract.language, options.tesseract.page_seg_mode).await?;
+        if !options.auto_language {
+            return Ok(result);
+        }
+        // 无法判断时沿用配置的语言
+        let Some(detected) = tessdata::detect_language(&result.text) else {
+            return Ok(result);
+        };
         
-        // 清理临时文件
-        let _ = std::fs::remove_file(&temp_path);
+        let configured = tessdata::split_languages(&options.tesseract.language);
+        let installed = tessdata::discover_languages();
+        if !configured.iter().any(|l| l == detected) && installed.iter().any(|l| l == detected) {
+            log::info!("检测到语言 {}，重新识别", detected);
+            result = self.process_with_tesseract(image, detected, options.tesseract.page_seg_mode).await?;
+        }
+        result.detected_language = detected.to_string();
+        Ok(result)
+    }
+    
+    // Tesseract 在后台线程中运行，FFI 调用中的 panic 转为错误返回，不会让整个程序崩溃
+    #[cfg(feature = "tesseract")]
+    async fn process_with_tesseract(
+        &self,
+        image: &DynamicImage,
+        language: &str,
+        page_seg_mode: PageSegMode,
+    ) -> Result<OcrResult> {
+        let image = image.clone();
+        let language = language.to_string();
+        let (text, confidence) = tokio::task::spawn_blocking(move || run_tesseract(&image, &language, page_seg_mode))
+            .await
+            .map_err(|e| {
+                if e.is_cancelled() {
+                    anyhow::Error::from(OcrError::Cancelled)
+                } else {
+                    anyhow::anyhow!("Tesseract 识别任务异常退出: {}", e)
+                }
+            })??;
         
         // 暂时简化边界框处理，因为新API可能有变化
         let bounding_boxes = vec![];
         
         Ok(OcrResult {
             text,
+            raw_text: String::new(),
             confidence,
+            weighted_confidence: 0.0,
             processing_time: 0.0, // 会在调用函数中设置
             bounding_boxes,
+            timings: Vec::new(),
+            engine: ENGINE_TESSERACT.to_string(),
+            attempts: Vec::new(),
+            detected_language: String::new(),
         })
     }
     
@@ -151,19 +838,136 @@ impl OcrEngine {
         &self,
         candle_model: &CandleOcrModel,
         image: &DynamicImage,
+        options: &ProcessOptions,
+        progress: Progress<'_>,
     ) -> Result<OcrResult> {
-        candle_model.recognize(image).await
+        let (device, warning) = options.candle.device.resolve(&available_devices());
+        if let Some(warning) = warning {
+            // 批量识别时每张图片都会走到这里，只提示一次
+            static WARNED: std::sync::Once = std::sync::Once::new();
+            WARNED.call_once(|| log::warn!("{}", warning));
+        }
+        log::debug!("Candle 推理设备: {}", device.label());
+        candle_model.recognize(image, progress).await
+    }
+}
+
+// 调用 Tesseract 识别，返回文本与平均置信度。
+// 语言包损坏等情况下 FFI 调用可能 panic，这里捕获后转为错误；C 库直接 abort 时仍无法恢复
+#[cfg(feature = "tesseract")]
+fn run_tesseract(image: &DynamicImage, language: &str, page_seg_mode: PageSegMode) -> Result<(String, f32)> {
+    // 每次识别使用独立的临时文件，避免并发识别相互覆盖
+    let temp_path = crate::scratch::unique_path("tesseract", "png")?.to_string_lossy().to_string();
+    image.save(&temp_path)?;
+    
+    let outcome = std::panic::catch_unwind(|| -> Result<(String, f32)> {
+        let mut tess = tesseract::Tesseract::new(None, Some(language))?.set_image(&temp_path)?;
+        tess.set_page_seg_mode(page_seg_mode.to_tesseract());
+        let mut tess = tess.recognize()?;
+        let text = tess.get_text()?;
+        let confidence = tess.mean_text_conf() as f32 / 100.0;
+        Ok((text, confidence))
+    });
+    
+    let _ = std::fs::remove_file(&temp_path);
+    
+    outcome.unwrap_or_else(|panic| {
+        let message = panic
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| panic.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "未知错误".to_string());
+        Err(anyhow::anyhow!("Tesseract 识别崩溃: {}", message))
+    })
+}
+
+// 以字符数为权重的检测框平均置信度，长段落比单个词对整体质量的影响更大
+pub fn weighted_confidence(boxes: &[BoundingBox]) -> Option<f32> {
+    let (weighted_sum, total_chars) = boxes.iter().fold((0.0, 0usize), |(sum, total), bbox| {
+        let chars = bbox.text.chars().filter(|c| !c.is_whitespace()).count();
+        (sum + bbox.confidence * chars as f32, total + chars)
+    });
+    (total_chars > 0).then(|| weighted_sum / total_chars as f32)
+}
+
+fn elapsed_ms(start: Instant) -> f64 {
+    start.elapsed().as_secs_f64() * 1000.0
+}
+
+// 限时等待识别，超时返回 OcrError::Timeout；timeout 为空时不限时
+pub async fn with_timeout(
+    recognition: impl std::future::Future<Output = Result<OcrResult, OcrError>>,
+    timeout: Option<Duration>,
+) -> Result<OcrResult, OcrError> {
+    match timeout {
+        Some(limit) => tokio::time::timeout(limit, recognition)
+            .await
+            .map_err(|_| OcrError::Timeout(limit))?,
+        None => recognition.await,
+    }
+}
+
+// 测试引擎（仅用于测试/CI）：用内置的版面分析找出文本区域，每个区域的文字由其序号、
+// 位置与尺寸组成，整体另加一行图片尺寸。同一张图片总是得到相同的结果
+#[cfg(any(test, feature = "test-engine"))]
+struct TestEngine;
+
+#[cfg(any(test, feature = "test-engine"))]
+impl TestEngine {
+    fn recognize(&self, image: &DynamicImage) -> OcrResult {
+        let (width, height) = image.dimensions();
+        let mut bounding_boxes = vec![BoundingBox {
+            text: format!("image {}x{}", width, height),
+            confidence: 1.0,
+            x: 0,
+            y: 0,
+            width,
+            height,
+            edited: false,
+            language: None,
+        }];
+        let regions = layout::detect_text_regions(image);
+        if !regions.is_empty() {
+            bounding_boxes = regions
+                .into_iter()
+                .enumerate()
+                .map(|(i, region)| BoundingBox {
+                    text: format!("region {} at {},{} size {}x{}", i + 1, region.x, region.y, region.width, region.height),
+                    confidence: 1.0,
+                    ..region
+                })
+                .collect();
+        }
+        
+        OcrResult {
+            text: bounding_boxes.iter().map(|b| b.text.as_str()).collect::<Vec<_>>().join("\n"),
+            raw_text: String::new(),
+            confidence: 1.0,
+            weighted_confidence: 0.0,
+            processing_time: 0.0,
+            bounding_boxes,
+            timings: Vec::new(),
+            engine: ENGINE_TEST.to_string(),
+            attempts: Vec::new(),
+            detected_language: String::new(),
+        }
     }
 }
 
 // Candle OCR 模型实现（待集成）
+#[allow(dead_code)]
 struct CandleOcrModel {
     model_path: String,
     demo_mode: bool,
 }
 
 impl CandleOcrModel {
-    fn new() -> Result<Self> {
+    fn new(allow_demo: bool) -> Result<Self> {
+        // 尚未集成真实模型，只能以演示模式运行
+        if !allow_demo {
+            return Err(anyhow::anyhow!("演示模式已禁用，且没有可用的 Candle 模型"));
+        }
+        
         // 暂时创建一个演示模式的模型
         Ok(Self {
             model_path: "demo_model".to_string(),
@@ -171,13 +975,40 @@ impl CandleOcrModel {
         })
     }
     
-    async fn recognize(&self, image: &DynamicImage) -> Result<OcrResult> {
-        // 模拟处理时间
+    // 从模型文件创建：只读取文件头检查格式是否有效。推理尚未集成，识别仍使用演示输出
+    fn from_path(path: &Path) -> Result<Self> {
+        if !is_model_file(path) {
+            anyhow::bail!("不支持的模型格式，请使用 {}", MODEL_EXTENSIONS.join(" / "));
+        }
+        let mut file = std::fs::File::open(path)?;
+        let file_len = file.metadata()?.len();
+        if file_len == 0 {
+            anyhow::bail!("模型文件为空");
+        }
+        let is_safetensors = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("safetensors"));
+        if is_safetensors {
+            validate_safetensors(&mut file, file_len)?;
+        }
+        
+        Ok(Self {
+            model_path: path.display().to_string(),
+            demo_mode: true,
+        })
+    }
+    
+    async fn recognize(&self, image: &DynamicImage, progress: Progress<'_>) -> Result<OcrResult> {
+        // 模拟处理时间，分几步报告解码进度
+        const PROGRESS_STEPS: u64 = 4;
         let processing_delay = (image.width() * image.height()) as u64 / 100000 + 50;
-        tokio::time::sleep(tokio::time::Duration::from_millis(processing_delay)).await;
+        for step in 1..=PROGRESS_STEPS {
+            tokio::time::sleep(tokio::time::Duration::from_millis(processing_delay / PROGRESS_STEPS)).await;
+            progress(step as f32 / PROGRESS_STEPS as f32);
+        }
         
         // 生成更真实的带格式的模拟结果
-        let demo_texts = vec![
+        let demo_texts = [
             // 文档类型
             "        OCR 文字识别报告\n\n项目名称：智能文档处理系统\n日期：2024年1月15日\n\n处理状态：\n  ✓ 图像预处理完成\n  ✓ 文字识别成功\n  ✓ 格式保持良好\n\n图片信息：\n  分辨率：{} × {}\n  格式：RGB\n  大小：约 {}KB",
             
@@ -223,9 +1054,15 @@ impl CandleOcrModel {
         
         Ok(OcrResult {
             text,
+            raw_text: String::new(),
             confidence,
+            weighted_confidence: 0.0,
             processing_time: 0.0, // 会在调用函数中设置
             bounding_boxes,
+            timings: Vec::new(),
+            engine: ENGINE_CANDLE.to_string(),
+            attempts: Vec::new(),
+            detected_language: String::new(),
         })
     }
     
@@ -251,6 +1088,8 @@ impl CandleOcrModel {
                 y,
                 width,
                 height,
+                edited: false,
+                language: None,
             });
         }
         
@@ -258,6 +1097,25 @@ impl CandleOcrModel {
     }
 }
 
+// safetensors 文件以 8 字节小端长度开头，随后是该长度的 JSON 头
+// 只读取开头 8 字节的头长度与其后的 JSON 头，不读取权重
+fn validate_safetensors(reader: &mut impl std::io::Read, file_len: u64) -> Result<()> {
+    let invalid = || anyhow::anyhow!("不是有效的 safetensors 文件");
+    let mut len_bytes = [0u8; 8];
+    reader.read_exact(&mut len_bytes).map_err(|_| invalid())?;
+    let header_len = u64::from_le_bytes(len_bytes);
+    if header_len > file_len - 8 || header_len > MAX_SAFETENSORS_HEADER {
+        return Err(invalid());
+    }
+    let mut header = vec![0u8; header_len as usize];
+    reader.read_exact(&mut header).map_err(|_| invalid())?;
+    serde_json::from_slice::<serde_json::Value>(&header)
+        .ok()
+        .filter(|header| header.is_object())
+        .map(|_| ())
+        .ok_or_else(invalid)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -270,10 +1128,236 @@ mod tests {
     
     #[tokio::test]
     async fn test_candle_model_recognition() {
-        let model = CandleOcrModel::new().unwrap();
+        let model = CandleOcrModel::new(true).unwrap();
         let image = DynamicImage::new_rgb8(100, 100);
-        let result = model.recognize(&image).await.unwrap();
+        let result = model.recognize(&image, &no_progress).await.unwrap();
         assert!(!result.text.is_empty());
         assert!(result.confidence > 0.0);
     }
+    
+    #[tokio::test]
+    async fn test_timeout_returns_timeout_error() {
+        let engine = OcrEngine::with_demo_mode(true);
+        let options = ProcessOptions::default();
+        let image = DynamicImage::new_rgb8(100, 100);
+        
+        let error = engine
+            .process_image_with_timeout(image.clone(), Path::new(""), &options, Some(Duration::from_millis(1)))
+            .await
+            .unwrap_err();
+        assert!(matches!(error, OcrError::Timeout(_)));
+        
+        let result = engine
+            .process_image_with_timeout(image, Path::new(""), &options, Some(Duration::from_secs(10)))
+            .await;
+        assert!(result.is_ok());
+    }
+    
+    #[tokio::test]
+    async fn test_progress_is_reported() {
+        use std::sync::Mutex;
+        
+        let engine = OcrEngine::with_demo_mode(true);
+        let reported = Mutex::new(Vec::new());
+        let record = |fraction: f32| reported.lock().unwrap().push(fraction);
+        let image = DynamicImage::new_rgb8(100, 100);
+        engine
+            .process_image_with_progress(image, Path::new(""), &ProcessOptions::default(), &record)
+            .await
+            .unwrap();
+        
+        let reported = reported.into_inner().unwrap();
+        assert!(reported.windows(2).all(|pair| pair[0] <= pair[1]));
+        assert_eq!(reported.last(), Some(&1.0));
+    }
+    
+    #[tokio::test]
+    async fn test_recognize_bytes_decodes_in_memory_png() {
+        let engine = OcrEngine::with_test_engine();
+        let mut png = Vec::new();
+        DynamicImage::new_rgb8(120, 40)
+            .write_to(&mut std::io::Cursor::new(&mut png), ImageFormat::Png)
+            .unwrap();
+        let options = ProcessOptions::default();
+        
+        let result = engine.recognize_bytes(&png, None, &options).await.unwrap();
+        let expected = engine.process_image(DynamicImage::new_rgb8(120, 40), Path::new(""), &options).await.unwrap();
+        assert_eq!(result.text, expected.text);
+        assert!(engine.recognize_bytes(&png, Some(ImageFormat::Png), &options).await.is_ok());
+        
+        let error = engine.recognize_bytes(b"not an image", None, &options).await.unwrap_err();
+        assert!(matches!(&error, OcrError::ImageLoad(message) if message.starts_with("无法解码图片")));
+        assert!(matches!(engine.recognize_bytes(&png, Some(ImageFormat::Jpeg), &options).await, Err(OcrError::ImageLoad(_))));
+    }
+    
+    #[tokio::test]
+    async fn test_events_end_with_result() {
+        let engine = OcrEngine::with_demo_mode(true);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let image = DynamicImage::new_rgb8(100, 60);
+        engine
+            .process_image_with_events(image, Path::new(""), &ProcessOptions::default(), sender)
+            .await;
+        
+        let events: Vec<OcrProgressEvent> = receiver.into_iter().collect();
+        assert!(matches!(events.first(), Some(OcrProgressEvent::Started { width: 100, height: 60 })));
+        assert!(events[1..events.len() - 1].iter().all(|event| matches!(event, OcrProgressEvent::Progress(_))));
+        assert!(matches!(events.last(), Some(OcrProgressEvent::Completed(result)) if !result.text.is_empty()));
+        
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let image = DynamicImage::new_rgb8(0, 10);
+        engine
+            .process_image_with_events(image, Path::new(""), &ProcessOptions::default(), sender)
+            .await;
+        assert!(matches!(receiver.into_iter().last(), Some(OcrProgressEvent::Failed(OcrError::ImageLoad(_)))));
+    }
+    
+    #[tokio::test]
+    async fn test_test_engine_is_deterministic() {
+        let engine = OcrEngine::with_test_engine();
+        let options = ProcessOptions::default();
+        
+        let blank = DynamicImage::new_rgb8(120, 80);
+        let result = engine.process_image(blank, Path::new(""), &options).await.unwrap();
+        assert_eq!(result.text, "image 120x80");
+        assert_eq!(result.engine, ENGINE_TEST);
+        
+        // 白底上的一块深色区域被识别为一个文本区域
+        let mut page = image::RgbImage::from_pixel(200, 100, image::Rgb([255, 255, 255]));
+        for x in 20..120 {
+            for y in 40..55 {
+                page.put_pixel(x, y, image::Rgb([0, 0, 0]));
+            }
+        }
+        let page = DynamicImage::ImageRgb8(page);
+        let first = engine.process_image(page.clone(), Path::new(""), &options).await.unwrap();
+        let second = engine.process_image(page, Path::new(""), &options).await.unwrap();
+        assert_eq!(first.text, second.text);
+        assert_eq!(first.bounding_boxes.len(), 1);
+        assert!(first.text.starts_with("region 1 at "));
+    }
+    
+    #[tokio::test]
+    async fn test_zero_sized_image_is_rejected() {
+        let engine = OcrEngine::with_test_engine();
+        let image = DynamicImage::new_rgb8(0, 10);
+        let result = engine.process_image(image, Path::new(""), &ProcessOptions::default()).await;
+        assert!(matches!(result, Err(OcrError::ImageLoad(_))));
+    }
+    
+    #[test]
+    fn test_compute_device_fallback() {
+        let cpu_only = [ComputeDevice::Cpu];
+        assert_eq!(ComputeDevice::Auto.resolve(&cpu_only), (ComputeDevice::Cpu, None));
+        let (device, warning) = ComputeDevice::Cuda.resolve(&cpu_only);
+        assert_eq!(device, ComputeDevice::Cpu);
+        assert!(warning.is_some());
+        
+        let with_metal = [ComputeDevice::Cpu, ComputeDevice::Metal];
+        assert_eq!(ComputeDevice::Auto.resolve(&with_metal), (ComputeDevice::Metal, None));
+        assert_eq!(ComputeDevice::Metal.resolve(&with_metal), (ComputeDevice::Metal, None));
+    }
+    
+    #[test]
+    fn test_load_candle_model_from_file() {
+        let dir = std::env::temp_dir();
+        let valid = dir.join(format!("ocr_rs_model_{}.safetensors", std::process::id()));
+        let header = br#"{"__metadata__":{}}"#;
+        let mut bytes = (header.len() as u64).to_le_bytes().to_vec();
+        bytes.extend(header);
+        std::fs::write(&valid, &bytes).unwrap();
+        let invalid = dir.join(format!("ocr_rs_model_bad_{}.safetensors", std::process::id()));
+        std::fs::write(&invalid, b"not a model").unwrap();
+        
+        let mut engine = OcrEngine::with_demo_mode(true);
+        let rejected = engine.load_candle_model(&invalid);
+        let loaded = engine.load_candle_model(&valid);
+        // 禁用演示模式时即使模型文件有效也不加载，引擎状态不变
+        let mut strict = OcrEngine::with_demo_mode(false);
+        let refused = strict.load_candle_model(&valid);
+        let _ = std::fs::remove_file(&valid);
+        let _ = std::fs::remove_file(&invalid);
+        
+        assert!(rejected.is_err());
+        assert!(loaded.is_ok());
+        assert!(matches!(engine.get_status(), EngineStatus::CandleOnly | EngineStatus::Ready));
+        assert!(refused.is_err());
+        assert!(!matches!(strict.get_status(), EngineStatus::CandleOnly | EngineStatus::Ready));
+        assert!(!is_model_file(Path::new("scan.png")));
+    }
+    
+    #[test]
+    fn test_engine_options_keep_flat_layout() {
+        // 旧版配置文件中各引擎的选项位于同一层
+        let options: ProcessOptions = serde_json::from_str(
+            r#"{"language": "eng", "low_confidence_fallback": true, "auto_language": true}"#,
+        )
+        .unwrap();
+        assert_eq!(options.tesseract.language, "eng");
+        assert!(options.candle.low_confidence_fallback);
+        assert_eq!(options.candle.fallback_threshold, CandleOptions::default().fallback_threshold);
+        assert!(options.auto_language);
+        
+        let json = serde_json::to_value(&options).unwrap();
+        assert_eq!(json["language"], "eng");
+        assert!(json.get("tesseract").is_none());
+    }
+    
+    #[test]
+    fn test_vertical_preset_sets_page_seg_mode() {
+        let mut options = TesseractOptions::default();
+        let vertical = tessdata::LANGUAGE_PRESETS.iter().find(|preset| preset.vertical).unwrap();
+        options.apply_preset(vertical);
+        assert_eq!(options.page_seg_mode, PageSegMode::SingleBlockVertical);
+        assert!(options.language.ends_with("_vert"));
+        
+        options.apply_preset(&tessdata::LANGUAGE_PRESETS[0]);
+        assert_eq!((options.language.as_str(), options.page_seg_mode), ("chi_sim", PageSegMode::Auto));
+    }
+    
+    #[test]
+    fn test_weighted_confidence() {
+        let bbox = |text: &str, confidence: f32| BoundingBox {
+            text: text.to_string(),
+            confidence,
+            x: 0,
+            y: 0,
+            width: 10,
+            height: 10,
+            edited: false,
+            language: None,
+        };
+        let boxes = [bbox("OK", 1.0), bbox("这是一段较长的低置信度文字", 0.5)];
+        let weighted = weighted_confidence(&boxes).unwrap();
+        assert!((weighted - (2.0 + 13.0 * 0.5) / 15.0).abs() < 1e-6);
+        assert_eq!(weighted_confidence(&[bbox("  ", 0.9)]), None);
+    }
+    
+    #[tokio::test]
+    async fn test_process_stream_yields_all_results() {
+        let engine = OcrEngine::with_demo_mode(true);
+        let options = ProcessOptions::default();
+        let images = (1..=5).map(|i| DynamicImage::new_rgb8(40 * i, 30));
+        
+        let results: Vec<(usize, Result<OcrResult, OcrError>)> = engine
+            .process_stream(futures::stream::iter(images), &options)
+            .collect()
+            .await;
+        
+        let mut indices: Vec<usize> = results.iter().map(|(index, _)| *index).collect();
+        indices.sort();
+        assert_eq!(indices, vec![0, 1, 2, 3, 4]);
+        assert!(results.iter().all(|(_, r)| r.as_ref().is_ok_and(|r| !r.text.is_empty())));
+    }
+    
+    #[cfg(not(feature = "tesseract"))]
+    #[tokio::test]
+    async fn test_demo_mode_disabled_returns_error() {
+        let engine = OcrEngine::with_demo_mode(false);
+        assert!(matches!(engine.get_status(), EngineStatus::NoEngineAvailable));
+        
+        let image = DynamicImage::new_rgb8(100, 100);
+        let result = engine.process_image(image, Path::new("test.png"), &ProcessOptions::default()).await;
+        assert!(matches!(result, Err(OcrError::NoEngine(_))));
+    }
 } 
\ No newline at end of file
@@ -0,0 +1,122 @@
+use crate::tessdata;
+
+// 命令面板（Ctrl+P）可执行的操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    OpenImage,
+    OpenUrl,
+    RerunOcr,
+    CopyText,
+    ExportResult,
+    ExportAnnotated,
+    ExportHeatmap,
+    TogglePreprocessCompare,
+    ToggleImageViewer,
+    ToggleClipboardMonitor,
+    ToggleLiveCapture,
+    OpenBatchSummary,
+    ExportMergedText,
+    Evaluate,
+    ToggleSettings,
+    ToggleDarkMode,
+    ToggleDebugOverlay,
+    ToggleFocusMode,
+    // 参数为 tessdata::LANGUAGE_PRESETS 中的序号
+    Language(usize),
+}
+
+pub struct CommandEntry {
+    pub command: Command,
+    pub title: String,
+    // 额外的搜索词（英文名、拼音首字母等），不显示
+    pub keywords: &'static str,
+}
+
+// 所有命令，语言预设各对应一条
+pub fn commands() -> Vec<CommandEntry> {
+    let fixed: [(Command, &str, &str); 18] = [
+        (Command::OpenImage, "打开图片…", "open file image dk"),
+        (Command::OpenUrl, "从 URL 打开…", "open url link"),
+        (Command::RerunOcr, "重新识别", "rerun ocr recognize cxsb"),
+        (Command::CopyText, "复制识别结果", "copy text fz"),
+        (Command::ExportResult, "导出识别结果…", "export save text json dc"),
+        (Command::ExportAnnotated, "导出标注图…", "export annotated boxes image"),
+        (Command::ExportHeatmap, "导出置信度热力图…", "export heatmap confidence"),
+        (Command::TogglePreprocessCompare, "切换预处理对比", "toggle preprocess compare yycl"),
+        (Command::ToggleImageViewer, "打开/关闭图片查看器", "viewer zoom image"),
+        (Command::ToggleClipboardMonitor, "开启/关闭剪贴板监视", "clipboard monitor"),
+        (Command::ToggleLiveCapture, "开启/关闭区域连续识别", "live capture screen subtitle"),
+        (Command::OpenBatchSummary, "批量识别汇总", "batch summary"),
+        (Command::ExportMergedText, "导出批量合并文本…", "batch merge export"),
+        (Command::Evaluate, "与标准文本比较（评估）…", "evaluate ground truth cer wer"),
+        (Command::ToggleSettings, "打开/关闭设置", "settings preferences sz"),
+        (Command::ToggleDarkMode, "切换深色主题", "dark theme"),
+        (Command::ToggleDebugOverlay, "切换调试浮层", "debug fps overlay"),
+        (Command::ToggleFocusMode, "切换专注模式", "focus zen fullscreen zzms"),
+    ];
+    let mut entries: Vec<CommandEntry> = fixed
+        .into_iter()
+        .map(|(command, title, keywords)| CommandEntry { command, title: title.to_string(), keywords })
+        .collect();
+    entries.extend(tessdata::LANGUAGE_PRESETS.iter().enumerate().map(|(index, preset)| CommandEntry {
+        command: Command::Language(index),
+        title: format!("识别语言: {}（{}）", preset.name, preset.language),
+        keywords: "language lang yy",
+    }));
+    entries
+}
+
+// 按空白分隔的每个关键字都出现在标题或搜索词中（不区分大小写）。标题以查询开头的排在最前，
+// 其次是标题包含整个查询的，其余保持原顺序
+pub fn filter<'a>(entries: &'a [CommandEntry], query: &str) -> Vec<&'a CommandEntry> {
+    let query = query.trim().to_lowercase();
+    let mut matches: Vec<(u8, &CommandEntry)> = entries
+        .iter()
+        .filter_map(|entry| {
+            let title = entry.title.to_lowercase();
+            let haystack = format!("{} {}", title, entry.keywords);
+            if !query.split_whitespace().all(|term| haystack.contains(term)) {
+                return None;
+            }
+            let rank = if title.starts_with(&query) {
+                0
+            } else if title.contains(&query) {
+                1
+            } else {
+                2
+            };
+            Some((rank, entry))
+        })
+        .collect();
+    matches.sort_by_key(|(rank, _)| *rank);
+    matches.into_iter().map(|(_, entry)| entry).collect()
+}
+
+// 命令面板的输入与当前选中项
+#[derive(Debug, Default)]
+pub struct CommandPalette {
+    pub query: String,
+    pub selected: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filter_commands() {
+        let entries = commands();
+        assert_eq!(filter(&entries, "").len(), entries.len());
+
+        let found = filter(&entries, "导出");
+        assert_eq!(found[0].command, Command::ExportResult);
+        assert!(found.iter().all(|entry| entry.title.contains("导出")));
+
+        // 英文搜索词与多个关键字
+        let found = filter(&entries, "export HEAT");
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].command, Command::ExportHeatmap);
+        assert!(filter(&entries, "jpn").iter().any(|entry| matches!(entry.command, Command::Language(_))));
+        assert!(filter(&entries, "不存在的命令").is_empty());
+    }
+}
@@ -0,0 +1,481 @@
+use serde::{Deserialize, Serialize};
+
+use crate::ocr::BoundingBox;
+use crate::reading_order;
+
+// 以这些字符结尾的行视为段落结束，不与下一行合并
+const PARAGRAPH_END: &[char] = &['。', '！', '？', '…', '.', '!', '?', '：', ':', '；', ';', '”', '"', '」', '』', '）', ')'];
+
+// 识别结果后处理选项
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PostprocessOptions {
+    pub enabled: bool,
+    // 去除行尾空白
+    pub strip_trailing_spaces: bool,
+    // 合并连续空行
+    pub collapse_blank_lines: bool,
+    // 全角/半角标点与数字规范化
+    pub normalize_width: bool,
+    // 按字符类别统一转换为全角或半角，在规范化之后执行
+    pub width: WidthConversion,
+    // 把扫描时的硬换行合并为段落：行末是句末标点或与下一行间距明显较大时才分段
+    pub merge_paragraphs: bool,
+}
+
+// 全角/半角转换方向
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WidthMode {
+    // 保持不变
+    #[default]
+    Keep,
+    Half,
+    Full,
+}
+
+impl WidthMode {
+    pub const ALL: [WidthMode; 3] = [WidthMode::Keep, WidthMode::Half, WidthMode::Full];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            WidthMode::Keep => "不变",
+            WidthMode::Half => "半角",
+            WidthMode::Full => "全角",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WidthConversion {
+    pub digits: WidthMode,
+    pub letters: WidthMode,
+    pub punctuation: WidthMode,
+}
+
+impl WidthConversion {
+    fn is_keep(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl Default for PostprocessOptions {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            strip_trailing_spaces: true,
+            collapse_blank_lines: true,
+            normalize_width: false,
+            width: WidthConversion::default(),
+            merge_paragraphs: false,
+        }
+    }
+}
+
+// 不保持空格格式时复制、保存与编辑的文本如何处理空白
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WhitespaceOptions {
+    pub trim_leading: bool,
+    pub trim_trailing: bool,
+    // 行内连续的空白合并为一个空格
+    pub collapse_internal: bool,
+    pub drop_blank_lines: bool,
+}
+
+impl Default for WhitespaceOptions {
+    fn default() -> Self {
+        Self {
+            trim_leading: true,
+            trim_trailing: true,
+            collapse_internal: false,
+            drop_blank_lines: true,
+        }
+    }
+}
+
+impl WhitespaceOptions {
+    pub fn apply(&self, text: &str) -> String {
+        text.lines()
+            .map(|line| {
+                let line = if self.trim_leading { line.trim_start() } else { line };
+                let line = if self.trim_trailing { line.trim_end() } else { line };
+                if self.collapse_internal {
+                    collapse_internal_whitespace(line)
+                } else {
+                    line.to_string()
+                }
+            })
+            .filter(|line| !(self.drop_blank_lines && line.trim().is_empty()))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+// 保留行首缩进，只合并第一个非空白字符之后的连续空白
+fn collapse_internal_whitespace(line: &str) -> String {
+    let body_start = line.len() - line.trim_start().len();
+    let mut out = line[..body_start].to_string();
+    let mut previous_space = false;
+    for c in line[body_start..].chars() {
+        if c.is_whitespace() {
+            if !previous_space {
+                out.push(' ');
+            }
+            previous_space = true;
+        } else {
+            out.push(c);
+            previous_space = false;
+        }
+    }
+    out
+}
+
+// 去除过小检测框的选项。阈值按识别图片的高度折算，不同分辨率下效果一致
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BoxFilterOptions {
+    pub enabled: bool,
+    // 检测框最小高度，为图片高度的比例
+    pub min_box_height: f32,
+    // 检测框最小面积，为图片高度平方的比例
+    pub min_box_area: f32,
+}
+
+impl Default for BoxFilterOptions {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_box_height: 0.01,
+            min_box_area: 0.0001,
+        }
+    }
+}
+
+// 去掉高度或面积低于阈值的检测框（多为扫描噪点），返回是否去掉了检测框
+pub fn drop_small_boxes(boxes: &mut Vec<BoundingBox>, image_height: u32, opts: &BoxFilterOptions) -> bool {
+    if !opts.enabled {
+        return false;
+    }
+    let height = image_height as f32;
+    let min_height = opts.min_box_height * height;
+    let min_area = opts.min_box_area * height * height;
+    let count = boxes.len();
+    boxes.retain(|bbox| bbox.height as f32 >= min_height && bbox.width as f32 * bbox.height as f32 >= min_area);
+    boxes.len() < count
+}
+
+// 识别结果的后处理：逐行处理后按需根据检测框间距合并段落，原始逐行文本保留在 raw_text 中
+pub fn postprocess_result(raw_text: &str, boxes: &[BoundingBox], opts: &PostprocessOptions) -> String {
+    let text = postprocess(raw_text, opts);
+    if !opts.enabled || !opts.merge_paragraphs {
+        return text;
+    }
+    let breaks = reading_order::paragraph_breaks(&text, boxes);
+    merge_paragraphs(&text, &breaks)
+}
+
+// 合并属于同一段落的连续行。空行、句末标点与 breaks 中标记的行后分段；
+// 中文之间直接相连，英文行尾的连字符去掉后与下一行相连，其他情况以空格连接
+fn merge_paragraphs(text: &str, breaks: &[bool]) -> String {
+    let mut paragraphs: Vec<String> = Vec::new();
+    let mut open = false;
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            paragraphs.push(String::new());
+            open = false;
+            continue;
+        }
+        match paragraphs.last_mut().filter(|_| open) {
+            Some(paragraph) => {
+                let last = paragraph.chars().next_back();
+                let first = line.chars().next();
+                if paragraph.ends_with('-') && first.is_some_and(char::is_lowercase) {
+                    paragraph.pop();
+                } else if !(last.is_some_and(is_cjk_or_fullwidth) || first.is_some_and(is_cjk_or_fullwidth)) {
+                    paragraph.push(' ');
+                }
+                paragraph.push_str(line);
+            }
+            None => paragraphs.push(line.to_string()),
+        }
+        open = !line.ends_with(PARAGRAPH_END) && !breaks.get(index).copied().unwrap_or(false);
+    }
+    paragraphs.join("\n")
+}
+
+fn is_cjk_or_fullwidth(c: char) -> bool {
+    is_cjk(c) || matches!(c as u32, 0x3000..=0x303F | 0xFF00..=0xFFEF)
+}
+
+pub fn postprocess(text: &str, opts: &PostprocessOptions) -> String {
+    if !opts.enabled {
+        return text.to_string();
+    }
+
+    let mut lines: Vec<String> = Vec::new();
+    let mut previous_blank = false;
+
+    for line in text.lines() {
+        let mut line = if opts.normalize_width {
+            normalize_width(line)
+        } else {
+            line.to_string()
+        };
+        if !opts.width.is_keep() {
+            line = convert_width(&line, &opts.width);
+        }
+
+        if opts.strip_trailing_spaces {
+            line.truncate(line.trim_end().len());
+        }
+
+        let is_blank = line.trim().is_empty();
+        if opts.collapse_blank_lines && is_blank && previous_blank {
+            continue;
+        }
+        previous_blank = is_blank;
+        lines.push(line);
+    }
+
+    lines.join("\n")
+}
+
+// 全角数字/字母统一为半角；标点根据上下文选择：
+// 中文之后使用全角，两侧都是半角字母数字时使用半角
+fn normalize_width(line: &str) -> String {
+    let chars: Vec<char> = line.chars().collect();
+    let mut output = String::with_capacity(line.len());
+
+    for (i, &c) in chars.iter().enumerate() {
+        let prev = chars[..i].iter().rev().find(|c| !c.is_whitespace()).copied();
+        let next = chars[i + 1..].iter().find(|c| !c.is_whitespace()).copied();
+
+        if let Some(half) = fullwidth_alnum_to_half(c) {
+            output.push(half);
+        } else if let Some(half) = fullwidth_punct_to_half(c) {
+            let ascii_context = prev.is_some_and(|p| p.is_ascii_alphanumeric())
+                && next.is_none_or(|n| n.is_ascii_alphanumeric());
+            output.push(if ascii_context { half } else { c });
+        } else if let Some(full) = half_punct_to_full(c) {
+            output.push(if prev.is_some_and(is_cjk) { full } else { c });
+        } else {
+            output.push(c);
+        }
+    }
+
+    output
+}
+
+// 按类别把数字、拉丁字母和标点统一转换为半角或全角。
+// 转为全角时句点使用中文句号，英文引号按前一个字符判断开闭转为弯引号
+fn convert_width(line: &str, conversion: &WidthConversion) -> String {
+    let mut output = String::with_capacity(line.len());
+    let mut prev: Option<char> = None;
+
+    for c in line.chars() {
+        let half = to_halfwidth(c).unwrap_or(c);
+        let mode = if half.is_ascii_digit() {
+            conversion.digits
+        } else if half.is_ascii_alphabetic() {
+            conversion.letters
+        } else if half.is_ascii_punctuation() {
+            conversion.punctuation
+        } else {
+            WidthMode::Keep
+        };
+        let converted = match mode {
+            WidthMode::Keep => c,
+            WidthMode::Half => half,
+            WidthMode::Full => to_fullwidth(half, prev),
+        };
+        output.push(converted);
+        prev = Some(converted);
+    }
+
+    output
+}
+
+fn to_halfwidth(c: char) -> Option<char> {
+    match c {
+        '\u{FF01}'..='\u{FF5E}' => char::from_u32(c as u32 - 0xFEE0),
+        '。' => Some('.'),
+        '、' => Some(','),
+        '“' | '”' => Some('"'),
+        '‘' | '’' => Some('\''),
+        _ => None,
+    }
+}
+
+fn to_fullwidth(c: char, prev: Option<char>) -> char {
+    // 行首、空白或开括号之后的引号为左引号
+    let opening = prev.is_none_or(|p| p.is_whitespace() || "（([【《“‘".contains(p));
+    match c {
+        '.' => '。',
+        '"' => if opening { '“' } else { '”' },
+        '\'' => if opening { '‘' } else { '’' },
+        '!'..='~' => char::from_u32(c as u32 + 0xFEE0).unwrap_or(c),
+        _ => c,
+    }
+}
+
+fn fullwidth_alnum_to_half(c: char) -> Option<char> {
+    match c {
+        '０'..='９' | 'Ａ'..='Ｚ' | 'ａ'..='ｚ' => char::from_u32(c as u32 - 0xFEE0),
+        '\u{3000}' => Some(' '),
+        _ => None,
+    }
+}
+
+fn fullwidth_punct_to_half(c: char) -> Option<char> {
+    match c {
+        '，' => Some(','),
+        '。' => Some('.'),
+        '：' => Some(':'),
+        '；' => Some(';'),
+        '！' => Some('!'),
+        '？' => Some('?'),
+        '（' => Some('('),
+        '）' => Some(')'),
+        _ => None,
+    }
+}
+
+fn half_punct_to_full(c: char) -> Option<char> {
+    match c {
+        ',' => Some('，'),
+        '.' => Some('。'),
+        ':' => Some('：'),
+        ';' => Some('；'),
+        '!' => Some('！'),
+        '?' => Some('？'),
+        _ => None,
+    }
+}
+
+pub fn is_cjk(c: char) -> bool {
+    matches!(c as u32,
+        0x4E00..=0x9FFF       // CJK 统一汉字
+        | 0x3400..=0x4DBF     // 扩展 A
+        | 0xF900..=0xFAFF     // 兼容汉字
+        | 0x3040..=0x30FF     // 平假名/片假名
+        | 0xAC00..=0xD7AF     // 谚文音节
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn all_on() -> PostprocessOptions {
+        PostprocessOptions {
+            enabled: true,
+            strip_trailing_spaces: true,
+            collapse_blank_lines: true,
+            normalize_width: true,
+            width: WidthConversion::default(),
+            merge_paragraphs: false,
+        }
+    }
+
+    #[test]
+    fn test_merge_paragraphs() {
+        let text = "第一段的第一行\n接着第一行。\n第二段\nThe quick brown\nfox jumps over the lazy\ndog. A hyphen-\nated word\n\nNew heading\nbody";
+        let breaks = [false, false, true, false, false, false, false, false, true, false];
+        assert_eq!(
+            merge_paragraphs(text, &breaks),
+            "第一段的第一行接着第一行。\n第二段\nThe quick brown fox jumps over the lazy dog. A hyphenated word\n\nNew heading\nbody"
+        );
+    }
+
+    #[test]
+    fn test_drop_small_boxes_scales_with_height() {
+        let bbox = |width, height| BoundingBox {
+            text: String::new(),
+            confidence: 0.9,
+            x: 0,
+            y: 0,
+            width,
+            height,
+            edited: false,
+            language: None,
+        };
+        let opts = BoxFilterOptions { enabled: true, min_box_height: 0.01, min_box_area: 0.0001 };
+        let mut boxes = vec![bbox(200, 30), bbox(2, 2), bbox(40, 8), bbox(4, 20)];
+        assert!(drop_small_boxes(&mut boxes, 1000, &opts));
+        let sizes: Vec<(u32, u32)> = boxes.iter().map(|b| (b.width, b.height)).collect();
+        assert_eq!(sizes, vec![(200, 30)]);
+
+        // 分辨率减半时阈值随之减半
+        let mut boxes = vec![bbox(100, 15), bbox(20, 4), bbox(1, 1)];
+        assert!(drop_small_boxes(&mut boxes, 500, &opts));
+        assert_eq!(boxes.len(), 1);
+
+        let mut boxes = vec![bbox(1, 1)];
+        assert!(!drop_small_boxes(&mut boxes, 1000, &BoxFilterOptions::default()));
+    }
+
+    #[test]
+    fn test_merge_paragraphs_keeps_raw_when_disabled() {
+        let opts = PostprocessOptions { merge_paragraphs: true, ..all_on() };
+        assert_eq!(postprocess_result("a\nb", &[], &opts), "a b");
+        let opts = PostprocessOptions { merge_paragraphs: false, ..all_on() };
+        assert_eq!(postprocess_result("a\nb", &[], &opts), "a\nb");
+    }
+
+    #[test]
+    fn test_strip_and_collapse() {
+        let text = "第一行   \n\n\n\n第二行\t\n";
+        assert_eq!(postprocess(text, &all_on()), "第一行\n\n第二行");
+    }
+
+    #[test]
+    fn test_whitespace_options() {
+        let text = "  标题\n\n  a   b\t c  \n   ";
+        assert_eq!(WhitespaceOptions::default().apply(text), "标题\na   b\t c");
+        let indent_kept = WhitespaceOptions { trim_leading: false, collapse_internal: true, ..Default::default() };
+        assert_eq!(indent_kept.apply(text), "  标题\n  a b c");
+        let blank_kept = WhitespaceOptions { drop_blank_lines: false, trim_trailing: false, ..Default::default() };
+        assert_eq!(blank_kept.apply(text), "标题\n\na   b\t c  \n");
+    }
+
+    #[test]
+    fn test_disabled_returns_raw() {
+        let opts = PostprocessOptions { enabled: false, ..all_on() };
+        let text = "raw  \n\n\n";
+        assert_eq!(postprocess(text, &opts), text);
+    }
+
+    #[test]
+    fn test_normalize_width() {
+        assert_eq!(postprocess("合计：１２３元", &all_on()), "合计：123元");
+        assert_eq!(postprocess("你好,世界!", &all_on()), "你好，世界！");
+        assert_eq!(postprocess("version 1，2", &all_on()), "version 1,2");
+        assert_eq!(postprocess("ＡＢＣ　abc", &all_on()), "ABC abc");
+    }
+
+    #[test]
+    fn test_width_conversion() {
+        let to_half = PostprocessOptions {
+            width: WidthConversion {
+                digits: WidthMode::Half,
+                letters: WidthMode::Half,
+                punctuation: WidthMode::Half,
+            },
+            ..PostprocessOptions::default()
+        };
+        assert_eq!(postprocess("合计：１２３元，ＯＫ。“好”", &to_half), "合计:123元,OK.\"好\"");
+
+        let to_full = PostprocessOptions {
+            width: WidthConversion {
+                digits: WidthMode::Full,
+                letters: WidthMode::Keep,
+                punctuation: WidthMode::Full,
+            },
+            ..PostprocessOptions::default()
+        };
+        assert_eq!(postprocess("共 12 件, 见 \"附表A\".", &to_full), "共 １２ 件， 见 “附表A”。");
+    }
+}
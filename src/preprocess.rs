@@ -0,0 +1,402 @@
+use image::{DynamicImage, GrayImage, Luma, Rgb};
+use serde::{Deserialize, Serialize};
+
+// 二值化方法
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BinarizeMethod {
+    None,
+    // 全局 Otsu 阈值
+    Otsu,
+    // 局部自适应阈值，适合光照不均的照片
+    Adaptive,
+}
+
+// 灰度色阶调整：black/white 之外的灰度被截断，中间调按 gamma 校正（大于 1 提亮，小于 1 加深）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Levels {
+    pub gamma: f32,
+    pub black: u8,
+    pub white: u8,
+}
+
+impl Default for Levels {
+    fn default() -> Self {
+        Self {
+            gamma: 1.0,
+            black: 0,
+            white: 255,
+        }
+    }
+}
+
+impl Levels {
+    pub fn is_identity(&self) -> bool {
+        *self == Self::default()
+    }
+
+    fn lookup_table(&self) -> [u8; 256] {
+        let black = self.black as f32;
+        let range = (self.white as f32 - black).max(1.0);
+        let exponent = 1.0 / self.gamma.max(0.01);
+
+        let mut table = [0u8; 256];
+        for (value, entry) in table.iter_mut().enumerate() {
+            let normalized = ((value as f32 - black) / range).clamp(0.0, 1.0);
+            *entry = (normalized.powf(exponent) * 255.0).round() as u8;
+        }
+        table
+    }
+}
+
+// 按色相范围处理彩色像素，如去掉黑色文字上的红色印章、荧光笔
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HueFilterMode {
+    Off,
+    // 去掉色相范围内的彩色像素
+    Remove,
+    // 只保留色相范围内的彩色像素
+    Keep,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HueFilter {
+    pub mode: HueFilterMode,
+    // 色相范围（度），起点大于终点时跨越 0°，如 330°~30° 为红色
+    pub hue_start: f32,
+    pub hue_end: f32,
+    // 饱和度低于该值的像素（黑、白、灰）视为无色，不受色相范围影响
+    pub min_saturation: f32,
+}
+
+impl Default for HueFilter {
+    fn default() -> Self {
+        Self {
+            mode: HueFilterMode::Off,
+            hue_start: 330.0,
+            hue_end: 30.0,
+            min_saturation: 0.35,
+        }
+    }
+}
+
+impl HueFilter {
+    // 只保留蓝色圆珠笔/钢笔墨迹：表格中预印的黑色线条和文字被去掉，只识别填写的内容。
+    // 扫描后的蓝色墨迹饱和度往往不高，因此饱和度门槛低于默认值
+    pub fn blue_ink() -> Self {
+        Self {
+            mode: HueFilterMode::Keep,
+            hue_start: 190.0,
+            hue_end: 260.0,
+            min_saturation: 0.2,
+        }
+    }
+
+    // 以目标色相与容差表示的色相范围，跨越 0° 的范围也按中心计算
+    pub fn center(&self) -> f32 {
+        (self.hue_start + self.span() / 2.0).rem_euclid(360.0)
+    }
+
+    pub fn tolerance(&self) -> f32 {
+        self.span() / 2.0
+    }
+
+    pub fn set_center(&mut self, center: f32, tolerance: f32) {
+        let tolerance = tolerance.clamp(0.0, 179.0);
+        self.hue_start = (center - tolerance).rem_euclid(360.0);
+        self.hue_end = (center + tolerance).rem_euclid(360.0);
+    }
+
+    fn span(&self) -> f32 {
+        (self.hue_end - self.hue_start).rem_euclid(360.0)
+    }
+
+    fn in_range(&self, hue: f32) -> bool {
+        if self.hue_start <= self.hue_end {
+            (self.hue_start..=self.hue_end).contains(&hue)
+        } else {
+            hue >= self.hue_start || hue <= self.hue_end
+        }
+    }
+
+    // 像素是否属于所选颜色：足够鲜艳且色相落在范围内
+    fn matches(&self, pixel: &Rgb<u8>) -> bool {
+        let (hue, saturation, _) = rgb_to_hsv(pixel);
+        saturation >= self.min_saturation && self.in_range(hue)
+    }
+}
+
+// 返回色相（0~360 度）、饱和度与明度（0~1）
+fn rgb_to_hsv(pixel: &Rgb<u8>) -> (f32, f32, f32) {
+    let [r, g, b] = pixel.0.map(|channel| channel as f32 / 255.0);
+    let max = r.max(g).max(b);
+    let delta = max - r.min(g).min(b);
+    let hue = if delta == 0.0 {
+        0.0
+    } else if max == r {
+        60.0 * ((g - b) / delta).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((b - r) / delta + 2.0)
+    } else {
+        60.0 * ((r - g) / delta + 4.0)
+    };
+    let saturation = if max == 0.0 { 0.0 } else { delta / max };
+    (hue, saturation, max)
+}
+
+// 去除或单独保留某一色相范围的像素，被去掉的像素变为白色背景。
+// Keep 模式下黑色文字等无色像素也会被去掉，只剩所选颜色的内容
+pub fn filter_hue(img: &DynamicImage, filter: &HueFilter) -> DynamicImage {
+    let white = Rgb([255, 255, 255]);
+    let mut rgb = img.to_rgb8();
+    for pixel in rgb.pixels_mut() {
+        let matches = filter.matches(pixel);
+        let drop = match filter.mode {
+            HueFilterMode::Off => false,
+            HueFilterMode::Remove => matches,
+            HueFilterMode::Keep => !matches,
+        };
+        if drop {
+            *pixel = white;
+        }
+    }
+    DynamicImage::ImageRgb8(rgb)
+}
+
+// 识别前的图像预处理选项
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PreprocessOptions {
+    pub hue_filter: HueFilter,
+    // 用于恢复褪色、铅笔等浅淡文字
+    pub levels: Levels,
+    pub binarize: BinarizeMethod,
+    // 自适应阈值的邻域大小（奇数像素）
+    pub block_size: u32,
+    // 自适应阈值的偏移量，越大越倾向于判为背景
+    pub adaptive_c: i32,
+}
+
+impl Default for PreprocessOptions {
+    fn default() -> Self {
+        Self {
+            hue_filter: HueFilter::default(),
+            levels: Levels::default(),
+            binarize: BinarizeMethod::None,
+            block_size: 31,
+            adaptive_c: 10,
+        }
+    }
+}
+
+pub fn preprocess(image: DynamicImage, opts: &PreprocessOptions) -> DynamicImage {
+    // 颜色过滤需在转为灰度之前进行
+    let image = match opts.hue_filter.mode {
+        HueFilterMode::Off => image,
+        _ => filter_hue(&image, &opts.hue_filter),
+    };
+    let image = if opts.levels.is_identity() {
+        image
+    } else {
+        apply_levels(&image, &opts.levels)
+    };
+    match opts.binarize {
+        BinarizeMethod::None => image,
+        BinarizeMethod::Otsu => binarize_otsu(&image),
+        BinarizeMethod::Adaptive => binarize_adaptive(&image, opts.block_size, opts.adaptive_c),
+    }
+}
+
+// 通过查找表对灰度图做色阶与 gamma 调整
+pub fn apply_levels(img: &DynamicImage, levels: &Levels) -> DynamicImage {
+    let table = levels.lookup_table();
+    let mut gray = img.to_luma8();
+    for pixel in gray.pixels_mut() {
+        pixel[0] = table[pixel[0] as usize];
+    }
+    DynamicImage::ImageLuma8(gray)
+}
+
+pub fn binarize_otsu(img: &DynamicImage) -> DynamicImage {
+    let gray = img.to_luma8();
+    let threshold = otsu_threshold(&gray);
+
+    let binary = GrayImage::from_fn(gray.width(), gray.height(), |x, y| {
+        if gray.get_pixel(x, y)[0] > threshold { Luma([255]) } else { Luma([0]) }
+    });
+    DynamicImage::ImageLuma8(binary)
+}
+
+pub fn otsu_threshold(gray: &GrayImage) -> u8 {
+    let mut histogram = [0u64; 256];
+    for pixel in gray.pixels() {
+        histogram[pixel[0] as usize] += 1;
+    }
+
+    let total = gray.pixels().len() as f64;
+    let sum_all: f64 = histogram.iter().enumerate().map(|(i, &n)| i as f64 * n as f64).sum();
+
+    let mut sum_background = 0.0;
+    let mut weight_background = 0.0;
+    let mut best_threshold = 0u8;
+    let mut best_variance = 0.0;
+
+    for (level, &count) in histogram.iter().enumerate() {
+        weight_background += count as f64;
+        if weight_background == 0.0 {
+            continue;
+        }
+        let weight_foreground = total - weight_background;
+        if weight_foreground == 0.0 {
+            break;
+        }
+
+        sum_background += level as f64 * count as f64;
+        let mean_background = sum_background / weight_background;
+        let mean_foreground = (sum_all - sum_background) / weight_foreground;
+        let variance = weight_background * weight_foreground * (mean_background - mean_foreground).powi(2);
+
+        if variance > best_variance {
+            best_variance = variance;
+            best_threshold = level as u8;
+        }
+    }
+
+    best_threshold
+}
+
+// 局部均值自适应阈值：像素值低于邻域均值减去 c 时判为前景（黑色）
+pub fn binarize_adaptive(img: &DynamicImage, block_size: u32, c: i32) -> DynamicImage {
+    let gray = img.to_luma8();
+    let (width, height) = gray.dimensions();
+    let radius = (block_size.max(3) / 2) as i64;
+
+    // 积分图，尺寸为 (width + 1) × (height + 1)
+    let stride = width as usize + 1;
+    let mut integral = vec![0u64; stride * (height as usize + 1)];
+    for y in 0..height as usize {
+        let mut row_sum = 0u64;
+        for x in 0..width as usize {
+            row_sum += gray.get_pixel(x as u32, y as u32)[0] as u64;
+            integral[(y + 1) * stride + x + 1] = integral[y * stride + x + 1] + row_sum;
+        }
+    }
+
+    let binary = GrayImage::from_fn(width, height, |x, y| {
+        let x0 = (x as i64 - radius).max(0) as usize;
+        let y0 = (y as i64 - radius).max(0) as usize;
+        let x1 = (x as i64 + radius + 1).min(width as i64) as usize;
+        let y1 = (y as i64 + radius + 1).min(height as i64) as usize;
+
+        let sum = integral[y1 * stride + x1] + integral[y0 * stride + x0]
+            - integral[y0 * stride + x1]
+            - integral[y1 * stride + x0];
+        let area = ((x1 - x0) * (y1 - y0)) as f64;
+        let threshold = sum as f64 / area - c as f64;
+
+        if gray.get_pixel(x, y)[0] as f64 > threshold { Luma([255]) } else { Luma([0]) }
+    });
+    DynamicImage::ImageLuma8(binary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 从左到右逐渐变亮的背景上，每隔 20 像素画一条比背景暗 40 的竖线
+    fn gradient_document() -> GrayImage {
+        GrayImage::from_fn(200, 50, |x, y| {
+            let background = (60 + x).min(255) as u8;
+            if x % 20 < 2 && (10..40).contains(&y) {
+                Luma([background - 40])
+            } else {
+                Luma([background])
+            }
+        })
+    }
+
+    #[test]
+    fn test_adaptive_handles_lighting_gradient() {
+        let image = DynamicImage::ImageLuma8(gradient_document());
+        let binary = binarize_adaptive(&image, 15, 10).to_luma8();
+
+        // 暗处和亮处的文字都应为黑色，背景都应为白色
+        for x in [20, 180] {
+            assert_eq!(binary.get_pixel(x, 25)[0], 0, "text at x={}", x);
+            assert_eq!(binary.get_pixel(x + 10, 25)[0], 255, "background at x={}", x + 10);
+        }
+    }
+
+    #[test]
+    fn test_otsu_fails_on_lighting_gradient() {
+        let image = DynamicImage::ImageLuma8(gradient_document());
+        let binary = binarize_otsu(&image).to_luma8();
+
+        // 全局阈值会把暗处的背景也判为前景
+        assert_eq!(binary.get_pixel(30, 25)[0], 0);
+        assert_eq!(binary.get_pixel(190, 25)[0], 255);
+    }
+
+    #[test]
+    fn test_remove_red_stamp_keeps_black_text() {
+        let red = Rgb([220, 30, 40]);
+        let black = Rgb([20, 20, 20]);
+        let blue = Rgb([30, 60, 200]);
+        // 印章压在文字上的像素偏暗，饱和度仍高于阈值
+        let overlap = Rgb([90, 10, 15]);
+        let image = DynamicImage::ImageRgb8(image::RgbImage::from_fn(4, 1, |x, _| [red, black, blue, overlap][x as usize]));
+
+        let remove = HueFilter { mode: HueFilterMode::Remove, ..Default::default() };
+        let removed = filter_hue(&image, &remove).to_rgb8();
+        assert_eq!(removed.pixels().copied().collect::<Vec<_>>(), vec![Rgb([255, 255, 255]), black, blue, Rgb([255, 255, 255])]);
+
+        let keep = HueFilter { mode: HueFilterMode::Keep, ..Default::default() };
+        let kept = filter_hue(&image, &keep).to_rgb8();
+        assert_eq!(kept.pixels().copied().collect::<Vec<_>>(), vec![red, Rgb([255, 255, 255]), Rgb([255, 255, 255]), overlap]);
+    }
+
+    #[test]
+    fn test_hue_range_wraps_around_zero() {
+        let filter = HueFilter::default();
+        assert!(filter.in_range(350.0) && filter.in_range(10.0));
+        assert!(!filter.in_range(120.0));
+
+        let green = HueFilter { hue_start: 90.0, hue_end: 150.0, ..Default::default() };
+        assert!(green.in_range(120.0) && !green.in_range(10.0));
+        assert_eq!(rgb_to_hsv(&Rgb([0, 255, 0])), (120.0, 1.0, 1.0));
+    }
+
+    #[test]
+    fn test_hue_center_and_tolerance() {
+        let red = HueFilter::default();
+        assert_eq!((red.center(), red.tolerance()), (0.0, 30.0));
+
+        let mut filter = HueFilter::default();
+        filter.set_center(10.0, 20.0);
+        assert_eq!((filter.hue_start, filter.hue_end), (350.0, 30.0));
+        assert_eq!((filter.center(), filter.tolerance()), (10.0, 20.0));
+    }
+
+    #[test]
+    fn test_blue_ink_drops_printed_form() {
+        let ink = HueFilter::blue_ink();
+        // 扫描后偏灰的蓝色笔迹保留，黑色表格线和红色印章去掉
+        assert!(ink.matches(&Rgb([60, 80, 150])));
+        assert!(!ink.matches(&Rgb([20, 20, 20])));
+        assert!(!ink.matches(&Rgb([200, 30, 30])));
+    }
+
+    #[test]
+    fn test_levels_lookup_table() {
+        assert!(Levels::default().is_identity());
+
+        let table = Levels { gamma: 1.0, black: 50, white: 200 }.lookup_table();
+        assert_eq!((table[0], table[50], table[125], table[200], table[255]), (0, 0, 128, 255, 255));
+
+        // gamma 小于 1 时加深中间调，使浅淡的笔迹更明显
+        let darker = Levels { gamma: 0.5, ..Default::default() }.lookup_table();
+        assert!(darker[128] < 128);
+        assert_eq!((darker[0], darker[255]), (0, 255));
+    }
+}
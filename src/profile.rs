@@ -0,0 +1,130 @@
+use std::path::Path;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::config;
+use crate::ocr::ProcessOptions;
+use crate::postprocess::PostprocessOptions;
+use crate::preprocess::{BinarizeMethod, PreprocessOptions};
+use crate::tiling::TilingOptions;
+
+const PROFILES_FILE: &str = "profiles.json";
+
+// 命名的预处理/引擎配置方案
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Profile {
+    pub name: String,
+    pub options: ProcessOptions,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProfileStore {
+    pub profiles: Vec<Profile>,
+    pub active: Option<String>,
+}
+
+impl ProfileStore {
+    // 从配置目录加载，文件不存在时使用内置方案
+    pub fn load() -> Self {
+        config::config_file(PROFILES_FILE)
+            .and_then(|path| Self::load_from(&path))
+            .unwrap_or_else(|_| Self::builtin())
+    }
+
+    pub fn load_from(path: &Path) -> Result<Self> {
+        config::load_json(path)
+    }
+
+    pub fn save(&self) -> Result<()> {
+        self.save_to(&config::config_file(PROFILES_FILE)?)
+    }
+
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        config::save_json(path, self)
+    }
+
+    fn builtin() -> Self {
+        let receipt = ProcessOptions {
+            preprocess: PreprocessOptions {
+                binarize: BinarizeMethod::Otsu,
+                ..Default::default()
+            },
+            postprocess: PostprocessOptions {
+                normalize_width: true,
+                ..Default::default()
+            },
+            tiling: TilingOptions {
+                enabled: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let book = ProcessOptions {
+            preprocess: PreprocessOptions {
+                binarize: BinarizeMethod::Adaptive,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        Self {
+            profiles: vec![
+                Profile { name: "收据".to_string(), options: receipt },
+                Profile { name: "书籍".to_string(), options: book },
+            ],
+            active: None,
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Profile> {
+        self.profiles.iter().find(|p| p.name == name)
+    }
+
+    // 同名方案存在时覆盖，否则新增
+    pub fn upsert(&mut self, name: &str, options: ProcessOptions) {
+        match self.profiles.iter_mut().find(|p| p.name == name) {
+            Some(profile) => profile.options = options,
+            None => self.profiles.push(Profile {
+                name: name.to_string(),
+                options,
+            }),
+        }
+    }
+
+    pub fn remove(&mut self, name: &str) {
+        self.profiles.retain(|p| p.name != name);
+        if self.active.as_deref() == Some(name) {
+            self.active = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ocr::TesseractOptions;
+
+    #[test]
+    fn test_profile_crud_roundtrip() {
+        let mut store = ProfileStore::default();
+        store.upsert("票据", ProcessOptions::default());
+        let options = ProcessOptions {
+            tesseract: TesseractOptions { language: "eng".to_string(), ..Default::default() },
+            ..Default::default()
+        };
+        store.upsert("票据", options.clone());
+        store.upsert("临时", ProcessOptions::default());
+        store.active = Some("临时".to_string());
+        store.remove("临时");
+
+        let path = std::env::temp_dir().join(format!("ocr_rs_profiles_{}.json", std::process::id()));
+        store.save_to(&path).unwrap();
+        let loaded = ProfileStore::load_from(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(loaded.profiles.len(), 1);
+        assert_eq!(loaded.get("票据").unwrap().options, options);
+        assert_eq!(loaded.active, None);
+    }
+}
@@ -0,0 +1,98 @@
+use crate::ocr::{EngineKind, EnginePreference, ProcessOptions};
+use crate::preprocess::BinarizeMethod;
+
+// 置信度门槛：交互识别的结果低于门槛时提示调整设置后重新识别
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QualityGate {
+    pub enabled: bool,
+    pub min_confidence: f32,
+}
+
+impl Default for QualityGate {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            min_confidence: 0.5,
+        }
+    }
+}
+
+impl QualityGate {
+    pub fn is_low(&self, confidence: f32) -> bool {
+        self.enabled && confidence < self.min_confidence
+    }
+}
+
+// 低质量结果的改进建议，选择后修改识别选项并重新识别
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Suggestion {
+    // 光照不均、背景杂乱时自适应二值化通常更好
+    Binarize,
+    // 改用另一个引擎
+    SwitchEngine(EngineKind),
+    AutoLanguage,
+}
+
+impl Suggestion {
+    pub fn label(&self) -> String {
+        match self {
+            Suggestion::Binarize => "自适应二值化后重试".to_string(),
+            Suggestion::SwitchEngine(EngineKind::Candle) => "改用 Candle 重试".to_string(),
+            Suggestion::SwitchEngine(EngineKind::Tesseract) => "改用 Tesseract 重试".to_string(),
+            Suggestion::AutoLanguage => "自动检测语言后重试".to_string(),
+        }
+    }
+
+    pub fn apply(&self, options: &mut ProcessOptions) {
+        match self {
+            Suggestion::Binarize => options.preprocess.binarize = BinarizeMethod::Adaptive,
+            Suggestion::SwitchEngine(EngineKind::Candle) => options.engine = EnginePreference::Candle,
+            Suggestion::SwitchEngine(EngineKind::Tesseract) => options.engine = EnginePreference::Tesseract,
+            Suggestion::AutoLanguage => options.auto_language = true,
+        }
+    }
+}
+
+// 当前设置下还能尝试的调整。used 为本次识别使用的引擎，other_available 表示另一个引擎也可用
+pub fn suggestions(options: &ProcessOptions, used: Option<EngineKind>, other_available: bool) -> Vec<Suggestion> {
+    let mut suggestions = Vec::new();
+    if options.preprocess.binarize != BinarizeMethod::Adaptive {
+        suggestions.push(Suggestion::Binarize);
+    }
+    if other_available {
+        match used {
+            Some(EngineKind::Candle) => suggestions.push(Suggestion::SwitchEngine(EngineKind::Tesseract)),
+            Some(EngineKind::Tesseract) => suggestions.push(Suggestion::SwitchEngine(EngineKind::Candle)),
+            None => {}
+        }
+    }
+    if !options.auto_language {
+        suggestions.push(Suggestion::AutoLanguage);
+    }
+    suggestions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_suggestions_skip_current_settings() {
+        let mut options = ProcessOptions::default();
+        let all = suggestions(&options, Some(EngineKind::Candle), true);
+        assert_eq!(
+            all,
+            vec![Suggestion::Binarize, Suggestion::SwitchEngine(EngineKind::Tesseract), Suggestion::AutoLanguage]
+        );
+
+        for suggestion in &all {
+            suggestion.apply(&mut options);
+        }
+        assert_eq!(options.engine, EnginePreference::Tesseract);
+        assert!(suggestions(&options, Some(EngineKind::Tesseract), false).is_empty());
+
+        let gate = QualityGate::default();
+        assert!(gate.is_low(0.3));
+        assert!(!QualityGate { enabled: false, ..gate }.is_low(0.3));
+    }
+}
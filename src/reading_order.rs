@@ -0,0 +1,371 @@
+use crate::ocr::BoundingBox;
+use crate::table::display_width;
+use crate::tessdata;
+
+// 两个检测框的纵向重叠超过较矮者高度的该比例时视为同一行
+const LINE_OVERLAP: f32 = 0.5;
+// 宽度超过版面该比例的检测框（如跨栏标题）不参与分栏检测
+const SPANNING_WIDTH: f32 = 0.6;
+// 每栏文字行的平均显示宽度（半角字符数）不低于该值才视为正文分栏，否则按表格逐行输出
+const MIN_COLUMN_CHARS: f32 = 10.0;
+// 相邻两行的垂直间距超过中位行高的该比例时视为段落之间的空隙
+const PARAGRAPH_GAP: f32 = 0.8;
+
+// 文字的书写方向，决定行内检测框与各栏的先后顺序
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TextDirection {
+    #[default]
+    LeftToRight,
+    // 阿拉伯文、希伯来文等
+    RightToLeft,
+}
+
+impl TextDirection {
+    pub fn for_language(language: &str) -> Self {
+        if tessdata::is_rtl_language(language) {
+            Self::RightToLeft
+        } else {
+            Self::LeftToRight
+        }
+    }
+
+    pub fn is_rtl(self) -> bool {
+        self == Self::RightToLeft
+    }
+}
+
+// 根据检测框位置重建阅读顺序：单栏按行从上到下；正文分栏时先读完左栏再读右栏
+// （从右向左书写时先读右栏），跨栏的行（如标题）会打断分栏；表格保持逐行输出。
+// 行内检测框按书写方向用空格连接
+pub fn reorder_text(boxes: &[BoundingBox], direction: TextDirection) -> String {
+    let mut lines = group_lines(boxes);
+    let mut columns = detect_columns(boxes);
+    if direction.is_rtl() {
+        lines.iter_mut().for_each(|line| line.reverse());
+        columns.reverse();
+    }
+    if columns.len() < 2 || !is_text_columns(&lines, &columns) {
+        return lines.iter().map(|line| join_line(line)).collect::<Vec<_>>().join("\n");
+    }
+
+    let mut output: Vec<String> = Vec::new();
+    let mut pending: Vec<Vec<String>> = vec![Vec::new(); columns.len()];
+    for line in &lines {
+        let crosses_gutter = line.iter().any(|bbox| column_of(&columns, bbox).is_none());
+        if crosses_gutter {
+            output.extend(pending.iter_mut().flat_map(std::mem::take));
+            output.push(join_line(line));
+            continue;
+        }
+        for (column, segment) in split_line(line, &columns).into_iter().enumerate() {
+            if !segment.is_empty() {
+                pending[column].push(join_line(&segment));
+            }
+        }
+    }
+    output.extend(pending.into_iter().flatten());
+    output.join("\n")
+}
+
+// 在图片上框选文字：返回与选区（原图坐标的左上角和右下角）相交的检测框序号，
+// 以及按阅读顺序连接的文字
+pub fn text_in_area(boxes: &[BoundingBox], min: (u32, u32), max: (u32, u32), direction: TextDirection) -> (Vec<usize>, String) {
+    let indices: Vec<usize> = boxes
+        .iter()
+        .enumerate()
+        .filter(|(_, b)| b.x <= max.0 && b.x + b.width >= min.0 && b.y <= max.1 && b.y + b.height >= min.1)
+        .map(|(index, _)| index)
+        .collect();
+    let selected: Vec<BoundingBox> = indices.iter().map(|&index| boxes[index].clone()).collect();
+    (indices, reorder_text(&selected, direction))
+}
+
+// 把检测框分成文本行（从上到下），行内按 x 排序。
+// 逐个比较与各行最后一个检测框的纵向重叠，倾斜的文字行也能连成一行
+pub fn group_lines(boxes: &[BoundingBox]) -> Vec<Vec<&BoundingBox>> {
+    let mut sorted: Vec<&BoundingBox> = boxes.iter().filter(|b| !b.text.trim().is_empty()).collect();
+    sorted.sort_by_key(|b| (b.x, b.y));
+
+    let mut lines: Vec<Vec<&BoundingBox>> = Vec::new();
+    for bbox in sorted {
+        let best = lines
+            .iter()
+            .enumerate()
+            .map(|(index, line)| (index, vertical_overlap(line[line.len() - 1], bbox)))
+            .filter(|(_, overlap)| *overlap >= LINE_OVERLAP)
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+        match best {
+            Some((index, _)) => lines[index].push(bbox),
+            None => lines.push(vec![bbox]),
+        }
+    }
+    lines.sort_by_key(|line| line[0].y * 2 + line[0].height);
+    lines
+}
+
+// 文本各行对应检测框的最低置信度，对应不上的行为 None
+pub fn line_confidences(text: &str, boxes: &[BoundingBox]) -> Vec<Option<f32>> {
+    match_lines(text, boxes)
+        .into_iter()
+        .map(|line| line?.iter().map(|b| b.confidence).reduce(f32::min))
+        .collect()
+}
+
+// 文本各行之后是否有段落间的空隙：与下一行对应的检测框之间的垂直间距明显大于行高。
+// 无法对应到检测框的行返回 false
+pub fn paragraph_breaks(text: &str, boxes: &[BoundingBox]) -> Vec<bool> {
+    let mut heights: Vec<u32> = boxes.iter().map(|b| b.height).collect();
+    heights.sort_unstable();
+    let Some(&median_height) = heights.get(heights.len() / 2) else {
+        return vec![false; text.lines().count()];
+    };
+    let lines = match_lines(text, boxes);
+    let top = |line: &[&BoundingBox]| line.iter().map(|b| b.y).min().unwrap_or(0);
+    let bottom = |line: &[&BoundingBox]| line.iter().map(|b| b.y + b.height).max().unwrap_or(0);
+    (0..lines.len())
+        .map(|index| match (&lines[index], lines.get(index + 1)) {
+            (Some(line), Some(Some(next))) => {
+                top(next).saturating_sub(bottom(line)) as f32 > median_height as f32 * PARAGRAPH_GAP
+            }
+            _ => false,
+        })
+        .collect()
+}
+
+// 把文本各行对应到检测框。先按忽略空白的文字找到包含该行（或被该行包含）的检测框行，
+// 再取其中文字出现在该行内的检测框；分栏时一个检测框行可对应多行文本。对应不上的行为 None
+fn match_lines<'a>(text: &str, boxes: &'a [BoundingBox]) -> Vec<Option<Vec<&'a BoundingBox>>> {
+    let compact = |text: &str| text.chars().filter(|c| !c.is_whitespace()).collect::<String>();
+    let box_lines: Vec<(String, Vec<(String, &BoundingBox)>)> = group_lines(boxes)
+        .into_iter()
+        .map(|line| {
+            let parts: Vec<(String, &BoundingBox)> = line.into_iter().map(|b| (compact(&b.text), b)).collect();
+            (parts.iter().map(|(text, _)| text.as_str()).collect(), parts)
+        })
+        .collect();
+
+    // 文本行与检测框行大体同序，从上一次对应的位置往后找，找不到再从头找
+    let mut next = 0;
+    text.lines()
+        .map(|line| {
+            let line = compact(line);
+            if line.is_empty() {
+                return None;
+            }
+            let matches = |index: &usize| {
+                let joined = &box_lines[*index].0;
+                joined.contains(line.as_str()) || line.contains(joined.as_str())
+            };
+            let index = (next..box_lines.len()).chain(0..next).find(matches)?;
+            let (joined, parts) = &box_lines[index];
+            next = if joined.len() > line.len() { index } else { index + 1 };
+            let inside: Vec<&BoundingBox> = parts
+                .iter()
+                .filter(|(text, _)| line.contains(text.as_str()))
+                .map(|&(_, bbox)| bbox)
+                .collect();
+            Some(if inside.is_empty() { parts.iter().map(|&(_, bbox)| bbox).collect() } else { inside })
+        })
+        .collect()
+}
+
+// 纵向重叠高度占较矮检测框高度的比例
+fn vertical_overlap(a: &BoundingBox, b: &BoundingBox) -> f32 {
+    let top = a.y.max(b.y);
+    let bottom = (a.y + a.height).min(b.y + b.height);
+    let min_height = a.height.min(b.height).max(1);
+    bottom.saturating_sub(top) as f32 / min_height as f32
+}
+
+// 根据非跨栏检测框在水平方向上的覆盖范围找出各栏，栏间空白需宽于中位行高
+fn detect_columns(boxes: &[BoundingBox]) -> Vec<(u32, u32)> {
+    let left = boxes.iter().map(|b| b.x).min().unwrap_or(0);
+    let right = boxes.iter().map(|b| b.x + b.width).max().unwrap_or(0);
+    let spanning = (right - left) as f32 * SPANNING_WIDTH;
+
+    let mut heights: Vec<u32> = boxes.iter().map(|b| b.height).collect();
+    heights.sort_unstable();
+    let min_gap = heights.get(heights.len() / 2).copied().unwrap_or(0);
+
+    let mut intervals: Vec<(u32, u32)> = boxes
+        .iter()
+        .filter(|b| !b.text.trim().is_empty() && (b.width as f32) < spanning)
+        .map(|b| (b.x, b.x + b.width))
+        .collect();
+    intervals.sort_unstable();
+
+    let mut columns: Vec<(u32, u32)> = Vec::new();
+    for (start, end) in intervals {
+        match columns.last_mut() {
+            Some(column) if start <= column.1 + min_gap => column.1 = column.1.max(end),
+            _ => columns.push((start, end)),
+        }
+    }
+    columns
+}
+
+// 各栏文字行都足够长时视为正文分栏；表格的单元格通常较短
+fn is_text_columns(lines: &[Vec<&BoundingBox>], columns: &[(u32, u32)]) -> bool {
+    let mut widths: Vec<Vec<usize>> = vec![Vec::new(); columns.len()];
+    for line in lines {
+        for (column, segment) in split_line(line, columns).into_iter().enumerate() {
+            if !segment.is_empty() {
+                widths[column].push(display_width(&join_line(&segment)));
+            }
+        }
+    }
+    widths.iter().all(|widths| {
+        widths.len() >= 2 && widths.iter().sum::<usize>() as f32 / widths.len() as f32 >= MIN_COLUMN_CHARS
+    })
+}
+
+fn column_of(columns: &[(u32, u32)], bbox: &BoundingBox) -> Option<usize> {
+    columns
+        .iter()
+        .position(|&(start, end)| bbox.x >= start && bbox.x + bbox.width <= end)
+}
+
+// 把一行中的检测框按所在栏拆开
+fn split_line<'a>(line: &[&'a BoundingBox], columns: &[(u32, u32)]) -> Vec<Vec<&'a BoundingBox>> {
+    let mut segments = vec![Vec::new(); columns.len()];
+    for bbox in line {
+        if let Some(column) = column_of(columns, bbox) {
+            segments[column].push(*bbox);
+        }
+    }
+    segments
+}
+
+fn join_line(line: &[&BoundingBox]) -> String {
+    line.iter().map(|b| b.text.trim()).collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bbox(text: &str, x: u32, y: u32, width: u32) -> BoundingBox {
+        BoundingBox {
+            text: text.to_string(),
+            confidence: 0.9,
+            x,
+            y,
+            width,
+            height: 20,
+            edited: false,
+            language: None,
+        }
+    }
+
+    #[test]
+    fn test_line_confidences() {
+        let mut boxes = vec![
+            bbox("Total", 10, 10, 100),
+            bbox("42.00", 200, 10, 100),
+            bbox("left", 10, 50, 100),
+            bbox("right", 440, 50, 100),
+        ];
+        boxes[1].confidence = 0.4;
+        boxes[3].confidence = 0.6;
+        let confidences = line_confidences("Total   42.00\n\nleft\nright\nadded by hand", &boxes);
+        assert_eq!(confidences, vec![Some(0.4), None, Some(0.9), Some(0.6), None]);
+    }
+
+    #[test]
+    fn test_paragraph_breaks_at_large_gaps() {
+        let boxes = vec![
+            bbox("first paragraph starts", 10, 10, 300),
+            bbox("and continues here", 10, 35, 300),
+            bbox("second paragraph", 10, 90, 300),
+        ];
+        let text = "first paragraph starts\nand continues here\nsecond paragraph\ntyped by hand";
+        assert_eq!(paragraph_breaks(text, &boxes), vec![false, true, false, false]);
+        assert_eq!(paragraph_breaks("a\nb", &[]), vec![false, false]);
+    }
+
+    #[test]
+    fn test_single_column() {
+        let boxes = vec![
+            bbox("third line of the page", 10, 90, 400),
+            bbox("first line of the page", 10, 10, 400),
+            bbox("second", 10, 50, 120),
+            bbox("line continues", 140, 52, 200),
+        ];
+        assert_eq!(
+            reorder_text(&boxes, TextDirection::LeftToRight),
+            "first line of the page\nsecond line continues\nthird line of the page"
+        );
+    }
+
+    #[test]
+    fn test_two_columns_read_left_then_right() {
+        let boxes = vec![
+            bbox("A spanning title across both columns", 10, 10, 780),
+            bbox("left column first line", 10, 50, 350),
+            bbox("right column first line", 440, 50, 350),
+            bbox("left column second line", 10, 80, 350),
+            bbox("right column second line", 440, 80, 350),
+            bbox("footer note spanning the full width", 10, 120, 780),
+        ];
+        assert_eq!(
+            reorder_text(&boxes, TextDirection::LeftToRight),
+            "A spanning title across both columns\n\
+             left column first line\nleft column second line\n\
+             right column first line\nright column second line\n\
+             footer note spanning the full width"
+        );
+    }
+
+    #[test]
+    fn test_table_keeps_row_order() {
+        let boxes = vec![
+            bbox("名称", 10, 10, 60),
+            bbox("数量", 200, 10, 60),
+            bbox("苹果", 10, 40, 60),
+            bbox("12", 200, 40, 30),
+            bbox("香蕉", 10, 70, 60),
+            bbox("3", 200, 70, 15),
+        ];
+        assert_eq!(reorder_text(&boxes, TextDirection::LeftToRight), "名称 数量\n苹果 12\n香蕉 3");
+    }
+
+    #[test]
+    fn test_rotated_lines_stay_together() {
+        // 倾斜的文字行：每个词比前一个低 6 像素
+        let line = |words: [&str; 4], y: u32| {
+            words
+                .iter()
+                .enumerate()
+                .map(|(i, word)| bbox(word, 10 + i as u32 * 110, y + i as u32 * 6, 100))
+                .collect::<Vec<_>>()
+        };
+        let mut boxes = line(["slightly", "rotated", "first", "line"], 10);
+        boxes.extend(line(["and", "the", "second", "one"], 50));
+
+        assert_eq!(reorder_text(&boxes, TextDirection::LeftToRight), "slightly rotated first line\nand the second one");
+    }
+
+    #[test]
+    fn test_text_in_area_keeps_reading_order() {
+        let boxes = vec![
+            bbox("second", 10, 50, 100),
+            bbox("first", 10, 10, 100),
+            bbox("aside", 300, 10, 100),
+            bbox("third", 120, 52, 100),
+        ];
+        let (indices, text) = text_in_area(&boxes, (50, 0), (150, 60), TextDirection::LeftToRight);
+        assert_eq!(indices, vec![0, 1, 3]);
+        assert_eq!(text, "first\nsecond third");
+
+        let (indices, text) = text_in_area(&boxes, (500, 500), (600, 600), TextDirection::LeftToRight);
+        assert!(indices.is_empty() && text.is_empty());
+    }
+
+    #[test]
+    fn test_rtl_line_reads_right_to_left() {
+        // "مرحبا بالعالم"：第一个词在右侧
+        let boxes = vec![bbox("بالعالم", 10, 10, 120), bbox("مرحبا", 150, 12, 100)];
+        assert_eq!(reorder_text(&boxes, TextDirection::RightToLeft), "مرحبا بالعالم");
+        assert_eq!(reorder_text(&boxes, TextDirection::LeftToRight), "بالعالم مرحبا");
+    }
+}
@@ -0,0 +1,224 @@
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::archive;
+use crate::config;
+
+const RECENT_FILE: &str = "recent.json";
+
+// 最多保留的最近文件数，有备注或标签的文件不计入、也不会被移除
+const MAX_RECENT: usize = 20;
+
+// 图片查看器的视图状态
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ViewState {
+    pub scale: f32,
+    // 顺时针旋转的 90° 次数（0-3）
+    pub rotation: u8,
+    pub scroll: [f32; 2],
+}
+
+impl Default for ViewState {
+    fn default() -> Self {
+        Self {
+            scale: 1.0,
+            rotation: 0,
+            scroll: [0.0, 0.0],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecentEntry {
+    pub path: PathBuf,
+    #[serde(default)]
+    pub view: ViewState,
+    // 记录视图状态时文件的修改时间（秒），文件变化后视图状态失效
+    #[serde(default)]
+    modified: Option<u64>,
+    // 用户为该图片填写的备注与标签（如“发票”“合同”），文件变化后仍然保留
+    #[serde(default)]
+    pub note: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+impl RecentEntry {
+    fn is_annotated(&self) -> bool {
+        !self.note.trim().is_empty() || !self.tags.is_empty()
+    }
+
+    // 文件名、备注或标签包含关键字（不区分大小写），且带有指定标签
+    pub fn matches(&self, query: &str, tag: Option<&str>) -> bool {
+        if tag.is_some_and(|tag| !self.tags.iter().any(|t| t == tag)) {
+            return false;
+        }
+        let query = query.trim().to_lowercase();
+        if query.is_empty() {
+            return true;
+        }
+        let name = self.path.file_name().unwrap_or_default().to_string_lossy().to_lowercase();
+        name.contains(&query)
+            || self.note.to_lowercase().contains(&query)
+            || self.tags.iter().any(|t| t.to_lowercase().contains(&query))
+    }
+}
+
+// 最近打开的文件及各自的视图状态，最近使用的在前
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RecentFiles {
+    entries: Vec<RecentEntry>,
+}
+
+impl RecentFiles {
+    pub fn load() -> Self {
+        config::config_file(RECENT_FILE)
+            .and_then(|path| Self::load_from(&path))
+            .unwrap_or_default()
+    }
+
+    pub fn load_from(path: &Path) -> Result<Self> {
+        config::load_json(path)
+    }
+
+    pub fn save(&self) -> Result<()> {
+        self.save_to(&config::config_file(RECENT_FILE)?)
+    }
+
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        config::save_json(path, self)
+    }
+
+    pub fn entries(&self) -> &[RecentEntry] {
+        &self.entries
+    }
+
+    // 记录打开了该文件并返回应恢复的视图状态；文件在磁盘上被修改过时重置视图状态
+    pub fn open(&mut self, path: &Path) -> ViewState {
+        let modified = modified_secs(path);
+        let mut entry = match self.entries.iter().position(|e| e.path == path) {
+            Some(index) => self.entries.remove(index),
+            None => RecentEntry {
+                path: path.to_path_buf(),
+                view: ViewState::default(),
+                modified,
+                note: String::new(),
+                tags: Vec::new(),
+            },
+        };
+        if entry.modified != modified {
+            entry.view = ViewState::default();
+            entry.modified = modified;
+        }
+
+        let view = entry.view;
+        self.entries.insert(0, entry);
+        let mut plain = 0;
+        self.entries.retain(|entry| {
+            plain += usize::from(!entry.is_annotated());
+            entry.is_annotated() || plain <= MAX_RECENT
+        });
+        view
+    }
+
+    pub fn entry(&self, path: &Path) -> Option<&RecentEntry> {
+        self.entries.iter().find(|e| e.path == path)
+    }
+
+    pub fn set_annotation(&mut self, path: &Path, note: String, tags: Vec<String>) {
+        if let Some(entry) = self.entries.iter_mut().find(|e| e.path == path) {
+            entry.note = note;
+            entry.tags = tags;
+        }
+    }
+
+    // 所有用过的标签，按名称排序
+    pub fn tags(&self) -> Vec<&str> {
+        let mut tags: Vec<&str> = self.entries.iter().flat_map(|e| e.tags.iter().map(String::as_str)).collect();
+        tags.sort_unstable();
+        tags.dedup();
+        tags
+    }
+
+    pub fn set_view(&mut self, path: &Path, view: ViewState) {
+        if let Some(entry) = self.entries.iter_mut().find(|e| e.path == path) {
+            entry.view = view;
+        }
+    }
+}
+
+// 把输入的标签文本按逗号（含全角）、分号或空白拆分，去掉重复
+pub fn parse_tags(input: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for tag in input.split(|c: char| matches!(c, ',' | '，' | ';' | '；' | '、') || c.is_whitespace()) {
+        if !tag.is_empty() && !tags.iter().any(|t| t == tag) {
+            tags.push(tag.to_string());
+        }
+    }
+    tags
+}
+
+// 压缩包中的图片使用压缩包的修改时间
+fn modified_secs(path: &Path) -> Option<u64> {
+    let modified = std::fs::metadata(archive::source_file(path)).and_then(|m| m.modified()).ok()?;
+    Some(modified.duration_since(UNIX_EPOCH).ok()?.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, SystemTime};
+
+    #[test]
+    fn test_view_state_restored_until_file_changes() {
+        let dir = std::env::temp_dir().join(format!("ocr_rs_recent_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let image = dir.join("page.png");
+        std::fs::write(&image, b"png").unwrap();
+
+        let mut recent = RecentFiles::default();
+        assert_eq!(recent.open(&image), ViewState::default());
+        let view = ViewState { scale: 2.5, rotation: 1, scroll: [10.0, 20.0] };
+        recent.set_view(&image, view);
+
+        let store = dir.join("recent.json");
+        recent.save_to(&store).unwrap();
+        let mut loaded = RecentFiles::load_from(&store).unwrap();
+        assert_eq!(loaded.open(&image), view);
+
+        // 修改文件时间后视图状态失效
+        let file = std::fs::File::options().write(true).open(&image).unwrap();
+        file.set_modified(SystemTime::now() + Duration::from_secs(3600)).unwrap();
+        let reopened = loaded.open(&image);
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_eq!(reopened, ViewState::default());
+        assert_eq!(loaded.entries().len(), 1);
+    }
+
+    #[test]
+    fn test_annotations_survive_and_filter() {
+        let mut recent = RecentFiles::default();
+        let invoice = Path::new("/scans/invoice_01.png");
+        recent.open(invoice);
+        recent.set_annotation(invoice, "三月报销".to_string(), parse_tags("发票, 2024，发票"));
+        assert_eq!(recent.entry(invoice).unwrap().tags, vec!["发票", "2024"]);
+
+        // 超出数量上限时只移除没有备注和标签的文件
+        for i in 0..MAX_RECENT + 5 {
+            recent.open(Path::new(&format!("/scans/page_{}.png", i)));
+        }
+        assert_eq!(recent.entries().len(), MAX_RECENT + 1);
+        assert!(recent.entry(invoice).is_some());
+
+        let entry = recent.entry(invoice).unwrap();
+        assert!(entry.matches("报销", None));
+        assert!(entry.matches("INVOICE", Some("发票")));
+        assert!(!entry.matches("", Some("合同")));
+        assert_eq!(recent.tags(), vec!["2024", "发票"]);
+    }
+}
@@ -0,0 +1,56 @@
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use anyhow::Result;
+use tempfile::TempDir;
+
+// 进程共用的临时目录：首次使用时在系统临时目录下创建，退出前由 cleanup 整个删除。
+// HTTP 服务与批量识别会同时产生大量临时文件，统一放在这里并用计数器命名，避免重名
+static SCRATCH_DIR: Mutex<Option<TempDir>> = Mutex::new(None);
+static NEXT_FILE_ID: AtomicUsize = AtomicUsize::new(0);
+
+// 返回临时目录中一个尚未使用的文件路径（只生成路径，不创建文件）
+pub fn unique_path(prefix: &str, extension: &str) -> Result<PathBuf> {
+    let mut guard = SCRATCH_DIR.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let dir = match &mut *guard {
+        Some(dir) => dir,
+        empty => empty.insert(tempfile::Builder::new().prefix("ocr-rs-").tempdir()?),
+    };
+    let id = NEXT_FILE_ID.fetch_add(1, Ordering::Relaxed);
+    Ok(dir.path().join(format!("{}_{}.{}", prefix, id, extension)))
+}
+
+// 删除临时目录及其中残留的文件；之后再次使用时会重新创建
+pub fn cleanup() {
+    let dir = SCRATCH_DIR.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).take();
+    if let Some(dir) = dir {
+        let path = dir.path().to_path_buf();
+        if let Err(e) = dir.close() {
+            log::warn!("删除临时目录 {} 失败: {}", path.display(), e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unique_paths_share_one_directory() {
+        let paths: Vec<PathBuf> = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..8).map(|_| scope.spawn(|| unique_path("page", "png").unwrap())).collect();
+            handles.into_iter().map(|handle| handle.join().unwrap()).collect()
+        });
+
+        let dir = paths[0].parent().unwrap().to_path_buf();
+        assert!(paths.iter().all(|path| path.parent() == Some(dir.as_path())));
+        let mut names: Vec<_> = paths.iter().map(|path| path.file_name().unwrap().to_owned()).collect();
+        names.sort();
+        names.dedup();
+        assert_eq!(names.len(), paths.len());
+
+        std::fs::write(&paths[0], b"temp").unwrap();
+        cleanup();
+        assert!(!dir.exists());
+    }
+}
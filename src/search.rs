@@ -0,0 +1,103 @@
+use std::ops::Range;
+
+use crate::ocr::BoundingBox;
+
+// 在文本中查找关键字（不区分大小写），返回各处匹配的字节范围，匹配之间不重叠
+pub fn text_matches(text: &str, query: &str) -> Vec<Range<usize>> {
+    let needle = fold(query.chars());
+    if needle.is_empty() {
+        return Vec::new();
+    }
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    let haystack = fold(chars.iter().map(|&(_, c)| c));
+    find_all(&haystack, &needle)
+        .into_iter()
+        .map(|start| {
+            let end = chars.get(start + needle.len()).map_or(text.len(), |&(offset, _)| offset);
+            chars[start].0..end
+        })
+        .collect()
+}
+
+// 把关键字映射回检测框：按检测框顺序拼接去掉空白的文字后查找，
+// 每处匹配返回它覆盖的检测框序号，关键字跨越多个检测框（如多个单词）时也能找到
+pub fn box_matches(boxes: &[BoundingBox], query: &str) -> Vec<Vec<usize>> {
+    let needle = fold(query.chars().filter(|c| !c.is_whitespace()));
+    if needle.is_empty() {
+        return Vec::new();
+    }
+    let mut haystack = Vec::new();
+    let mut owners = Vec::new();
+    for (index, bbox) in boxes.iter().enumerate() {
+        let folded = fold(bbox.text.chars().filter(|c| !c.is_whitespace()));
+        owners.extend(std::iter::repeat_n(index, folded.len()));
+        haystack.extend(folded);
+    }
+    find_all(&haystack, &needle)
+        .into_iter()
+        .map(|start| {
+            let mut covered: Vec<usize> = owners[start..start + needle.len()].to_vec();
+            covered.dedup();
+            covered
+        })
+        .collect()
+}
+
+// 逐字符转小写，保持字符数不变以便换算回原文位置
+fn fold(chars: impl Iterator<Item = char>) -> Vec<char> {
+    chars.map(|c| c.to_lowercase().next().unwrap_or(c)).collect()
+}
+
+fn find_all(haystack: &[char], needle: &[char]) -> Vec<usize> {
+    let mut starts = Vec::new();
+    let mut start = 0;
+    while start + needle.len() <= haystack.len() {
+        if haystack[start..start + needle.len()] == *needle {
+            starts.push(start);
+            start += needle.len();
+        } else {
+            start += 1;
+        }
+    }
+    starts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bbox(text: &str) -> BoundingBox {
+        BoundingBox {
+            x: 0,
+            y: 0,
+            width: 10,
+            height: 10,
+            text: text.to_string(),
+            confidence: 0.9,
+            edited: false,
+            language: None,
+        }
+    }
+
+    #[test]
+    fn test_text_matches_ignore_case() {
+        let text = "Hello 世界, hello again";
+        let matches = text_matches(text, "HELLO");
+        assert_eq!(matches.len(), 2);
+        assert_eq!(&text[matches[0].clone()], "Hello");
+        assert_eq!(&text[matches[1].clone()], "hello");
+
+        assert_eq!(&text[text_matches(text, "世界")[0].clone()], "世界");
+        assert!(text_matches(text, "").is_empty());
+        assert!(text_matches(text, "missing").is_empty());
+    }
+
+    #[test]
+    fn test_box_matches_span_words() {
+        let boxes = vec![bbox("Total"), bbox("amount due"), bbox("total")];
+        assert_eq!(box_matches(&boxes, "total"), vec![vec![0], vec![2]]);
+        assert_eq!(box_matches(&boxes, "total amount"), vec![vec![0, 1]]);
+        assert_eq!(box_matches(&boxes, "due"), vec![vec![1]]);
+        assert!(box_matches(&boxes, "  ").is_empty());
+    }
+}
@@ -0,0 +1,151 @@
+use std::io::Cursor;
+use std::sync::Arc;
+use std::time::Duration;
+use anyhow::Result;
+use axum::body::Bytes;
+use axum::extract::{DefaultBodyLimit, FromRequest, Multipart, Query, Request, State};
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::{Json, Router};
+use image::ImageReader;
+use serde::Deserialize;
+
+use crate::export::{self, OutputFormat};
+use crate::file_config::FileConfig;
+use crate::ocr::{self, EngineStatus, OcrEngine, OcrError, ProcessOptions};
+
+// 上传图片的大小限制
+const MAX_UPLOAD_BYTES: usize = 50 * 1024 * 1024;
+// 单个识别请求的默认时限，超时返回 408
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(120);
+
+struct ServerState {
+    engine: OcrEngine,
+    timeout: Duration,
+    // ocr-rs.toml 中的默认识别选项
+    options: ProcessOptions,
+}
+
+#[derive(Debug, Deserialize)]
+struct OcrQuery {
+    lang: Option<String>,
+    #[serde(default)]
+    format: OutputFormat,
+}
+
+// 启动本地 HTTP 服务（阻塞直到服务退出）
+pub fn run(port: u16, timeout: Duration) -> Result<()> {
+    let rt = tokio::runtime::Runtime::new()?;
+    let result = rt.block_on(serve(port, timeout));
+    // 超时请求的识别可能仍在后台线程中运行，退出时不再等待
+    rt.shutdown_background();
+    result
+}
+
+async fn serve(port: u16, timeout: Duration) -> Result<()> {
+    let config = FileConfig::load().unwrap_or_default();
+    let engine = config.engine();
+    if matches!(engine.get_status(), EngineStatus::NoEngineAvailable) {
+        log::warn!("没有可用的 OCR 引擎，识别请求将返回 503");
+    }
+
+    let app = Router::new()
+        .route("/ocr", post(recognize))
+        .layer(DefaultBodyLimit::max(MAX_UPLOAD_BYTES))
+        .with_state(Arc::new(ServerState { engine, timeout, options: config.options }));
+
+    // 只监听本机地址
+    let listener = tokio::net::TcpListener::bind(("127.0.0.1", port)).await?;
+    println!("OCR 服务已启动: http://{}/ocr", listener.local_addr()?);
+    // Ctrl+C 时正常退出，以便清理临时目录
+    axum::serve(listener, app)
+        .with_graceful_shutdown(async {
+            let _ = tokio::signal::ctrl_c().await;
+        })
+        .await?;
+    Ok(())
+}
+
+// POST /ocr：请求体为 multipart 表单中的图片文件或原始图片字节
+async fn recognize(
+    State(state): State<Arc<ServerState>>,
+    Query(query): Query<OcrQuery>,
+    request: Request,
+) -> Response {
+    let engine = &state.engine;
+    if matches!(engine.get_status(), EngineStatus::NoEngineAvailable) {
+        return error_response(StatusCode::SERVICE_UNAVAILABLE, "没有可用的 OCR 引擎");
+    }
+
+    let bytes = match read_image_bytes(request).await {
+        Ok(bytes) => bytes,
+        Err(message) => return error_response(StatusCode::BAD_REQUEST, &message),
+    };
+    // hOCR 需要原图尺寸，只读取文件头
+    let dimensions = ImageReader::new(Cursor::new(&bytes))
+        .with_guessed_format()
+        .map_err(image::ImageError::from)
+        .and_then(|reader| reader.into_dimensions());
+    let (width, height) = match dimensions {
+        Ok(dimensions) => dimensions,
+        Err(e) => return error_response(StatusCode::BAD_REQUEST, &format!("无法解码图片: {}", e)),
+    };
+
+    let mut options = state.options.clone();
+    if let Some(lang) = query.lang.filter(|lang| !lang.trim().is_empty()) {
+        options.tesseract.language = lang;
+    }
+
+    match ocr::with_timeout(engine.recognize_bytes(&bytes, None, &options), Some(state.timeout)).await {
+        Ok(result) => match query.format {
+            OutputFormat::Json => Json(result).into_response(),
+            OutputFormat::Text => result.text.into_response(),
+            OutputFormat::Hocr => (
+                [(header::CONTENT_TYPE, "text/html; charset=utf-8")],
+                export::to_hocr(&result, width, height, None),
+            )
+                .into_response(),
+        },
+        Err(e @ OcrError::Timeout(_)) => error_response(StatusCode::REQUEST_TIMEOUT, &e.to_string()),
+        Err(e @ (OcrError::NoEngine(_) | OcrError::Cancelled)) => error_response(StatusCode::SERVICE_UNAVAILABLE, &e.to_string()),
+        Err(e @ (OcrError::ImageLoad(_) | OcrError::Preprocess(_))) => error_response(StatusCode::UNPROCESSABLE_ENTITY, &e.to_string()),
+        Err(e @ OcrError::Recognize(_)) => error_response(StatusCode::INTERNAL_SERVER_ERROR, &format!("识别失败: {}", e)),
+    }
+}
+
+async fn read_image_bytes(request: Request) -> Result<Bytes, String> {
+    let is_multipart = request
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("multipart/form-data"));
+
+    let bytes = if is_multipart {
+        let mut multipart = Multipart::from_request(request, &())
+            .await
+            .map_err(|e| format!("无效的表单数据: {}", e))?;
+        // 使用第一个文件字段
+        let mut image = None;
+        while let Some(field) = multipart.next_field().await.map_err(|e| format!("读取表单失败: {}", e))? {
+            if field.file_name().is_some() || matches!(field.name(), Some("image" | "file")) {
+                image = Some(field.bytes().await.map_err(|e| format!("读取图片失败: {}", e))?);
+                break;
+            }
+        }
+        image.ok_or_else(|| "表单中没有图片文件".to_string())?
+    } else {
+        Bytes::from_request(request, &())
+            .await
+            .map_err(|e| format!("读取请求失败: {}", e))?
+    };
+
+    if bytes.is_empty() {
+        return Err("请求中没有图片数据".to_string());
+    }
+    Ok(bytes)
+}
+
+fn error_response(status: StatusCode, message: &str) -> Response {
+    (status, Json(serde_json::json!({ "error": message }))).into_response()
+}
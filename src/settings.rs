@@ -0,0 +1,335 @@
+use std::path::{Path, PathBuf};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::annotate::OverlayStyle;
+use crate::archive;
+use crate::config;
+use crate::export::OutputFormat;
+use crate::live::LiveCaptureOptions;
+use crate::loader::GrayWindow;
+use crate::metadata::DEFAULT_DPI;
+use crate::postprocess::WhitespaceOptions;
+use crate::status_log::StatusLogOptions;
+use crate::preprocess::Levels;
+use crate::ui::{Interpolation, PreviewRendering, ResultFont};
+
+const SETTINGS_FILE: &str = "settings.json";
+
+// 图片查看器的缩放范围与每次缩放的倍率
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ViewerZoom {
+    pub min: f32,
+    pub max: f32,
+    pub step: f32,
+}
+
+impl Default for ViewerZoom {
+    fn default() -> Self {
+        Self {
+            min: 0.1,
+            max: 10.0,
+            step: 1.25,
+        }
+    }
+}
+
+impl ViewerZoom {
+    pub fn clamp(&self, scale: f32) -> f32 {
+        scale.clamp(self.min, self.max.max(self.min))
+    }
+
+    pub fn zoom_in(&self, scale: f32) -> f32 {
+        self.clamp(scale * self.step)
+    }
+
+    pub fn zoom_out(&self, scale: f32) -> f32 {
+        self.clamp(scale / self.step)
+    }
+}
+
+// 识别结果的字体、字体大小、行距（字高的倍数）与逐行显示时每页的行数
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ResultDisplay {
+    pub font: ResultFont,
+    pub font_size: f32,
+    pub line_spacing: f32,
+    pub lines_per_page: usize,
+    // 逐行显示时在行首用色条标出该行的最低置信度
+    pub confidence_gutter: bool,
+    // 不保持空格格式时的空白处理
+    pub whitespace: WhitespaceOptions,
+}
+
+impl Default for ResultDisplay {
+    fn default() -> Self {
+        Self {
+            font: ResultFont::Monospace,
+            font_size: 14.0,
+            line_spacing: 1.2,
+            lines_per_page: 500,
+            confidence_gutter: true,
+            whitespace: WhitespaceOptions::default(),
+        }
+    }
+}
+
+// 导出结果时的默认目录与文件名模板
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExportNaming {
+    // 为空时使用系统对话框的默认位置
+    pub directory: Option<PathBuf>,
+    // 支持 {name}（源文件名，不含扩展名）、{ext}（导出格式扩展名）、{date}（当天日期）
+    pub template: String,
+    // 批量识别时按模板为每个文件自动保存识别文本
+    pub batch_auto_save: bool,
+    // 导出文本时在开头加上来源、尺寸、引擎等注释行
+    pub text_header: bool,
+    // 导出 JSON 时嵌入源图片的小缩略图，默认关闭以免导出文件变大
+    pub embed_thumbnail: bool,
+    // 导出文本时按书写方向添加标记：从右向左的行加 Unicode 方向控制符，竖排文本加说明行
+    pub direction_marks: bool,
+    // 逐个导出或自动保存批量结果时在结果文件旁写入（合并进）manifest.json
+    pub batch_manifest: bool,
+    // 最近一次保存结果时的目录与格式（扩展名），下次打开保存对话框时预先选中
+    pub last_directory: Option<PathBuf>,
+    pub last_extension: Option<String>,
+}
+
+impl Default for ExportNaming {
+    fn default() -> Self {
+        Self {
+            directory: None,
+            template: "{name}_ocr.{ext}".to_string(),
+            batch_auto_save: false,
+            text_header: false,
+            embed_thumbnail: false,
+            direction_marks: false,
+            batch_manifest: true,
+            last_directory: None,
+            last_extension: None,
+        }
+    }
+}
+
+impl ExportNaming {
+    // 按模板生成文件名；没有源文件时 {name} 使用 ocr_result
+    pub fn file_name(&self, source: Option<&Path>, ext: &str) -> String {
+        let name = source
+            .and_then(|path| path.file_stem())
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_else(|| "ocr_result".to_string());
+        let date = chrono::Local::now().format("%Y%m%d").to_string();
+        let template = if self.template.trim().is_empty() {
+            ExportNaming::default().template
+        } else {
+            self.template.clone()
+        };
+        let file_name = template
+            .replace("{name}", &name)
+            .replace("{ext}", ext)
+            .replace("{date}", &date)
+            .replace(['/', '\\'], "_");
+        if Path::new(&file_name).extension().is_none() {
+            format!("{}.{}", file_name, ext)
+        } else {
+            file_name
+        }
+    }
+
+    // 保存对话框的初始目录：优先使用最近一次保存的目录
+    pub fn dialog_directory(&self) -> Option<&Path> {
+        self.last_directory.as_deref().or(self.directory.as_deref())
+    }
+
+    // 最近一次使用的格式在可选格式中时沿用，否则使用第一个格式
+    pub fn preferred_extension<'a>(&'a self, allowed: &[&'a str]) -> &'a str {
+        self.last_extension
+            .as_deref()
+            .filter(|ext| allowed.contains(ext))
+            .unwrap_or(allowed[0])
+    }
+
+    // 记住本次保存的目录与格式，返回是否有变化
+    pub fn remember(&mut self, path: &Path) -> bool {
+        let directory = path.parent().filter(|dir| !dir.as_os_str().is_empty()).map(Path::to_path_buf);
+        let extension = path.extension().map(|ext| ext.to_string_lossy().to_lowercase());
+        let changed = directory != self.last_directory || extension != self.last_extension;
+        self.last_directory = directory;
+        self.last_extension = extension;
+        changed
+    }
+
+    // 批量自动保存的目标路径：未配置目录时保存在源文件旁边
+    pub fn auto_save_path(&self, source: &Path, ext: &str) -> PathBuf {
+        let directory = self
+            .directory
+            .clone()
+            .or_else(|| archive::containing_dir(source).map(Path::to_path_buf))
+            .unwrap_or_default();
+        directory.join(self.file_name(Some(source), ext))
+    }
+}
+
+// 图片没有记录分辨率（截图、多数 PNG）或记录有误时手动指定的扫描 DPI，
+// 用于打印尺寸显示与 hOCR 的 scan_res（转换为可搜索 PDF 时决定页面大小）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DpiOverride {
+    pub enabled: bool,
+    pub dpi: f32,
+}
+
+impl Default for DpiOverride {
+    fn default() -> Self {
+        Self { enabled: false, dpi: 300.0 }
+    }
+}
+
+impl DpiOverride {
+    // 启用时使用指定值，否则使用图片记录的分辨率，都没有时按默认的 72 DPI
+    pub fn resolve(&self, recorded: Option<(f32, f32)>) -> (f32, f32) {
+        if self.enabled && self.dpi > 0.0 {
+            (self.dpi, self.dpi)
+        } else {
+            recorded.unwrap_or((DEFAULT_DPI, DEFAULT_DPI))
+        }
+    }
+}
+
+// 识别成功后执行的命令，需明确启用；结果按 format 写入临时文件供命令读取
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PostOcrHook {
+    pub enabled: bool,
+    // 支持 {image}（源图片路径）和 {result}（结果文件路径）
+    pub command: String,
+    pub format: OutputFormat,
+}
+
+impl Default for PostOcrHook {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            command: String::new(),
+            format: OutputFormat::Text,
+        }
+    }
+}
+
+// 需要在重启后保留的应用设置
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub viewer_zoom: ViewerZoom,
+    // 查看器窗口大小变化时自动适应窗口
+    pub viewer_fit_to_window: bool,
+    // 图片大于查看器窗口时在角落显示缩略导航图
+    pub viewer_minimap: bool,
+    pub interpolation: Interpolation,
+    pub preview_rendering: PreviewRendering,
+    // 上次使用的色阶与 gamma 调整
+    pub levels: Levels,
+    // 性能模式：空闲时也持续重绘界面
+    pub continuous_repaint: bool,
+    // 关闭演示模式：没有真实引擎时识别直接报错，不输出模拟文本
+    pub demo_mode_disabled: bool,
+    pub export: ExportNaming,
+    // 在识别结果中选中文字后自动复制
+    pub copy_on_select: bool,
+    pub result_display: ResultDisplay,
+    pub post_ocr_hook: PostOcrHook,
+    // 16 位灰度图（医学、科学图像）降为 8 位时的窗口
+    pub gray_window: GrayWindow,
+    // 检测框叠加层与标注图的样式
+    pub overlay_style: OverlayStyle,
+    // 区域连续识别的屏幕区域、间隔与截图命令
+    pub live_capture: LiveCaptureOptions,
+    pub dpi_override: DpiOverride,
+    // 状态栏详细程度与日志面板
+    pub status_log: StatusLogOptions,
+}
+
+impl Settings {
+    pub fn load() -> Self {
+        config::config_file(SETTINGS_FILE)
+            .and_then(|path| Self::load_from(&path))
+            .unwrap_or_default()
+    }
+
+    pub fn load_from(path: &Path) -> Result<Self> {
+        config::load_json(path)
+    }
+
+    pub fn save(&self) -> Result<()> {
+        self.save_to(&config::config_file(SETTINGS_FILE)?)
+    }
+
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        config::save_json(path, self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zoom_steps_respect_clamps() {
+        let zoom = ViewerZoom { min: 0.5, max: 20.0, step: 2.0 };
+        assert_eq!(zoom.zoom_in(8.0), 16.0);
+        assert_eq!(zoom.zoom_in(16.0), 20.0);
+        assert_eq!(zoom.zoom_out(0.8), 0.5);
+    }
+
+    #[test]
+    fn test_missing_fields_use_defaults() {
+        let settings: Settings = serde_json::from_str(r#"{"viewer_zoom": {"max": 20.0}}"#).unwrap();
+        assert_eq!(settings.viewer_zoom, ViewerZoom { max: 20.0, ..Default::default() });
+    }
+
+    #[test]
+    fn test_dpi_override() {
+        let dpi = DpiOverride::default();
+        assert_eq!(dpi.resolve(None), (DEFAULT_DPI, DEFAULT_DPI));
+        assert_eq!(dpi.resolve(Some((300.0, 600.0))), (300.0, 600.0));
+        let dpi = DpiOverride { enabled: true, dpi: 400.0 };
+        assert_eq!(dpi.resolve(Some((72.0, 72.0))), (400.0, 400.0));
+    }
+
+    #[test]
+    fn test_export_file_name_template() {
+        let naming = ExportNaming::default();
+        let source = Path::new("/scans/invoice 01.png");
+        assert_eq!(naming.file_name(Some(source), "txt"), "invoice 01_ocr.txt");
+        assert_eq!(naming.file_name(None, "json"), "ocr_result_ocr.json");
+        assert_eq!(naming.auto_save_path(source, "txt"), PathBuf::from("/scans/invoice 01_ocr.txt"));
+
+        let naming = ExportNaming {
+            directory: Some(PathBuf::from("/out")),
+            template: "{name}".to_string(),
+            batch_auto_save: true,
+            ..Default::default()
+        };
+        assert_eq!(naming.auto_save_path(source, "txt"), PathBuf::from("/out/invoice 01.txt"));
+    }
+
+    #[test]
+    fn test_remember_last_export() {
+        let mut naming = ExportNaming { directory: Some(PathBuf::from("/out")), ..Default::default() };
+        assert_eq!(naming.dialog_directory(), Some(Path::new("/out")));
+        assert_eq!(naming.preferred_extension(&["txt", "json"]), "txt");
+
+        assert!(naming.remember(Path::new("/work/page.JSON")));
+        assert!(!naming.remember(Path::new("/work/other.json")));
+        assert_eq!(naming.dialog_directory(), Some(Path::new("/work")));
+        assert_eq!(naming.preferred_extension(&["txt", "json"]), "json");
+        // 只能保存为文本时不使用 json
+        assert_eq!(naming.preferred_extension(&["txt"]), "txt");
+        // 自动保存仍使用配置的目录
+        assert_eq!(naming.auto_save_path(Path::new("/scans/a.png"), "txt"), PathBuf::from("/out/a_ocr.txt"));
+    }
+}
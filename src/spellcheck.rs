@@ -0,0 +1,273 @@
+use std::collections::HashSet;
+use std::io::Write;
+use std::ops::Range;
+use anyhow::Result;
+
+use crate::config;
+
+// 系统自带的英文词表（多数 Linux 与 macOS 提供）
+const SYSTEM_WORD_LISTS: &[&str] = &["/usr/share/dict/words", "/usr/dict/words"];
+// 用户词典：每行一个词，“加入词典”的词追加到这里
+const USER_DICTIONARY_FILE: &str = "dictionary.txt";
+const MAX_SUGGESTIONS: usize = 5;
+// 识别中常见的形近字符混淆，候选词优先按这些替换生成
+const OCR_CONFUSIONS: &[(&str, &str)] = &[
+    ("rn", "m"),
+    ("m", "rn"),
+    ("vv", "w"),
+    ("cl", "d"),
+    ("ii", "u"),
+    ("0", "o"),
+    ("1", "l"),
+    ("1", "i"),
+    ("l", "i"),
+    ("i", "l"),
+    ("5", "s"),
+    ("8", "b"),
+    ("6", "b"),
+    ("e", "c"),
+    ("c", "e"),
+];
+
+// 英文词典，查询时不区分大小写
+#[derive(Debug, Default)]
+pub struct Dictionary {
+    words: HashSet<String>,
+}
+
+impl Dictionary {
+    // 合并系统词表与用户词典；都不存在时为空，此时只检查汉字
+    pub fn load() -> Self {
+        let mut dictionary = Self::default();
+        for path in SYSTEM_WORD_LISTS {
+            if let Ok(content) = std::fs::read_to_string(path) {
+                dictionary.extend(content.lines());
+                break;
+            }
+        }
+        if let Some(content) = config::config_file(USER_DICTIONARY_FILE)
+            .ok()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+        {
+            dictionary.extend(content.lines());
+        }
+        dictionary
+    }
+
+    #[cfg(test)]
+    pub fn from_words<'a>(words: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut dictionary = Self::default();
+        dictionary.extend(words);
+        dictionary
+    }
+
+    fn extend<'a>(&mut self, words: impl IntoIterator<Item = &'a str>) {
+        self.words.extend(
+            words
+                .into_iter()
+                .map(str::trim)
+                .filter(|word| !word.is_empty())
+                .map(str::to_lowercase),
+        );
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    pub fn contains(&self, word: &str) -> bool {
+        self.words.contains(&word.to_lowercase())
+    }
+
+    // 加入词典并写入用户词典文件，之后不再标记
+    pub fn add_user_word(&mut self, word: &str) -> Result<()> {
+        let path = config::config_file(USER_DICTIONARY_FILE)?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut file = std::fs::OpenOptions::new().create(true).append(true).open(path)?;
+        writeln!(file, "{}", word)?;
+        self.extend([word]);
+        Ok(())
+    }
+
+    // 拼写建议：先尝试 OCR 形近字符替换，再尝试编辑距离为 1 的词，保留原词的首字母大写
+    pub fn suggestions(&self, word: &str) -> Vec<String> {
+        let lower = word.to_lowercase();
+        let mut candidates = Vec::new();
+        for (from, to) in OCR_CONFUSIONS {
+            for (index, _) in lower.match_indices(from) {
+                candidates.push(format!("{}{}{}", &lower[..index], to, &lower[index + from.len()..]));
+            }
+        }
+        candidates.extend(edits(&lower));
+
+        let capitalized = word.chars().next().is_some_and(char::is_uppercase);
+        let mut suggestions: Vec<String> = Vec::new();
+        for candidate in candidates {
+            if !self.words.contains(&candidate) {
+                continue;
+            }
+            let candidate = if capitalized { capitalize(&candidate) } else { candidate };
+            if !suggestions.contains(&candidate) {
+                suggestions.push(candidate);
+            }
+            if suggestions.len() == MAX_SUGGESTIONS {
+                break;
+            }
+        }
+        suggestions
+    }
+}
+
+// 编辑距离为 1 的所有拼写：删除、相邻交换、替换与插入一个字母
+fn edits(word: &str) -> Vec<String> {
+    let chars: Vec<char> = word.chars().collect();
+    let splice = |prefix: &[char], middle: &str, suffix: &[char]| {
+        prefix.iter().collect::<String>() + middle + &suffix.iter().collect::<String>()
+    };
+    let mut edits = Vec::new();
+    for i in 0..chars.len() {
+        edits.push(splice(&chars[..i], "", &chars[i + 1..]));
+        if i + 1 < chars.len() {
+            let swapped: String = [chars[i + 1], chars[i]].iter().collect();
+            edits.push(splice(&chars[..i], &swapped, &chars[i + 2..]));
+        }
+    }
+    for letter in 'a'..='z' {
+        for i in 0..=chars.len() {
+            if i < chars.len() {
+                edits.push(splice(&chars[..i], &letter.to_string(), &chars[i + 1..]));
+            }
+            edits.push(splice(&chars[..i], &letter.to_string(), &chars[i..]));
+        }
+    }
+    edits
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IssueKind {
+    // 词典中没有的英文单词
+    UnknownWord,
+    // 常用字表（GB2312）之外的生僻汉字，往往是形近字识别错误
+    RareCharacter,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpellIssue {
+    // 在文本中的字节范围
+    pub range: Range<usize>,
+    pub word: String,
+    pub kind: IssueKind,
+    pub suggestions: Vec<String>,
+}
+
+// 检查文本中的可疑词。词典为空时跳过英文检查；check_hanzi 为 true 时检查简体中文的生僻字
+pub fn check(text: &str, dictionary: &Dictionary, check_hanzi: bool) -> Vec<SpellIssue> {
+    let mut issues = Vec::new();
+    if !dictionary.is_empty() {
+        for (range, word) in words(text) {
+            if is_checkable(word) && !dictionary.contains(word) && !dictionary.contains(word.trim_end_matches("'s")) {
+                issues.push(SpellIssue {
+                    suggestions: dictionary.suggestions(word),
+                    word: word.to_string(),
+                    range,
+                    kind: IssueKind::UnknownWord,
+                });
+            }
+        }
+    }
+    if check_hanzi {
+        for (offset, c) in text.char_indices().filter(|&(_, c)| is_rare_hanzi(c)) {
+            issues.push(SpellIssue {
+                range: offset..offset + c.len_utf8(),
+                word: c.to_string(),
+                kind: IssueKind::RareCharacter,
+                suggestions: Vec::new(),
+            });
+        }
+    }
+    issues.sort_by_key(|issue| issue.range.start);
+    issues
+}
+
+// 由字母、数字和撇号组成的词及其字节范围
+fn words(text: &str) -> Vec<(Range<usize>, &str)> {
+    let is_word_char = |c: char| c.is_ascii_alphanumeric() || c == '\'';
+    let mut words = Vec::new();
+    let mut start = None;
+    for (offset, c) in text.char_indices().chain(std::iter::once((text.len(), ' '))) {
+        match (start, is_word_char(c)) {
+            (None, true) => start = Some(offset),
+            (Some(begin), false) => {
+                let word = text[begin..offset].trim_matches('\'');
+                let begin = begin + text[begin..offset].find(word).unwrap_or(0);
+                if !word.is_empty() {
+                    words.push((begin..begin + word.len(), word));
+                }
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    words
+}
+
+// 跳过单个字母、纯数字和全大写的缩写；字母中夹杂数字（如 he1lo）通常是识别错误，需要检查
+fn is_checkable(word: &str) -> bool {
+    let letters = word.chars().filter(char::is_ascii_alphabetic).count();
+    letters >= 2 && !(letters == word.len() && word.chars().all(|c| c.is_ascii_uppercase()))
+}
+
+// 不在 GB2312 字符集中的汉字。GB2312 收录了简体中文的常用字和次常用字，
+// 简体文本中出现集外字多半是把常用字识别成了形近的生僻字
+pub fn is_rare_hanzi(c: char) -> bool {
+    if !matches!(c as u32, 0x4E00..=0x9FFF | 0x3400..=0x4DBF | 0xF900..=0xFAFF) {
+        return false;
+    }
+    let mut buffer = [0u8; 4];
+    let (bytes, _, had_errors) = encoding_rs::GBK.encode(c.encode_utf8(&mut buffer));
+    // GB2312 的汉字区：首字节 0xB0~0xF7，次字节 0xA1~0xFE
+    had_errors || !matches!(bytes.as_ref(), [0xB0..=0xF7, 0xA1..=0xFE])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flags_unknown_words_with_ocr_suggestions() {
+        let dictionary = Dictionary::from_words(["hello", "modern", "world", "the", "invoice", "it's"]);
+        let issues = check("The rnodern he1lo world, ID 42 it's OK", &dictionary, false);
+        let words: Vec<&str> = issues.iter().map(|issue| issue.word.as_str()).collect();
+        assert_eq!(words, vec!["rnodern", "he1lo"]);
+        assert_eq!(issues[0].suggestions, vec!["modern"]);
+        assert_eq!(issues[1].suggestions, vec!["hello"]);
+        assert_eq!(&"The rnodern he1lo"[issues[1].range.clone()], "he1lo");
+
+        assert_eq!(dictionary.suggestions("Invoce"), vec!["Invoice"]);
+        assert!(check("anything goes", &Dictionary::default(), false).is_empty());
+    }
+
+    #[test]
+    fn test_flags_rare_hanzi() {
+        assert!(!is_rare_hanzi('中'));
+        assert!(!is_rare_hanzi('a'));
+        // 龘 不在 GB2312 中
+        assert!(is_rare_hanzi('龘'));
+
+        let issues = check("识别龘结果", &Dictionary::default(), true);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].kind, IssueKind::RareCharacter);
+        assert_eq!(&"识别龘结果"[issues[0].range.clone()], "龘");
+        assert!(check("识别龘结果", &Dictionary::default(), false).is_empty());
+    }
+}
@@ -0,0 +1,208 @@
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use log::{LevelFilter, Log, Metadata, Record};
+use serde::{Deserialize, Serialize};
+
+// 日志最多保留的条数，超出后丢弃最早的
+pub const MAX_ENTRIES: usize = 500;
+
+// 日志级别，越靠后越详细；状态栏与日志面板只显示不超过所选详细程度的消息
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Error,
+    Warning,
+    #[default]
+    Info,
+    Debug,
+}
+
+impl LogLevel {
+    pub const ALL: [LogLevel; 4] = [LogLevel::Error, LogLevel::Warning, LogLevel::Info, LogLevel::Debug];
+
+    // 作为详细程度选项时的名称
+    pub fn label(&self) -> &'static str {
+        match self {
+            LogLevel::Error => "仅错误",
+            LogLevel::Warning => "错误与警告",
+            LogLevel::Info => "常规",
+            LogLevel::Debug => "详细（含调试）",
+        }
+    }
+
+    pub fn icon(&self) -> &'static str {
+        match self {
+            LogLevel::Error => "❌",
+            LogLevel::Warning => "⚠",
+            LogLevel::Info => "ℹ",
+            LogLevel::Debug => "·",
+        }
+    }
+
+    fn from_log(level: log::Level) -> Self {
+        match level {
+            log::Level::Error => LogLevel::Error,
+            log::Level::Warn => LogLevel::Warning,
+            log::Level::Info => LogLevel::Info,
+            log::Level::Debug | log::Level::Trace => LogLevel::Debug,
+        }
+    }
+}
+
+// 状态栏详细程度与是否把程序日志（log 宏输出）也记入日志面板
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StatusLogOptions {
+    pub verbosity: LogLevel,
+    pub mirror_log: bool,
+}
+
+impl Default for StatusLogOptions {
+    fn default() -> Self {
+        Self {
+            verbosity: LogLevel::Info,
+            mirror_log: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogEntry {
+    pub time: String,
+    pub level: LogLevel,
+    pub message: String,
+}
+
+impl LogEntry {
+    fn now(level: LogLevel, message: &str) -> Self {
+        Self {
+            time: chrono::Local::now().format("%H:%M:%S").to_string(),
+            level,
+            message: message.to_string(),
+        }
+    }
+}
+
+// 带时间与级别的最近消息
+#[derive(Debug, Default)]
+pub struct StatusLog {
+    entries: VecDeque<LogEntry>,
+}
+
+impl StatusLog {
+    pub fn push(&mut self, level: LogLevel, message: &str) {
+        self.push_entry(LogEntry::now(level, message));
+    }
+
+    pub fn push_entry(&mut self, entry: LogEntry) {
+        self.entries.push_back(entry);
+        while self.entries.len() > MAX_ENTRIES {
+            self.entries.pop_front();
+        }
+    }
+
+    // 补充到最近一条消息的下一行
+    pub fn append_to_last(&mut self, note: &str) {
+        if let Some(last) = self.entries.back_mut() {
+            last.message.push('\n');
+            last.message.push_str(note);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    pub fn visible(&self, verbosity: LogLevel) -> impl Iterator<Item = &LogEntry> {
+        self.entries.iter().filter(move |entry| entry.level <= verbosity)
+    }
+
+    pub fn to_text(&self, verbosity: LogLevel) -> String {
+        self.visible(verbosity)
+            .map(|entry| format!("[{}] {} {}", entry.time, entry.level.icon(), entry.message))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+// 本程序输出的 log 记录，由界面每帧取走
+static MIRRORED: Mutex<VecDeque<LogEntry>> = Mutex::new(VecDeque::new());
+
+// 本程序（而非依赖库）的日志目标
+fn is_own_target(target: &str) -> bool {
+    let crate_name = env!("CARGO_CRATE_NAME");
+    target == crate_name || target.strip_prefix(crate_name).is_some_and(|rest| rest.starts_with("::"))
+}
+
+// 在 env_logger 之外把本程序的日志保存一份，终端输出仍由 RUST_LOG 控制
+struct MirrorLogger {
+    inner: env_logger::Logger,
+}
+
+impl Log for MirrorLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        is_own_target(metadata.target()) || self.inner.enabled(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        if self.inner.matches(record) {
+            self.inner.log(record);
+        }
+        if is_own_target(record.target()) {
+            if let Ok(mut mirrored) = MIRRORED.lock() {
+                mirrored.push_back(LogEntry::now(LogLevel::from_log(record.level()), &record.args().to_string()));
+                while mirrored.len() > MAX_ENTRIES {
+                    mirrored.pop_front();
+                }
+            }
+        }
+    }
+
+    fn flush(&self) {
+        self.inner.flush();
+    }
+}
+
+// 代替 env_logger::init()
+pub fn init_logger() {
+    let inner = env_logger::Builder::from_default_env().build();
+    let max_level = inner.filter().max(LevelFilter::Debug);
+    if log::set_boxed_logger(Box::new(MirrorLogger { inner })).is_ok() {
+        log::set_max_level(max_level);
+    }
+}
+
+// 取走自上次调用以来记录的日志
+pub fn drain_mirrored() -> Vec<LogEntry> {
+    MIRRORED.lock().map(|mut mirrored| mirrored.drain(..).collect()).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_is_capped_and_filtered() {
+        let mut log = StatusLog::default();
+        for index in 0..MAX_ENTRIES + 10 {
+            log.push(LogLevel::Debug, &index.to_string());
+        }
+        log.push(LogLevel::Error, "失败");
+        log.append_to_last("详情");
+        assert_eq!(log.visible(LogLevel::Debug).count(), MAX_ENTRIES);
+        assert_eq!(log.visible(LogLevel::Debug).next().unwrap().message, "11");
+
+        let errors: Vec<_> = log.visible(LogLevel::Warning).collect();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].message, "失败\n详情");
+        assert!(log.to_text(LogLevel::Error).ends_with("❌ 失败\n详情"));
+    }
+
+    #[test]
+    fn test_own_targets() {
+        assert!(is_own_target("ocr_rs"));
+        assert!(is_own_target("ocr_rs::ocr"));
+        assert!(!is_own_target("ocr_rs_extra"));
+        assert!(!is_own_target("wgpu_core::device"));
+    }
+}
@@ -0,0 +1,213 @@
+use crate::ocr::BoundingBox;
+use crate::postprocess::is_cjk;
+use crate::reading_order::group_lines;
+
+// 列之间至少保留的空格数
+const COLUMN_GAP: usize = 2;
+
+// 根据检测框的 x 坐标识别表格列，并用空格填充使各列在等宽字体下对齐。
+// 没有检测到表格结构时返回 None
+pub fn align_columns(boxes: &[BoundingBox]) -> Option<String> {
+    let rows = group_lines(boxes);
+    // 至少两行、每行至少两个单元格才视为表格
+    if rows.iter().filter(|row| row.len() >= 2).count() < 2 {
+        return None;
+    }
+
+    let tolerance = median_height(boxes).max(1) as f32 * 1.5;
+    let anchors = column_anchors(&rows, tolerance);
+    if anchors.len() < 2 {
+        return None;
+    }
+
+    let table: Vec<Vec<String>> = rows
+        .iter()
+        .map(|row| {
+            let mut cells = vec![String::new(); anchors.len()];
+            for bbox in row {
+                let column = nearest_anchor(&anchors, bbox.x as f32);
+                let cell = &mut cells[column];
+                if !cell.is_empty() {
+                    cell.push(' ');
+                }
+                cell.push_str(bbox.text.trim());
+            }
+            cells
+        })
+        .collect();
+
+    let widths: Vec<usize> = (0..anchors.len())
+        .map(|column| table.iter().map(|cells| display_width(&cells[column])).max().unwrap_or(0))
+        .collect();
+    // 多数单元格为数字的列（允许有表头）视为数字列
+    let numeric: Vec<bool> = (0..anchors.len())
+        .map(|column| {
+            let values: Vec<&str> = table.iter().map(|cells| cells[column].as_str()).filter(|cell| !cell.is_empty()).collect();
+            values.iter().filter(|cell| is_numeric(cell)).count() * 2 > values.len()
+        })
+        .collect();
+
+    let lines: Vec<String> = table
+        .iter()
+        .map(|cells| {
+            let mut line = String::new();
+            for (column, cell) in cells.iter().enumerate() {
+                if column > 0 {
+                    line.push_str(&" ".repeat(COLUMN_GAP));
+                }
+                let padding = " ".repeat(widths[column] - display_width(cell));
+                // 数字列右对齐，其余左对齐
+                if numeric[column] {
+                    line.push_str(&padding);
+                    line.push_str(cell);
+                } else {
+                    line.push_str(cell);
+                    line.push_str(&padding);
+                }
+            }
+            line.trim_end().to_string()
+        })
+        .collect();
+
+    Some(lines.join("\n"))
+}
+
+// 按每行最左侧检测框的 x 坐标计算行首缩进，粘贴到纯文本编辑器时近似保留原版面
+pub fn indent_by_position(boxes: &[BoundingBox]) -> Option<String> {
+    let rows = group_lines(boxes);
+    let margin = rows.iter().map(|row| row[0].x).min()?;
+    let char_width = median_char_width(boxes)?;
+
+    let lines: Vec<String> = rows
+        .iter()
+        .map(|row| {
+            let indent = ((row[0].x - margin) as f32 / char_width).round() as usize;
+            let text = row.iter().map(|b| b.text.trim()).collect::<Vec<_>>().join(" ");
+            format!("{}{}", " ".repeat(indent), text)
+        })
+        .collect();
+    Some(lines.join("\n"))
+}
+
+// 估算单个半角字符的平均像素宽度
+fn median_char_width(boxes: &[BoundingBox]) -> Option<f32> {
+    let mut widths: Vec<f32> = boxes
+        .iter()
+        .filter_map(|b| {
+            let chars = display_width(b.text.trim());
+            (chars > 0 && b.width > 0).then(|| b.width as f32 / chars as f32)
+        })
+        .collect();
+    if widths.is_empty() {
+        return None;
+    }
+    widths.sort_by(|a, b| a.total_cmp(b));
+    Some(widths[widths.len() / 2])
+}
+
+// 聚类所有单元格的起始 x 坐标，每簇取最小值作为列起点
+fn column_anchors(rows: &[Vec<&BoundingBox>], tolerance: f32) -> Vec<f32> {
+    let mut xs: Vec<f32> = rows.iter().flatten().map(|b| b.x as f32).collect();
+    xs.sort_by(|a, b| a.total_cmp(b));
+
+    let mut anchors: Vec<f32> = Vec::new();
+    let mut last = f32::NEG_INFINITY;
+    for x in xs {
+        if x - last > tolerance {
+            anchors.push(x);
+        }
+        last = x;
+    }
+    anchors
+}
+
+fn nearest_anchor(anchors: &[f32], x: f32) -> usize {
+    anchors
+        .iter()
+        .enumerate()
+        .min_by(|(_, a), (_, b)| (*a - x).abs().total_cmp(&(*b - x).abs()))
+        .map(|(i, _)| i)
+        .unwrap_or(0)
+}
+
+fn median_height(boxes: &[BoundingBox]) -> u32 {
+    let mut heights: Vec<u32> = boxes.iter().map(|b| b.height).collect();
+    if heights.is_empty() {
+        return 0;
+    }
+    heights.sort_unstable();
+    heights[heights.len() / 2]
+}
+
+fn is_numeric(cell: &str) -> bool {
+    cell.chars().any(|c| c.is_ascii_digit())
+        && cell.chars().all(|c| c.is_ascii_digit() || ".,-+%¥$￥元".contains(c))
+}
+
+// 等宽字体下的显示宽度，中文和全角字符占两格
+pub fn display_width(text: &str) -> usize {
+    text.chars()
+        .map(|c| if is_cjk(c) || ('\u{3000}'..='\u{303F}').contains(&c) || ('\u{FF01}'..='\u{FF60}').contains(&c) { 2 } else { 1 })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cell(text: &str, x: u32, y: u32) -> BoundingBox {
+        BoundingBox {
+            text: text.to_string(),
+            confidence: 0.9,
+            x,
+            y,
+            width: 40,
+            height: 20,
+            edited: false,
+            language: None,
+        }
+    }
+
+    #[test]
+    fn test_align_receipt_columns() {
+        let boxes = vec![
+            cell("商品", 10, 10),
+            cell("数量", 200, 12),
+            cell("单价", 300, 9),
+            cell("苹果", 10, 40),
+            cell("1", 215, 41),
+            cell("5999", 305, 40),
+            cell("蓝牙耳机", 10, 70),
+            cell("2", 214, 70),
+            cell("299", 310, 71),
+        ];
+
+        let aligned = align_columns(&boxes).unwrap();
+        assert_eq!(
+            aligned,
+            "商品      数量  单价\n苹果         1  5999\n蓝牙耳机     2   299"
+        );
+    }
+
+    #[test]
+    fn test_indent_by_position() {
+        let line = |text: &str, x: u32, y: u32| BoundingBox {
+            width: text.len() as u32 * 10,
+            ..cell(text, x, y)
+        };
+        let boxes = vec![
+            line("fn main() {", 20, 10),
+            line("let x = 1;", 60, 40),
+            line("}", 20, 70),
+        ];
+
+        let text = indent_by_position(&boxes).unwrap();
+        assert_eq!(text, "fn main() {\n    let x = 1;\n}");
+    }
+
+    #[test]
+    fn test_single_column_is_not_a_table() {
+        let boxes = vec![cell("第一行", 10, 10), cell("第二行", 10, 40)];
+        assert_eq!(align_columns(&boxes), None);
+    }
+}
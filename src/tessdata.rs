@@ -0,0 +1,235 @@
+use std::path::{Path, PathBuf};
+
+// 常见的 tessdata 安装位置
+const TESSDATA_CANDIDATES: &[&str] = &[
+    "/usr/share/tesseract-ocr/5/tessdata",
+    "/usr/share/tesseract-ocr/4.00/tessdata",
+    "/usr/share/tessdata",
+    "/usr/local/share/tessdata",
+    "/opt/homebrew/share/tessdata",
+    "/opt/local/share/tessdata",
+    "C:\\Program Files\\Tesseract-OCR\\tessdata",
+];
+
+// 查找存在的 tessdata 目录，优先使用 TESSDATA_PREFIX
+pub fn tessdata_dirs() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    if let Ok(prefix) = std::env::var("TESSDATA_PREFIX") {
+        let prefix = PathBuf::from(prefix);
+        // TESSDATA_PREFIX 可能指向 tessdata 本身或其上级目录
+        dirs.push(prefix.join("tessdata"));
+        dirs.push(prefix);
+    }
+    dirs.extend(TESSDATA_CANDIDATES.iter().map(PathBuf::from));
+    dirs.retain(|dir| dir.is_dir());
+    dirs
+}
+
+// 扫描所有 tessdata 目录中已安装的语言包
+pub fn discover_languages() -> Vec<String> {
+    let mut languages: Vec<String> = tessdata_dirs()
+        .iter()
+        .flat_map(|dir| discover_languages_in(dir))
+        .collect();
+    languages.sort();
+    languages.dedup();
+    languages
+}
+
+pub fn discover_languages_in(dir: &Path) -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut languages: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let path = entry.path();
+            if path.extension()? != "traineddata" {
+                return None;
+            }
+            let name = path.file_stem()?.to_string_lossy().to_string();
+            // osd 用于方向与文字系统检测，不是识别语言
+            (name != "osd").then_some(name)
+        })
+        .collect();
+    languages.sort();
+    languages
+}
+
+// 将勾选的语言拼接为 Tesseract 使用的 "chi_sim+eng" 格式
+pub fn join_languages(languages: &[String]) -> String {
+    languages.join("+")
+}
+
+pub fn split_languages(language: &str) -> Vec<String> {
+    language
+        .split('+')
+        .map(str::trim)
+        .filter(|code| !code.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+// 常用语言组合，免去记忆 Tesseract 语言代码。竖排使用 *_vert 语言包
+pub struct LanguagePreset {
+    pub name: &'static str,
+    pub language: &'static str,
+    pub vertical: bool,
+}
+
+pub const LANGUAGE_PRESETS: &[LanguagePreset] = &[
+    LanguagePreset { name: "简体中文", language: "chi_sim", vertical: false },
+    LanguagePreset { name: "简体中文（竖排）", language: "chi_sim_vert", vertical: true },
+    LanguagePreset { name: "繁體中文", language: "chi_tra", vertical: false },
+    LanguagePreset { name: "繁體中文（直排）", language: "chi_tra_vert", vertical: true },
+    LanguagePreset { name: "日本語", language: "jpn", vertical: false },
+    LanguagePreset { name: "日本語（縦書き）", language: "jpn_vert", vertical: true },
+    LanguagePreset { name: "한국어", language: "kor", vertical: false },
+    LanguagePreset { name: "한국어（세로）", language: "kor_vert", vertical: true },
+    LanguagePreset { name: "中英混合", language: "chi_sim+eng", vertical: false },
+];
+
+impl LanguagePreset {
+    // 预设中尚未安装的语言包
+    pub fn missing_languages(&self, installed: &[String]) -> Vec<String> {
+        split_languages(self.language)
+            .into_iter()
+            .filter(|code| !installed.contains(code))
+            .collect()
+    }
+}
+
+// 从右向左书写的 Tesseract 语言
+const RTL_LANGUAGES: &[&str] = &["ara", "heb", "fas", "urd", "yid", "pus", "snd", "uig", "div", "syr"];
+
+// 语言组合中任一语言为从右向左书写时返回 true，如 "ara+eng"
+pub fn is_rtl_language(language: &str) -> bool {
+    split_languages(language)
+        .iter()
+        .any(|code| RTL_LANGUAGES.contains(&code.as_str()))
+}
+
+// 语言组合中包含竖排语言包时返回 true，如 "jpn_vert"
+pub fn is_vertical_language(language: &str) -> bool {
+    split_languages(language).iter().any(|code| code.ends_with("_vert"))
+}
+
+// 自动检测语言时至少需要的字母数，太少时结果不可靠
+const MIN_DETECT_CHARS: usize = 8;
+// 主要文字系统占全部字母的最低比例
+const MIN_DOMINANT_SHARE: f32 = 0.6;
+
+// 各文字系统对应的 Tesseract 语言，顺序与 script_counts 一致
+const SCRIPT_LANGUAGES: [&str; 6] = ["chi_sim", "jpn", "kor", "rus", "ara", "eng"];
+
+// 统计各文字系统的字符数，依次为：汉字、假名、谚文、西里尔字母、阿拉伯字母、拉丁字母
+fn script_counts(text: &str) -> [usize; 6] {
+    let mut counts = [0usize; 6];
+    for c in text.chars() {
+        let script = match c {
+            '\u{4E00}'..='\u{9FFF}' | '\u{3400}'..='\u{4DBF}' => 0,
+            '\u{3040}'..='\u{30FF}' => 1,
+            '\u{AC00}'..='\u{D7AF}' | '\u{1100}'..='\u{11FF}' => 2,
+            '\u{0400}'..='\u{04FF}' => 3,
+            '\u{0600}'..='\u{06FF}' => 4,
+            c if c.is_ascii_alphabetic() || ('\u{00C0}'..='\u{024F}').contains(&c) => 5,
+            _ => continue,
+        };
+        counts[script] += 1;
+    }
+    counts
+}
+
+// 根据识别文本中各文字系统的字符占比推测 Tesseract 语言，无法判断时返回 None
+pub fn detect_language(text: &str) -> Option<&'static str> {
+    let counts = script_counts(text);
+    let total: usize = counts.iter().sum();
+    if total < MIN_DETECT_CHARS {
+        return None;
+    }
+    // 日文中汉字与假名混用，只要假名足够多就按日文处理
+    if counts[1] > 0 && (counts[0] + counts[1]) as f32 / total as f32 >= MIN_DOMINANT_SHARE && counts[1] * 5 >= counts[0] {
+        return Some("jpn");
+    }
+
+    let (script, &count) = counts.iter().enumerate().max_by_key(|(_, count)| **count)?;
+    if (count as f32 / total as f32) < MIN_DOMINANT_SHARE {
+        return None;
+    }
+    Some(SCRIPT_LANGUAGES[script])
+}
+
+// 单个检测框文字的语言：检测框文字很短，不设最少字数，取字符最多的文字系统；
+// 含假名时按日文处理。没有任何字母（如纯数字、标点）时返回 None
+pub fn box_language(text: &str) -> Option<&'static str> {
+    let counts = script_counts(text);
+    if counts[1] > 0 {
+        return Some("jpn");
+    }
+    let (script, &count) = counts.iter().enumerate().max_by_key(|(_, count)| **count)?;
+    (count > 0).then_some(SCRIPT_LANGUAGES[script])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preset_missing_languages() {
+        let installed = vec!["chi_sim".to_string(), "jpn_vert".to_string()];
+        let mixed = LANGUAGE_PRESETS.iter().find(|preset| preset.name == "中英混合").unwrap();
+        assert_eq!(mixed.missing_languages(&installed), vec!["eng"]);
+
+        let vertical = LANGUAGE_PRESETS.iter().find(|preset| preset.language == "jpn_vert").unwrap();
+        assert!(vertical.vertical && vertical.missing_languages(&installed).is_empty());
+    }
+
+    #[test]
+    fn test_discover_languages_in_dir() {
+        let dir = std::env::temp_dir().join(format!("ocr_rs_tessdata_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for name in ["eng.traineddata", "chi_sim.traineddata", "osd.traineddata", "readme.txt"] {
+            std::fs::write(dir.join(name), b"").unwrap();
+        }
+
+        let languages = discover_languages_in(&dir);
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_eq!(languages, vec!["chi_sim", "eng"]);
+    }
+
+    #[test]
+    fn test_detect_language() {
+        assert_eq!(detect_language("这是一段用于测试的中文文本"), Some("chi_sim"));
+        assert_eq!(detect_language("The quick brown fox jumps"), Some("eng"));
+        assert_eq!(detect_language("これは日本語のテキストです"), Some("jpn"));
+        assert_eq!(detect_language("Привет, как дела сегодня"), Some("rus"));
+        // 文字太少或混杂时无法判断
+        assert_eq!(detect_language("OK 好"), None);
+        assert_eq!(detect_language("中文文本测试 mixed"), None);
+    }
+
+    #[test]
+    fn test_join_and_split_languages() {
+        let languages = split_languages("chi_sim+ eng+");
+        assert_eq!(languages, vec!["chi_sim", "eng"]);
+        assert_eq!(join_languages(&languages), "chi_sim+eng");
+    }
+
+    #[test]
+    fn test_box_language() {
+        assert_eq!(box_language("识别结果"), Some("chi_sim"));
+        assert_eq!(box_language("OCR"), Some("eng"));
+        assert_eq!(box_language("ひらがな漢字"), Some("jpn"));
+        assert_eq!(box_language("2024-01-01"), None);
+    }
+
+    #[test]
+    fn test_is_rtl_language() {
+        assert!(is_rtl_language("ara+eng"));
+        assert!(is_rtl_language("heb"));
+        assert!(!is_rtl_language("chi_sim+eng"));
+        assert!(!is_rtl_language(""));
+    }
+}
@@ -0,0 +1,226 @@
+use image::DynamicImage;
+use serde::{Deserialize, Serialize};
+
+use crate::ocr::{self, BoundingBox, OcrResult};
+use crate::reading_order::{self, TextDirection};
+
+// 两个检测框的交集占较小者面积超过该比例时视为重叠区中的同一段文字
+const DUPLICATE_OVERLAP: f32 = 0.5;
+
+// 超宽/超长图片（全景拼接、长截图、收据）的分块识别选项
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TilingOptions {
+    pub enabled: bool,
+    // 长边与短边之比超过该值时才分块
+    pub aspect_threshold: f32,
+    // 每块沿长边的长度，为短边的倍数
+    pub tile_ratio: f32,
+    // 相邻分块的重叠长度，为短边的比例，需足以容纳一整行文字
+    pub overlap: f32,
+}
+
+impl Default for TilingOptions {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            aspect_threshold: 3.0,
+            tile_ratio: 2.0,
+            overlap: 0.25,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tile {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Tile {
+    pub fn crop(&self, image: &DynamicImage) -> DynamicImage {
+        image.crop_imm(self.x, self.y, self.width, self.height)
+    }
+}
+
+// 沿长边切分出相互重叠的分块；未启用或长宽比未超过阈值时返回空列表
+pub fn plan_tiles(width: u32, height: u32, options: &TilingOptions) -> Vec<Tile> {
+    let (long, short) = (width.max(height), width.min(height));
+    if !options.enabled || short == 0 || (long as f32 / short as f32) <= options.aspect_threshold {
+        return Vec::new();
+    }
+
+    let tile_len = ((short as f32 * options.tile_ratio) as u32).clamp(1, long);
+    let overlap = ((short as f32 * options.overlap) as u32).min(tile_len - 1);
+    let step = tile_len - overlap;
+
+    let mut tiles = Vec::new();
+    let mut start = 0;
+    loop {
+        // 最后一块与末端对齐，保证大小一致
+        let offset = start.min(long - tile_len);
+        tiles.push(if width >= height {
+            Tile { x: offset, y: 0, width: tile_len, height }
+        } else {
+            Tile { x: 0, y: offset, width, height: tile_len }
+        });
+        if offset + tile_len >= long {
+            break;
+        }
+        start += step;
+    }
+    tiles
+}
+
+// 合并各分块的识别结果：检测框换算到原图坐标并去除重叠区中的重复，
+// 再按阅读顺序重建文本。引擎没有返回检测框时按分块顺序拼接文本
+pub fn merge_results(tiles: Vec<(Tile, OcrResult)>, direction: TextDirection) -> Option<OcrResult> {
+    let mut merged = tiles.first()?.1.clone();
+
+    let mut boxes: Vec<(usize, BoundingBox)> = Vec::new();
+    for (index, (tile, result)) in tiles.iter().enumerate() {
+        for bbox in &result.bounding_boxes {
+            let mut bbox = bbox.clone();
+            bbox.x += tile.x;
+            bbox.y += tile.y;
+            insert_deduplicated(&mut boxes, index, bbox);
+        }
+    }
+    let boxes: Vec<BoundingBox> = boxes.into_iter().map(|(_, bbox)| bbox).collect();
+
+    let confidences: Vec<f32> = tiles.iter().map(|(_, result)| result.confidence).collect();
+    let mean_confidence = confidences.iter().sum::<f32>() / confidences.len() as f32;
+    merged.text = if boxes.is_empty() {
+        merge_texts(tiles.iter().map(|(_, result)| result.text.as_str()))
+    } else {
+        reading_order::reorder_text(&boxes, direction)
+    };
+    merged.confidence = ocr::weighted_confidence(&boxes).unwrap_or(mean_confidence);
+    merged.detected_language = tiles
+        .iter()
+        .map(|(_, result)| result.detected_language.clone())
+        .find(|language| !language.is_empty())
+        .unwrap_or_default();
+    merged.bounding_boxes = boxes;
+    Some(merged)
+}
+
+// 与其他分块中的检测框重复时保留面积较大的一个（另一个多半在分块边缘被截断）
+fn insert_deduplicated(boxes: &mut Vec<(usize, BoundingBox)>, tile: usize, bbox: BoundingBox) {
+    let duplicate = boxes
+        .iter()
+        .position(|(other_tile, other)| *other_tile != tile && overlap_ratio(other, &bbox) >= DUPLICATE_OVERLAP);
+    match duplicate {
+        Some(index) => {
+            let existing = &boxes[index].1;
+            let area = |b: &BoundingBox| b.width as u64 * b.height as u64;
+            let better = (area(&bbox), bbox.confidence) > (area(existing), existing.confidence);
+            if better {
+                boxes[index] = (tile, bbox);
+            }
+        }
+        None => boxes.push((tile, bbox)),
+    }
+}
+
+// 交集面积占较小检测框面积的比例
+fn overlap_ratio(a: &BoundingBox, b: &BoundingBox) -> f32 {
+    let width = (a.x + a.width).min(b.x + b.width).saturating_sub(a.x.max(b.x));
+    let height = (a.y + a.height).min(b.y + b.height).saturating_sub(a.y.max(b.y));
+    let min_area = (a.width as u64 * a.height as u64).min(b.width as u64 * b.height as u64).max(1);
+    (width as u64 * height as u64) as f32 / min_area as f32
+}
+
+// 没有检测框时只能按文本去重：下一块开头与上一块结尾相同的行只保留一次
+fn merge_texts<'a>(texts: impl Iterator<Item = &'a str>) -> String {
+    let mut lines: Vec<&str> = Vec::new();
+    for text in texts {
+        let tile_lines: Vec<&str> = text.lines().filter(|line| !line.trim().is_empty()).collect();
+        let repeated = (1..=tile_lines.len().min(lines.len()))
+            .rev()
+            .find(|&n| {
+                lines[lines.len() - n..]
+                    .iter()
+                    .zip(&tile_lines[..n])
+                    .all(|(a, b)| a.trim() == b.trim())
+            })
+            .unwrap_or(0);
+        lines.extend(&tile_lines[repeated..]);
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(text: &str, boxes: Vec<BoundingBox>) -> OcrResult {
+        OcrResult {
+            text: text.to_string(),
+            raw_text: String::new(),
+            confidence: 0.9,
+            weighted_confidence: 0.0,
+            processing_time: 0.0,
+            bounding_boxes: boxes,
+            timings: Vec::new(),
+            engine: "Candle".to_string(),
+            attempts: Vec::new(),
+            detected_language: String::new(),
+        }
+    }
+
+    fn bbox(text: &str, x: u32, width: u32) -> BoundingBox {
+        BoundingBox {
+            text: text.to_string(),
+            confidence: 0.9,
+            x,
+            y: 10,
+            width,
+            height: 20,
+            edited: false,
+            language: None,
+        }
+    }
+
+    #[test]
+    fn test_plan_tiles_covers_panorama() {
+        let options = TilingOptions { enabled: true, ..Default::default() };
+        assert!(plan_tiles(800, 600, &options).is_empty());
+        assert!(plan_tiles(4000, 100, &TilingOptions::default()).is_empty());
+
+        let tiles = plan_tiles(1000, 100, &options);
+        assert_eq!(tiles.first(), Some(&Tile { x: 0, y: 0, width: 200, height: 100 }));
+        assert_eq!(tiles.last(), Some(&Tile { x: 800, y: 0, width: 200, height: 100 }));
+        assert!(tiles.windows(2).all(|pair| pair[1].x < pair[0].x + pair[0].width));
+
+        let tiles = plan_tiles(100, 1000, &options);
+        assert!(tiles.iter().all(|tile| tile.width == 100 && tile.height == 200));
+        assert_eq!(tiles.last().map(|tile| tile.y + tile.height), Some(1000));
+    }
+
+    #[test]
+    fn test_merge_removes_duplicates_in_overlap() {
+        let left = Tile { x: 0, y: 0, width: 200, height: 100 };
+        let right = Tile { x: 150, y: 0, width: 200, height: 100 };
+        let tiles = vec![
+            (left, result("", vec![bbox("hello", 10, 60), bbox("wor", 160, 40)])),
+            // 右侧分块中的 "world" 完整可见，应替换左侧被截断的 "wor"
+            (right, result("", vec![bbox("world", 10, 60), bbox("again", 100, 60)])),
+        ];
+        let merged = merge_results(tiles, TextDirection::LeftToRight).unwrap();
+        assert_eq!(merged.text, "hello world again");
+        assert_eq!(merged.bounding_boxes.len(), 3);
+        assert_eq!(merged.bounding_boxes[1].x, 160);
+    }
+
+    #[test]
+    fn test_merge_texts_without_boxes() {
+        let tiles = vec![
+            (Tile { x: 0, y: 0, width: 100, height: 200 }, result("第一行\n第二行\n第三行", Vec::new())),
+            (Tile { x: 0, y: 150, width: 100, height: 200 }, result("第三行\n第四行", Vec::new())),
+        ];
+        assert_eq!(merge_results(tiles, TextDirection::LeftToRight).unwrap().text, "第一行\n第二行\n第三行\n第四行");
+    }
+}
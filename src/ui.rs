@@ -1,11 +1,167 @@
 use eframe::egui;
 use image::{DynamicImage, GenericImageView};
-use crate::ocr::OcrResult;
+use serde::{Deserialize, Serialize};
+use crate::annotate::{self, OverlayStyle};
+use crate::clipboard;
+use crate::diff::{self, LineChange, LineChangeKind};
+use crate::export;
+use crate::latex;
+use crate::ocr::{BoundingBox, OcrResult};
+use crate::postprocess::WhitespaceOptions;
+use crate::preprocess::{self, PreprocessOptions};
+use crate::reading_order::{self, TextDirection};
+use crate::recent;
+use crate::search;
+use crate::settings::{ExportNaming, ResultDisplay};
+use crate::spellcheck::{self, Dictionary, IssueKind, SpellIssue};
+use crate::status_log::{self, LogEntry, LogLevel, StatusLog};
+use crate::table;
+
+// 点击区域列表后检测框高亮闪烁的持续时间（秒）
+const FLASH_DURATION: f64 = 1.5;
+
+// 识别结果向下滚动超过该距离后显示“回到顶部”按钮（像素）
+const SCROLL_TOP_THRESHOLD: f32 = 100.0;
+
+// 行首置信度色条占用的宽度（像素）
+const CONFIDENCE_GUTTER_WIDTH: i8 = 8;
+
+// 编辑检测框时角上拖动手柄的边长（像素）
+const HANDLE_SIZE: f32 = 8.0;
+
+// 预处理对比预览使用的缩略图最长边，保证拖动滑块时能实时刷新
+const PREVIEW_MAX_SIDE: u32 = 1024;
+// 低内存模式下识别完成后保留的缩略图最长边
+const THUMBNAIL_MAX_SIDE: u32 = 512;
+
+// 图片背后的衬底，用于区分透明区域和浅色扫描件的边界
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Backdrop {
+    None,
+    Checkerboard,
+    NeutralGray,
+}
+
+// 预览纹理的插值方式：放大查看时最近邻可以看清每个像素
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum Interpolation {
+    #[default]
+    Linear,
+    Nearest,
+}
+
+impl Interpolation {
+    fn texture_options(self) -> egui::TextureOptions {
+        match self {
+            Interpolation::Linear => egui::TextureOptions::LINEAR,
+            Interpolation::Nearest => egui::TextureOptions::NEAREST,
+        }
+    }
+}
+
+// 结果文本的字体：等宽字体保持表格与按版面缩进的列对齐，比例字体阅读段落更自然
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum ResultFont {
+    #[default]
+    Monospace,
+    Proportional,
+}
+
+impl ResultFont {
+    pub const ALL: [Self; 2] = [Self::Monospace, Self::Proportional];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ResultFont::Monospace => "等宽",
+            ResultFont::Proportional => "比例",
+        }
+    }
+
+    fn font_id(self, size: f32) -> egui::FontId {
+        match self {
+            ResultFont::Monospace => egui::FontId::monospace(size),
+            ResultFont::Proportional => egui::FontId::proportional(size),
+        }
+    }
+}
+
+// 主预览纹理的显示方式，便于校对打印扫描件时观察接近纸面的效果。
+// 只影响界面上的纹理，识别使用的图片不变
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PreviewRendering {
+    // 缩小显示时使用 mipmap 三线性过滤，细笔画不再断裂闪烁，观感更接近打印稿
+    pub mipmaps: bool,
+    // 对显示的纹理做轻微的 USM 锐化
+    pub sharpen: bool,
+}
+
+impl PreviewRendering {
+    // 锐化的高斯半径与阈值，保持轻微以免放大噪点
+    const SHARPEN_SIGMA: f32 = 0.8;
+    const SHARPEN_THRESHOLD: i32 = 3;
+
+    fn texture_options(self, interpolation: Interpolation) -> egui::TextureOptions {
+        let mut options = interpolation.texture_options();
+        if self.mipmaps {
+            options.mipmap_mode = Some(egui::TextureFilter::Linear);
+        }
+        options
+    }
+}
+
+// 放大镜的直径（像素）与放大倍数
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LoupeOptions {
+    pub diameter: f32,
+    pub zoom: f32,
+}
+
+impl Default for LoupeOptions {
+    fn default() -> Self {
+        Self {
+            diameter: 160.0,
+            zoom: 3.0,
+        }
+    }
+}
 
 pub struct ImageDisplay {
     texture: Option<egui::TextureHandle>,
     image_size: Option<(u32, u32)>,
     image_data: Option<DynamicImage>,
+    boxes: Vec<BoundingBox>,
+    show_boxes: bool,
+    // 在检测框左上角显示序号
+    show_numbers: bool,
+    // 正在闪烁的检测框序号及开始时间
+    flash: Option<(usize, f64)>,
+    // 键盘导航选中的检测框，切换后需要滚动到该框
+    active_box: Option<usize>,
+    scroll_to_active: bool,
+    box_editor: BoxEditor,
+    backdrop: Backdrop,
+    loupe: LoupeOptions,
+    interpolation: Interpolation,
+    rendering: PreviewRendering,
+    // 在图片上拖动框选的文字
+    text_selection: Option<TextSelection>,
+    text_direction: TextDirection,
+    // 结果查找匹配的检测框，当前匹配的检测框切换后闪烁并滚动到该处
+    search_boxes: Vec<usize>,
+    search_current: Vec<usize>,
+    search_flash_start: f64,
+    scroll_to_search: bool,
+    overlay_style: OverlayStyle,
+}
+
+// 框选区域的起点与终点（原图坐标），以及选中的检测框和文字
+struct TextSelection {
+    start: egui::Pos2,
+    end: egui::Pos2,
+    boxes: Vec<usize>,
+    text: String,
+    dragging: bool,
 }
 
 impl ImageDisplay {
@@ -14,6 +170,54 @@ impl ImageDisplay {
             texture: None,
             image_size: None,
             image_data: None,
+            boxes: Vec::new(),
+            show_boxes: true,
+            show_numbers: false,
+            flash: None,
+            active_box: None,
+            scroll_to_active: false,
+            box_editor: BoxEditor::default(),
+            backdrop: Backdrop::Checkerboard,
+            loupe: LoupeOptions::default(),
+            interpolation: Interpolation::default(),
+            rendering: PreviewRendering::default(),
+            text_selection: None,
+            text_direction: TextDirection::default(),
+            search_boxes: Vec::new(),
+            search_current: Vec::new(),
+            search_flash_start: 0.0,
+            scroll_to_search: false,
+            overlay_style: OverlayStyle::default(),
+        }
+    }
+    
+    pub fn set_text_direction(&mut self, direction: TextDirection) {
+        self.text_direction = direction;
+    }
+    
+    pub fn set_overlay_style(&mut self, style: OverlayStyle) {
+        self.overlay_style = style;
+    }
+    
+    pub fn set_backdrop(&mut self, backdrop: Backdrop) {
+        self.backdrop = backdrop;
+    }
+    
+    pub fn set_loupe(&mut self, loupe: LoupeOptions) {
+        self.loupe = loupe;
+    }
+    
+    pub fn set_interpolation(&mut self, interpolation: Interpolation) {
+        if self.interpolation != interpolation {
+            self.interpolation = interpolation;
+            self.texture = None;
+        }
+    }
+    
+    pub fn set_rendering(&mut self, rendering: PreviewRendering) {
+        if self.rendering != rendering {
+            self.rendering = rendering;
+            self.texture = None;
         }
     }
     
@@ -22,6 +226,67 @@ impl ImageDisplay {
         self.image_size = Some((width, height));
         self.image_data = Some(image);
         self.texture = None; // 重置纹理，将在show中重新创建
+        self.boxes.clear();
+        self.flash = None;
+        self.active_box = None;
+        self.box_editor.reset();
+        self.text_selection = None;
+        self.search_boxes.clear();
+        self.search_current.clear();
+    }
+    
+    // 用缩略图替换原图并释放纹理；保留原始尺寸，检测框仍按原图坐标显示
+    pub fn shrink_to_thumbnail(&mut self) {
+        if let Some(image) = &self.image_data {
+            if image.width().max(image.height()) > THUMBNAIL_MAX_SIDE {
+                self.image_data = Some(image.thumbnail(THUMBNAIL_MAX_SIDE, THUMBNAIL_MAX_SIDE));
+                self.texture = None;
+            }
+        }
+    }
+    
+    pub fn set_boxes(&mut self, boxes: Vec<BoundingBox>) {
+        self.boxes = boxes;
+        self.flash = None;
+        self.active_box = None;
+        self.box_editor.reset();
+        self.text_selection = None;
+    }
+    
+    // 替换单个检测框（例如重新识别选区后），不影响当前选择
+    pub fn update_box(&mut self, index: usize, bbox: BoundingBox) {
+        if let Some(slot) = self.boxes.get_mut(index) {
+            *slot = bbox;
+        }
+    }
+    
+    // 拖动修改过检测框时返回修改后的全部检测框
+    pub fn take_edited_boxes(&mut self) -> Option<Vec<BoundingBox>> {
+        std::mem::take(&mut self.box_editor.changed).then(|| self.boxes.clone())
+    }
+    
+    pub fn take_reocr_request(&mut self) -> Option<usize> {
+        self.box_editor.reocr_request.take()
+    }
+    
+    pub fn set_active_box(&mut self, index: Option<usize>) {
+        if self.active_box != index {
+            self.active_box = index;
+            self.scroll_to_active = index.is_some();
+        }
+    }
+    
+    pub fn flash_box(&mut self, index: usize, now: f64) {
+        self.flash = Some((index, now));
+    }
+    
+    pub fn set_search_highlight(&mut self, boxes: Vec<usize>, current: Vec<usize>, now: f64) {
+        if self.search_current != current {
+            self.scroll_to_search = !current.is_empty();
+            self.search_flash_start = now;
+            self.search_current = current;
+        }
+        self.search_boxes = boxes;
     }
     
     pub fn has_image(&self) -> bool {
@@ -51,32 +316,165 @@ impl ImageDisplay {
             // 如果还没有纹理，从图像数据创建
             if self.texture.is_none() {
                 if let Some(image) = &self.image_data {
-                    self.texture = Some(create_texture_from_image(ui.ctx(), image, "main_image"));
+                    let options = self.rendering.texture_options(self.interpolation);
+                    let texture = if self.rendering.sharpen {
+                        let sharpened = image.unsharpen(PreviewRendering::SHARPEN_SIGMA, PreviewRendering::SHARPEN_THRESHOLD);
+                        create_texture_from_image(ui.ctx(), &sharpened, "main_image", options)
+                    } else {
+                        create_texture_from_image(ui.ctx(), image, "main_image", options)
+                    };
+                    self.texture = Some(texture);
                 }
             }
             
             if let Some(texture) = &self.texture {
                 ui.vertical_centered(|ui| {
-                    // 添加可点击的图片
+                    // 先占位，图片布局完成后再填充衬底，保证衬底在图片下方
+                    let backdrop_shape = ui.painter().add(egui::Shape::Noop);
+                    
+                    // 添加可点击的图片，有检测框时还可以拖动框选文字
+                    let editing = self.box_editor.enabled && self.show_boxes;
+                    let selectable = !editing && !self.boxes.is_empty();
+                    let sense = if selectable { egui::Sense::click_and_drag() } else { egui::Sense::click() };
                     let image_response = ui.add(
                         egui::Image::from_texture(texture)
                             .fit_to_exact_size(egui::vec2(display_width, display_height))
-                            .sense(egui::Sense::click())
+                            .sense(sense)
                     );
+                    ui.painter().set(backdrop_shape, backdrop_shape_for(self.backdrop, image_response.rect));
                     
                     if image_response.clicked() {
-                        clicked = true;
+                        if editing {
+                            self.box_editor.selected = None;
+                        } else if self.text_selection.take().is_none() {
+                            clicked = true;
+                        }
+                    }
+                    if selectable {
+                        update_text_selection(
+                            &mut self.text_selection,
+                            ui,
+                            &image_response,
+                            (width, height),
+                            &self.boxes,
+                            self.text_direction,
+                        );
                     }
                     
-                    // 鼠标悬停提示
-                    if image_response.hovered() {
+                    // 检测框叠加层
+                    let now = ui.input(|i| i.time);
+                    let flash_index = match self.flash {
+                        Some((index, start)) if now - start < FLASH_DURATION => Some(index),
+                        _ => None,
+                    };
+                    if flash_index.is_some() {
+                        ui.ctx().request_repaint();
+                    }
+                    let active = self.active_box.and_then(|i| self.boxes.get(i));
+                    if self.show_boxes || flash_index.is_some() || active.is_some() {
+                        let boxes: &[BoundingBox] = if self.show_boxes { &self.boxes } else { &[] };
+                        draw_box_overlay(
+                            &ui.painter().with_clip_rect(image_response.rect),
+                            image_response.rect,
+                            (width, height),
+                            boxes,
+                            &self.overlay_style,
+                            flash_index.and_then(|i| self.boxes.get(i)),
+                            active,
+                        );
+                    }
+                    if let (true, Some(image)) = (self.show_boxes && self.show_numbers, &self.image_data) {
+                        draw_box_numbers(
+                            &ui.painter().with_clip_rect(image_response.rect),
+                            image_response.rect,
+                            image,
+                            &self.boxes,
+                        );
+                    }
+                    if !self.search_boxes.is_empty() {
+                        let elapsed = now - self.search_flash_start;
+                        let pulse = if elapsed < FLASH_DURATION {
+                            ui.ctx().request_repaint();
+                            (0.5 + 0.5 * (elapsed * std::f64::consts::TAU * 2.0).cos()) as f32
+                        } else {
+                            1.0
+                        };
+                        let to_box = |&index: &usize| self.boxes.get(index);
+                        draw_search_highlight(
+                            &ui.painter().with_clip_rect(image_response.rect),
+                            image_response.rect,
+                            (width, height),
+                            self.search_boxes.iter().filter_map(to_box),
+                            self.search_current.iter().filter_map(to_box),
+                            pulse,
+                        );
+                        if std::mem::take(&mut self.scroll_to_search) {
+                            let rect = self.search_current
+                                .iter()
+                                .filter_map(to_box)
+                                .map(|bbox| box_screen_rect(bbox, image_response.rect, (width, height)))
+                                .reduce(|a, b| a.union(b));
+                            if let Some(rect) = rect {
+                                ui.scroll_to_rect(rect, Some(egui::Align::Center));
+                            }
+                        }
+                    }
+                    if let Some(selection) = &self.text_selection {
+                        draw_text_selection(
+                            &ui.painter().with_clip_rect(image_response.rect),
+                            image_response.rect,
+                            (width, height),
+                            selection,
+                            &self.boxes,
+                        );
+                    }
+                    if let Some(bbox) = active.filter(|_| std::mem::take(&mut self.scroll_to_active)) {
+                        let rect = box_screen_rect(bbox, image_response.rect, (width, height));
+                        ui.scroll_to_rect(rect, Some(egui::Align::Center));
+                    }
+                    if editing {
+                        self.box_editor.show(ui, image_response.rect, (width, height), &mut self.boxes);
+                    }
+                    
+                    // 按住 Shift 时显示放大镜，否则显示悬停提示
+                    let loupe_pos = image_response.hover_pos().filter(|_| ui.input(|i| i.modifiers.shift));
+                    if let Some(pointer) = loupe_pos {
+                        ui.ctx().set_cursor_icon(egui::CursorIcon::Crosshair);
+                        draw_loupe(ui.ctx(), texture, image_response.rect, pointer, self.loupe);
+                    } else if image_response.dragged() && selectable {
+                        ui.ctx().set_cursor_icon(egui::CursorIcon::Text);
+                    } else if image_response.hovered() && !editing {
                         ui.ctx().set_cursor_icon(egui::CursorIcon::PointingHand);
                         image_response.on_hover_text("点击查看原图");
                     }
                     
                     ui.add_space(8.0);
                     ui.weak(format!("原始尺寸: {}×{}", width, height));
-                    ui.weak("点击图片查看原图");
+                    if editing {
+                        ui.weak("拖动检测框移动位置，拖动选中框的四角调整大小");
+                    } else if let Some(selection) = self.text_selection.as_ref().filter(|s| !s.dragging && !s.boxes.is_empty()) {
+                        ui.weak(format!("已复制框选的 {} 个文字块，点击图片取消选择", selection.boxes.len()));
+                    } else if selectable {
+                        ui.weak("点击图片查看原图，拖动框选文字并复制，按住 Shift 使用放大镜");
+                    } else {
+                        ui.weak("点击图片查看原图，按住 Shift 使用放大镜");
+                    }
+                    if !self.boxes.is_empty() {
+                        ui.horizontal(|ui| {
+                            ui.checkbox(&mut self.show_boxes, "显示检测框");
+                            ui.add_enabled(self.show_boxes, egui::Checkbox::new(&mut self.show_numbers, "显示序号"));
+                            ui.checkbox(&mut self.box_editor.enabled, "编辑检测框");
+                            if editing {
+                                let selected = self.box_editor.selected;
+                                if ui.add_enabled(selected.is_some(), egui::Button::new("🔁 重新识别选区"))
+                                    .on_disabled_hover_text("先点击选中一个检测框")
+                                    .clicked()
+                                {
+                                    self.box_editor.reocr_request = selected;
+                                }
+                            }
+                        });
+                    }
                 });
             }
         }
@@ -85,10 +483,588 @@ impl ImageDisplay {
     }
 }
 
-// 状态显示组件
+// 拖动时更新框选区域，松开时复制选中的文字
+fn update_text_selection(
+    text_selection: &mut Option<TextSelection>,
+    ui: &egui::Ui,
+    response: &egui::Response,
+    image_size: (u32, u32),
+    boxes: &[BoundingBox],
+    direction: TextDirection,
+) {
+    let rect = response.rect;
+    let to_image = |pos: egui::Pos2| {
+        let relative = ((pos - rect.min) / rect.size()).clamp(egui::Vec2::ZERO, egui::Vec2::splat(1.0));
+        egui::pos2(relative.x * image_size.0 as f32, relative.y * image_size.1 as f32)
+    };
+    
+    if response.drag_started() {
+        if let Some(origin) = ui.input(|i| i.pointer.press_origin()) {
+            let start = to_image(origin);
+            *text_selection = Some(TextSelection { start, end: start, boxes: Vec::new(), text: String::new(), dragging: true });
+        }
+    }
+    let Some(selection) = text_selection else {
+        return;
+    };
+    if let (true, Some(pointer)) = (response.dragged(), response.interact_pointer_pos()) {
+        selection.end = to_image(pointer);
+        let area = egui::Rect::from_two_pos(selection.start, selection.end);
+        let (selected, text) = reading_order::text_in_area(
+            boxes,
+            (area.min.x as u32, area.min.y as u32),
+            (area.max.x as u32, area.max.y as u32),
+            direction,
+        );
+        selection.boxes = selected;
+        selection.text = text;
+    }
+    if response.drag_stopped() {
+        selection.dragging = false;
+        if selection.text.is_empty() {
+            *text_selection = None;
+        } else {
+            ui.ctx().copy_text(selection.text.clone());
+        }
+    }
+}
+
+// 框选时绘制选区矩形，并高亮选中的检测框
+fn draw_text_selection(
+    painter: &egui::Painter,
+    image_rect: egui::Rect,
+    image_size: (u32, u32),
+    selection: &TextSelection,
+    boxes: &[BoundingBox],
+) {
+    let fill = egui::Color32::from_rgba_unmultiplied(51, 153, 255, 70);
+    for bbox in selection.boxes.iter().filter_map(|&index| boxes.get(index)) {
+        painter.rect_filled(box_screen_rect(bbox, image_rect, image_size), 0.0, fill);
+    }
+    if selection.dragging {
+        let scale = egui::vec2(image_rect.width() / image_size.0 as f32, image_rect.height() / image_size.1 as f32);
+        let to_screen = |pos: egui::Pos2| image_rect.min + pos.to_vec2() * scale;
+        let area = egui::Rect::from_two_pos(to_screen(selection.start), to_screen(selection.end));
+        painter.rect_stroke(area, 0.0, egui::Stroke::new(1.0, egui::Color32::from_rgb(51, 153, 255)), egui::StrokeKind::Inside);
+    }
+}
+
+// 检测框上可拖动的部位
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum BoxHandle {
+    Move,
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl BoxHandle {
+    const CORNERS: [BoxHandle; 4] = [BoxHandle::TopLeft, BoxHandle::TopRight, BoxHandle::BottomLeft, BoxHandle::BottomRight];
+    
+    fn position(self, rect: egui::Rect) -> egui::Pos2 {
+        match self {
+            BoxHandle::Move => rect.center(),
+            BoxHandle::TopLeft => rect.left_top(),
+            BoxHandle::TopRight => rect.right_top(),
+            BoxHandle::BottomLeft => rect.left_bottom(),
+            BoxHandle::BottomRight => rect.right_bottom(),
+        }
+    }
+    
+    fn cursor(self) -> egui::CursorIcon {
+        match self {
+            BoxHandle::Move => egui::CursorIcon::Move,
+            BoxHandle::TopLeft | BoxHandle::BottomRight => egui::CursorIcon::ResizeNwSe,
+            BoxHandle::TopRight | BoxHandle::BottomLeft => egui::CursorIcon::ResizeNeSw,
+        }
+    }
+}
+
+// 检测框编辑所在的图片区域：控件 ID、图片在屏幕上的位置与原图尺寸
+struct EditCanvas {
+    id: egui::Id,
+    image_rect: egui::Rect,
+    image_size: (u32, u32),
+}
+
+impl EditCanvas {
+    fn screen_rect(&self, bbox: &BoundingBox) -> egui::Rect {
+        box_screen_rect(bbox, self.image_rect, self.image_size)
+    }
+    
+    // 屏幕上的拖动距离换算为图片像素
+    fn to_image(&self, delta: egui::Vec2) -> egui::Vec2 {
+        delta / egui::vec2(self.image_rect.width() / self.image_size.0 as f32, self.image_rect.height() / self.image_size.1 as f32)
+    }
+}
+
+// 在图片上拖动修改检测框的位置和大小
+#[derive(Default)]
+struct BoxEditor {
+    enabled: bool,
+    selected: Option<usize>,
+    // 正在拖动的部位及拖动开始时的检测框
+    drag: Option<(BoxHandle, BoundingBox)>,
+    changed: bool,
+    reocr_request: Option<usize>,
+}
+
+impl BoxEditor {
+    fn reset(&mut self) {
+        self.selected = None;
+        self.drag = None;
+        self.reocr_request = None;
+    }
+    
+    fn show(&mut self, ui: &mut egui::Ui, image_rect: egui::Rect, image_size: (u32, u32), boxes: &mut [BoundingBox]) {
+        let canvas = EditCanvas { id: ui.id().with("box_editor"), image_rect, image_size };
+        for (index, bbox) in boxes.iter_mut().enumerate() {
+            let rect = canvas.screen_rect(bbox);
+            self.interact(ui, &canvas, rect, (index, BoxHandle::Move), bbox);
+        }
+        // 选中框的手柄最后注册，保证位于其他检测框之上
+        let Some(index) = self.selected.filter(|&i| i < boxes.len()) else {
+            return;
+        };
+        let bbox = &mut boxes[index];
+        let rect = canvas.screen_rect(bbox);
+        for handle in BoxHandle::CORNERS {
+            let target = egui::Rect::from_center_size(handle.position(rect), egui::Vec2::splat(HANDLE_SIZE * 1.5));
+            self.interact(ui, &canvas, target, (index, handle), bbox);
+        }
+        
+        let rect = canvas.screen_rect(bbox);
+        let painter = ui.painter().with_clip_rect(image_rect.expand(HANDLE_SIZE));
+        let color = egui::Color32::from_rgb(220, 20, 60);
+        painter.rect_stroke(rect, 0.0, egui::Stroke::new(2.0, color), egui::StrokeKind::Outside);
+        for handle in BoxHandle::CORNERS {
+            painter.rect_filled(egui::Rect::from_center_size(handle.position(rect), egui::Vec2::splat(HANDLE_SIZE)), 0.0, color);
+        }
+    }
+    
+    // part 为检测框序号与拖动的部位
+    fn interact(&mut self, ui: &egui::Ui, canvas: &EditCanvas, target: egui::Rect, part: (usize, BoxHandle), bbox: &mut BoundingBox) {
+        let (index, handle) = part;
+        let response = ui
+            .interact(target, canvas.id.with(part), egui::Sense::click_and_drag())
+            .on_hover_cursor(handle.cursor());
+        if response.clicked() || response.drag_started() {
+            self.selected = Some(index);
+        }
+        if response.drag_started() {
+            self.drag = Some((handle, bbox.clone()));
+        }
+        if response.dragged() {
+            let origin = ui.input(|i| i.pointer.press_origin());
+            if let (Some((handle, original)), Some(origin), Some(pointer)) = (&self.drag, origin, response.interact_pointer_pos()) {
+                *bbox = drag_box(original, *handle, canvas.to_image(pointer - origin), canvas.image_size);
+                self.changed = true;
+            }
+        }
+        if response.drag_stopped() {
+            self.drag = None;
+        }
+    }
+}
+
+// 按拖动距离（图片像素）移动检测框或调整其某个角，结果限制在图片范围内
+fn drag_box(original: &BoundingBox, handle: BoxHandle, delta: egui::Vec2, image_size: (u32, u32)) -> BoundingBox {
+    let (image_width, image_height) = (image_size.0 as i64, image_size.1 as i64);
+    let (dx, dy) = (delta.x.round() as i64, delta.y.round() as i64);
+    let mut left = original.x as i64;
+    let mut top = original.y as i64;
+    let mut right = left + original.width as i64;
+    let mut bottom = top + original.height as i64;
+    
+    match handle {
+        BoxHandle::Move => {
+            let dx = dx.max(-left).min(image_width - right);
+            let dy = dy.max(-top).min(image_height - bottom);
+            left += dx;
+            right += dx;
+            top += dy;
+            bottom += dy;
+        }
+        BoxHandle::TopLeft => {
+            left += dx;
+            top += dy;
+        }
+        BoxHandle::TopRight => {
+            right += dx;
+            top += dy;
+        }
+        BoxHandle::BottomLeft => {
+            left += dx;
+            bottom += dy;
+        }
+        BoxHandle::BottomRight => {
+            right += dx;
+            bottom += dy;
+        }
+    }
+    
+    // 拖过对边时交换，并保证至少 1 像素
+    let clamp_x = |v: i64| v.clamp(0, image_width);
+    let clamp_y = |v: i64| v.clamp(0, image_height);
+    let (left, right) = (clamp_x(left.min(right)), clamp_x(left.max(right)));
+    let (top, bottom) = (clamp_y(top.min(bottom)), clamp_y(top.max(bottom)));
+    BoundingBox {
+        x: left as u32,
+        y: top as u32,
+        width: (right - left).max(1) as u32,
+        height: (bottom - top).max(1) as u32,
+        edited: true,
+        ..original.clone()
+    }
+}
+
+fn backdrop_shape_for(backdrop: Backdrop, rect: egui::Rect) -> egui::Shape {
+    const CELL: f32 = 8.0;
+    
+    match backdrop {
+        Backdrop::None => egui::Shape::Noop,
+        Backdrop::NeutralGray => egui::Shape::rect_filled(rect, 0.0, egui::Color32::from_gray(128)),
+        Backdrop::Checkerboard => {
+            let mut shapes = vec![egui::Shape::rect_filled(rect, 0.0, egui::Color32::from_gray(200))];
+            let columns = (rect.width() / CELL).ceil() as usize;
+            let rows = (rect.height() / CELL).ceil() as usize;
+            for row in 0..rows {
+                for column in (row % 2..columns).step_by(2) {
+                    let min = rect.min + egui::vec2(column as f32 * CELL, row as f32 * CELL);
+                    let cell = egui::Rect::from_min_size(min, egui::vec2(CELL, CELL)).intersect(rect);
+                    shapes.push(egui::Shape::rect_filled(cell, 0.0, egui::Color32::from_gray(150)));
+                }
+            }
+            egui::Shape::Vec(shapes)
+        }
+    }
+}
+
+// 在指针处绘制圆形放大镜，通过调整 UV 坐标对纹理局部放大采样
+fn draw_loupe(
+    ctx: &egui::Context,
+    texture: &egui::TextureHandle,
+    image_rect: egui::Rect,
+    pointer: egui::Pos2,
+    loupe: LoupeOptions,
+) {
+    const SEGMENTS: usize = 48;
+    
+    let radius = loupe.diameter / 2.0;
+    let uv_center = egui::pos2(
+        (pointer.x - image_rect.min.x) / image_rect.width(),
+        (pointer.y - image_rect.min.y) / image_rect.height(),
+    );
+    // 放大镜半径对应的 UV 跨度
+    let uv_radius = egui::vec2(
+        radius / loupe.zoom / image_rect.width(),
+        radius / loupe.zoom / image_rect.height(),
+    );
+    
+    let mut mesh = egui::Mesh::with_texture(texture.id());
+    mesh.vertices.push(egui::epaint::Vertex {
+        pos: pointer,
+        uv: uv_center,
+        color: egui::Color32::WHITE,
+    });
+    for i in 0..=SEGMENTS {
+        let angle = i as f32 / SEGMENTS as f32 * std::f32::consts::TAU;
+        let direction = egui::vec2(angle.cos(), angle.sin());
+        mesh.vertices.push(egui::epaint::Vertex {
+            pos: pointer + direction * radius,
+            uv: uv_center + direction * uv_radius,
+            color: egui::Color32::WHITE,
+        });
+    }
+    for i in 1..=SEGMENTS as u32 {
+        mesh.add_triangle(0, i, i + 1);
+    }
+    
+    let painter = ctx.layer_painter(egui::LayerId::new(egui::Order::Tooltip, egui::Id::new("loupe")));
+    painter.circle_filled(pointer, radius, egui::Color32::from_gray(200));
+    painter.add(mesh);
+    painter.circle_stroke(pointer, radius, egui::Stroke::new(2.0, egui::Color32::from_gray(60)));
+}
+
+// 原图与预处理结果并排对比，参数变化时重新生成右侧纹理
+pub struct PreprocessPreview {
+    enabled: bool,
+    source: Option<DynamicImage>,
+    original: Option<egui::TextureHandle>,
+    processed: Option<egui::TextureHandle>,
+    // 生成 processed 纹理时使用的参数
+    applied: Option<PreprocessOptions>,
+    interpolation: Interpolation,
+}
+
+impl PreprocessPreview {
+    pub fn new() -> Self {
+        Self {
+            enabled: false,
+            source: None,
+            original: None,
+            processed: None,
+            applied: None,
+            interpolation: Interpolation::default(),
+        }
+    }
+    
+    pub fn set_interpolation(&mut self, interpolation: Interpolation) {
+        if self.interpolation != interpolation {
+            self.interpolation = interpolation;
+            self.original = None;
+            self.applied = None;
+        }
+    }
+    
+    pub fn set_image(&mut self, image: &DynamicImage) {
+        let (width, height) = image.dimensions();
+        let source = if width.max(height) > PREVIEW_MAX_SIDE {
+            image.thumbnail(PREVIEW_MAX_SIDE, PREVIEW_MAX_SIDE)
+        } else {
+            image.clone()
+        };
+        self.source = Some(source);
+        self.original = None;
+        self.processed = None;
+        self.applied = None;
+    }
+    
+    pub fn clear(&mut self) {
+        self.source = None;
+        self.original = None;
+        self.processed = None;
+        self.applied = None;
+    }
+    
+    pub fn is_enabled(&self) -> bool {
+        self.enabled && self.source.is_some()
+    }
+    
+    pub fn toggle(&mut self) {
+        if self.source.is_some() {
+            self.enabled = !self.enabled;
+        }
+    }
+    
+    pub fn show_toggle(&mut self, ui: &mut egui::Ui) {
+        if self.source.is_some() {
+            ui.checkbox(&mut self.enabled, "对比预处理效果")
+                .on_hover_text("左侧为原图，右侧为当前预处理参数下的结果");
+        }
+    }
+    
+    pub fn show(&mut self, ui: &mut egui::Ui, options: &PreprocessOptions) {
+        let Some(source) = &self.source else {
+            return;
+        };
+        
+        if self.original.is_none() {
+            self.original = Some(create_texture_from_image(ui.ctx(), source, "preview_original", self.interpolation.texture_options()));
+        }
+        if self.applied.as_ref() != Some(options) {
+            let processed = preprocess::preprocess(source.clone(), options);
+            self.processed = Some(create_texture_from_image(ui.ctx(), &processed, "preview_processed", self.interpolation.texture_options()));
+            self.applied = Some(options.clone());
+        }
+        
+        let (width, height) = source.dimensions();
+        let aspect_ratio = width as f32 / height as f32;
+        let available = ui.available_size();
+        let column_width = ((available.x - 30.0) / 2.0).max(140.0);
+        let max_height = (available.y - 100.0).max(200.0);
+        let size = if aspect_ratio > column_width / max_height {
+            egui::vec2(column_width, column_width / aspect_ratio)
+        } else {
+            egui::vec2(max_height * aspect_ratio, max_height)
+        };
+        
+        ui.horizontal_top(|ui| {
+            for (label, texture) in [("原图", &self.original), ("预处理后", &self.processed)] {
+                if let Some(texture) = texture {
+                    ui.vertical(|ui| {
+                        ui.weak(label);
+                        ui.add(egui::Image::from_texture(texture).fit_to_exact_size(size));
+                    });
+                }
+            }
+        });
+    }
+}
+
+// 检测框在屏幕上的位置
+fn box_screen_rect(bbox: &BoundingBox, image_rect: egui::Rect, image_size: (u32, u32)) -> egui::Rect {
+    let scale_x = image_rect.width() / image_size.0 as f32;
+    let scale_y = image_rect.height() / image_size.1 as f32;
+    egui::Rect::from_min_size(
+        image_rect.min + egui::vec2(bbox.x as f32 * scale_x, bbox.y as f32 * scale_y),
+        egui::vec2(bbox.width as f32 * scale_x, bbox.height as f32 * scale_y),
+    )
+}
+
+// 在图片区域上绘制检测框，highlighted 为闪烁提示的框，active 为键盘导航选中的框
+pub fn draw_box_overlay(
+    painter: &egui::Painter,
+    image_rect: egui::Rect,
+    image_size: (u32, u32),
+    boxes: &[BoundingBox],
+    style: &OverlayStyle,
+    highlighted: Option<&BoundingBox>,
+    active: Option<&BoundingBox>,
+) {
+    let to_screen = |bbox: &BoundingBox| box_screen_rect(bbox, image_rect, image_size);
+    
+    for bbox in boxes {
+        let rect = to_screen(bbox);
+        let [r, g, b] = style.box_color(bbox);
+        let color = egui::Color32::from_rgb(r, g, b);
+        if style.fill {
+            painter.rect_filled(rect, 0.0, egui::Color32::from_rgba_unmultiplied(r, g, b, annotate::FILL_ALPHA));
+        }
+        painter.rect_stroke(rect, 0.0, egui::Stroke::new(style.stroke_width, color), egui::StrokeKind::Outside);
+        if style.show_labels && !bbox.text.trim().is_empty() {
+            painter.text(
+                rect.left_top() - egui::vec2(0.0, style.stroke_width),
+                egui::Align2::LEFT_BOTTOM,
+                bbox.text.trim(),
+                egui::FontId::proportional(11.0),
+                color,
+            );
+        }
+    }
+    
+    if let Some(bbox) = highlighted {
+        let rect = to_screen(bbox);
+        painter.rect_filled(rect, 0.0, egui::Color32::from_rgba_unmultiplied(255, 200, 0, 60));
+        painter.rect_stroke(rect, 0.0, egui::Stroke::new(3.0, egui::Color32::from_rgb(255, 165, 0)), egui::StrokeKind::Outside);
+    }
+    
+    if let Some(bbox) = active {
+        let rect = to_screen(bbox);
+        painter.rect_filled(rect, 0.0, egui::Color32::from_rgba_unmultiplied(0, 200, 120, 50));
+        painter.rect_stroke(rect, 0.0, egui::Stroke::new(2.5, egui::Color32::from_rgb(0, 170, 100)), egui::StrokeKind::Outside);
+    }
+}
+
+// 为结果查找匹配的检测框绘制底色，当前匹配加粗描边，pulse 为闪烁时的不透明度
+fn draw_search_highlight<'a>(
+    painter: &egui::Painter,
+    image_rect: egui::Rect,
+    image_size: (u32, u32),
+    matches: impl Iterator<Item = &'a BoundingBox>,
+    current: impl Iterator<Item = &'a BoundingBox>,
+    pulse: f32,
+) {
+    for bbox in matches {
+        painter.rect_filled(box_screen_rect(bbox, image_rect, image_size), 0.0, search_match_color(false));
+    }
+    let stroke = egui::Stroke::new(3.0, egui::Color32::from_rgb(255, 120, 0).gamma_multiply(pulse));
+    for bbox in current {
+        let rect = box_screen_rect(bbox, image_rect, image_size);
+        painter.rect_filled(rect, 0.0, search_match_color(true).gamma_multiply(pulse));
+        painter.rect_stroke(rect, 0.0, stroke, egui::StrokeKind::Outside);
+    }
+}
+
+// 在检测框左上角绘制序号标签，文字颜色随标签下方原图的亮度选择黑色或白色
+fn draw_box_numbers(painter: &egui::Painter, image_rect: egui::Rect, image: &DynamicImage, boxes: &[BoundingBox]) {
+    let (width, height) = image.dimensions();
+    let to_image = image_rect.width() / width as f32;
+    let font = egui::FontId::proportional(11.0);
+    
+    for (index, bbox) in boxes.iter().enumerate() {
+        let rect = box_screen_rect(bbox, image_rect, (width, height));
+        let galley = painter.layout_no_wrap((index + 1).to_string(), font.clone(), egui::Color32::PLACEHOLDER);
+        let pill = egui::Rect::from_min_size(rect.left_top(), galley.size() + egui::vec2(8.0, 2.0));
+        
+        // 标签在原图中覆盖的区域
+        let offset = (pill.min - image_rect.min) / to_image;
+        let luminance = annotate::region_luminance(
+            image,
+            offset.x.max(0.0) as u32,
+            offset.y.max(0.0) as u32,
+            (pill.width() / to_image).ceil() as u32,
+            (pill.height() / to_image).ceil() as u32,
+        );
+        let (text, background) = annotate::contrast_colors(luminance);
+        let [r, g, b] = background;
+        painter.rect_filled(
+            pill,
+            pill.height() / 2.0,
+            egui::Color32::from_rgba_unmultiplied(r, g, b, annotate::LABEL_BACKGROUND_ALPHA),
+        );
+        let [r, g, b] = text;
+        painter.galley(pill.min + egui::vec2(4.0, 1.0), galley, egui::Color32::from_rgb(r, g, b));
+    }
+}
+
+// 在行首的留白处画出该行最低置信度的色条，悬停显示数值
+fn draw_confidence_gutter(ui: &egui::Ui, left: f32, rows: egui::Rangef, confidence: f32, line_index: usize) {
+    let area = egui::Rect::from_x_y_ranges(left..=left + CONFIDENCE_GUTTER_WIDTH as f32 - 2.0, rows);
+    let bar = egui::Rect::from_x_y_ranges(left + 1.0..=left + 4.0, rows.shrink(1.0));
+    let [r, g, b] = annotate::Colormap::RedGreen.color(confidence);
+    ui.painter().rect_filled(bar, 1.0, egui::Color32::from_rgb(r, g, b));
+    ui.interact(area, ui.id().with(("confidence_gutter", line_index)), egui::Sense::hover())
+        .on_hover_text(format!("本行最低置信度 {:.1}%", confidence * 100.0));
+}
+
+// 各检测框置信度的分布直方图（10 个区间）
+fn show_confidence_histogram(ui: &mut egui::Ui, boxes: &[BoundingBox]) {
+    const BINS: usize = 10;
+    
+    let mut counts = [0usize; BINS];
+    for bbox in boxes {
+        let bin = ((bbox.confidence.clamp(0.0, 1.0) * BINS as f32) as usize).min(BINS - 1);
+        counts[bin] += 1;
+    }
+    
+    let mut confidences: Vec<f32> = boxes.iter().map(|b| b.confidence).collect();
+    confidences.sort_by(|a, b| a.total_cmp(b));
+    let mean = confidences.iter().sum::<f32>() / confidences.len() as f32;
+    let middle = confidences.len() / 2;
+    let median = if confidences.len().is_multiple_of(2) {
+        (confidences[middle - 1] + confidences[middle]) / 2.0
+    } else {
+        confidences[middle]
+    };
+    
+    ui.horizontal(|ui| {
+        ui.label("置信度分布:");
+        ui.weak(format!("均值 {:.1}%  中位数 {:.1}%", mean * 100.0, median * 100.0));
+    });
+    
+    let (rect, response) = ui.allocate_exact_size(egui::vec2(240.0, 60.0), egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(rect, 2.0, ui.visuals().extreme_bg_color);
+    
+    let max_count = counts.iter().copied().max().unwrap_or(0).max(1);
+    let bar_width = rect.width() / BINS as f32;
+    for (bin, &count) in counts.iter().enumerate() {
+        if count == 0 {
+            continue;
+        }
+        let height = rect.height() * count as f32 / max_count as f32;
+        let bar = egui::Rect::from_min_max(
+            egui::pos2(rect.left() + bin as f32 * bar_width + 1.0, rect.bottom() - height),
+            egui::pos2(rect.left() + (bin + 1) as f32 * bar_width - 1.0, rect.bottom()),
+        );
+        painter.rect_filled(bar, 0.0, egui::Color32::from_rgb(100, 149, 237));
+    }
+    
+    // 悬停时显示所在区间的数量
+    if let Some(pos) = response.hover_pos() {
+        let bin = (((pos.x - rect.left()) / bar_width) as usize).min(BINS - 1);
+        response.on_hover_text(format!("{}%–{}%: {} 个区域", bin * 10, (bin + 1) * 10, counts[bin]));
+    }
+}
+
+// 状态显示组件。所有消息都记入日志，状态栏只显示不超过所选详细程度的消息
 pub struct StatusDisplay {
     message: String,
     status_type: StatusType,
+    log: StatusLog,
+    verbosity: LogLevel,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -104,22 +1080,85 @@ impl StatusDisplay {
         Self {
             message: String::new(),
             status_type: StatusType::None,
+            log: StatusLog::default(),
+            verbosity: LogLevel::Info,
         }
     }
     
     pub fn set_message(&mut self, message: &str) {
-        self.message = message.to_string();
-        self.status_type = StatusType::Info;
+        self.record(LogLevel::Info, StatusType::Info, message);
     }
     
     pub fn set_success(&mut self, message: &str) {
-        self.message = message.to_string();
-        self.status_type = StatusType::Success;
+        self.record(LogLevel::Info, StatusType::Success, message);
     }
     
     pub fn set_error(&mut self, message: &str) {
-        self.message = message.to_string();
-        self.status_type = StatusType::Error;
+        self.record(LogLevel::Error, StatusType::Error, message);
+    }
+    
+    fn record(&mut self, level: LogLevel, status_type: StatusType, message: &str) {
+        self.log.push(level, message);
+        if level <= self.verbosity {
+            self.message = message.to_string();
+            self.status_type = status_type;
+        }
+    }
+    
+    // 在当前状态下方补充一行提示，保留原有的状态类型
+    pub fn append_note(&mut self, note: &str) {
+        self.log.append_to_last(note);
+        if !self.message.is_empty() {
+            self.message.push('\n');
+            self.message.push_str(note);
+        }
+    }
+    
+    pub fn set_verbosity(&mut self, verbosity: LogLevel) {
+        self.verbosity = verbosity;
+    }
+    
+    // 记入程序日志（log 宏）的消息，只进入日志面板
+    pub fn push_log(&mut self, entry: LogEntry) {
+        self.log.push_entry(entry);
+    }
+    
+    pub fn log_count(&self) -> usize {
+        self.log.visible(self.verbosity).count()
+    }
+    
+    // 日志面板：按时间顺序列出消息，新消息出现时保持滚动到底部
+    pub fn show_log(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.strong("📜 日志");
+            ui.weak(format!("（最近 {} 条）", status_log::MAX_ENTRIES));
+            if ui.small_button("复制").clicked() {
+                ui.ctx().copy_text(self.log.to_text(self.verbosity));
+            }
+            if ui.small_button("清空").clicked() {
+                self.log.clear();
+            }
+        });
+        ui.separator();
+        egui::ScrollArea::vertical()
+            .id_salt("status_log")
+            .auto_shrink([false, false])
+            .stick_to_bottom(true)
+            .show(ui, |ui| {
+                for entry in self.log.visible(self.verbosity) {
+                    let color = match entry.level {
+                        LogLevel::Error => egui::Color32::from_rgb(220, 20, 60),
+                        LogLevel::Warning => egui::Color32::from_rgb(200, 120, 0),
+                        LogLevel::Info => ui.visuals().text_color(),
+                        LogLevel::Debug => ui.visuals().weak_text_color(),
+                    };
+                    ui.horizontal_top(|ui| {
+                        ui.weak(&entry.time);
+                        ui.label(entry.level.icon());
+                        ui.colored_label(color, &entry.message);
+                    });
+                }
+            });
     }
     
     pub fn clear(&mut self) {
@@ -144,33 +1183,281 @@ impl StatusDisplay {
             ui.weak("就绪");
         }
     }
-}
-
-// 结果面板组件
-pub struct ResultPanel {
-    result: Option<OcrResult>,
-    text_content: String,
-    show_details: bool,
-    preserve_whitespace: bool,
-    font_size: f32,
-    line_spacing: f32,
-}
-
-impl ResultPanel {
-    pub fn new() -> Self {
-        Self {
-            result: None,
-            text_content: String::new(),
-            show_details: false,
-            preserve_whitespace: true,
-            font_size: 14.0,
-            line_spacing: 1.2,
+}
+
+// 区域列表排序方式
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum RegionSort {
+    ReadingOrder,
+    Position,
+}
+
+// 结果面板组件
+pub struct ResultPanel {
+    result: Option<OcrResult>,
+    text_content: String,
+    show_details: bool,
+    preserve_whitespace: bool,
+    show_raw: bool,
+    // 按检测框位置对齐表格列
+    align_table: bool,
+    // 按检测框位置重建阅读顺序（处理分栏）
+    reading_order: bool,
+    display: ResultDisplay,
+    // 字体大小或行距被修改，需要保存到设置
+    display_changed: bool,
+    region_sort: RegionSort,
+    // 识别区域列表只显示该语言的检测框，None 为全部
+    region_language: Option<String>,
+    // 请求在图片上闪烁显示的检测框序号
+    flash_request: Option<usize>,
+    // 文本区域获得焦点时用 Tab/Shift+Tab 切换的当前检测框
+    active_box: Option<usize>,
+    scroll_to_active: bool,
+    // 文本被手动编辑过；此时重新识别会先显示差异供逐行确认
+    edited: bool,
+    pending_diff: Option<Vec<LineChange>>,
+    // 保存文本时的默认目录与文件名
+    export_naming: ExportNaming,
+    // 保存后记住的目录与格式有变化，需要保存到设置
+    export_naming_changed: bool,
+    source_path: Option<std::path::PathBuf>,
+    // 当前图片的备注与标签（标签为输入框中的原文），编辑完成后由调用方保存到历史记录
+    note: String,
+    tags_input: String,
+    annotation_changed: bool,
+    // 在可编辑文本中选中文字后松开鼠标即复制到剪贴板
+    copy_on_select: bool,
+    scroll_to_top: bool,
+    // 从右向左书写的语言按行右对齐显示，重排阅读顺序时行内从右向左
+    text_direction: TextDirection,
+    // 在结果中查找的关键字与当前定位到的第几处匹配，切换匹配后文本与图片都滚动到该处
+    search_query: String,
+    search_current: usize,
+    search_scroll: bool,
+    // 逐行显示时的当前页，超长结果只渲染当前页的行
+    page: usize,
+    // 校对侧栏：词典首次打开时加载，文本变化后重新检查
+    proofreading: bool,
+    dictionary: Option<Dictionary>,
+    spell_issues: Vec<SpellIssue>,
+    spell_checked_text: Option<String>,
+    // 识别语言包含简体中文时检查生僻字
+    check_hanzi: bool,
+    // 各行最低置信度，随文本缓存；检测框变化时清空
+    line_confidences: Option<(String, Vec<Option<f32>>)>,
+}
+
+impl ResultPanel {
+    pub fn new() -> Self {
+        Self {
+            result: None,
+            text_content: String::new(),
+            show_details: false,
+            preserve_whitespace: true,
+            show_raw: false,
+            align_table: false,
+            reading_order: false,
+            display: ResultDisplay::default(),
+            display_changed: false,
+            region_sort: RegionSort::ReadingOrder,
+            region_language: None,
+            flash_request: None,
+            active_box: None,
+            scroll_to_active: false,
+            edited: false,
+            pending_diff: None,
+            export_naming: ExportNaming::default(),
+            export_naming_changed: false,
+            source_path: None,
+            note: String::new(),
+            tags_input: String::new(),
+            annotation_changed: false,
+            copy_on_select: false,
+            scroll_to_top: false,
+            text_direction: TextDirection::LeftToRight,
+            search_query: String::new(),
+            search_current: 0,
+            search_scroll: false,
+            page: 0,
+            proofreading: false,
+            dictionary: None,
+            spell_issues: Vec::new(),
+            spell_checked_text: None,
+            check_hanzi: false,
+            line_confidences: None,
+        }
+    }
+    
+    pub fn set_language(&mut self, language: &str) {
+        self.check_hanzi = language.split('+').any(|code| code == "chi_sim");
+        self.spell_checked_text = None;
+    }
+    
+    pub fn set_text_direction(&mut self, direction: TextDirection) {
+        self.text_direction = direction;
+    }
+    
+    // 查找关键字在图片上对应的检测框：全部匹配的检测框，以及当前匹配覆盖的检测框
+    pub fn search_highlight(&self) -> (Vec<usize>, Vec<usize>) {
+        let Some(result) = self.result.as_ref().filter(|_| !self.search_query.trim().is_empty()) else {
+            return (Vec::new(), Vec::new());
+        };
+        let matches = search::box_matches(&result.bounding_boxes, &self.search_query);
+        let current = matches
+            .get(self.search_current)
+            .or(matches.last())
+            .cloned()
+            .unwrap_or_default();
+        let mut all: Vec<usize> = matches.into_iter().flatten().collect();
+        all.sort_unstable();
+        all.dedup();
+        (all, current)
+    }
+    
+    // 切换到下一处（step 为 1）或上一处（step 为 -1）匹配
+    fn step_search(&mut self, count: usize, step: isize) {
+        if count > 0 {
+            self.search_current = (self.search_current as isize + step).rem_euclid(count as isize) as usize;
+            self.search_scroll = true;
+        }
+    }
+    
+    pub fn set_display(&mut self, display: ResultDisplay) {
+        self.display = display;
+    }
+    
+    pub fn take_display_change(&mut self) -> Option<ResultDisplay> {
+        std::mem::take(&mut self.display_changed).then_some(self.display)
+    }
+    
+    pub fn set_copy_on_select(&mut self, enabled: bool) {
+        self.copy_on_select = enabled;
+    }
+    
+    pub fn take_export_change(&mut self) -> Option<ExportNaming> {
+        std::mem::take(&mut self.export_naming_changed).then(|| self.export_naming.clone())
+    }
+    
+    pub fn set_export_defaults(&mut self, naming: ExportNaming, source_path: Option<std::path::PathBuf>) {
+        self.export_naming = naming;
+        self.source_path = source_path;
+    }
+    
+    pub fn set_annotation(&mut self, note: &str, tags: &[String]) {
+        self.note = note.to_string();
+        self.tags_input = tags.join(", ");
+        self.annotation_changed = false;
+    }
+    
+    pub fn take_annotation(&mut self) -> Option<(String, Vec<String>)> {
+        std::mem::take(&mut self.annotation_changed).then(|| (self.note.trim().to_string(), recent::parse_tags(&self.tags_input)))
+    }
+    
+    pub fn set_result(&mut self, result: OcrResult) {
+        let edited_text = self.edited.then(|| self.text_content.clone());
+        self.result = Some(result);
+        self.active_box = None;
+        self.pending_diff = None;
+        self.line_confidences = None;
+        self.refresh_text_content();
+        
+        // 保留手动编辑的文本，待用户逐行确认新结果
+        if let Some(edited_text) = edited_text {
+            let changes = diff::line_diff(&edited_text, &self.text_content);
+            if diff::has_changes(&changes) {
+                self.pending_diff = Some(changes);
+                self.text_content = edited_text;
+                self.edited = true;
+            }
+        }
+    }
+    
+    // 检测框被手动修改后更新，不影响文本内容与编辑状态
+    pub fn update_boxes(&mut self, boxes: Vec<BoundingBox>) {
+        if let Some(result) = &mut self.result {
+            result.bounding_boxes = boxes;
+        }
+        self.line_confidences = None;
+    }
+    
+    pub fn discard_edits(&mut self) {
+        self.edited = false;
+        self.pending_diff = None;
+    }
+    
+    pub fn take_flash_request(&mut self) -> Option<usize> {
+        self.flash_request.take()
+    }
+    
+    pub fn active_box(&self) -> Option<usize> {
+        self.active_box
+    }
+    
+    // 按区域列表的顺序切换当前检测框，step 为 1（下一个）或 -1（上一个）
+    fn cycle_active_box(&mut self, boxes: &[BoundingBox], step: isize) {
+        let order = self.region_order(boxes);
+        if order.is_empty() {
+            return;
+        }
+        let next = match self.active_box.and_then(|active| order.iter().position(|&i| i == active)) {
+            Some(position) => (position as isize + step).rem_euclid(order.len() as isize) as usize,
+            None if step > 0 => 0,
+            None => order.len() - 1,
+        };
+        self.active_box = Some(order[next]);
+        self.scroll_to_active = true;
+    }
+    
+    fn region_order(&self, boxes: &[BoundingBox]) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..boxes.len()).collect();
+        if self.region_sort == RegionSort::Position {
+            order.sort_by_key(|&i| (boxes[i].y, boxes[i].x));
+        }
+        order
+    }
+    
+    // 根据显示选项刷新显示内容，表格对齐未检测到表格时退回逐行文本
+    fn refresh_text_content(&mut self) {
+        self.edited = false;
+        self.page = 0;
+        if let Some(result) = &self.result {
+            let aligned = if self.align_table {
+                table::align_columns(&result.bounding_boxes)
+            } else {
+                None
+            };
+            self.text_content = if let Some(aligned) = aligned {
+                aligned
+            } else if self.reading_order && !result.bounding_boxes.is_empty() {
+                reading_order::reorder_text(&result.bounding_boxes, self.text_direction)
+            } else if self.show_raw && !result.raw_text.is_empty() {
+                result.raw_text.clone()
+            } else {
+                result.text.clone()
+            };
+            if !self.preserve_whitespace {
+                self.text_content = self.display.whitespace.apply(&self.text_content);
+            }
+        }
+    }
+    
+    // 切换空格格式或修改空白处理后更新文本；手动编辑过的文本只做空白处理，不重新生成
+    fn apply_whitespace_mode(&mut self) {
+        if !self.edited {
+            self.refresh_text_content();
+        } else if !self.preserve_whitespace {
+            self.text_content = self.display.whitespace.apply(&self.text_content);
         }
     }
     
-    pub fn set_result(&mut self, result: OcrResult) {
-        self.text_content = result.text.clone();
-        self.result = Some(result);
+    // 复制与保存的文本
+    fn output_text(&self) -> String {
+        if self.preserve_whitespace {
+            self.text_content.clone()
+        } else {
+            self.display.whitespace.apply(&self.text_content)
+        }
     }
     
     pub fn show(&mut self, ui: &mut egui::Ui) {
@@ -182,76 +1469,389 @@ impl ResultPanel {
         // 简化的格式控制选项，默认收起
         ui.collapsing("🔧 显示选项", |ui| {
             ui.horizontal(|ui| {
-                ui.checkbox(&mut self.preserve_whitespace, "保持空格格式");
+                if ui.checkbox(&mut self.preserve_whitespace, "保持空格格式").changed() {
+                    self.apply_whitespace_mode();
+                }
+                ui.separator();
+                if ui.checkbox(&mut self.show_raw, "显示原始文本")
+                    .on_hover_text("显示后处理之前的识别结果")
+                    .changed()
+                {
+                    self.refresh_text_content();
+                }
+                if ui.checkbox(&mut self.align_table, "表格对齐")
+                    .on_hover_text("根据检测框位置对齐表格列，未检测到表格时显示原文")
+                    .changed()
+                {
+                    self.refresh_text_content();
+                }
+                if ui.checkbox(&mut self.reading_order, "按阅读顺序重排")
+                    .on_hover_text("根据检测框位置重建阅读顺序，分栏排版时先读完左栏再读右栏")
+                    .changed()
+                {
+                    self.refresh_text_content();
+                }
                 ui.separator();
+                ui.label("字体:");
+                egui::ComboBox::from_id_salt("result_font")
+                    .selected_text(self.display.font.label())
+                    .show_ui(ui, |ui| {
+                        for font in ResultFont::ALL {
+                            self.display_changed |= ui.selectable_value(&mut self.display.font, font, font.label()).changed();
+                        }
+                    })
+                    .response
+                    .on_hover_text("等宽字体保持表格和按版面缩进的列对齐，比例字体适合阅读段落");
                 ui.label("字体大小:");
-                ui.add(egui::Slider::new(&mut self.font_size, 10.0..=20.0));
+                let font_size = ui.add(egui::Slider::new(&mut self.display.font_size, 10.0..=20.0));
+                ui.label("行距:");
+                let line_spacing = ui.add(egui::Slider::new(&mut self.display.line_spacing, 1.0..=2.5).suffix("×"))
+                    .on_hover_text("“保持空格格式”显示时的行间距，为字高的倍数");
+                ui.label("每页行数:");
+                let lines_per_page = ui.add(egui::DragValue::new(&mut self.display.lines_per_page).range(50..=5000).speed(10.0))
+                    .on_hover_text("“保持空格格式”显示时超长结果分页显示，复制和保存仍针对全文");
+                self.display_changed |= ui.checkbox(&mut self.display.confidence_gutter, "置信度色条")
+                    .on_hover_text("“保持空格格式”显示时在行首用色条标出该行检测框的最低置信度，红色为低置信度")
+                    .changed();
+                // 拖动滑块过程中不保存，松开后再保存
+                self.display_changed |= [font_size, line_spacing, lines_per_page]
+                    .iter()
+                    .any(|response| response.drag_stopped() || (response.changed() && !response.dragged()));
             });
+            if !self.preserve_whitespace && whitespace_checkboxes(ui, &mut self.display.whitespace) {
+                self.display_changed = true;
+                self.apply_whitespace_mode();
+            }
         });
         
         ui.add_space(4.0);
         
+        // 当前检测框在文本中对应的行
+        let active_line = self.active_box
+            .and_then(|index| result.bounding_boxes.get(index))
+            .map(|bbox| bbox.text.trim())
+            .filter(|text| !text.is_empty())
+            .and_then(|text| self.text_content.lines().position(|line| line.contains(text)));
+        let scroll_to_line = std::mem::take(&mut self.scroll_to_active);
+        
         // 文本内容显示区域 - 保持原有格式
-        ui.group(|ui| {
-            ui.strong("识别内容:");
+        if self.pending_diff.is_some() {
+            self.show_diff_review(ui);
+            return;
+        }
+        
+        if self.proofreading {
+            egui::SidePanel::right("proofreading_panel")
+                .resizable(true)
+                .default_width(220.0)
+                .show_inside(ui, |ui| self.show_proofreading(ui));
+        }
+        
+        let text_group = ui.group(|ui| {
+            ui.horizontal(|ui| {
+                ui.strong("识别内容:");
+                if self.edited {
+                    ui.weak("（已手动编辑）");
+                    if ui.small_button("↺ 还原").on_hover_text("放弃手动编辑，恢复识别结果").clicked() {
+                        self.refresh_text_content();
+                    }
+                } else if self.preserve_whitespace {
+                    ui.weak("取消“保持空格格式”后可直接编辑");
+                }
+            });
+            let text_matches = search::text_matches(&self.text_content, &self.search_query);
+            ui.horizontal(|ui| {
+                ui.label("🔍");
+                let query = ui.add(
+                    egui::TextEdit::singleline(&mut self.search_query)
+                        .hint_text("在结果中查找")
+                        .desired_width(180.0),
+                );
+                if query.changed() {
+                    self.search_current = 0;
+                    self.search_scroll = true;
+                }
+                if self.search_query.is_empty() {
+                    return;
+                }
+                if text_matches.is_empty() {
+                    ui.weak("无匹配");
+                    return;
+                }
+                self.search_current = self.search_current.min(text_matches.len() - 1);
+                ui.weak(format!("{}/{}", self.search_current + 1, text_matches.len()));
+                if ui.small_button("▲").on_hover_text("上一处").clicked() {
+                    self.step_search(text_matches.len(), -1);
+                }
+                let enter = query.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                if ui.small_button("▼").on_hover_text("下一处（回车）").clicked() || enter {
+                    self.step_search(text_matches.len(), 1);
+                    if enter {
+                        query.request_focus();
+                    }
+                }
+            });
+            let search_scroll = std::mem::take(&mut self.search_scroll);
+            
+            // 逐行显示时分页：各行的查找匹配数用于给当前页的匹配编号，以及翻到当前匹配所在页
+            let lines_per_page = self.display.lines_per_page.max(1);
+            let mut page_starts_at_match = 0;
+            if self.preserve_whitespace {
+                let match_counts: Vec<usize> = self.text_content
+                    .lines()
+                    .map(|line| search::text_matches(&line.replace('\t', "    "), &self.search_query).len())
+                    .collect();
+                let page_count = match_counts.len().div_ceil(lines_per_page).max(1);
+                if search_scroll && !text_matches.is_empty() {
+                    let mut seen = 0;
+                    if let Some(line) = match_counts.iter().position(|&count| {
+                        seen += count;
+                        seen > self.search_current
+                    }) {
+                        self.page = line / lines_per_page;
+                    }
+                }
+                if let Some(line) = active_line.filter(|_| scroll_to_line) {
+                    self.page = line / lines_per_page;
+                }
+                self.page = self.page.min(page_count - 1);
+                page_starts_at_match = match_counts[..(self.page * lines_per_page).min(match_counts.len())].iter().sum();
+                
+                if page_count > 1 {
+                    ui.horizontal(|ui| {
+                        if ui.add_enabled(self.page > 0, egui::Button::new("◀ 上一页")).clicked() {
+                            self.page -= 1;
+                            self.scroll_to_top = true;
+                        }
+                        ui.label(format!("第 {} / {} 页", self.page + 1, page_count));
+                        if ui.add_enabled(self.page + 1 < page_count, egui::Button::new("下一页 ▶")).clicked() {
+                            self.page += 1;
+                            self.scroll_to_top = true;
+                        }
+                        ui.weak(format!("共 {} 行", match_counts.len()));
+                    });
+                }
+            }
             ui.separator();
             
             // 计算可用高度，为其他UI元素留出空间
             let available_height = ui.available_height() - 120.0; // 为按钮和其他元素留出空间
-            let scroll_height = available_height.max(200.0).min(600.0); // 最小200px，最大600px
+            let scroll_height = available_height.clamp(200.0, 600.0); // 最小200px，最大600px
             
-            egui::ScrollArea::vertical()
+            // 行首置信度色条：文本不变时沿用缓存
+            let gutter = self.preserve_whitespace && self.display.confidence_gutter && !result.bounding_boxes.is_empty();
+            if gutter && self.line_confidences.as_ref().is_none_or(|(text, _)| *text != self.text_content) {
+                let confidences = reading_order::line_confidences(&self.text_content, &result.bounding_boxes);
+                self.line_confidences = Some((self.text_content.clone(), confidences));
+            }
+            let line_confidences = self.line_confidences.as_ref().filter(|_| gutter).map(|(_, confidences)| confidences);
+            
+            let mut scroll_area = egui::ScrollArea::vertical()
                 .id_salt("ocr_result_display")
                 .max_height(scroll_height)
-                .auto_shrink([false, false])
-                .show(ui, |ui| {
-                    // 设置等宽字体
-                    ui.style_mut().override_font_id = Some(egui::FontId::monospace(self.font_size));
+                .auto_shrink([false, false]);
+            if std::mem::take(&mut self.scroll_to_top) {
+                scroll_area = scroll_area.vertical_scroll_offset(0.0);
+            }
+            let mut menu_action = None;
+            let scroll_output = scroll_area.show(ui, |ui| {
+                    // 设置结果字体，默认等宽
+                    ui.style_mut().override_font_id = Some(self.display.font.font_id(self.display.font_size));
+                    let align = if self.text_direction.is_rtl() { egui::Align::RIGHT } else { egui::Align::LEFT };
                     
                     if self.preserve_whitespace {
-                        // 保持原有格式模式 - 逐行显示
-                        for line in self.text_content.lines() {
-                            if line.trim().is_empty() {
-                                // 空行显示为空白行
-                                ui.add_space(ui.text_style_height(&egui::TextStyle::Body));
-                            } else {
-                                // 保持行内的空格和制表符
-                                let formatted_line = line.replace('\t', "    ");
-                                ui.label(&formatted_line);
+                        // 行与行之间的额外间距
+                        let row_height = ui.fonts(|fonts| fonts.row_height(&self.display.font.font_id(self.display.font_size)));
+                        ui.spacing_mut().item_spacing.y = (self.display.line_spacing - 1.0).max(0.0) * row_height;
+                        let gutter_margin = match line_confidences {
+                            Some(_) => egui::Margin { left: CONFIDENCE_GUTTER_WIDTH, ..Default::default() },
+                            None => egui::Margin::ZERO,
+                        };
+                        egui::Frame::NONE.inner_margin(gutter_margin).show(ui, |ui| {
+                            let gutter_left = ui.max_rect().left() - CONFIDENCE_GUTTER_WIDTH as f32;
+                            ui.with_layout(egui::Layout::top_down(align), |ui| {
+                                // 查找关键字在各行中的匹配按出现顺序编号，与上方的匹配计数一致
+                                let mut match_index = page_starts_at_match;
+                                // 保持原有格式模式 - 逐行显示，只渲染当前页
+                                let lines = self.text_content.lines().enumerate().skip(self.page * lines_per_page).take(lines_per_page);
+                                for (line_index, line) in lines {
+                                    let formatted_line = line.replace('\t', "    ");
+                                    let line_matches = search::text_matches(&formatted_line, &self.search_query);
+                                    let label = if line.trim().is_empty() {
+                                        // 空行显示为空白行
+                                        ui.add_space(ui.text_style_height(&egui::TextStyle::Body));
+                                        None
+                                    } else if !line_matches.is_empty() {
+                                        let current = (match_index..match_index + line_matches.len()).contains(&self.search_current);
+                                        let job = search_highlight_job(
+                                            ui,
+                                            &formatted_line,
+                                            &line_matches,
+                                            self.search_current.checked_sub(match_index),
+                                            self.display.font.font_id(self.display.font_size),
+                                        );
+                                        let label = ui.label(job);
+                                        if current && search_scroll {
+                                            label.scroll_to_me(Some(egui::Align::Center));
+                                        }
+                                        Some(label)
+                                    } else if active_line == Some(line_index) {
+                                        let label = ui.label(
+                                            egui::RichText::new(formatted_line).background_color(ui.visuals().selection.bg_fill),
+                                        );
+                                        if scroll_to_line {
+                                            label.scroll_to_me(Some(egui::Align::Center));
+                                        }
+                                        Some(label)
+                                    } else {
+                                        // 保持行内的空格和制表符
+                                        Some(ui.label(&formatted_line))
+                                    };
+                                    match_index += line_matches.len();
+                                    if let Some(label) = &label {
+                                        label.context_menu(|ui| {
+                                            if let Some(action) = text_context_menu(ui, &self.text_content, None, line, None) {
+                                                menu_action = Some(action);
+                                            }
+                                        });
+                                    }
+                                
+                                    let confidence = line_confidences.and_then(|confidences| confidences.get(line_index).copied().flatten());
+                                    if let (Some(label), Some(confidence)) = (label, confidence) {
+                                        draw_confidence_gutter(ui, gutter_left, label.rect.y_range(), confidence, line_index);
+                                    }
+                                }
+                            });
+                        });
+                    } else {
+                        // 标准格式模式 - 可编辑，手动修改后重新识别时会显示差异
+                        let text_edit_id = ui.id().with("result_text_edit");
+                        let output = egui::TextEdit::multiline(&mut self.text_content)
+                            .id(text_edit_id)
+                            .desired_width(f32::INFINITY)
+                            .horizontal_align(align)
+                            .show(ui);
+                        if output.response.changed() {
+                            self.edited = true;
+                        }
+                        // 右键菜单作用于选中的文字；菜单打开后编辑框失去焦点，选区从保存的状态中读取
+                        let selection = output.state.cursor.char_range().map(|range| {
+                            let [start, end] = range.sorted();
+                            char_to_byte(&self.text_content, start.index)..char_to_byte(&self.text_content, end.index)
+                        });
+                        output.response.context_menu(|ui| {
+                            let text = &self.text_content;
+                            let selected = selection.clone().map(|range| &text[range]).filter(|s| !s.is_empty());
+                            let cursor = selection.clone().map_or(0, |range| range.start);
+                            let line_start = text[..cursor].rfind('\n').map_or(0, |i| i + 1);
+                            let line_end = text[cursor..].find('\n').map_or(text.len(), |i| cursor + i);
+                            if let Some(action) = text_context_menu(ui, text, selected, &text[line_start..line_end], Some(text_edit_id)) {
+                                menu_action = Some(action);
+                            }
+                        });
+                        // 在编辑框的文字上叠加查找匹配的底色。本帧修改过文字时按修改后的内容重新查找，
+                        // 不在字符边界上的位置跳过，避免越界或切开多字节字符
+                        let edited_matches;
+                        let matches = if output.response.changed() {
+                            edited_matches = search::text_matches(&self.text_content, &self.search_query);
+                            &edited_matches
+                        } else {
+                            &text_matches
+                        };
+                        for (index, range) in matches.iter().enumerate() {
+                            if !self.text_content.is_char_boundary(range.start) || !self.text_content.is_char_boundary(range.end) {
+                                continue;
+                            }
+                            let to_ccursor = |offset: usize| {
+                                egui::text::CCursor::new(self.text_content[..offset].chars().count())
+                            };
+                            let start = output.galley.pos_from_ccursor(to_ccursor(range.start));
+                            let end = output.galley.pos_from_ccursor(to_ccursor(range.end));
+                            let rect = start.union(end).translate(output.galley_pos.to_vec2());
+                            let current = index == self.search_current;
+                            ui.painter().rect_filled(rect, 0.0, search_match_color(current));
+                            if current && search_scroll {
+                                ui.scroll_to_rect(rect, Some(egui::Align::Center));
+                            }
+                        }
+                        if self.copy_on_select && output.response.drag_stopped() {
+                            if let Some(range) = output.cursor_range {
+                                let selected = range.slice_str(&self.text_content);
+                                if !selected.is_empty() {
+                                    ui.ctx().copy_text(selected.to_string());
+                                }
                             }
                         }
-                    } else {
-                        // 标准格式模式 - 使用可选择的标签
-                        ui.add(
-                            egui::TextEdit::multiline(&mut self.text_content.clone())
-                                .desired_width(f32::INFINITY)
-                                .interactive(false)
-                        );
                     }
                 });
-        });
+            
+            if let Some(action) = menu_action {
+                self.apply_text_menu_action(ui.ctx(), action);
+            }
+            
+            // 向下滚动后在文本区域右下角浮动显示“回到顶部”
+            if scroll_output.state.offset.y > SCROLL_TOP_THRESHOLD {
+                let corner = scroll_output.inner_rect.right_bottom() - egui::vec2(100.0, 36.0);
+                egui::Area::new(ui.id().with("scroll_to_top"))
+                    .fixed_pos(corner)
+                    .order(egui::Order::Foreground)
+                    .show(ui.ctx(), |ui| {
+                        if ui.button("⬆ 回到顶部").clicked() {
+                            self.scroll_to_top = true;
+                        }
+                    });
+            }
+        }).response;
+        self.handle_box_navigation(ui, &text_group, &result.bounding_boxes);
         
         ui.add_space(8.0);
         
         // 操作按钮 - 简化版
         ui.horizontal(|ui| {
             let copy_clicked = ui.button("📋 复制").clicked();
+            let has_boxes = !result.bounding_boxes.is_empty();
+            let copy_layout_clicked = ui.add_enabled(has_boxes, egui::Button::new("📐 按版面复制"))
+                .on_hover_text("根据检测框位置添加行首缩进，粘贴到纯文本编辑器时保留原版面")
+                .clicked();
+            let copy_tsv_clicked = ui.add_enabled(has_boxes, egui::Button::new("📊 复制为 TSV"))
+                .on_hover_text("每个检测区域一行：文字、置信度、x、y、宽、高，可直接粘贴到电子表格")
+                .clicked();
+            let copy_rich_clicked = ui.button("📝 复制为富文本")
+                .on_hover_text("以等宽字体的 HTML 复制，粘贴到 Word 或邮件时保留换行与空格；不支持富文本的程序会得到纯文本")
+                .clicked();
+            let copy_latex_clicked = ui.button("∑ 复制为 LaTeX（实验）")
+                .on_hover_text("实验功能：把像公式的行包成 $...$，并转换上下标、a/b 分数、根号与常见符号，其余行原样复制。转换结果请检查后再使用")
+                .clicked();
             let save_file_clicked = ui.button("💾 保存").clicked();
             let show_details_clicked = ui.button("🔍 详情").clicked();
+            ui.toggle_value(&mut self.proofreading, "🔤 校对")
+                .on_hover_text("用词典检查可能识别错误的单词和生僻字，点击建议即可替换");
             
             // 处理按钮点击事件
             if copy_clicked {
-                let text_to_copy = if self.preserve_whitespace {
-                    self.text_content.clone()
-                } else {
-                    self.text_content
-                        .lines()
-                        .map(|line| line.trim())
-                        .filter(|line| !line.is_empty())
-                        .collect::<Vec<_>>()
-                        .join("\n")
-                };
-                ui.ctx().copy_text(text_to_copy);
+                ui.ctx().copy_text(self.output_text());
+            }
+            
+            if copy_layout_clicked {
+                if let Some(text) = table::indent_by_position(&result.bounding_boxes) {
+                    ui.ctx().copy_text(text);
+                }
+            }
+            
+            if copy_rich_clicked {
+                let html = export::to_html_fragment(&self.text_content);
+                if let Err(e) = clipboard::copy_html(&html, &self.text_content) {
+                    log::warn!("复制富文本失败，改为复制纯文本: {}", e);
+                    ui.ctx().copy_text(self.text_content.clone());
+                }
+            }
+            
+            if copy_latex_clicked {
+                ui.ctx().copy_text(latex::to_latex(&self.text_content));
+            }
+            
+            if copy_tsv_clicked {
+                ui.ctx().copy_text(export::to_tsv(&result.bounding_boxes));
             }
             
             if save_file_clicked {
@@ -264,6 +1864,10 @@ impl ResultPanel {
         });
 
         
+        if self.source_path.is_some() {
+            self.show_annotation_editor(ui);
+        }
+        
         ui.add_space(8.0);
         
         // 详细信息
@@ -275,8 +1879,32 @@ impl ResultPanel {
                 ui.horizontal(|ui| {
                     ui.label("置信度:");
                     ui.strong(format!("{:.1}%", result.confidence * 100.0));
+                    ui.separator();
+                    ui.label("按字数加权:");
+                    ui.strong(format!("{:.1}%", result.weighted_confidence * 100.0))
+                        .on_hover_text("按每个检测区域的字符数加权平均，长段落的置信度影响更大");
                 });
                 
+                if !result.engine.is_empty() {
+                    ui.horizontal(|ui| {
+                        ui.label("识别引擎:");
+                        ui.strong(&result.engine);
+                    });
+                }
+                if !result.detected_language.is_empty() {
+                    ui.horizontal(|ui| {
+                        ui.label("检测语言:");
+                        ui.strong(&result.detected_language);
+                    });
+                }
+                if result.attempts.len() > 1 {
+                    ui.label("低置信度回退:");
+                    for attempt in &result.attempts {
+                        let chosen = if attempt.engine == result.engine { "（已采用）" } else { "" };
+                        ui.weak(format!("  {} {:.1}%{}", attempt.engine, attempt.confidence * 100.0, chosen));
+                    }
+                }
+                
                 ui.horizontal(|ui| {
                     ui.label("处理时间:");
                     ui.strong(format!("{:.0}ms", result.processing_time));
@@ -304,29 +1932,419 @@ impl ResultPanel {
                         ui.strong(format!("{} 个", result.bounding_boxes.len()));
                     });
                 }
+                
+                if !result.bounding_boxes.is_empty() {
+                    ui.separator();
+                    show_confidence_histogram(ui, &result.bounding_boxes);
+                    ui.separator();
+                    self.show_region_list(ui, &result.bounding_boxes);
+                }
+                
+                if !result.timings.is_empty() {
+                    ui.separator();
+                    ui.label("阶段耗时:");
+                    let total: f64 = result.timings.iter().map(|t| t.millis).sum();
+                    for timing in &result.timings {
+                        let fraction = if total > 0.0 { (timing.millis / total) as f32 } else { 0.0 };
+                        ui.add(
+                            egui::ProgressBar::new(fraction)
+                                .desired_width(240.0)
+                                .text(format!("{} {:.1}ms", timing.stage, timing.millis))
+                        );
+                    }
+                }
+            });
+        }
+    }
+    
+    // 校对侧栏：列出词典中没有的单词与生僻字，点击建议替换原文中的该处
+    fn show_proofreading(&mut self, ui: &mut egui::Ui) {
+        let dictionary = self.dictionary.get_or_insert_with(Dictionary::load);
+        if self.spell_checked_text.as_deref() != Some(self.text_content.as_str()) {
+            self.spell_issues = spellcheck::check(&self.text_content, dictionary, self.check_hanzi);
+            self.spell_checked_text = Some(self.text_content.clone());
+        }
+        let dictionary_empty = dictionary.is_empty();
+        
+        ui.strong(format!("校对（{} 处）", self.spell_issues.len()));
+        if dictionary_empty {
+            ui.weak("未找到系统词表，只检查生僻字；加入词典的词保存在配置目录的 dictionary.txt");
+        }
+        ui.separator();
+        
+        let mut replacement = None;
+        let mut learned = None;
+        egui::ScrollArea::vertical().id_salt("proofreading_issues").show(ui, |ui| {
+            if self.spell_issues.is_empty() {
+                ui.weak("未发现可疑词");
+            }
+            for (index, issue) in self.spell_issues.iter().enumerate() {
+                ui.horizontal_wrapped(|ui| {
+                    let hint = match issue.kind {
+                        IssueKind::UnknownWord => "词典中没有该词，点击在文本中定位",
+                        IssueKind::RareCharacter => "不常用的汉字，可能是形近字识别错误，点击在文本中定位",
+                    };
+                    let word = egui::RichText::new(&issue.word).color(ui.visuals().warn_fg_color);
+                    if ui.link(word).on_hover_text(hint).clicked() {
+                        self.search_query = issue.word.clone();
+                        self.search_current = 0;
+                        self.search_scroll = true;
+                    }
+                    ui.label("→");
+                    for suggestion in &issue.suggestions {
+                        if ui.small_button(suggestion).clicked() {
+                            replacement = Some((index, suggestion.clone()));
+                        }
+                    }
+                    if issue.suggestions.is_empty() {
+                        ui.weak("无建议");
+                    }
+                    if issue.kind == IssueKind::UnknownWord
+                        && ui.small_button("加入词典").on_hover_text("以后不再标记该词").clicked()
+                    {
+                        learned = Some(issue.word.clone());
+                    }
+                });
+            }
+        });
+        
+        if let Some((index, suggestion)) = replacement {
+            let range = self.spell_issues[index].range.clone();
+            self.text_content.replace_range(range, &suggestion);
+            self.edited = true;
+        }
+        if let Some(word) = learned {
+            if let Some(dictionary) = &mut self.dictionary {
+                if let Err(e) = dictionary.add_user_word(&word) {
+                    log::warn!("保存用户词典失败: {}", e);
+                }
+            }
+            self.spell_checked_text = None;
+        }
+    }
+    
+    // 重新识别后与手动编辑文本的逐行差异，勾选表示接受该处变化
+    fn show_diff_review(&mut self, ui: &mut egui::Ui) {
+        let Some(changes) = &mut self.pending_diff else {
+            return;
+        };
+        let mut apply = false;
+        let mut keep_edits = false;
+        
+        ui.group(|ui| {
+            ui.strong("重新识别的结果与手动编辑的文本不同");
+            ui.weak("勾选的行接受新结果的变化，取消勾选则保留手动编辑的内容");
+            ui.horizontal(|ui| {
+                if ui.small_button("全部接受").clicked() {
+                    changes.iter_mut().for_each(|change| change.accepted = true);
+                }
+                if ui.small_button("全部拒绝").clicked() {
+                    changes.iter_mut().for_each(|change| change.accepted = false);
+                }
             });
+            ui.separator();
+            
+            egui::ScrollArea::vertical()
+                .id_salt("ocr_result_diff")
+                .max_height(400.0)
+                .auto_shrink([false, true])
+                .show(ui, |ui| {
+                    ui.style_mut().override_font_id = Some(egui::FontId::monospace(self.display.font_size));
+                    for change in changes.iter_mut() {
+                        let (prefix, color) = match change.kind {
+                            LineChangeKind::Unchanged => {
+                                ui.label(format!("    {}", change.text));
+                                continue;
+                            }
+                            LineChangeKind::Removed => ("-", egui::Color32::from_rgb(220, 20, 60)),
+                            LineChangeKind::Added => ("+", egui::Color32::from_rgb(34, 139, 34)),
+                        };
+                        ui.horizontal(|ui| {
+                            ui.checkbox(&mut change.accepted, "");
+                            ui.colored_label(color, format!("{} {}", prefix, change.text));
+                        });
+                    }
+                });
+            
+            ui.separator();
+            ui.horizontal(|ui| {
+                apply = ui.button("✔ 应用").clicked();
+                keep_edits = ui.button("保留我的编辑").clicked();
+            });
+        });
+        
+        if apply {
+            self.text_content = diff::merge(changes);
+            self.pending_diff = None;
+        } else if keep_edits {
+            self.pending_diff = None;
         }
     }
     
-    fn save_to_file(&self) {
-        if let Some(path) = rfd::FileDialog::new()
-            .set_file_name("ocr_result.txt")
-            .add_filter("文本文件", &["txt"])
-            .save_file()
-        {
-            let content = if self.preserve_whitespace {
-                self.text_content.clone()
+    // 点击文本区域使其获得焦点，获得焦点后 Tab/Shift+Tab 在检测框之间切换，而不是移动控件焦点
+    fn handle_box_navigation(&mut self, ui: &mut egui::Ui, text_group: &egui::Response, boxes: &[BoundingBox]) {
+        let focus = ui.interact(text_group.rect, ui.id().with("result_text_focus"), egui::Sense::focusable_noninteractive());
+        let pressed_inside = ui.input(|i| {
+            i.pointer.primary_pressed() && i.pointer.interact_pos().is_some_and(|pos| text_group.rect.contains(pos))
+        });
+        if pressed_inside {
+            focus.request_focus();
+        }
+        if !focus.has_focus() || boxes.is_empty() {
+            return;
+        }
+        
+        ui.memory_mut(|m| m.set_focus_lock_filter(focus.id, egui::EventFilter { tab: true, ..Default::default() }));
+        ui.painter().rect_stroke(
+            text_group.rect,
+            ui.visuals().widgets.noninteractive.corner_radius,
+            ui.visuals().selection.stroke,
+            egui::StrokeKind::Inside,
+        );
+        
+        let step = ui.input_mut(|i| {
+            if i.consume_key(egui::Modifiers::SHIFT, egui::Key::Tab) {
+                -1
+            } else if i.consume_key(egui::Modifiers::NONE, egui::Key::Tab) {
+                1
             } else {
-                self.text_content
-                    .lines()
-                    .map(|line| line.trim())
-                    .filter(|line| !line.is_empty())
-                    .collect::<Vec<_>>()
-                    .join("\n")
-            };
-            let _ = std::fs::write(path, content);
+                0
+            }
+        });
+        if step != 0 {
+            self.cycle_active_box(boxes, step);
+            ui.ctx().request_repaint();
+        }
+    }
+    
+    // 备注与标签编辑器，输入框失去焦点时提交
+    fn show_annotation_editor(&mut self, ui: &mut egui::Ui) {
+        let title = if self.tags_input.trim().is_empty() {
+            "🏷 备注与标签".to_string()
+        } else {
+            format!("🏷 备注与标签（{}）", self.tags_input.trim())
+        };
+        egui::CollapsingHeader::new(title)
+            .id_salt("result_annotation")
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("标签:");
+                    self.annotation_changed |= ui.add(
+                        egui::TextEdit::singleline(&mut self.tags_input)
+                            .hint_text("用逗号分隔，如 发票, 2024")
+                            .desired_width(f32::INFINITY),
+                    )
+                    .lost_focus();
+                });
+                self.annotation_changed |= ui.add(
+                    egui::TextEdit::multiline(&mut self.note)
+                        .hint_text("备注")
+                        .desired_rows(2)
+                        .desired_width(f32::INFINITY),
+                )
+                .lost_focus();
+            });
+    }
+    
+    // 识别区域列表：逐个复制，点击条目在图片上闪烁对应检测框
+    fn show_region_list(&mut self, ui: &mut egui::Ui, boxes: &[BoundingBox]) {
+        ui.horizontal(|ui| {
+            ui.label("识别区域:");
+            ui.radio_value(&mut self.region_sort, RegionSort::ReadingOrder, "阅读顺序");
+            ui.radio_value(&mut self.region_sort, RegionSort::Position, "按位置");
+            
+            // 多语言混排时按检测框语言筛选
+            let mut languages: Vec<&str> = boxes.iter().filter_map(|bbox| bbox.language.as_deref()).collect();
+            languages.sort_unstable();
+            languages.dedup();
+            if languages.len() < 2 || self.region_language.as_deref().is_some_and(|language| !languages.contains(&language)) {
+                self.region_language = None;
+            }
+            if languages.len() > 1 {
+                ui.separator();
+                egui::ComboBox::from_id_salt("region_language")
+                    .selected_text(self.region_language.as_deref().unwrap_or("全部语言"))
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut self.region_language, None, "全部语言");
+                        for language in languages {
+                            ui.selectable_value(&mut self.region_language, Some(language.to_string()), language);
+                        }
+                    });
+            }
+        });
+        
+        let mut order = self.region_order(boxes);
+        if let Some(language) = &self.region_language {
+            order.retain(|&index| boxes[index].language.as_ref() == Some(language));
+            if ui.button(format!("📋 复制 {} 区域文字", language)).clicked() {
+                let text: Vec<&str> = order.iter().map(|&index| boxes[index].text.trim()).collect();
+                ui.ctx().copy_text(text.join("\n"));
+            }
+        }
+        
+        egui::ScrollArea::vertical()
+            .id_salt("region_list")
+            .max_height(200.0)
+            .show(ui, |ui| {
+                for index in order {
+                    let bbox = &boxes[index];
+                    ui.horizontal(|ui| {
+                        if ui.small_button("📋").on_hover_text("复制此区域文字").clicked() {
+                            ui.ctx().copy_text(bbox.text.clone());
+                        }
+                        if let Some(language) = &bbox.language {
+                            let [r, g, b] = annotate::language_color(Some(language));
+                            ui.label(egui::RichText::new(language).small().color(egui::Color32::from_rgb(r, g, b)));
+                        }
+                        let marker = if bbox.edited { " ✏" } else { "" };
+                        let label = ui.selectable_label(self.active_box == Some(index), format!("{}. {}{}", index + 1, bbox.text.trim(), marker))
+                            .on_hover_text(format!(
+                                "位置: ({}, {}) 尺寸: {}×{} 置信度: {:.1}%",
+                                bbox.x, bbox.y, bbox.width, bbox.height, bbox.confidence * 100.0
+                            ));
+                        if label.clicked() {
+                            self.flash_request = Some(index);
+                        }
+                    });
+                }
+            });
+    }
+    
+    fn apply_text_menu_action(&mut self, ctx: &egui::Context, action: TextMenuAction) {
+        match action {
+            TextMenuAction::Copy(text) => ctx.copy_text(text),
+            TextMenuAction::SelectAll(id) => {
+                let mut state = egui::TextEdit::load_state(ctx, id).unwrap_or_default();
+                let end = egui::text::CCursor::new(self.text_content.chars().count());
+                state.cursor.set_char_range(Some(egui::text::CCursorRange::two(egui::text::CCursor::new(0), end)));
+                state.store(ctx, id);
+                ctx.memory_mut(|memory| memory.request_focus(id));
+            }
+            TextMenuAction::Search(text) => {
+                self.search_query = text.lines().map(str::trim).find(|line| !line.is_empty()).unwrap_or_default().to_string();
+                self.search_current = 0;
+                self.search_scroll = true;
+            }
+            TextMenuAction::Export(text) => self.save_text_to_file(text),
+        }
+    }
+    
+    fn save_to_file(&mut self) {
+        self.save_text_to_file(self.output_text());
+    }
+    
+    fn save_text_to_file(&mut self, content: String) {
+        let mut dialog = rfd::FileDialog::new()
+            .set_file_name(self.export_naming.file_name(self.source_path.as_deref(), "txt"))
+            .add_filter("文本文件", &["txt"]);
+        if let Some(directory) = self.export_naming.dialog_directory() {
+            dialog = dialog.set_directory(directory);
         }
+        if let Some(path) = dialog.save_file() {
+            let _ = std::fs::write(&path, content);
+            self.export_naming_changed |= self.export_naming.remember(&path);
+        }
+    }
+}
+
+// 不保持空格格式时的空白处理选项，返回是否有修改
+fn whitespace_checkboxes(ui: &mut egui::Ui, whitespace: &mut WhitespaceOptions) -> bool {
+    ui.horizontal(|ui| {
+        ui.label("空白处理:");
+        let responses = [
+            ui.checkbox(&mut whitespace.trim_leading, "去除行首空白"),
+            ui.checkbox(&mut whitespace.trim_trailing, "去除行尾空白"),
+            ui.checkbox(&mut whitespace.collapse_internal, "合并行内连续空白")
+                .on_hover_text("行内连续的空格与制表符合并为一个空格，保留行首缩进"),
+            ui.checkbox(&mut whitespace.drop_blank_lines, "删除空行"),
+        ];
+        responses.iter().any(|response| response.changed())
+    })
+    .inner
+}
+
+// 结果文本右键菜单的操作
+enum TextMenuAction {
+    Copy(String),
+    // 参数为可编辑文本框的 Id
+    SelectAll(egui::Id),
+    Search(String),
+    Export(String),
+}
+
+// 结果文本的右键菜单：有选中文字时作用于选区，否则复制全文，查找和导出当前行
+fn text_context_menu(
+    ui: &mut egui::Ui,
+    full_text: &str,
+    selected: Option<&str>,
+    line: &str,
+    text_edit: Option<egui::Id>,
+) -> Option<TextMenuAction> {
+    let mut action = None;
+    let copy_label = if selected.is_some() { "📋 复制" } else { "📋 复制全文" };
+    if ui.button(copy_label).clicked() {
+        action = Some(TextMenuAction::Copy(selected.unwrap_or(full_text).to_string()));
+    }
+    if ui.add_enabled(!line.trim().is_empty(), egui::Button::new("复制此行")).clicked() {
+        action = Some(TextMenuAction::Copy(line.to_string()));
+    }
+    if ui.add_enabled(text_edit.is_some(), egui::Button::new("全选")).clicked() {
+        action = text_edit.map(TextMenuAction::SelectAll);
+    }
+    ui.separator();
+    let target = selected.unwrap_or(line);
+    if ui.add_enabled(!target.trim().is_empty(), egui::Button::new("🔍 查找所选内容")).clicked() {
+        action = Some(TextMenuAction::Search(target.to_string()));
+    }
+    if ui.add_enabled(!target.trim().is_empty(), egui::Button::new("💾 导出所选内容…")).clicked() {
+        action = Some(TextMenuAction::Export(target.to_string()));
+    }
+    if action.is_some() {
+        ui.close_menu();
+    }
+    action
+}
+
+// 字符序号转换为字节偏移，超出末尾时返回文本长度
+fn char_to_byte(text: &str, index: usize) -> usize {
+    text.char_indices().nth(index).map_or(text.len(), |(offset, _)| offset)
+}
+
+// 查找匹配的底色，当前定位的匹配颜色更深
+fn search_match_color(current: bool) -> egui::Color32 {
+    if current {
+        egui::Color32::from_rgba_unmultiplied(255, 150, 0, 140)
+    } else {
+        egui::Color32::from_rgba_unmultiplied(255, 220, 0, 80)
+    }
+}
+
+// 把一行文字排版为高亮查找匹配的文本，current 为当前匹配在本行匹配中的序号
+fn search_highlight_job(
+    ui: &egui::Ui,
+    line: &str,
+    matches: &[std::ops::Range<usize>],
+    current: Option<usize>,
+    font_id: egui::FontId,
+) -> egui::text::LayoutJob {
+    let format = egui::TextFormat {
+        font_id,
+        color: ui.visuals().text_color(),
+        ..Default::default()
+    };
+    let mut job = egui::text::LayoutJob::default();
+    let mut offset = 0;
+    for (index, range) in matches.iter().enumerate() {
+        job.append(&line[offset..range.start], 0.0, format.clone());
+        let highlighted = egui::TextFormat {
+            background: search_match_color(current == Some(index)),
+            ..format.clone()
+        };
+        job.append(&line[range.clone()], 0.0, highlighted);
+        offset = range.end;
     }
+    job.append(&line[offset..], 0.0, format);
+    job
 }
 
 // 辅助函数：创建简单的图像纹理
@@ -334,6 +2352,7 @@ pub fn create_texture_from_image(
     ctx: &egui::Context,
     image: &DynamicImage,
     name: &str,
+    options: egui::TextureOptions,
 ) -> egui::TextureHandle {
     let rgba_image = image.to_rgba8();
     let (width, height) = image.dimensions();
@@ -344,7 +2363,7 @@ pub fn create_texture_from_image(
         pixels.as_slice(),
     );
     
-    ctx.load_texture(name, color_image, egui::TextureOptions::default())
+    ctx.load_texture(name, color_image, options)
 }
 
 // UI样式辅助函数
@@ -360,11 +2379,13 @@ pub fn setup_custom_style(ctx: &egui::Context) {
 }
 
 // 错误显示组件
+#[allow(dead_code)]
 pub struct ErrorDisplay {
     message: String,
     show_details: bool,
 }
 
+#[allow(dead_code)]
 impl ErrorDisplay {
     pub fn new(message: String) -> Self {
         Self {
@@ -394,11 +2415,85 @@ impl ErrorDisplay {
     }
 }
 
+// 调试用的性能浮层（F12 切换）：帧耗时、纹理内存和当前状态
+pub struct DebugOverlay {
+    visible: bool,
+    // 最近若干帧的界面更新耗时（秒）
+    frame_times: std::collections::VecDeque<f32>,
+}
+
+impl DebugOverlay {
+    const HISTORY: usize = 120;
+    
+    pub fn new() -> Self {
+        Self {
+            visible: false,
+            frame_times: std::collections::VecDeque::with_capacity(Self::HISTORY),
+        }
+    }
+    
+    pub fn toggle(&mut self) {
+        self.visible = !self.visible;
+        self.frame_times.clear();
+    }
+    
+    // cpu_usage 为上一帧 update 的耗时，由 eframe 提供
+    pub fn record(&mut self, cpu_usage: Option<f32>) {
+        if let Some(seconds) = cpu_usage.filter(|_| self.visible) {
+            if self.frame_times.len() == Self::HISTORY {
+                self.frame_times.pop_front();
+            }
+            self.frame_times.push_back(seconds);
+        }
+    }
+    
+    pub fn show(&self, ctx: &egui::Context, state: &str) {
+        if !self.visible {
+            return;
+        }
+        
+        let (frame_interval, fps) = ctx.input(|i| (i.unstable_dt, 1.0 / i.stable_dt.max(f32::EPSILON)));
+        let (average, worst) = if self.frame_times.is_empty() {
+            (0.0, 0.0)
+        } else {
+            let sum: f32 = self.frame_times.iter().sum();
+            (sum / self.frame_times.len() as f32, self.frame_times.iter().copied().fold(0.0, f32::max))
+        };
+        let textures = ctx.tex_manager();
+        let textures = textures.read();
+        let texture_count = textures.allocated().len();
+        let texture_bytes: usize = textures.allocated().map(|(_, meta)| meta.bytes_used()).sum();
+        drop(textures);
+        
+        egui::Area::new(egui::Id::new("debug_overlay"))
+            .anchor(egui::Align2::RIGHT_TOP, egui::vec2(-8.0, 8.0))
+            .order(egui::Order::Foreground)
+            .interactable(false)
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style())
+                    .fill(egui::Color32::from_black_alpha(200))
+                    .show(ui, |ui| {
+                        ui.style_mut().override_font_id = Some(egui::FontId::monospace(12.0));
+                        ui.visuals_mut().override_text_color = Some(egui::Color32::from_gray(230));
+                        ui.label(format!("FPS      {:>7.1}", fps));
+                        ui.label(format!("帧间隔   {:>7.1} ms", frame_interval * 1000.0));
+                        ui.label(format!("更新耗时 {:>7.2} ms（最慢 {:.2}）", average * 1000.0, worst * 1000.0));
+                        ui.label(format!("纹理     {:>7} 个 / {:.1} MB", texture_count, texture_bytes as f64 / 1024.0 / 1024.0));
+                        ui.label(format!("状态     {}", state));
+                        ui.weak("F12 关闭");
+                    });
+            });
+        // 浮层显示期间持续刷新，数值才会实时变化
+        ctx.request_repaint();
+    }
+}
+
 // 进度指示器组件
 pub struct ProgressIndicator {
     current: usize,
     total: usize,
     message: String,
+    paused: bool,
 }
 
 impl ProgressIndicator {
@@ -407,6 +2502,7 @@ impl ProgressIndicator {
             current: 0,
             total,
             message,
+            paused: false,
         }
     }
     
@@ -414,9 +2510,18 @@ impl ProgressIndicator {
         self.current = current;
     }
     
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
+    
     pub fn show(&self, ui: &mut egui::Ui) {
         ui.vertical(|ui| {
-            ui.label(&self.message);
+            ui.horizontal(|ui| {
+                ui.label(&self.message);
+                if self.paused {
+                    ui.colored_label(egui::Color32::from_rgb(255, 165, 0), "⏸ 已暂停");
+                }
+            });
             
             let progress = if self.total > 0 {
                 self.current as f32 / self.total as f32